@@ -103,6 +103,61 @@ fn emit_version_cfg_flags(version: &str) {
             version
         );
     }
+
+    // XGBoost 1.4.0+ predicts with a JSON config (XGBoosterPredictFromDMatrix)
+    if major > 1 || (major == 1 && minor >= 4) {
+        println!("cargo:rustc-cfg=xgboost_predict_config");
+    }
+
+    // XGBoost 1.4.0+ reports the number of trained rounds (XGBoosterBoostedRounds)
+    if major > 1 || (major == 1 && minor >= 4) {
+        println!("cargo:rustc-cfg=xgboost_boosted_rounds");
+    }
+
+    // XGBoost 1.5.0+ computes feature importance (XGBoosterFeatureScore)
+    if major > 1 || (major == 1 && minor >= 5) {
+        println!("cargo:rustc-cfg=xgboost_feature_score");
+    }
+
+    // XGBoost 1.6.0+ can serialize models into memory (XGBoosterSaveModelToBuffer)
+    if major > 1 || (major == 1 && minor >= 6) {
+        println!("cargo:rustc-cfg=xgboost_save_to_buffer");
+    }
+
+    // XGBoost 1.6.0+ reports its build configuration (XGBuildInfo)
+    if major > 1 || (major == 1 && minor >= 6) {
+        println!("cargo:rustc-cfg=xgboost_build_info");
+    }
+
+    // XGBoost 1.7.0+ builds QuantileDMatrix from iterators (XGQuantileDMatrixCreateFromCallback)
+    if major > 1 || (major == 1 && minor >= 7) {
+        println!("cargo:rustc-cfg=xgboost_quantile_dmatrix");
+    }
+
+    // XGBoost 1.7.0+ accepts host data through the array interface (XGDMatrixCreateFromDense)
+    if major > 1 || (major == 1 && minor >= 7) {
+        println!("cargo:rustc-cfg=xgboost_array_interface");
+    }
+
+    // XGBoost 2.0.0+ selects the device with `device` instead of `gpu_id`/`predictor`
+    if major >= 2 {
+        println!("cargo:rustc-cfg=xgboost_device_param");
+    }
+
+    // XGBoost 2.0.0+ exports the data of a DMatrix (XGDMatrixGetDataAsCSR)
+    if major >= 2 {
+        println!("cargo:rustc-cfg=xgboost_get_data");
+    }
+
+    // XGBoost 2.0.0+ configures the collective communicator with `dmlc_*` keys
+    if major >= 2 {
+        println!("cargo:rustc-cfg=xgboost_collective");
+    }
+
+    // XGBoost 2.1.0+ exposes the Rabit tracker (XGTrackerCreate)
+    if major > 2 || (major == 2 && minor >= 1) {
+        println!("cargo:rustc-cfg=xgboost_tracker");
+    }
 }
 
 fn get_platform_info() -> (String, String) {
@@ -133,12 +188,14 @@ fn get_platform_info() -> (String, String) {
     (os.to_string(), arch.to_string())
 }
 
-fn download_xgboost_headers(out_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
-    let version = get_xgboost_version();
+fn download_xgboost_headers(
+    out_dir: &Path,
+    version: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
     let checksums = get_header_checksums();
 
     // Get expected checksums for this version
-    let (c_api_expected, base_expected) = checksums.get(version.as_str()).ok_or_else(|| {
+    let (c_api_expected, base_expected) = checksums.get(version).ok_or_else(|| {
         format!(
             "No known SHA256 checksums for XGBoost version {}. \
              Please verify this version manually or add checksums to build.rs",
@@ -249,17 +306,78 @@ fn download_with_retry(url: &str, max_retries: u32) -> Result<Vec<u8>, Box<dyn s
     .into())
 }
 
+// SHA256 of a wheel as published by PyPI, read from the release's JSON metadata
+//
+// The metadata is fetched over the same TLS connection to PyPI as the wheel,
+// so this catches corrupted or truncated downloads and tampered caches but
+// trusts PyPI itself. XGBOOST_WHEEL_SHA256 replaces it with a digest vetted
+// out of band, making that pin the trust anchor.
+fn get_wheel_checksum(
+    version: &str,
+    wheel_filename: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if let Ok(pinned) = env::var("XGBOOST_WHEEL_SHA256") {
+        return Ok(pinned.trim().to_lowercase());
+    }
+
+    let url = format!("https://pypi.org/pypi/xgboost/{}/json", version);
+    let metadata: serde_json::Value = serde_json::from_slice(&download_with_retry(&url, 3)?)?;
+    let digest = metadata["urls"]
+        .as_array()
+        .and_then(|files| {
+            files
+                .iter()
+                .find(|file| file["filename"].as_str() == Some(wheel_filename))
+        })
+        .ok_or_else(|| {
+            format!(
+                "{} is not listed in the PyPI release {}",
+                wheel_filename, version
+            )
+        })?["digests"]["sha256"]
+        .as_str()
+        .ok_or_else(|| format!("No SHA256 digest on PyPI for {}", wheel_filename))?;
+    if digest.len() != 64 || !digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("Malformed SHA256 digest on PyPI for {}", wheel_filename).into());
+    }
+    Ok(digest.to_lowercase())
+}
+
 fn download_and_extract_wheel(out_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
     let (os, arch) = get_platform_info();
     let version = get_xgboost_version();
     let (major, minor, _patch) = parse_version(&version);
+    let cuda = env::var("CARGO_FEATURE_CUDA").is_ok();
+
+    // The wheels link against glibc
+    if env::var("TARGET").unwrap().contains("musl") {
+        return Err("XGBoost publishes no musl wheel. \
+             Build libxgboost for the target and link it with the `system` feature and XGBOOST_LIB_DIR"
+            .into());
+    }
+
+    // Only the Linux x86_64 and Windows wheels ship with CUDA support
+    if cuda
+        && !matches!(
+            (os.as_str(), arch.as_str()),
+            ("linux", "x86_64") | ("windows", "x86_64")
+        )
+    {
+        return Err(format!(
+            "No CUDA-enabled XGBoost wheel exists for {}-{}. \
+             Build libxgboost with -DUSE_CUDA=ON and set XGBOOST_LIB_DIR to its directory",
+            os, arch
+        )
+        .into());
+    }
 
     // Determine wheel filename based on platform and version
     // Different XGBoost versions use different manylinux tags
     let wheel_filename = match (os.as_str(), arch.as_str()) {
         ("linux", "x86_64") => {
-            // Choose manylinux tag based on version
-            let manylinux_tag = if major >= 3 {
+            // Choose manylinux tag based on version; from 2.1 on the
+            // manylinux2014 wheel is CPU-only and CUDA needs manylinux_2_28
+            let manylinux_tag = if major >= 3 || (cuda && major == 2 && minor >= 1) {
                 "manylinux_2_28"
             } else if major == 1 && minor == 4 {
                 "manylinux2010"
@@ -344,6 +462,11 @@ fn download_and_extract_wheel(out_dir: &Path) -> Result<(), Box<dyn std::error::
             download_url
         );
         let buffer = download_with_retry(&download_url, 3)?;
+        verify_checksum(
+            &buffer,
+            &get_wheel_checksum(&version, &wheel_filename)?,
+            &wheel_filename,
+        )?;
 
         // Write atomically (temp file + rename)
         let temp_path = wheel_path.with_extension("tmp");
@@ -421,35 +544,146 @@ fn download_and_extract_wheel(out_dir: &Path) -> Result<(), Box<dyn std::error::
     Ok(())
 }
 
-fn main() {
-    // Tell cargo about custom cfg flags we emit
-    println!("cargo:rustc-check-cfg=cfg(xgboost_thread_safe)");
+fn copy_prebuilt_library(out_dir: &Path, lib_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let (os, _arch) = get_platform_info();
+    let lib_filename = match os.as_str() {
+        "windows" => "xgboost.dll",
+        "darwin" => "libxgboost.dylib",
+        _ => "libxgboost.so",
+    };
 
-    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
-    let xgb_include_root = out_dir.join("include");
+    let source = lib_dir.join(lib_filename);
+    if !source.exists() {
+        return Err(format!("{} not found in XGBOOST_LIB_DIR", source.display()).into());
+    }
 
-    // Get version and emit cfg flags for thread safety
-    let version = get_xgboost_version();
-    emit_version_cfg_flags(&version);
+    let dest_dir = out_dir.join("libs");
+    fs::create_dir_all(&dest_dir)?;
+    fs::copy(&source, dest_dir.join(lib_filename))?;
+    println!(
+        "cargo:warning=✓ Using prebuilt XGBoost library from: {}",
+        source.display()
+    );
+    Ok(())
+}
 
-    // Download the headers
-    if let Err(e) = download_xgboost_headers(&out_dir) {
-        eprintln!("Failed to download XGBoost headers: {}", e);
-        panic!("Cannot proceed without headers");
+/// A libxgboost installed outside of this build, linked in place.
+struct SystemLibrary {
+    /// Directory containing `xgboost/c_api.h`, if the headers are installed too
+    include_dir: Option<PathBuf>,
+    /// Version reported by pkg-config
+    version: Option<String>,
+}
+
+fn has_headers(dir: &Path) -> bool {
+    dir.join("xgboost").join("c_api.h").exists()
+}
+
+/// Version of installed headers, from the `XGBOOST_VER_*` macros in
+/// `xgboost/version_config.h` (or `c_api.h`, which includes it)
+fn header_version(include_dir: &Path) -> Option<String> {
+    let headers = ["version_config.h", "c_api.h"]
+        .iter()
+        .filter_map(|name| fs::read_to_string(include_dir.join("xgboost").join(name)).ok())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let component = |name: &str| {
+        headers.lines().find_map(|line| {
+            let mut tokens = line.split_whitespace();
+            if tokens.next() != Some("#define") || tokens.next() != Some(name) {
+                return None;
+            }
+            tokens.next()?.parse::<u32>().ok()
+        })
+    };
+    Some(format!(
+        "{}.{}.{}",
+        component("XGBOOST_VER_MAJOR")?,
+        component("XGBOOST_VER_MINOR")?,
+        component("XGBOOST_VER_PATCH").unwrap_or(0)
+    ))
+}
+
+// Link against libxgboost from XGBOOST_LIB_DIR, or else from pkg-config, without copying it
+fn find_system_library() -> Result<SystemLibrary, Box<dyn std::error::Error>> {
+    let explicit_include = env::var("XGBOOST_INCLUDE_DIR").ok().map(PathBuf::from);
+
+    if let Ok(dir) = env::var("XGBOOST_LIB_DIR") {
+        let lib_dir = PathBuf::from(dir);
+        if !lib_dir.is_dir() {
+            return Err(format!("XGBOOST_LIB_DIR {} is not a directory", lib_dir.display()).into());
+        }
+        println!("cargo:rustc-link-search=native={}", lib_dir.display());
+        println!("cargo:rustc-link-lib=dylib=xgboost");
+        emit_rpath(&lib_dir);
+        println!(
+            "cargo:warning=✓ Linking system XGBoost library in: {}",
+            lib_dir.display()
+        );
+
+        // A CMake install puts the headers next to lib/
+        let include_dir = explicit_include.or_else(|| {
+            lib_dir
+                .parent()
+                .map(|prefix| prefix.join("include"))
+                .filter(|dir| has_headers(dir))
+        });
+        return Ok(SystemLibrary {
+            include_dir,
+            version: None,
+        });
     }
 
-    // Download and extract the wheel
-    if let Err(e) = download_and_extract_wheel(&out_dir) {
-        eprintln!("Failed to download and extract wheel: {}", e);
-        panic!("Cannot proceed without compiled library");
+    let library = pkg_config::Config::new()
+        .atleast_version("1.0")
+        .probe("xgboost")
+        .map_err(|e| {
+            format!(
+                "libxgboost not found via pkg-config ({}). \
+                 Set XGBOOST_LIB_DIR to the directory containing the library instead",
+                e
+            )
+        })?;
+    for dir in &library.link_paths {
+        emit_rpath(dir);
     }
+    println!(
+        "cargo:warning=✓ Linking system XGBoost {} found via pkg-config",
+        library.version
+    );
+    let include_dir = explicit_include.or_else(|| {
+        library
+            .include_paths
+            .iter()
+            .find(|dir| has_headers(dir))
+            .cloned()
+    });
+    Ok(SystemLibrary {
+        include_dir,
+        version: Some(library.version),
+    })
+}
 
+fn emit_rpath(dir: &Path) {
+    let target = env::var("TARGET").unwrap();
+    if !target.contains("windows") {
+        println!("cargo:rustc-link-arg=-Wl,-rpath,{}", dir.display());
+    }
+}
+
+fn generate_bindings(include_root: &Path, out_dir: &Path) {
     let bindings = bindgen::Builder::default()
         .header("wrapper.h")
-        .clang_arg(format!("-I{}", xgb_include_root.display()))
+        .clang_arg(format!("-I{}", include_root.display()))
         // Generate bindings for XGB and XGD functions (Booster and DMatrix)
         .allowlist_function("XGB.*")
         .allowlist_function("XGD.*")
+        // Proxy DMatrix and QuantileDMatrix (iterator-based construction)
+        .allowlist_function("XGProxy.*")
+        .allowlist_function("XGQuantile.*")
+        // Collective communicator and tracker for distributed training
+        .allowlist_function("XGCommunicator.*")
+        .allowlist_function("XGTracker.*")
         // Allowlist the main types we need
         .allowlist_type("BoosterHandle")
         .allowlist_type("DMatrixHandle")
@@ -463,6 +697,120 @@ fn main() {
     bindings
         .write_to_file(out_dir.join("bindings.rs"))
         .expect("Couldn't write bindings.");
+}
+
+fn check_cuda_version(version: &str) {
+    // The CUDA array interface entry points take JSON configs from 1.6.0 on
+    if env::var("CARGO_FEATURE_CUDA").is_ok() {
+        let (major, minor, _patch) = parse_version(version);
+        if major < 1 || (major == 1 && minor < 6) {
+            panic!(
+                "The `cuda` feature requires XGBoost >= 1.6, but the XGBoost version is {}",
+                version
+            );
+        }
+    }
+}
+
+fn main() {
+    // Tell cargo about custom cfg flags we emit
+    println!("cargo:rustc-check-cfg=cfg(xgboost_thread_safe)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_predict_config)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_boosted_rounds)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_feature_score)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_save_to_buffer)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_build_info)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_device_param)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_quantile_dmatrix)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_array_interface)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_get_data)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_collective)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_tracker)");
+
+    // Without the native booster there is nothing to link: the pure-Rust
+    // predictor needs neither headers nor the compiled library
+    if env::var("CARGO_FEATURE_NATIVE").is_err() {
+        return;
+    }
+
+    // There is no libxgboost for WebAssembly, only the pure-Rust predictor works there
+    let target = env::var("TARGET").unwrap();
+    if target.starts_with("wasm32") {
+        panic!(
+            "Target {} cannot link against libxgboost. Disable default features and enable `pure` to build for WebAssembly",
+            target
+        );
+    }
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let xgb_include_root = out_dir.join("include");
+
+    println!("cargo:rerun-if-env-changed=XGBOOST_VERSION");
+    println!("cargo:rerun-if-env-changed=XGBOOST_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=XGBOOST_WHEEL_SHA256");
+
+    // Link an installed libxgboost in place instead of downloading the wheel
+    println!("cargo:rerun-if-env-changed=XGBOOST_SYSTEM");
+    println!("cargo:rerun-if-env-changed=XGBOOST_INCLUDE_DIR");
+    if env::var("CARGO_FEATURE_SYSTEM").is_ok() || env::var("XGBOOST_SYSTEM").is_ok() {
+        let system = find_system_library().unwrap_or_else(|e| {
+            eprintln!("Failed to find a system XGBoost library: {}", e);
+            panic!("Cannot proceed without compiled library");
+        });
+
+        // The installed library decides which API is available: take its
+        // version from pkg-config or its headers, and only then from
+        // XGBOOST_VERSION, since the default would not match it
+        let version = system
+            .version
+            .or_else(|| system.include_dir.as_deref().and_then(header_version))
+            .or_else(|| env::var("XGBOOST_VERSION").ok())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Cannot tell the version of the system XGBoost library: \
+                     no pkg-config version and no installed headers. \
+                     Set XGBOOST_VERSION to the installed version"
+                )
+            });
+        emit_version_cfg_flags(&version);
+        check_cuda_version(&version);
+
+        let include_root = match system.include_dir {
+            Some(dir) => dir,
+            None => {
+                if let Err(e) = download_xgboost_headers(&out_dir, &version) {
+                    eprintln!("Failed to download XGBoost headers: {}", e);
+                    panic!("Cannot proceed without headers; set XGBOOST_INCLUDE_DIR");
+                }
+                xgb_include_root
+            }
+        };
+        generate_bindings(&include_root, &out_dir);
+        return;
+    }
+
+    // Get version and emit cfg flags for version-dependent features
+    let version = get_xgboost_version();
+    emit_version_cfg_flags(&version);
+    check_cuda_version(&version);
+
+    // Download the headers
+    if let Err(e) = download_xgboost_headers(&out_dir, &version) {
+        eprintln!("Failed to download XGBoost headers: {}", e);
+        panic!("Cannot proceed without headers");
+    }
+
+    // Use a prebuilt library (e.g. a source build with CUDA) or download and extract the wheel
+    let library = match env::var("XGBOOST_LIB_DIR") {
+        Ok(dir) => copy_prebuilt_library(&out_dir, Path::new(&dir)),
+        Err(_) => download_and_extract_wheel(&out_dir),
+    };
+    if let Err(e) = library {
+        eprintln!("Failed to obtain the XGBoost library: {}", e);
+        panic!("Cannot proceed without compiled library");
+    }
+
+    generate_bindings(&xgb_include_root, &out_dir);
 
     // Get platform info
     let (os, _arch) = get_platform_info();
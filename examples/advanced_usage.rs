@@ -1,4 +1,4 @@
-use xgboost_rust::{Booster, XGBoostResult};
+use xgboost_rust::{Booster, PredictOptions, XGBoostResult};
 
 fn main() -> XGBoostResult<()> {
     println!("XGBoost Rust Bindings - Advanced Usage Example");
@@ -24,14 +24,12 @@ fn main() -> XGBoostResult<()> {
     println!("=== Example 1: Normal Prediction ===");
     let data = vec![5.1, 3.5, 1.4, 0.2, 6.7, 3.0, 5.2, 2.3];
 
-    let predictions = booster.predict(&data, 2, 4, 0, false)?;
+    let predictions = booster.predict(&data, 2, 4, PredictOptions::empty(), false)?;
     println!("Normal predictions (probabilities): {:?}\n", predictions);
 
     // Example: Get SHAP values (feature contributions)
     println!("=== Example 2: Feature Contributions (SHAP) ===");
-    use xgboost_rust::predict_option;
-
-    let shap_values = booster.predict(&data, 2, 4, predict_option::PRED_CONTRIBS, false)?;
+    let shap_values = booster.predict(&data, 2, 4, PredictOptions::PRED_CONTRIBS, false)?;
 
     println!("SHAP values for first sample:");
     // SHAP values include one extra value for bias term
@@ -64,7 +62,7 @@ fn main() -> XGBoostResult<()> {
 
     // Make a prediction with the buffer-loaded model
     let test_data = vec![5.1, 3.5, 1.4, 0.2];
-    let pred = booster_from_buffer.predict(&test_data, 1, 4, 0, false)?;
+    let pred = booster_from_buffer.predict(&test_data, 1, 4, PredictOptions::empty(), false)?;
     println!("Prediction from buffer-loaded model: {:?}\n", pred);
 
     println!("Advanced examples completed successfully!");
@@ -0,0 +1,70 @@
+//! A scoring service: `POST /predict` with a JSON array of rows, e.g.
+//! `[[5.1, 3.5, 1.4, 0.2], [6.7, 3.0, 5.2, 2.3]]`, answers with the
+//! predictions of all rows in one array.
+//!
+//! Requests from concurrent connections are batched by a `BatchPredictor`,
+//! which the handler calls as a `tower::Service`, so no handler blocks the
+//! runtime while its rows wait for a batch.
+//!
+//! Run with `cargo run -p axum-server -- model.json`. It is a separate crate
+//! so that axum and tokio are not dependencies of xgboost-rust itself.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+use xgboost_rust::serve::{BatchPredictor, PredictRequest};
+use xgboost_rust::Booster;
+
+async fn predict(
+    State(predictor): State<Arc<BatchPredictor>>,
+    Json(rows): Json<Vec<Vec<f32>>>,
+) -> Result<Json<Vec<f32>>, (StatusCode, String)> {
+    let num_features = predictor.num_features();
+    if let Some(row) = rows.iter().find(|row| row.len() != num_features) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "expected {} features per row, got {}",
+                num_features,
+                row.len()
+            ),
+        ));
+    }
+    let request = PredictRequest {
+        num_rows: rows.len(),
+        data: rows.concat(),
+    };
+    // The queue is bounded: once it is full, shed load instead of waiting
+    let predictions = predictor
+        .oneshot(request)
+        .await
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.description))?;
+    Ok(Json(predictions))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "model.json".to_string());
+    let booster = Arc::new(Booster::load(&path)?);
+    let predictor = Arc::new(
+        BatchPredictor::builder(booster)
+            .max_batch_rows(256)
+            .max_delay(Duration::from_millis(2))
+            .max_queued_rows(10_000)
+            .build()?,
+    );
+
+    let app = Router::new()
+        .route("/predict", post(predict))
+        .with_state(predictor);
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+    println!("listening on http://0.0.0.0:3000/predict");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
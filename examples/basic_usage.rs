@@ -1,4 +1,4 @@
-use xgboost_rust::{Booster, XGBoostResult};
+use xgboost_rust::{Booster, PredictOptions, XGBoostResult};
 
 fn main() -> XGBoostResult<()> {
     println!("XGBoost Rust Bindings - Basic Usage Example");
@@ -65,7 +65,13 @@ fn main() -> XGBoostResult<()> {
     println!("Making predictions on {} samples...", num_rows);
 
     // Make predictions
-    let predictions = booster.predict(&data, num_rows, num_features, 0, false)?;
+    let predictions = booster.predict(
+        &data,
+        num_rows,
+        num_features,
+        PredictOptions::empty(),
+        false,
+    )?;
 
     println!("✓ Predictions complete\n");
 
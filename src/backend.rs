@@ -0,0 +1,680 @@
+//! What the native and the pure-Rust booster have in common.
+//!
+//! Both implement [`Backend`], the core the batch, Arrow and validation
+//! helpers are written against, and expand [`shared_methods!`] into their
+//! `impl` block for the public methods built only on that core and on
+//! `load_from_buffer`, `save` and `learner`.
+
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::predict_options::PredictOptions;
+
+/// A loaded model that can predict dense rows
+///
+/// Public only so that it can bound [`PredictBatches`](crate::PredictBatches);
+/// it is not reachable from outside the crate.
+pub trait Backend {
+    /// Inference on row-major data, as the booster's `predict`
+    fn predict_dense(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>>;
+
+    /// Number of features the model expects
+    fn num_features(&self) -> XGBoostResult<usize>;
+
+    /// Feature names stored in the model, empty if it has none or the
+    /// library cannot report them
+    fn model_feature_names(&self) -> XGBoostResult<Vec<String>>;
+
+    /// A model attribute
+    fn attr(&self, key: &str) -> XGBoostResult<Option<String>>;
+}
+
+/// Check that a dense buffer holds exactly `num_rows × num_features` values
+pub(crate) fn check_dense_len(
+    len: usize,
+    num_rows: usize,
+    num_features: usize,
+) -> XGBoostResult<()> {
+    let expected_len = num_rows
+        .checked_mul(num_features)
+        .ok_or_else(|| XGBoostError {
+            description: format!(
+                "Integer overflow: num_rows ({}) * num_features ({}) exceeds usize::MAX",
+                num_rows, num_features
+            ),
+        })?;
+
+    if len != expected_len {
+        return Err(XGBoostError {
+            description: format!(
+                "Data length mismatch: expected {} elements ({}×{}), got {}",
+                expected_len, num_rows, num_features, len
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// `path` without an `.enc` extension
+#[cfg(all(feature = "encryption", any(feature = "pure", xgboost_save_to_buffer)))]
+pub(crate) fn encrypted_path(path: &std::path::Path) -> std::path::PathBuf {
+    match path.extension() {
+        Some(ext) if ext.eq_ignore_ascii_case("enc") => path.with_extension(""),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Methods of both boosters that only depend on the rest of their API
+///
+/// `feature_names` and `save_to_buffer` are the `cfg` predicates under
+/// which the booster can read feature names and serialize itself, e.g.
+/// `all()` for one that always can.
+macro_rules! shared_methods {
+    (feature_names: $feature_names:meta, save_to_buffer: $save_to_buffer:meta) => {
+        /// Load a model from a URL
+        ///
+        /// Supports local paths, `file://`, `http(s)://`, `s3://bucket/key`
+        /// (signed with the `AWS_*` credentials in the environment),
+        /// `gs://bucket/object` and `az://account/container/blob`. See the
+        /// `object_store` feature in the README for authentication details.
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::Booster;
+        ///
+        /// let booster = Booster::load_from_url("s3://models/churn/v7.ubj").unwrap();
+        /// ```
+        #[cfg(feature = "object_store")]
+        pub fn load_from_url(url: &str) -> $crate::XGBoostResult<Self> {
+            Self::load_from_buffer(&$crate::object_store::fetch(url)?)
+        }
+
+        /// Load a model from a URL, downloading it into `cache_dir` on first
+        /// use and reading the cached copy afterwards
+        ///
+        /// The cache is keyed by URL and never revalidated, so publish new
+        /// models under new URLs (e.g. with a version in the key).
+        #[cfg(feature = "object_store")]
+        pub fn load_from_url_cached<P: AsRef<::std::path::Path>>(
+            url: &str,
+            cache_dir: P,
+        ) -> $crate::XGBoostResult<Self> {
+            Self::load_from_buffer(&$crate::object_store::fetch_cached(
+                url,
+                cache_dir.as_ref(),
+            )?)
+        }
+
+        /// Load a model encrypted with
+        /// [`encryption::encrypt`](crate::encryption::encrypt), decrypting it
+        /// in memory with the key from `key_provider`
+        ///
+        /// The decrypted model may be compressed. See the `encryption` module
+        /// for an example.
+        #[cfg(feature = "encryption")]
+        pub fn load_encrypted<P, K>(path: P, key_provider: &K) -> $crate::XGBoostResult<Self>
+        where
+            P: AsRef<::std::path::Path>,
+            K: $crate::encryption::KeyProvider + ?Sized,
+        {
+            let encrypted = ::std::fs::read(path.as_ref()).map_err(|e| $crate::XGBoostError {
+                description: format!(
+                    "Failed to read model file {}: {}",
+                    path.as_ref().display(),
+                    e
+                ),
+            })?;
+            let key = key_provider.key(path.as_ref())?;
+            Self::load_from_buffer(&$crate::encryption::decrypt(&encrypted, &key)?)
+        }
+
+        /// Load a model after checking it against its `.sha256` checksum file
+        ///
+        /// Fails with [`IntegrityError::Missing`](crate::IntegrityError::Missing)
+        /// without a checksum file and
+        /// [`IntegrityError::ChecksumMismatch`](crate::IntegrityError::ChecksumMismatch)
+        /// if the model was corrupted or truncated after it was saved.
+        ///
+        /// The verified bytes are loaded with `load_from_buffer`, so the
+        /// deprecated text format is not supported.
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::{Booster, IntegrityError};
+        ///
+        /// match Booster::load_verified("model.json") {
+        ///     Ok(booster) => { /* serve */ }
+        ///     Err(IntegrityError::ChecksumMismatch { path, .. }) => {
+        ///         panic!("{} is corrupt, keeping the previous model", path.display())
+        ///     }
+        ///     Err(e) => panic!("{}", e),
+        /// }
+        /// ```
+        #[cfg(feature = "integrity")]
+        pub fn load_verified<P: AsRef<::std::path::Path>>(
+            path: P,
+        ) -> Result<Self, $crate::IntegrityError> {
+            let model = $crate::integrity::verify_checksum(path.as_ref())?;
+            Ok(Self::load_from_buffer(&model)?)
+        }
+
+        /// Load a model after checking its checksum and its `.sig` signature
+        /// made by [`save_signed`](Self::save_signed) with the same key
+        ///
+        /// Fails with
+        /// [`IntegrityError::InvalidSignature`](crate::IntegrityError::InvalidSignature)
+        /// for a model that was not signed with `key`.
+        #[cfg(feature = "integrity")]
+        pub fn load_signed<P: AsRef<::std::path::Path>>(
+            path: P,
+            key: &[u8],
+        ) -> Result<Self, $crate::IntegrityError> {
+            let model = $crate::integrity::verify_signature(path.as_ref(), key)?;
+            Ok(Self::load_from_buffer(&model)?)
+        }
+
+        /// Predict untransformed margins (raw scores)
+        ///
+        /// The margin is the sum of the tree outputs and the base score before
+        /// the objective's transformation: log-odds for `binary:logistic`, one
+        /// score per class before the softmax for `multi:softprob`, the log of
+        /// the prediction for `count:poisson`. Same as [`predict`](Self::predict)
+        /// with [`PredictOptions::OUTPUT_MARGIN`](crate::PredictOptions::OUTPUT_MARGIN).
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::Booster;
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+        /// let log_odds = booster.predict_margin(&data, 2, 2).unwrap();
+        /// ```
+        pub fn predict_margin(
+            &self,
+            data: &[f32],
+            num_rows: usize,
+            num_features: usize,
+        ) -> $crate::XGBoostResult<Vec<f32>> {
+            self.predict(
+                data,
+                num_rows,
+                num_features,
+                $crate::PredictOptions::OUTPUT_MARGIN,
+                false,
+            )
+        }
+
+        /// Predict a stream of row-major batches, one batch at a time
+        ///
+        /// Each item of `batches` holds whole rows of the model's number of
+        /// features, row-major or, with the `arrow` feature, as an Arrow
+        /// `RecordBatch` (see [`RowBatch`](crate::RowBatch)). Batches are
+        /// pulled from the source only as the returned iterator is advanced,
+        /// so a file or network stream of any length is scored with the
+        /// memory of a single batch.
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::{Booster, PredictOptions};
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// let data = vec![0.5; 100_000 * 2]; // 100k rows, 2 features
+        /// for predictions in booster.predict_batches(data.chunks(2 * 8192), PredictOptions::empty()).unwrap() {
+        ///     let predictions = predictions.unwrap(); // up to 8192 rows
+        /// }
+        /// ```
+        pub fn predict_batches<I>(
+            &self,
+            batches: I,
+            options: $crate::PredictOptions,
+        ) -> $crate::XGBoostResult<$crate::PredictBatches<'_, I::IntoIter, Self>>
+        where
+            I: IntoIterator,
+            I::Item: $crate::RowBatch,
+        {
+            $crate::PredictBatches::new(self, batches.into_iter(), options)
+        }
+
+        /// Predict a stream of Arrow record batches into a Parquet file
+        ///
+        /// Every batch is predicted and written before the next one is pulled,
+        /// so backfills of any size run with the memory of one batch. Each
+        /// input batch becomes one row group holding its `key_columns`
+        /// (copied unchanged) and a `prediction` column (`prediction_0`,
+        /// `prediction_1`, … for multi-output models). Read the input with a
+        /// batch size equal to its row group size to keep the row groups of
+        /// input and output aligned. Returns the number of rows written.
+        ///
+        /// # Arguments
+        /// * `batches` - Record batches with the model's features (see
+        ///   [`RowBatch`](crate::RowBatch)) and the key columns
+        /// * `key_columns` - Columns copied into the output, e.g. IDs
+        /// * `path` - Parquet file to create
+        /// * `options` - Prediction options
+        ///
+        /// # Example
+        /// ```no_run
+        /// use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+        /// use xgboost_rust::{Booster, PredictOptions};
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// let input = std::fs::File::open("customers.parquet").unwrap();
+        /// let reader = ParquetRecordBatchReaderBuilder::try_new(input)
+        ///     .unwrap()
+        ///     .with_batch_size(65_536)
+        ///     .build()
+        ///     .unwrap();
+        /// let batches = reader.map(|batch| batch.unwrap());
+        /// let rows = booster
+        ///     .sink_parquet(batches, &["customer_id"], "scores.parquet", PredictOptions::empty())
+        ///     .unwrap();
+        /// ```
+        #[cfg(feature = "parquet")]
+        pub fn sink_parquet<I, S, P>(
+            &self,
+            batches: I,
+            key_columns: &[S],
+            path: P,
+            options: $crate::PredictOptions,
+        ) -> $crate::XGBoostResult<usize>
+        where
+            I: IntoIterator<Item = ::arrow::array::RecordBatch>,
+            S: AsRef<str>,
+            P: AsRef<::std::path::Path>,
+        {
+            $crate::record_batches::sink_parquet(self, batches, key_columns, path.as_ref(), options)
+        }
+
+        /// Make predictions on rows described by a struct
+        ///
+        /// The rows' features
+        /// ([`XgbFeatures::FEATURE_NAMES`](crate::XgbFeatures::FEATURE_NAMES))
+        /// must match the model's feature count and, in order, the model's
+        /// feature names, when it has them (read with XGBoost ≥ 1.5 by the
+        /// native booster). Derive [`XgbFeatures`](crate::XgbFeatures) with
+        /// the `derive` feature.
+        ///
+        /// # Arguments
+        /// * `rows` - Rows to score
+        /// * `options` - Prediction options
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::{Booster, PredictOptions, XgbFeatures};
+        ///
+        /// struct Point {
+        ///     x: f32,
+        ///     y: f32,
+        /// }
+        ///
+        /// impl XgbFeatures for Point {
+        ///     const FEATURE_NAMES: &'static [&'static str] = &["x", "y"];
+        ///
+        ///     fn write_features(&self, out: &mut Vec<f32>) {
+        ///         out.extend([self.x, self.y]);
+        ///     }
+        /// }
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// let rows = [Point { x: 1.0, y: 2.0 }, Point { x: 3.0, y: 4.0 }];
+        /// let predictions = booster.predict_structs(&rows, PredictOptions::empty()).unwrap();
+        /// ```
+        pub fn predict_structs<T: $crate::XgbFeatures>(
+            &self,
+            rows: &[T],
+            options: $crate::PredictOptions,
+        ) -> $crate::XGBoostResult<Vec<f32>> {
+            let num_features = self.num_features()?;
+            let model_names = $crate::backend::Backend::model_feature_names(self)?;
+            $crate::batches::predict_chunked(rows.len(), self.max_chunk_rows, |chunk| {
+                let data =
+                    $crate::features::to_dense(&rows[chunk.clone()], num_features, &model_names)?;
+                self.predict(&data, chunk.len(), num_features, options, false)
+            })
+        }
+
+        /// Make a prediction on one row given as feature values by name
+        ///
+        /// The values are put in the order of the model's feature names
+        /// ([`feature_names`](Self::feature_names)); features without a value
+        /// are missing. Names the model doesn't have are an error, as is a
+        /// model without feature names. The native booster needs XGBoost ≥ 1.5.
+        ///
+        /// # Example
+        /// ```no_run
+        /// use std::collections::HashMap;
+        /// use xgboost_rust::{Booster, PredictOptions};
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// let features = HashMap::from([("age", 41.0), ("spend", 89.5)]);
+        /// let prediction = booster.predict_named(&features, PredictOptions::empty()).unwrap();
+        /// ```
+        #[cfg($feature_names)]
+        pub fn predict_named<K>(
+            &self,
+            features: &::std::collections::HashMap<K, f32>,
+            options: $crate::PredictOptions,
+        ) -> $crate::XGBoostResult<Vec<f32>>
+        where
+            K: ::std::borrow::Borrow<str> + ::std::hash::Hash + Eq,
+        {
+            let model_names = self.feature_names()?;
+            let row = $crate::features::named_row(features, &model_names)?;
+            self.predict(&row, 1, model_names.len(), options, false)
+        }
+
+        /// Make predictions on JSON records, e.g. the body of a scoring request
+        ///
+        /// `records` is a JSON array of objects, one per row, or a single
+        /// object. The fields named in `feature_order` become the features, in
+        /// that order; absent and `null` fields are missing values, booleans
+        /// are 0 or 1, numeric strings are parsed and other fields are ignored.
+        ///
+        /// # Arguments
+        /// * `records` - JSON text
+        /// * `feature_order` - Field name of each model feature
+        /// * `options` - Prediction options
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::{Booster, PredictOptions};
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// let body = r#"[{"age": 41, "spend": 89.5}, {"age": 23, "spend": null, "id": "c-2"}]"#;
+        /// let predictions = booster
+        ///     .predict_json(body.as_bytes(), &["age", "spend"], PredictOptions::empty())
+        ///     .unwrap();
+        /// ```
+        pub fn predict_json<S: AsRef<str>>(
+            &self,
+            records: &[u8],
+            feature_order: &[S],
+            options: $crate::PredictOptions,
+        ) -> $crate::XGBoostResult<Vec<f32>> {
+            let num_features = self.num_features()?;
+            let (data, num_rows) = $crate::records::to_dense(records, feature_order, num_features)?;
+            self.predict(&data, num_rows, num_features, options, false)
+        }
+
+        /// Make predictions on records already parsed with `serde_json`
+        ///
+        /// Same as [`predict_json`](Self::predict_json), for services whose
+        /// framework hands over the parsed body, e.g. `axum::Json<Vec<Value>>`.
+        ///
+        /// # Arguments
+        /// * `records` - One JSON object per row
+        /// * `feature_order` - Field name of each model feature
+        /// * `options` - Prediction options
+        ///
+        /// # Example
+        /// ```no_run
+        /// use serde_json::json;
+        /// use xgboost_rust::{Booster, PredictOptions};
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// let records = [json!({"age": 41, "spend": 89.5}), json!({"age": 23, "spend": null})];
+        /// let predictions = booster
+        ///     .predict_json_values(&records, &["age", "spend"], PredictOptions::empty())
+        ///     .unwrap();
+        /// ```
+        #[cfg(feature = "serde_json")]
+        pub fn predict_json_values<S: AsRef<str>>(
+            &self,
+            records: &[::serde_json::Value],
+            feature_order: &[S],
+            options: $crate::PredictOptions,
+        ) -> $crate::XGBoostResult<Vec<f32>> {
+            let num_features = self.num_features()?;
+            let data = $crate::records::values_to_dense(records, feature_order, num_features)?;
+            self.predict(&data, records.len(), num_features, options, false)
+        }
+
+        /// Check a batch against the feature statistics stored in the model
+        ///
+        /// Reports features with values outside the training range or missing
+        /// values where training had none; see
+        /// [`FeatureStats`](crate::FeatureStats). Fails if the model has no
+        /// statistics. To validate many batches, restore the statistics once
+        /// with [`FeatureStats::from_booster`](crate::FeatureStats::from_booster)
+        /// instead.
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::Booster;
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// let batch = vec![0.5, f32::NAN, 120.0, 3.0]; // 2 rows, 2 features
+        /// let report = booster.validate_inputs(&batch, 2, 2).unwrap();
+        /// if !report.is_ok() {
+        ///     eprintln!("input drift: {:?}", report.features());
+        /// }
+        /// ```
+        pub fn validate_inputs(
+            &self,
+            data: &[f32],
+            num_rows: usize,
+            num_features: usize,
+        ) -> $crate::XGBoostResult<$crate::InputReport> {
+            let stats =
+                $crate::FeatureStats::from_model(self)?.ok_or_else(|| $crate::XGBoostError {
+                    description: "The model has no feature statistics".to_string(),
+                })?;
+            stats.validate(data, num_rows, num_features)
+        }
+
+        /// Run throwaway predictions on sample rows before serving traffic
+        ///
+        /// The first predictions of a loaded model are slow: libxgboost
+        /// configures the learner, starts its thread pool and allocates
+        /// prediction buffers on first use, and the trees are not yet in the
+        /// CPU caches. Calling this after loading moves that cost out of the
+        /// first requests. Use rows shaped like real requests (the same batch
+        /// size gives the most representative buffers).
+        ///
+        /// # Arguments
+        /// * `sample` - Sample rows (row-major, num_rows x the model's features)
+        /// * `num_rows` - Number of sample rows
+        /// * `iterations` - Number of predictions to run
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::Booster;
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// let sample = vec![0.5, 1.0]; // 1 row, 2 features
+        /// booster.warmup(&sample, 1, 10).unwrap();
+        /// ```
+        pub fn warmup(
+            &self,
+            sample: &[f32],
+            num_rows: usize,
+            iterations: usize,
+        ) -> $crate::XGBoostResult<()> {
+            let num_features = self.num_features()?;
+            for _ in 0..iterations {
+                self.predict(
+                    sample,
+                    num_rows,
+                    num_features,
+                    $crate::PredictOptions::empty(),
+                    false,
+                )?;
+            }
+            Ok(())
+        }
+
+        /// The chunk size set by [`set_max_chunk_rows`](Self::set_max_chunk_rows)
+        pub fn max_chunk_rows(&self) -> Option<usize> {
+            self.max_chunk_rows
+        }
+
+        /// Save the model with a `.sha256` checksum file next to it
+        ///
+        /// The checksum, in `sha256sum` format, covers the file as written
+        /// (compressed or not); [`load`](Self::load) and
+        /// [`load_verified`](Self::load_verified) check it. Upload both files,
+        /// the checksum last.
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::Booster;
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// booster.save_with_checksum("model.ubj").unwrap(); // also writes model.ubj.sha256
+        /// ```
+        #[cfg(feature = "integrity")]
+        pub fn save_with_checksum<P: AsRef<::std::path::Path>>(
+            &self,
+            path: P,
+        ) -> $crate::XGBoostResult<()> {
+            self.save(path.as_ref())?;
+            $crate::integrity::write_checksum(path.as_ref()).map(|_| ())
+        }
+
+        /// Save the model with a checksum file and a `.sig` file holding its
+        /// HMAC-SHA256 under `key`
+        ///
+        /// The signature is symmetric: whoever verifies it with
+        /// [`load_signed`](Self::load_signed) needs the same secret key.
+        #[cfg(feature = "integrity")]
+        pub fn save_signed<P: AsRef<::std::path::Path>>(
+            &self,
+            path: P,
+            key: &[u8],
+        ) -> $crate::XGBoostResult<()> {
+            self.save(path.as_ref())?;
+            $crate::integrity::write_signature(path.as_ref(), key)
+        }
+
+        /// Export a tree model to ONNX
+        ///
+        /// Produces a `TreeEnsembleClassifier` for `binary:logistic` and `multi:*`
+        /// objectives and a `TreeEnsembleRegressor` otherwise, so the model can be
+        /// served by ONNX Runtime. The graph takes a float tensor named `input` of
+        /// shape `[N, num_features]`. The native booster needs XGBoost ≥ 1.6.
+        ///
+        /// Linear boosters, categorical splits and `binary:hinge` are not supported.
+        ///
+        /// # Arguments
+        /// * `path` - Path where to write the `.onnx` file
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::Booster;
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// booster.export_onnx("model.onnx").unwrap();
+        /// ```
+        #[cfg($save_to_buffer)]
+        pub fn export_onnx<P: AsRef<::std::path::Path>>(
+            &self,
+            path: P,
+        ) -> $crate::XGBoostResult<()> {
+            let model = $crate::onnx::to_onnx(&self.learner()?)?;
+            ::std::fs::write(path.as_ref(), model).map_err(|e| $crate::XGBoostError {
+                description: format!(
+                    "Failed to write ONNX model {}: {}",
+                    path.as_ref().display(),
+                    e
+                ),
+            })
+        }
+
+        /// Export a tree model to PMML 4.4
+        ///
+        /// The document is a `MiningModel` chaining one sum-of-trees segment per
+        /// output group into a `RegressionModel` that applies the base score and
+        /// link function, for scoring engines that only accept PMML. Missing values
+        /// follow the learned default direction. The native booster needs
+        /// XGBoost ≥ 1.6.
+        ///
+        /// Linear boosters, categorical splits and `binary:hinge` are not supported.
+        ///
+        /// # Arguments
+        /// * `path` - Path where to write the `.pmml` file
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::Booster;
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// booster.export_pmml("model.pmml").unwrap();
+        /// ```
+        #[cfg($save_to_buffer)]
+        pub fn export_pmml<P: AsRef<::std::path::Path>>(
+            &self,
+            path: P,
+        ) -> $crate::XGBoostResult<()> {
+            let document = $crate::pmml::to_pmml(&self.learner()?)?;
+            ::std::fs::write(path.as_ref(), document).map_err(|e| $crate::XGBoostError {
+                description: format!(
+                    "Failed to write PMML model {}: {}",
+                    path.as_ref().display(),
+                    e
+                ),
+            })
+        }
+
+        /// Translate a tree model into a SQL expression that scores one row
+        ///
+        /// Each tree becomes a nested `CASE` over the feature columns, named by
+        /// the model's feature names (`f0`, `f1`, … without them) and qualified
+        /// by `table_alias`. The trees are summed with the base score and the
+        /// objective's link function is applied, e.g. a sigmoid for
+        /// `binary:logistic`, so simple models can be batch-scored inside a data
+        /// warehouse. The native booster needs XGBoost ≥ 1.6.
+        ///
+        /// Missing values must be `NULL`; they follow the learned default
+        /// direction. The database compares in its own column precision, so
+        /// rows within float rounding of a threshold, and sums over many trees,
+        /// can differ slightly from [`predict`](Self::predict). All trees are
+        /// used, whatever the best iteration.
+        ///
+        /// Linear boosters and models with several outputs (`multi:*`,
+        /// multi-target) are not supported.
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::{Booster, SqlDialect};
+        ///
+        /// let booster = Booster::load("model.json").unwrap();
+        /// let score = booster.to_sql(SqlDialect::Ansi, Some("t")).unwrap();
+        /// let query = format!("SELECT t.id, {} AS score FROM features AS t", score);
+        /// ```
+        #[cfg($save_to_buffer)]
+        pub fn to_sql(
+            &self,
+            dialect: $crate::SqlDialect,
+            table_alias: Option<&str>,
+        ) -> $crate::XGBoostResult<String> {
+            $crate::sql::to_sql(&self.learner()?, dialect, table_alias)
+        }
+
+        /// Per-class weights and bias of a `gblinear` model
+        ///
+        /// Lets linear models be audited or reimplemented in a simpler scorer
+        /// (e.g. SQL). The native booster needs XGBoost ≥ 1.6.
+        ///
+        /// # Example
+        /// ```no_run
+        /// use xgboost_rust::Booster;
+        ///
+        /// let booster = Booster::load("linear.json").unwrap();
+        /// let coefficients = booster.linear_coefficients().unwrap();
+        /// for (feature, weight) in coefficients.weights[0].iter().enumerate() {
+        ///     println!("f{}: {}", feature, weight);
+        /// }
+        /// println!("bias: {}", coefficients.bias[0]);
+        /// ```
+        #[cfg($save_to_buffer)]
+        pub fn linear_coefficients(&self) -> $crate::XGBoostResult<$crate::LinearCoefficients> {
+            $crate::LinearCoefficients::from_learner(&self.learner()?)
+        }
+    };
+}
+
+pub(crate) use shared_methods;
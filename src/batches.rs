@@ -0,0 +1,150 @@
+use crate::backend::Backend;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::predict_options::PredictOptions;
+use crate::Booster;
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// A batch of rows for [`Booster::predict_batches`].
+///
+/// Implemented for row-major `f32` data (`&[f32]`, `Vec<f32>`, arrays and
+/// boxed slices) and, with the `arrow` feature, for Arrow `RecordBatch`es,
+/// whose columns are matched to the model's feature names, or taken in
+/// order when the model has none.
+pub trait RowBatch: sealed::Sealed {}
+
+impl<T: sealed::Sealed> RowBatch for T {}
+
+pub(crate) mod sealed {
+    use crate::error::XGBoostResult;
+    use std::borrow::Cow;
+
+    /// What a batch is laid out against
+    pub struct Layout {
+        pub num_features: usize,
+        #[cfg_attr(not(feature = "arrow"), allow(dead_code))]
+        pub feature_names: Vec<String>,
+    }
+
+    pub trait Sealed {
+        /// The batch as row-major values
+        fn rows(&self, layout: &Layout) -> XGBoostResult<Cow<'_, [f32]>>;
+    }
+}
+
+macro_rules! row_major_batch {
+    ($($ty:ty),*) => {
+        $(impl sealed::Sealed for $ty {
+            fn rows(&self, _layout: &sealed::Layout) -> XGBoostResult<Cow<'_, [f32]>> {
+                Ok(Cow::Borrowed(&self[..]))
+            }
+        })*
+    };
+}
+
+row_major_batch!(&[f32], &mut [f32], Vec<f32>, &Vec<f32>, Box<[f32]>);
+
+impl<const N: usize> sealed::Sealed for [f32; N] {
+    fn rows(&self, _layout: &sealed::Layout) -> XGBoostResult<Cow<'_, [f32]>> {
+        Ok(Cow::Borrowed(self))
+    }
+}
+
+impl<const N: usize> sealed::Sealed for &[f32; N] {
+    fn rows(&self, _layout: &sealed::Layout) -> XGBoostResult<Cow<'_, [f32]>> {
+        Ok(Cow::Borrowed(*self))
+    }
+}
+
+/// Predictions of a stream of row batches, from
+/// [`Booster::predict_batches`].
+///
+/// Each batch is predicted when the iterator is advanced, so only one
+/// batch and its predictions are held at a time, however long the stream.
+pub struct PredictBatches<'a, I, B = Booster> {
+    booster: &'a B,
+    batches: I,
+    layout: sealed::Layout,
+    options: PredictOptions,
+}
+
+impl<'a, I, B: Backend> PredictBatches<'a, I, B> {
+    pub(crate) fn new(booster: &'a B, batches: I, options: PredictOptions) -> XGBoostResult<Self> {
+        #[cfg(feature = "arrow")]
+        let feature_names = booster.model_feature_names()?;
+        #[cfg(not(feature = "arrow"))]
+        let feature_names = Vec::new();
+        Ok(PredictBatches {
+            booster,
+            batches,
+            layout: sealed::Layout {
+                num_features: booster.num_features()?,
+                feature_names,
+            },
+            options,
+        })
+    }
+}
+
+impl<I, R, B> Iterator for PredictBatches<'_, I, B>
+where
+    I: Iterator<Item = R>,
+    R: RowBatch,
+    B: Backend,
+{
+    type Item = XGBoostResult<Vec<f32>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.batches.next()?;
+        let data = match batch.rows(&self.layout) {
+            Ok(data) => data,
+            Err(error) => return Some(Err(error)),
+        };
+        let num_features = self.layout.num_features;
+        if num_features == 0 || data.len() % num_features != 0 {
+            return Some(Err(XGBoostError {
+                description: format!(
+                    "Batch of {} values is not a whole number of rows of {} features",
+                    data.len(),
+                    num_features
+                ),
+            }));
+        }
+        let num_rows = data.len() / num_features;
+        if num_rows == 0 {
+            return Some(Ok(Vec::new()));
+        }
+        Some(
+            self.booster
+                .predict_dense(&data, num_rows, num_features, self.options),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.batches.size_hint()
+    }
+}
+
+/// Predictions for `num_rows` rows, made at most `max_chunk_rows` rows at a
+/// time by `predict` and concatenated in order
+pub(crate) fn predict_chunked(
+    num_rows: usize,
+    max_chunk_rows: Option<usize>,
+    mut predict: impl FnMut(Range<usize>) -> XGBoostResult<Vec<f32>>,
+) -> XGBoostResult<Vec<f32>> {
+    let chunk_rows = match max_chunk_rows {
+        Some(chunk_rows) if chunk_rows < num_rows => chunk_rows,
+        _ => return predict(0..num_rows),
+    };
+    let mut out = Vec::new();
+    for start in (0..num_rows).step_by(chunk_rows) {
+        let end = (start + chunk_rows).min(num_rows);
+        let predictions = predict(start..end)?;
+        if start == 0 {
+            // outputs per row are the same for every chunk
+            out.reserve_exact(predictions.len() / end * num_rows);
+        }
+        out.extend(predictions);
+    }
+    Ok(out)
+}
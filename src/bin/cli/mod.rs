@@ -0,0 +1,182 @@
+//! Argument parsing and data loading shared by the command-line tools.
+
+use std::path::Path;
+use std::process;
+use xgboost_rust::io::csv::CsvReader;
+use xgboost_rust::io::libsvm::LibsvmReader;
+use xgboost_rust::DMatrix;
+
+/// `--name value` options and `--flag` switches.
+pub struct Args {
+    options: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    /// Parse the process arguments; `flags` are the switches that take no
+    /// value. Prints `usage` and exits for `--help`.
+    pub fn parse(usage: &str, flags: &[&str]) -> Result<Self, String> {
+        let mut args = Args {
+            options: Vec::new(),
+            flags: Vec::new(),
+        };
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                return Err(format!("unexpected argument '{}'", arg));
+            };
+            if name == "help" {
+                print!("{}", usage);
+                process::exit(0);
+            }
+            if flags.contains(&name) {
+                args.flags.push(name.to_string());
+            } else {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("--{} needs a value", name))?;
+                args.options.push((name.to_string(), value));
+            }
+        }
+        Ok(args)
+    }
+
+    /// Value of a required option
+    pub fn required(&mut self, name: &str) -> Result<String, String> {
+        self.optional(name)
+            .ok_or_else(|| format!("missing --{}", name))
+    }
+
+    /// Value of an optional option; the last one wins if it is repeated
+    pub fn optional(&mut self, name: &str) -> Option<String> {
+        self.repeated(name).pop()
+    }
+
+    /// Every value of an option that may be given several times
+    pub fn repeated(&mut self, name: &str) -> Vec<String> {
+        let (matching, rest) = std::mem::take(&mut self.options)
+            .into_iter()
+            .partition(|(option, _)| option == name);
+        self.options = rest;
+        matching.into_iter().map(|(_, value)| value).collect()
+    }
+
+    /// Whether a switch was given
+    pub fn flag(&mut self, name: &str) -> bool {
+        let given = self.flags.iter().any(|flag| flag == name);
+        self.flags.retain(|flag| flag != name);
+        given
+    }
+
+    /// Fail on options that were not consumed
+    pub fn finish(self) -> Result<(), String> {
+        let mut names = self.options.iter().map(|(name, _)| name).chain(&self.flags);
+        match names.next() {
+            Some(name) => Err(format!("unknown option --{}", name)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// How to read input files.
+pub struct InputOptions {
+    /// CSV column with the labels
+    pub label_column: Option<String>,
+    /// CSV columns kept as row keys instead of features
+    pub key_columns: Vec<String>,
+    /// CSV files have no header row; columns are then named by index
+    pub no_header: bool,
+    /// CSV field separator
+    pub delimiter: char,
+    /// libsvm feature indices start at 1
+    pub one_based: bool,
+    /// Append a missing-value indicator column per CSV feature
+    pub missing_indicators: bool,
+}
+
+impl InputOptions {
+    /// Take the input options (`--label-column`, `--key-column`,
+    /// `--no-header`, `--delimiter`, `--one-based`, `--missing-indicators`)
+    /// from `args`
+    pub fn from_args(args: &mut Args) -> Result<Self, String> {
+        let delimiter = match args.optional("delimiter").as_deref() {
+            None => ',',
+            Some("\\t") | Some("tab") => '\t',
+            Some(d) if d.chars().count() == 1 => d.chars().next().unwrap(),
+            Some(d) => return Err(format!("invalid delimiter '{}'", d)),
+        };
+        Ok(InputOptions {
+            label_column: args.optional("label-column"),
+            key_columns: args.repeated("key-column"),
+            no_header: args.flag("no-header"),
+            delimiter,
+            one_based: args.flag("one-based"),
+            missing_indicators: args.flag("missing-indicators"),
+        })
+    }
+}
+
+/// Load a CSV or libsvm file (`.svm`, `.libsvm`) into a DMatrix with its
+/// labels, if any
+pub fn load_dmatrix(path: &str, options: &InputOptions) -> Result<DMatrix, String> {
+    let result = if is_csv(path) {
+        csv_reader(options)
+            .read_file(path)
+            .and_then(|data| data.to_dmatrix())
+    } else if extension(path) == "parquet" || extension(path) == "pq" {
+        return Err(format!(
+            "{}: Parquet input is not supported; convert it to CSV",
+            path
+        ));
+    } else {
+        LibsvmReader::new()
+            .one_based(options.one_based)
+            .read_file(path)
+            .and_then(|data| data.to_dmatrix())
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Whether a file is read as CSV: anything but libsvm (`.svm`, `.libsvm`)
+/// and Parquet (`.parquet`, `.pq`)
+pub fn is_csv(path: &str) -> bool {
+    !matches!(
+        extension(path).as_str(),
+        "svm" | "libsvm" | "parquet" | "pq"
+    )
+}
+
+/// A CSV reader configured by the input options
+pub fn csv_reader(options: &InputOptions) -> CsvReader {
+    let mut reader = CsvReader::new()
+        .has_header(!options.no_header)
+        .delimiter(options.delimiter)
+        .missing_indicators(options.missing_indicators);
+    if let Some(label) = &options.label_column {
+        reader = match label.parse() {
+            Ok(index) if options.no_header => reader.label_column(index),
+            _ => reader.label_name(label),
+        };
+    }
+    for key in &options.key_columns {
+        reader = match key.parse() {
+            Ok(index) if options.no_header => reader.key_column(index),
+            _ => reader.key_name(key),
+        };
+    }
+    reader
+}
+
+fn extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase()
+}
+
+/// Print an error and exit with status 1
+pub fn exit_with_error(program: &str, error: &str) -> ! {
+    eprintln!("{}: {}", program, error);
+    process::exit(1)
+}
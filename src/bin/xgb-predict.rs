@@ -0,0 +1,183 @@
+//! Batch scoring from the command line.
+//!
+//! ```text
+//! xgb-predict --model model.json --input data.csv --output predictions.csv \
+//!     --type value --key-column customer_id
+//! ```
+
+mod cli;
+
+use cli::{csv_reader, exit_with_error, is_csv, load_dmatrix, Args, InputOptions};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use xgboost_rust::{Booster, PredictOptions};
+
+const USAGE: &str = "\
+Score a CSV or libsvm file with an XGBoost model.
+
+Usage: xgb-predict --model <path> --input <path> [options]
+
+Options:
+  --model <path>          Model file (.json, .ubj or legacy binary)
+  --input <path>          CSV file, or libsvm file (.svm, .libsvm)
+  --output <path>         Write predictions here instead of stdout
+  --type <type>           value (default), margin, contribs (SHAP values) or leaf
+  --label-column <col>    CSV column to leave out, by name (or index with --no-header)
+  --key-column <col>      CSV column to copy to the output before the predictions (repeatable)
+  --batch-rows <n>        Rows of CSV input scored at a time (default 65536)
+  --no-header             The CSV file has no header row
+  --delimiter <char>      CSV field separator (default ','; 'tab' for tabs)
+  --one-based             libsvm feature indices start at 1
+  --missing-indicators    Add a 0/1 <name>_missing column per CSV feature
+  --help                  Show this message
+";
+
+fn main() {
+    if let Err(error) = run() {
+        exit_with_error("xgb-predict", &error);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut args = Args::parse(USAGE, &["no-header", "one-based", "missing-indicators"])?;
+    let model = args.required("model")?;
+    let input = args.required("input")?;
+    let output = args.optional("output");
+    let (options, column) = match args.optional("type").as_deref().unwrap_or("value") {
+        "value" => (PredictOptions::empty(), "prediction"),
+        "margin" => (PredictOptions::OUTPUT_MARGIN, "margin"),
+        "contribs" => (PredictOptions::PRED_CONTRIBS, "contrib"),
+        "leaf" => (PredictOptions::PRED_LEAF, "leaf"),
+        other => return Err(format!("unknown --type '{}'", other)),
+    };
+    let batch_rows = match args.optional("batch-rows") {
+        Some(n) => n
+            .parse()
+            .map_err(|_| format!("--batch-rows expects a number, got '{}'", n))?,
+        None => 65_536,
+    };
+    let input_options = InputOptions::from_args(&mut args)?;
+    args.finish()?;
+    if !input_options.key_columns.is_empty() && !is_csv(&input) {
+        return Err("--key-column needs CSV input".to_string());
+    }
+
+    let booster = Booster::load(&model).map_err(|e| e.to_string())?;
+    let writer: Box<dyn Write> = match &output {
+        Some(path) => {
+            Box::new(File::create(path).map_err(|e| format!("failed to create {}: {}", path, e))?)
+        }
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut output = PredictionWriter::new(BufWriter::new(writer), column);
+
+    if is_csv(&input) {
+        // stream the file so memory stays bounded by one batch
+        let batches = csv_reader(&input_options)
+            .read_file_batches(&input, batch_rows)
+            .map_err(|e| e.to_string())?;
+        for batch in batches {
+            let batch = batch.map_err(|e| e.to_string())?;
+            let predictions = booster
+                .predict(
+                    batch.data(),
+                    batch.num_rows(),
+                    batch.num_features(),
+                    options,
+                    false,
+                )
+                .map_err(|e| e.to_string())?;
+            let key_names = if batch.key_names().is_empty() {
+                &input_options.key_columns
+            } else {
+                batch.key_names()
+            };
+            output
+                .write(
+                    key_names,
+                    |row| batch.keys(row),
+                    &predictions,
+                    batch.num_rows(),
+                )
+                .map_err(write_error)?;
+        }
+    } else {
+        let dmatrix = load_dmatrix(&input, &input_options)?;
+        let num_rows = dmatrix.num_rows().map_err(|e| e.to_string())?;
+        let predictions = booster
+            .predict_dmatrix(&dmatrix, options, false)
+            .map_err(|e| e.to_string())?;
+        output
+            .write(&[], |_| &[], &predictions, num_rows)
+            .map_err(write_error)?;
+    }
+    output.finish().map_err(write_error)
+}
+
+fn write_error(error: io::Error) -> String {
+    format!("failed to write predictions: {}", error)
+}
+
+/// CSV output with one row per input row: the keys, then the predictions
+struct PredictionWriter<'a, W> {
+    writer: W,
+    column: &'a str,
+    wrote_header: bool,
+}
+
+impl<'a, W: Write> PredictionWriter<'a, W> {
+    fn new(writer: W, column: &'a str) -> Self {
+        PredictionWriter {
+            writer,
+            column,
+            wrote_header: false,
+        }
+    }
+
+    /// Write the rows of a batch, and the header before the first batch
+    fn write<'k>(
+        &mut self,
+        key_names: &[String],
+        keys: impl Fn(usize) -> &'k [String],
+        predictions: &[f32],
+        num_rows: usize,
+    ) -> io::Result<()> {
+        let width = predictions.len().checked_div(num_rows).unwrap_or(1);
+        if !self.wrote_header {
+            let mut header: Vec<String> = key_names.iter().map(|name| quote(name)).collect();
+            if width == 1 {
+                header.push(self.column.to_string());
+            } else {
+                header.extend((0..width).map(|i| format!("{}_{}", self.column, i)));
+            }
+            writeln!(self.writer, "{}", header.join(","))?;
+            self.wrote_header = true;
+        }
+        for (row, values) in predictions.chunks(width.max(1)).enumerate() {
+            let fields: Vec<String> = keys(row)
+                .iter()
+                .map(|key| quote(key))
+                .chain(values.iter().map(f32::to_string))
+                .collect();
+            writeln!(self.writer, "{}", fields.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// Write the header if there were no rows, and flush
+    fn finish(mut self) -> io::Result<()> {
+        if !self.wrote_header {
+            writeln!(self.writer, "{}", self.column)?;
+        }
+        self.writer.flush()
+    }
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,224 @@
+//! Training from the command line.
+//!
+//! ```text
+//! xgb-train --params params.toml --train train.csv --valid valid.csv \
+//!     --label-column target --num-rounds 500 --early-stopping 20 --output model.json
+//! ```
+
+mod cli;
+
+use cli::{exit_with_error, load_dmatrix, Args, InputOptions};
+use std::fs::{self, File};
+use std::io::BufWriter;
+use xgboost_rust::{BoosterParams, Trainer};
+
+const USAGE: &str = "\
+Train an XGBoost model on CSV or libsvm files.
+
+Usage: xgb-train --train <path> --output <path> [options]
+
+Options:
+  --params <path>          Parameters as a flat TOML (key = value) or JSON object file
+  --set <key=value>        Set a parameter, overriding --params (repeatable)
+  --train <path>           Training data: CSV file, or libsvm file (.svm, .libsvm)
+  --valid <path>           Validation data, evaluated every round
+  --output <path>          Where to save the model (.json or .ubj)
+  --num-rounds <n>         Maximum number of boosting rounds (default 100)
+  --early-stopping <n>     Stop when --valid has not improved for n rounds
+  --verbose-eval <n>       Print the evaluation results every n rounds (default 1)
+  --history <path>         Write the per-round evaluation results as CSV
+  --label-column <col>     CSV label column, by name (or index with --no-header)
+  --no-header              The CSV files have no header row
+  --delimiter <char>       CSV field separator (default ','; 'tab' for tabs)
+  --one-based              libsvm feature indices start at 1
+  --missing-indicators     Add a 0/1 <name>_missing column per CSV feature
+  --help                   Show this message
+";
+
+fn main() {
+    if let Err(error) = run() {
+        exit_with_error("xgb-train", &error);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut args = Args::parse(USAGE, &["no-header", "one-based", "missing-indicators"])?;
+    let train_path = args.required("train")?;
+    let output = args.required("output")?;
+    let valid_path = args.optional("valid");
+    let history_path = args.optional("history");
+    let num_rounds = parse_number(&mut args, "num-rounds")?.unwrap_or(100);
+    let early_stopping = parse_number(&mut args, "early-stopping")?;
+    let verbose_eval = parse_number(&mut args, "verbose-eval")?.unwrap_or(1);
+
+    let mut params = BoosterParams::new();
+    if let Some(path) = args.optional("params") {
+        let text =
+            fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+        for (name, value) in parse_params_file(&text).map_err(|e| format!("{}: {}", path, e))? {
+            params = params.param(&name, value);
+        }
+    }
+    for assignment in args.repeated("set") {
+        let (name, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| format!("--set expects key=value, got '{}'", assignment))?;
+        params = params.param(name.trim(), value.trim());
+    }
+    let input_options = InputOptions::from_args(&mut args)?;
+    args.finish()?;
+
+    let dtrain = load_dmatrix(&train_path, &input_options)?;
+    let dvalid = valid_path
+        .as_deref()
+        .map(|path| load_dmatrix(path, &input_options))
+        .transpose()?;
+    let mut evals = vec![(&dtrain, "train")];
+    if let Some(dvalid) = &dvalid {
+        evals.push((dvalid, "valid"));
+    }
+
+    let mut trainer = Trainer::new(&params, &dtrain)
+        .num_boost_round(num_rounds)
+        .evals(&evals)
+        .verbose_eval(verbose_eval);
+    if let Some(rounds) = early_stopping {
+        if dvalid.is_none() {
+            return Err("--early-stopping needs --valid".to_string());
+        }
+        trainer = trainer.early_stopping_rounds(rounds);
+    }
+    let result = trainer.train().map_err(|e| e.to_string())?;
+
+    result.booster.save(&output).map_err(|e| e.to_string())?;
+    if let Some(path) = history_path {
+        let file = File::create(&path).map_err(|e| format!("failed to create {}: {}", path, e))?;
+        result
+            .history
+            .to_csv(BufWriter::new(file))
+            .map_err(|e| e.to_string())?;
+    }
+    match (result.best_iteration, result.best_score) {
+        (Some(best), Some(score)) => eprintln!(
+            "trained {} rounds, best round {} ({}); saved {}",
+            result.num_rounds, best, score, output
+        ),
+        _ => eprintln!("trained {} rounds; saved {}", result.num_rounds, output),
+    }
+    Ok(())
+}
+
+fn parse_number(args: &mut Args, name: &str) -> Result<Option<usize>, String> {
+    args.optional(name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("--{} expects a number, got '{}'", name, value))
+        })
+        .transpose()
+}
+
+/// `(name, value)` pairs of a flat TOML table or JSON object
+///
+/// Arrays become one pair per element for `eval_metric` (which XGBoost
+/// accumulates) and a `[a,b]` list value otherwise, as `quantile_alpha`
+/// expects. Nested tables and objects are not supported.
+fn parse_params_file(text: &str) -> Result<Vec<(String, String)>, String> {
+    let trimmed = text.trim_start();
+    let entries: Vec<(String, String)> = if let Some(body) = trimmed.strip_prefix('{') {
+        let body = body
+            .trim_end()
+            .strip_suffix('}')
+            .ok_or("unterminated JSON object")?;
+        split_top_level(body, ',')
+            .into_iter()
+            .filter(|entry| !entry.trim().is_empty())
+            .map(|entry| {
+                let (name, value) = split_pair(&entry, ':')?;
+                Ok((unquote(&name), value))
+            })
+            .collect::<Result<_, String>>()?
+    } else {
+        text.lines()
+            .map(|line| strip_comment(line).trim().to_string())
+            .filter(|line| !line.is_empty() && !line.starts_with('['))
+            .map(|line| {
+                let (name, value) = split_pair(&line, '=')?;
+                Ok((unquote(&name), value))
+            })
+            .collect::<Result<_, String>>()?
+    };
+
+    let mut params = Vec::new();
+    for (name, value) in entries {
+        match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            Some(items) => {
+                let items: Vec<String> = split_top_level(items, ',')
+                    .iter()
+                    .map(|item| unquote(item))
+                    .filter(|item| !item.is_empty())
+                    .collect();
+                if name == "eval_metric" {
+                    params.extend(items.into_iter().map(|item| (name.clone(), item)));
+                } else {
+                    params.push((name, format!("[{}]", items.join(","))));
+                }
+            }
+            None => params.push((name, unquote(&value))),
+        }
+    }
+    Ok(params)
+}
+
+fn split_pair(entry: &str, separator: char) -> Result<(String, String), String> {
+    let parts = split_top_level(entry, separator);
+    match parts.as_slice() {
+        [name, value] => Ok((name.trim().to_string(), value.trim().to_string())),
+        _ => Err(format!(
+            "expected key{}value, got '{}'",
+            separator,
+            entry.trim()
+        )),
+    }
+}
+
+/// Split on `separator` outside of quotes and brackets
+fn split_top_level(text: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let (mut quoted, mut depth) = (false, 0usize);
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '[' if !quoted => depth += 1,
+            ']' if !quoted => depth = depth.saturating_sub(1),
+            c if c == separator && !quoted && depth == 0 => {
+                parts.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        parts.last_mut().unwrap().push(c);
+    }
+    parts
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
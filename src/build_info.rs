@@ -0,0 +1,123 @@
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::json::{self, Value};
+use crate::sys;
+use std::ffi::CStr;
+use std::ptr;
+
+/// Build configuration of the linked libxgboost, as reported by `XGBuildInfo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// Compiled with CUDA (GPU) support
+    pub use_cuda: bool,
+    /// Compiled with NCCL for multi-GPU communication
+    pub use_nccl: bool,
+    /// Compiled with the RAPIDS memory manager
+    pub use_rmm: bool,
+    /// Compiled with OpenMP
+    pub use_openmp: bool,
+    /// Compiled with federated learning support
+    pub use_federated: bool,
+    /// CUDA toolkit version (major, minor) used for the build
+    pub cuda_version: Option<(u32, u32)>,
+    /// The raw JSON document returned by XGBoost
+    pub json: String,
+}
+
+/// Query the build configuration of the linked libxgboost
+///
+/// Use this to check at runtime whether GPU support is available, e.g.
+/// before calling `set_device("cuda")`. Requires XGBoost ≥ 1.6.
+///
+/// # Example
+/// ```no_run
+/// let info = xgboost_rust::build_info().unwrap();
+/// if info.use_cuda {
+///     println!("CUDA {:?}", info.cuda_version);
+/// }
+/// ```
+pub fn build_info() -> XGBoostResult<BuildInfo> {
+    let mut out: *const std::os::raw::c_char = ptr::null();
+    XGBoostError::check_return_value(unsafe { sys::XGBuildInfo(&mut out) })?;
+    if out.is_null() {
+        return Err(XGBoostError {
+            description: "XGBoost returned null build info".to_string(),
+        });
+    }
+    let raw = unsafe { CStr::from_ptr(out) }
+        .to_string_lossy()
+        .into_owned();
+
+    let document = json::parse_json(raw.as_bytes())?;
+    let flag = |key: &str| matches!(document.get(key), Some(Value::Bool(true)));
+    let cuda_version = document
+        .get("CUDA_VERSION")
+        .and_then(Value::as_array)
+        .and_then(|v| match v {
+            [major, minor, ..] => Some((major.as_f64()? as u32, minor.as_f64()? as u32)),
+            _ => None,
+        });
+
+    Ok(BuildInfo {
+        use_cuda: flag("USE_CUDA"),
+        use_nccl: flag("USE_NCCL"),
+        use_rmm: flag("USE_RMM"),
+        use_openmp: flag("USE_OPENMP"),
+        use_federated: flag("USE_FEDERATED"),
+        cuda_version,
+        json: raw,
+    })
+}
+
+/// Version `(major, minor, patch)` of the linked libxgboost
+///
+/// This is the library loaded at runtime, which can differ from
+/// `XGBOOST_VERSION` at build time when linking via `XGBOOST_LIB_DIR`.
+/// Requires XGBoost ≥ 1.6.
+///
+/// # Example
+/// ```no_run
+/// let (major, minor, patch) = xgboost_rust::xgboost_version();
+/// println!("libxgboost {}.{}.{}", major, minor, patch);
+/// ```
+pub fn xgboost_version() -> (u32, u32, u32) {
+    let (mut major, mut minor, mut patch) = (0, 0, 0);
+    unsafe { sys::XGBoostVersion(&mut major, &mut minor, &mut patch) };
+    (major as u32, minor as u32, patch as u32)
+}
+
+/// Explain a failure to load `model` when it was saved by a newer XGBoost
+/// than the linked library, which otherwise shows up as an obscure parse
+/// error. Other errors are returned unchanged.
+pub(crate) fn explain_load_error(error: XGBoostError, model: &[u8]) -> XGBoostError {
+    let saved_by = json::parse_model_document(model).ok().and_then(|document| {
+        match document.get("version")?.as_array()? {
+            [major, minor, patch] => Some((
+                major.as_f64()? as u32,
+                minor.as_f64()? as u32,
+                patch.as_f64()? as u32,
+            )),
+            _ => None,
+        }
+    });
+    let linked = xgboost_version();
+    match saved_by {
+        Some(saved_by) if saved_by > linked => XGBoostError {
+            description: format!(
+                "Model was saved by XGBoost {}.{}.{}, which is newer than the linked \
+                 library ({}.{}.{}); rebuild with XGBOOST_VERSION={}.{}.{} or newer. \
+                 Original error: {}",
+                saved_by.0,
+                saved_by.1,
+                saved_by.2,
+                linked.0,
+                linked.1,
+                linked.2,
+                saved_by.0,
+                saved_by.1,
+                saved_by.2,
+                error.description
+            ),
+        },
+        _ => error,
+    }
+}
@@ -0,0 +1,350 @@
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::Booster;
+
+/// How [`CalibratedBooster`] maps margins to probabilities.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CalibrationMethod {
+    /// Platt scaling: a logistic regression on the margin. Works with little
+    /// calibration data, but can only correct sigmoid-shaped distortions.
+    #[default]
+    Platt,
+    /// Isotonic regression: any monotonic mapping. Needs more data (roughly
+    /// a thousand rows or more) to avoid overfitting.
+    Isotonic,
+}
+
+/// A fitted margin → probability mapping.
+#[derive(Debug, Clone, PartialEq)]
+enum Calibrator {
+    /// `p = 1 / (1 + exp(a * margin + b))`
+    Platt { a: f64, b: f64 },
+    /// Piecewise-linear interpolation through increasing `points`
+    Isotonic { points: Vec<(f64, f64)> },
+}
+
+impl Calibrator {
+    fn apply(&self, margin: f64) -> f64 {
+        match self {
+            Calibrator::Platt { a, b } => sigmoid(-(a * margin + b)),
+            Calibrator::Isotonic { points } => {
+                let i = points.partition_point(|&(x, _)| x < margin);
+                if i == 0 {
+                    points[0].1
+                } else if i == points.len() {
+                    points[points.len() - 1].1
+                } else {
+                    let ((x0, y0), (x1, y1)) = (points[i - 1], points[i]);
+                    y0 + (y1 - y0) * (margin - x0) / (x1 - x0)
+                }
+            }
+        }
+    }
+}
+
+/// A binary classifier with calibrated probabilities.
+///
+/// Boosted models often output over- or under-confident probabilities.
+/// The calibrator is fitted on the model's margins for held-out data (not
+/// the training data, where the model is overconfident) and applied by
+/// [`CalibratedBooster::predict_proba`].
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{Booster, CalibratedBooster, CalibrationMethod};
+///
+/// let booster = Booster::load("model.json").unwrap();
+/// let holdout = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+/// let labels = vec![0.0, 1.0];
+///
+/// let calibrated =
+///     CalibratedBooster::fit(booster, CalibrationMethod::Isotonic, &holdout, 2, 2, &labels)
+///         .unwrap();
+/// let probabilities = calibrated.predict_proba(&holdout, 2, 2).unwrap();
+/// ```
+pub struct CalibratedBooster {
+    booster: Booster,
+    calibrator: Calibrator,
+}
+
+impl CalibratedBooster {
+    /// Fit a calibrator on held-out data
+    ///
+    /// # Arguments
+    /// * `booster` - A binary classifier (one output per row)
+    /// * `method` - Platt scaling or isotonic regression
+    /// * `data` - Held-out rows (row-major, num_rows x num_features)
+    /// * `labels` - 0/1 labels of the held-out rows
+    pub fn fit(
+        booster: Booster,
+        method: CalibrationMethod,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        labels: &[f32],
+    ) -> XGBoostResult<Self> {
+        let margins = margins(&booster, data, num_rows, num_features)?;
+        let calibrator = fit_calibrator(method, &margins, labels)?;
+        Ok(CalibratedBooster {
+            booster,
+            calibrator,
+        })
+    }
+
+    /// Calibrated probability of the positive class for each row
+    pub fn predict_proba(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Vec<f32>> {
+        Ok(self.calibrate(&margins(&self.booster, data, num_rows, num_features)?))
+    }
+
+    /// Calibrate margins predicted elsewhere (with [`Booster::predict_margin`])
+    pub fn calibrate(&self, margins: &[f32]) -> Vec<f32> {
+        margins
+            .iter()
+            .map(|&m| self.calibrator.apply(m as f64) as f32)
+            .collect()
+    }
+
+    /// The underlying model
+    pub fn booster(&self) -> &Booster {
+        &self.booster
+    }
+
+    /// Take back the underlying model
+    pub fn into_booster(self) -> Booster {
+        self.booster
+    }
+}
+
+fn margins(
+    booster: &Booster,
+    data: &[f32],
+    num_rows: usize,
+    num_features: usize,
+) -> XGBoostResult<Vec<f32>> {
+    let margins = booster.predict_margin(data, num_rows, num_features)?;
+    if margins.len() != num_rows {
+        return Err(XGBoostError {
+            description: format!(
+                "Calibration needs a binary model with one output per row, got {} outputs for {} rows",
+                margins.len(),
+                num_rows
+            ),
+        });
+    }
+    Ok(margins)
+}
+
+fn fit_calibrator(
+    method: CalibrationMethod,
+    margins: &[f32],
+    labels: &[f32],
+) -> XGBoostResult<Calibrator> {
+    if labels.len() != margins.len() {
+        return Err(XGBoostError {
+            description: format!(
+                "Length mismatch: {} labels, {} rows",
+                labels.len(),
+                margins.len()
+            ),
+        });
+    }
+    let positives = labels.iter().filter(|&&y| y > 0.5).count();
+    if positives == 0 || positives == labels.len() {
+        return Err(XGBoostError {
+            description: "Calibration requires both positive and negative samples".to_string(),
+        });
+    }
+    let margins: Vec<f64> = margins.iter().map(|&m| m as f64).collect();
+    let labels: Vec<bool> = labels.iter().map(|&y| y > 0.5).collect();
+    Ok(match method {
+        CalibrationMethod::Platt => fit_platt(&margins, &labels),
+        CalibrationMethod::Isotonic => fit_isotonic(&margins, &labels),
+    })
+}
+
+fn sigmoid(x: f64) -> f64 {
+    if x >= 0.0 {
+        1.0 / (1.0 + (-x).exp())
+    } else {
+        x.exp() / (1.0 + x.exp())
+    }
+}
+
+/// Platt scaling with the Newton method of Lin, Lin & Weng (2007), using
+/// Platt's smoothed targets to avoid overfitting.
+fn fit_platt(margins: &[f64], labels: &[bool]) -> Calibrator {
+    let positives = labels.iter().filter(|&&y| y).count() as f64;
+    let negatives = labels.len() as f64 - positives;
+    let high = (positives + 1.0) / (positives + 2.0);
+    let low = 1.0 / (negatives + 2.0);
+    let targets: Vec<f64> = labels.iter().map(|&y| if y { high } else { low }).collect();
+
+    // negative log-likelihood of p = 1 / (1 + exp(a * f + b))
+    let objective = |a: f64, b: f64| -> f64 {
+        margins
+            .iter()
+            .zip(&targets)
+            .map(|(&f, &t)| {
+                let z = a * f + b;
+                if z >= 0.0 {
+                    t * z + (-z).exp().ln_1p()
+                } else {
+                    (t - 1.0) * z + z.exp().ln_1p()
+                }
+            })
+            .sum()
+    };
+
+    let (mut a, mut b) = (0.0, ((negatives + 1.0) / (positives + 1.0)).ln());
+    let mut value = objective(a, b);
+    for _ in 0..100 {
+        let (mut h11, mut h22, mut h21, mut g1, mut g2) = (1e-12, 1e-12, 0.0, 0.0, 0.0);
+        for (&f, &t) in margins.iter().zip(&targets) {
+            let p = sigmoid(-(a * f + b));
+            let d2 = p * (1.0 - p);
+            h11 += f * f * d2;
+            h22 += d2;
+            h21 += f * d2;
+            let d1 = t - p;
+            g1 += f * d1;
+            g2 += d1;
+        }
+        if g1.abs() < 1e-5 && g2.abs() < 1e-5 {
+            break;
+        }
+
+        let det = h11 * h22 - h21 * h21;
+        let da = -(h22 * g1 - h21 * g2) / det;
+        let db = -(-h21 * g1 + h11 * g2) / det;
+        let gd = g1 * da + g2 * db;
+        let mut step = 1.0;
+        while step >= 1e-10 {
+            let candidate = objective(a + step * da, b + step * db);
+            if candidate < value + 1e-4 * step * gd {
+                a += step * da;
+                b += step * db;
+                value = candidate;
+                break;
+            }
+            step /= 2.0;
+        }
+        if step < 1e-10 {
+            break;
+        }
+    }
+    Calibrator::Platt { a, b }
+}
+
+/// Isotonic regression by pool-adjacent-violators.
+fn fit_isotonic(margins: &[f64], labels: &[bool]) -> Calibrator {
+    let mut order: Vec<usize> = (0..margins.len()).collect();
+    order.sort_by(|&i, &j| margins[i].total_cmp(&margins[j]));
+
+    // blocks of (first margin, last margin, mean label, weight)
+    let mut blocks: Vec<(f64, f64, f64, f64)> = Vec::new();
+    for i in order {
+        let y = if labels[i] { 1.0 } else { 0.0 };
+        match blocks.last_mut() {
+            // ties share one fitted value
+            Some(last) if last.1 == margins[i] => {
+                last.2 = (last.2 * last.3 + y) / (last.3 + 1.0);
+                last.3 += 1.0;
+            }
+            _ => blocks.push((margins[i], margins[i], y, 1.0)),
+        }
+        while blocks.len() > 1 && blocks[blocks.len() - 2].2 >= blocks[blocks.len() - 1].2 {
+            let (start, _, y0, w0) = blocks[blocks.len() - 2];
+            let (_, end, y1, w1) = blocks.pop().unwrap();
+            *blocks.last_mut().unwrap() = (start, end, (y0 * w0 + y1 * w1) / (w0 + w1), w0 + w1);
+        }
+    }
+
+    let mut points = Vec::with_capacity(blocks.len() * 2);
+    for (start, end, y, _) in blocks {
+        points.push((start, y));
+        if end > start {
+            points.push((end, y));
+        }
+    }
+    Calibrator::Isotonic { points }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isotonic_pools_violators() {
+        let calibrator = fit_calibrator(
+            CalibrationMethod::Isotonic,
+            &[0.0, 1.0, 2.0, 3.0],
+            &[0.0, 1.0, 0.0, 1.0],
+        )
+        .unwrap();
+        assert_eq!(
+            calibrator,
+            Calibrator::Isotonic {
+                points: vec![(0.0, 0.0), (1.0, 0.5), (2.0, 0.5), (3.0, 1.0)]
+            }
+        );
+        // constant outside the fitted range, linear in between
+        assert_eq!(calibrator.apply(-1.0), 0.0);
+        assert_eq!(calibrator.apply(1.5), 0.5);
+        assert_eq!(calibrator.apply(2.5), 0.75);
+        assert_eq!(calibrator.apply(10.0), 1.0);
+    }
+
+    #[test]
+    fn isotonic_ties_share_a_value() {
+        let calibrator = fit_calibrator(
+            CalibrationMethod::Isotonic,
+            &[1.0, 1.0, 1.0],
+            &[0.0, 1.0, 1.0],
+        )
+        .unwrap();
+        assert_eq!(
+            calibrator,
+            Calibrator::Isotonic {
+                points: vec![(1.0, 2.0 / 3.0)]
+            }
+        );
+    }
+
+    #[test]
+    fn platt_is_increasing_in_the_margin() {
+        let margins = [-3.0, -2.0, -1.0, -0.5, 0.5, 1.0, 2.0, 3.0];
+        let labels = [0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0];
+        let calibrator = fit_calibrator(CalibrationMethod::Platt, &margins, &labels).unwrap();
+        let Calibrator::Platt { a, b } = &calibrator else {
+            panic!("expected Platt scaling");
+        };
+        assert!(*a < 0.0);
+        // the data is symmetric around 0
+        assert!(b.abs() < 1e-4);
+        let probabilities: Vec<f64> = margins
+            .iter()
+            .map(|&m| calibrator.apply(m as f64))
+            .collect();
+        assert!(probabilities.windows(2).all(|w| w[0] < w[1]));
+        assert!(probabilities.iter().all(|p| (0.0..1.0).contains(p)));
+    }
+
+    #[test]
+    fn calibration_needs_both_classes() {
+        for method in [CalibrationMethod::Platt, CalibrationMethod::Isotonic] {
+            assert!(fit_calibrator(method, &[0.0, 1.0], &[1.0, 1.0]).is_err());
+            assert!(fit_calibrator(method, &[0.0, 1.0], &[0.0]).is_err());
+        }
+    }
+
+    #[test]
+    fn sigmoid_is_stable() {
+        assert_eq!(sigmoid(0.0), 0.5);
+        assert_eq!(sigmoid(-1000.0), 0.0);
+        assert_eq!(sigmoid(1000.0), 1.0);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::error::{XGBoostError, XGBoostResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// Set by the Ctrl-C handler installed by [`CancellationToken::ctrl_c`]
+static CTRL_C: AtomicBool = AtomicBool::new(false);
+/// Outcome of installing the handler, once, on the first
+/// [`CancellationToken::ctrl_c`]
+static CTRL_C_HANDLER: OnceLock<Result<(), String>> = OnceLock::new();
+
+/// Cooperative cancellation of training.
+///
+/// Clones share the same flag: hand one to [`Trainer::cancellation`] and
+/// call [`cancel`](Self::cancel) on another, e.g. from a different thread.
+/// Training checks the flag between rounds, stops, and returns the model
+/// trained so far.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{BoosterParams, CancellationToken, DMatrix, Trainer};
+///
+/// # let dtrain = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+/// let token = CancellationToken::new();
+/// let watchdog = token.clone();
+/// std::thread::spawn(move || {
+///     std::thread::sleep(std::time::Duration::from_secs(60));
+///     watchdog.cancel();
+/// });
+///
+/// let params = BoosterParams::new();
+/// let output = Trainer::new(&params, &dtrain)
+///     .num_boost_round(10_000)
+///     .cancellation(&token)
+///     .train()
+///     .unwrap();
+/// println!("stopped: {:?}", output.stop_reason);
+/// ```
+///
+/// [`Trainer::cancellation`]: crate::Trainer::cancellation
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    ctrl_c: bool,
+}
+
+impl CancellationToken {
+    /// A token that is cancelled only by [`cancel`](Self::cancel)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token that is also cancelled by Ctrl-C (SIGINT)
+    ///
+    /// Installs a process-wide handler on the first call. The first Ctrl-C
+    /// cancels every such token, including ones created afterwards, and
+    /// restores the default handler, so a second one terminates the process
+    /// as usual. Supported on Unix and Windows.
+    pub fn ctrl_c() -> XGBoostResult<Self> {
+        CTRL_C_HANDLER
+            .get_or_init(|| install_ctrl_c_handler().map_err(|e| e.description))
+            .clone()
+            .map_err(|description| XGBoostError { description })?;
+        Ok(CancellationToken {
+            cancelled: Arc::default(),
+            ctrl_c: true,
+        })
+    }
+
+    /// Request cancellation
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether cancellation was requested
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst) || (self.ctrl_c && CTRL_C.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(unix)]
+fn install_ctrl_c_handler() -> XGBoostResult<()> {
+    use std::os::raw::c_int;
+
+    const SIGINT: c_int = 2;
+    const SIG_DFL: usize = 0;
+    const SIG_ERR: usize = !0;
+
+    extern "C" {
+        fn signal(signum: c_int, handler: usize) -> usize;
+    }
+
+    extern "C" fn on_sigint(_: c_int) {
+        CTRL_C.store(true, Ordering::SeqCst);
+        unsafe { signal(SIGINT, SIG_DFL) };
+    }
+
+    let handler: extern "C" fn(c_int) = on_sigint;
+    if unsafe { signal(SIGINT, handler as usize) } == SIG_ERR {
+        return Err(XGBoostError {
+            description: "Failed to install the Ctrl-C handler".to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn install_ctrl_c_handler() -> XGBoostResult<()> {
+    type Handler = unsafe extern "system" fn(u32) -> i32;
+
+    extern "system" {
+        fn SetConsoleCtrlHandler(handler: Option<Handler>, add: i32) -> i32;
+    }
+
+    unsafe extern "system" fn on_ctrl_c(event: u32) -> i32 {
+        // CTRL_C_EVENT; other events (close, logoff) keep their default
+        if event != 0 || CTRL_C.swap(true, Ordering::SeqCst) {
+            return 0;
+        }
+        1
+    }
+
+    if unsafe { SetConsoleCtrlHandler(Some(on_ctrl_c), 1) } == 0 {
+        return Err(XGBoostError {
+            description: "Failed to install the Ctrl-C handler".to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn install_ctrl_c_handler() -> XGBoostResult<()> {
+    Err(XGBoostError {
+        description: "Ctrl-C handling is not supported on this platform".to_string(),
+    })
+}
@@ -0,0 +1,365 @@
+//! Collective communication for distributed (data-parallel) training.
+//!
+//! Every worker initializes a [`Communicator`] that connects to a tracker;
+//! XGBoost then synchronizes histograms and gradients across workers
+//! transparently during training. The communicator stays active until the
+//! returned guard is dropped.
+//!
+//! Workers either join a Rabit group ([`Communicator::rabit`]) or a federated
+//! learning setup ([`Communicator::federated`]).
+//!
+//! Requires XGBoost ≥ 2.0 (≥ 2.1 for [`Tracker`]).
+//!
+//! # Example
+//! ```no_run
+//! use xgboost_rust::collective::{self, Communicator, RabitConfig};
+//!
+//! // e.g. a Kubernetes headless service in front of the rank-0 pod
+//! let config = RabitConfig::new("xgboost-tracker.default.svc", 9091);
+//! let _communicator = Communicator::rabit(&config).unwrap();
+//! collective::print(&format!("worker {} of {}", collective::rank(), collective::world_size()))
+//!     .unwrap();
+//! ```
+
+use crate::build_info;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::sys;
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+/// Quote a string as a JSON string literal.
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn c_string(value: String) -> XGBoostResult<CString> {
+    CString::new(value).map_err(|e| XGBoostError {
+        description: format!("Configuration contains NUL byte: {}", e),
+    })
+}
+
+/// Connection settings for the Rabit communicator.
+#[derive(Debug, Clone)]
+pub struct RabitConfig {
+    /// Hostname or IP address of the tracker
+    pub tracker_uri: String,
+    /// Port of the tracker
+    pub tracker_port: u16,
+    /// Task identifier, used to obtain a deterministic rank assignment
+    pub task_id: Option<String>,
+    /// Number of retries when handling network errors
+    pub retry: Option<u32>,
+    /// Timeout in seconds for network operations
+    pub timeout: Option<u64>,
+    /// Path to the NCCL library to load for GPU communication
+    pub nccl_path: Option<String>,
+}
+
+impl RabitConfig {
+    /// Connect to the tracker at `tracker_uri:tracker_port`
+    pub fn new(tracker_uri: impl Into<String>, tracker_port: u16) -> Self {
+        RabitConfig {
+            tracker_uri: tracker_uri.into(),
+            tracker_port,
+            task_id: None,
+            retry: None,
+            timeout: None,
+            nccl_path: None,
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let mut fields = vec![
+            "\"dmlc_communicator\": \"rabit\"".to_string(),
+            format!("\"dmlc_tracker_uri\": {}", json_string(&self.tracker_uri)),
+            format!("\"dmlc_tracker_port\": {}", self.tracker_port),
+        ];
+        if let Some(task_id) = &self.task_id {
+            fields.push(format!("\"dmlc_task_id\": {}", json_string(task_id)));
+        }
+        if let Some(retry) = self.retry {
+            fields.push(format!("\"dmlc_retry\": {}", retry));
+        }
+        if let Some(timeout) = self.timeout {
+            fields.push(format!("\"dmlc_timeout\": {}", timeout));
+        }
+        if let Some(nccl_path) = &self.nccl_path {
+            fields.push(format!("\"dmlc_nccl_path\": {}", json_string(nccl_path)));
+        }
+        format!("{{{}}}", fields.join(", "))
+    }
+}
+
+/// Connection settings for the federated communicator.
+///
+/// Used to join a federated learning setup coordinated by an XGBoost
+/// federated server (e.g. `xgboost.federated.run_federated_server` in Python),
+/// alongside Python or other workers. Requires a libxgboost built with the
+/// federated plugin.
+#[derive(Debug, Clone)]
+pub struct FederatedConfig {
+    /// Address of the federated server (`host:port`)
+    pub server_address: String,
+    /// Number of federated workers
+    pub world_size: usize,
+    /// Rank of the current worker
+    pub rank: usize,
+    /// Server certificate file path, for SSL
+    pub server_cert_path: Option<String>,
+    /// Client key file path, for SSL
+    pub client_key_path: Option<String>,
+    /// Client certificate file path, for SSL
+    pub client_cert_path: Option<String>,
+}
+
+impl FederatedConfig {
+    /// Connect to the federated server at `server_address` as worker `rank` of `world_size`
+    ///
+    /// The connection is insecure unless certificates are set with
+    /// [`FederatedConfig::with_ssl`].
+    pub fn new(server_address: impl Into<String>, world_size: usize, rank: usize) -> Self {
+        FederatedConfig {
+            server_address: server_address.into(),
+            world_size,
+            rank,
+            server_cert_path: None,
+            client_key_path: None,
+            client_cert_path: None,
+        }
+    }
+
+    /// Use SSL with the given server certificate and client key/certificate files
+    pub fn with_ssl(
+        mut self,
+        server_cert_path: impl Into<String>,
+        client_key_path: impl Into<String>,
+        client_cert_path: impl Into<String>,
+    ) -> Self {
+        self.server_cert_path = Some(server_cert_path.into());
+        self.client_key_path = Some(client_key_path.into());
+        self.client_cert_path = Some(client_cert_path.into());
+        self
+    }
+
+    fn to_json(&self) -> XGBoostResult<String> {
+        if self.rank >= self.world_size {
+            return Err(XGBoostError {
+                description: format!(
+                    "Federated rank {} is out of range for world size {}",
+                    self.rank, self.world_size
+                ),
+            });
+        }
+
+        let mut fields = vec![
+            "\"dmlc_communicator\": \"federated\"".to_string(),
+            format!(
+                "\"federated_server_address\": {}",
+                json_string(&self.server_address)
+            ),
+            format!("\"federated_world_size\": {}", self.world_size),
+            format!("\"federated_rank\": {}", self.rank),
+        ];
+        let certs = [
+            ("federated_server_cert_path", &self.server_cert_path),
+            ("federated_client_key_path", &self.client_key_path),
+            ("federated_client_cert_path", &self.client_cert_path),
+        ];
+        let num_certs = certs.iter().filter(|(_, path)| path.is_some()).count();
+        if num_certs != 0 && num_certs != certs.len() {
+            return Err(XGBoostError {
+                description: "Federated SSL needs the server certificate, client key and client \
+                              certificate together"
+                    .to_string(),
+            });
+        }
+        for (key, path) in certs {
+            if let Some(path) = path {
+                fields.push(format!("\"{}\": {}", key, json_string(path)));
+            }
+        }
+        Ok(format!("{{{}}}", fields.join(", ")))
+    }
+}
+
+/// Guard for an initialized communicator; finalizes it on drop.
+///
+/// Only one communicator can be active per process.
+#[derive(Debug)]
+pub struct Communicator {
+    _private: (),
+}
+
+impl Communicator {
+    /// Join a Rabit group through its tracker
+    ///
+    /// Blocks until all workers have connected to the tracker.
+    pub fn rabit(config: &RabitConfig) -> XGBoostResult<Self> {
+        Self::init(&config.to_json())
+    }
+
+    /// Join a federated learning group through its server
+    ///
+    /// Checks that the linked libxgboost was built with federated support
+    /// before connecting.
+    pub fn federated(config: &FederatedConfig) -> XGBoostResult<Self> {
+        let config = config.to_json()?;
+        if !build_info::build_info()?.use_federated {
+            return Err(XGBoostError {
+                description: "Federated learning was requested but the linked XGBoost library \
+                              was not built with the federated plugin"
+                    .to_string(),
+            });
+        }
+        Self::init(&config)
+    }
+
+    /// Initialize from the `DMLC_*` environment variables
+    ///
+    /// Reads `DMLC_TRACKER_URI`, `DMLC_TRACKER_PORT`, `DMLC_TASK_ID` etc., as
+    /// set by the XGBoost launch tooling.
+    pub fn from_env() -> XGBoostResult<Self> {
+        Self::init("{}")
+    }
+
+    /// Initialize with a raw `XGCommunicatorInit` JSON configuration
+    pub fn init(config: &str) -> XGBoostResult<Self> {
+        let config = c_string(config.to_string())?;
+        XGBoostError::check_return_value(unsafe { sys::XGCommunicatorInit(config.as_ptr()) })?;
+        Ok(Communicator { _private: () })
+    }
+}
+
+impl Drop for Communicator {
+    fn drop(&mut self) {
+        unsafe {
+            sys::XGCommunicatorFinalize();
+        }
+    }
+}
+
+/// Rank of the current worker (0 when not distributed)
+pub fn rank() -> usize {
+    unsafe { sys::XGCommunicatorGetRank() }.max(0) as usize
+}
+
+/// Total number of workers (1 when not distributed)
+pub fn world_size() -> usize {
+    unsafe { sys::XGCommunicatorGetWorldSize() }.max(1) as usize
+}
+
+/// Whether a distributed communicator is active
+pub fn is_distributed() -> bool {
+    unsafe { sys::XGCommunicatorIsDistributed() != 0 }
+}
+
+/// Print a message through the communicator (on the tracker when distributed)
+pub fn print(message: &str) -> XGBoostResult<()> {
+    let message = c_string(message.to_string())?;
+    XGBoostError::check_return_value(unsafe { sys::XGCommunicatorPrint(message.as_ptr()) })
+}
+
+/// Name of the processor (host) this worker runs on
+pub fn processor_name() -> XGBoostResult<String> {
+    let mut out: *const std::os::raw::c_char = ptr::null();
+    XGBoostError::check_return_value(unsafe { sys::XGCommunicatorGetProcessorName(&mut out) })?;
+    if out.is_null() {
+        return Err(XGBoostError {
+            description: "XGBoost returned null processor name".to_string(),
+        });
+    }
+    Ok(unsafe { CStr::from_ptr(out) }
+        .to_string_lossy()
+        .into_owned())
+}
+
+/// Rendezvous tracker that workers connect to, usually run next to rank 0.
+///
+/// Requires XGBoost ≥ 2.1.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::collective::Tracker;
+///
+/// let tracker = Tracker::new("0.0.0.0", 9091, 4).unwrap();
+/// tracker.run().unwrap();
+/// println!("workers connect with {}", tracker.worker_args().unwrap());
+/// tracker.wait_for(None).unwrap();
+/// ```
+#[cfg(xgboost_tracker)]
+pub struct Tracker {
+    handle: sys::TrackerHandle,
+}
+
+#[cfg(xgboost_tracker)]
+impl Tracker {
+    /// Create a tracker listening on `host:port` for `n_workers` workers
+    ///
+    /// A `port` of 0 picks a free port; read it back from [`Tracker::worker_args`].
+    pub fn new(host: &str, port: u16, n_workers: usize) -> XGBoostResult<Self> {
+        let config = c_string(format!(
+            "{{\"dmlc_communicator\": \"rabit\", \"host_ip\": {}, \"port\": {}, \"n_workers\": {}}}",
+            json_string(host),
+            port,
+            n_workers
+        ))?;
+        let mut handle: sys::TrackerHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGTrackerCreate(config.as_ptr(), &mut handle)
+        })?;
+        Ok(Tracker { handle })
+    }
+
+    /// Start accepting workers in the background
+    pub fn run(&self) -> XGBoostResult<()> {
+        let config = c_string("{}".to_string())?;
+        XGBoostError::check_return_value(unsafe { sys::XGTrackerRun(self.handle, config.as_ptr()) })
+    }
+
+    /// JSON arguments (`dmlc_tracker_uri`, `dmlc_tracker_port`) for the workers
+    pub fn worker_args(&self) -> XGBoostResult<String> {
+        let mut out: *const std::os::raw::c_char = ptr::null();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGTrackerWorkerArgs(self.handle, &mut out)
+        })?;
+        if out.is_null() {
+            return Err(XGBoostError {
+                description: "XGBoost returned null tracker arguments".to_string(),
+            });
+        }
+        Ok(unsafe { CStr::from_ptr(out) }
+            .to_string_lossy()
+            .into_owned())
+    }
+
+    /// Block until all workers have finished, or until `timeout` seconds elapse
+    pub fn wait_for(&self, timeout: Option<u64>) -> XGBoostResult<()> {
+        let config = c_string(match timeout {
+            Some(timeout) => format!("{{\"timeout\": {}}}", timeout),
+            None => "{}".to_string(),
+        })?;
+        XGBoostError::check_return_value(unsafe {
+            sys::XGTrackerWaitFor(self.handle, config.as_ptr())
+        })
+    }
+}
+
+#[cfg(xgboost_tracker)]
+impl Drop for Tracker {
+    fn drop(&mut self) {
+        unsafe {
+            sys::XGTrackerFree(self.handle);
+        }
+    }
+}
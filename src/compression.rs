@@ -0,0 +1,125 @@
+use crate::error::{XGBoostError, XGBoostResult};
+use std::borrow::Cow;
+use std::path::Path;
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+use std::path::PathBuf;
+
+/// Compression of a saved model.
+///
+/// Gzip needs the `gzip` feature and zstd the `zstd` feature. Compressed
+/// models are recognized by their content when loaded, whatever their file
+/// name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// gzip, for `.gz` files
+    Gzip,
+    /// Zstandard, for `.zst` files; faster and usually smaller than gzip
+    Zstd,
+}
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+impl Compression {
+    /// The compression named by a file extension (`.gz`, `.zst` or
+    /// `.zstd`), if any
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "gz" => Some(Compression::Gzip),
+            "zst" | "zstd" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    fn failed(self, action: &str, error: std::io::Error) -> XGBoostError {
+        XGBoostError {
+            description: format!("Failed to {} the {} model: {}", action, self.name(), error),
+        }
+    }
+
+    /// The error for a compression whose feature is disabled
+    #[cfg(not(all(feature = "gzip", feature = "zstd")))]
+    fn unavailable(self) -> XGBoostError {
+        XGBoostError {
+            description: format!(
+                "{} compressed models require the `{}` feature",
+                self.name(),
+                self.name()
+            ),
+        }
+    }
+}
+
+/// `path` without a compression extension, e.g. `model.ubj` for
+/// `model.ubj.gz`, which decides the serialization format
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+pub(crate) fn uncompressed_path(path: &Path) -> PathBuf {
+    match Compression::from_path(path) {
+        Some(_) => path.with_extension(""),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Compress a serialized model
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+#[cfg_attr(not(any(feature = "gzip", feature = "zstd")), allow(unused_variables))]
+pub(crate) fn compress(bytes: &[u8], compression: Compression) -> XGBoostResult<Vec<u8>> {
+    match compression {
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => {
+            use std::io::Write;
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder
+                .write_all(bytes)
+                .and_then(|()| encoder.finish())
+                .map_err(|e| compression.failed("compress", e))
+        }
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => zstd::encode_all(bytes, zstd::DEFAULT_COMPRESSION_LEVEL)
+            .map_err(|e| compression.failed("compress", e)),
+        #[cfg(not(feature = "gzip"))]
+        Compression::Gzip => Err(compression.unavailable()),
+        #[cfg(not(feature = "zstd"))]
+        Compression::Zstd => Err(compression.unavailable()),
+    }
+}
+
+/// Decompress a model if it starts with a gzip or zstd header
+pub(crate) fn decompress(bytes: &[u8]) -> XGBoostResult<Cow<'_, [u8]>> {
+    let compression = if bytes.starts_with(GZIP_MAGIC) {
+        Compression::Gzip
+    } else if bytes.starts_with(ZSTD_MAGIC) {
+        Compression::Zstd
+    } else {
+        return Ok(Cow::Borrowed(bytes));
+    };
+    match compression {
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => {
+            use std::io::Read;
+            let mut out = Vec::new();
+            flate2::read::MultiGzDecoder::new(bytes)
+                .read_to_end(&mut out)
+                .map_err(|e| compression.failed("decompress", e))?;
+            Ok(Cow::Owned(out))
+        }
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => zstd::decode_all(bytes)
+            .map(Cow::Owned)
+            .map_err(|e| compression.failed("decompress", e)),
+        #[cfg(not(feature = "gzip"))]
+        Compression::Gzip => Err(compression.unavailable()),
+        #[cfg(not(feature = "zstd"))]
+        Compression::Zstd => Err(compression.unavailable()),
+    }
+}
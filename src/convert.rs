@@ -0,0 +1,139 @@
+//! Conversion of `f64` and half-precision feature data to the `f32` the
+//! trees compare against.
+//!
+//! With the `simd` feature the `f64` conversion uses AVX (detected at run
+//! time) or SSE2 on x86_64 and NEON on aarch64, converting four values per
+//! instruction, and the half-precision conversion uses F16C when the CPU
+//! has it; otherwise they are scalar loops. The vector and scalar paths
+//! give identical results.
+
+/// `data` rounded to `f32`
+#[cfg(feature = "pure")]
+pub(crate) fn f64_to_f32(data: &[f64]) -> Vec<f32> {
+    let mut out = Vec::with_capacity(data.len());
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    let done = if is_x86_feature_detected!("avx") {
+        // SAFETY: AVX is available and `out` has capacity for `data`
+        unsafe { x86::convert_avx(data, out.as_mut_ptr()) }
+    } else {
+        // SAFETY: SSE2 is part of x86_64 and `out` has capacity for `data`
+        unsafe { x86::convert_sse2(data, out.as_mut_ptr()) }
+    };
+    #[cfg(all(feature = "simd", target_arch = "aarch64"))]
+    // SAFETY: NEON is part of aarch64 and `out` has capacity for `data`
+    let done = unsafe { neon::convert(data, out.as_mut_ptr()) };
+    #[cfg(not(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+    let done = 0;
+
+    // SAFETY: the first `done` values were written above
+    unsafe { out.set_len(done) };
+    out.extend(data[done..].iter().map(|&x| x as f32));
+    out
+}
+
+#[cfg(all(feature = "pure", feature = "simd", target_arch = "x86_64"))]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    /// Convert whole groups of four values into `out`, returning how many
+    /// were converted
+    #[target_feature(enable = "avx")]
+    pub(super) unsafe fn convert_avx(data: &[f64], out: *mut f32) -> usize {
+        let chunks = data.chunks_exact(4);
+        let done = data.len() - chunks.remainder().len();
+        for (i, chunk) in chunks.enumerate() {
+            let wide = _mm256_loadu_pd(chunk.as_ptr());
+            _mm_storeu_ps(out.add(i * 4), _mm256_cvtpd_ps(wide));
+        }
+        done
+    }
+
+    /// As [`convert_avx`], two values per conversion
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn convert_sse2(data: &[f64], out: *mut f32) -> usize {
+        let chunks = data.chunks_exact(4);
+        let done = data.len() - chunks.remainder().len();
+        for (i, chunk) in chunks.enumerate() {
+            let low = _mm_cvtpd_ps(_mm_loadu_pd(chunk.as_ptr()));
+            let high = _mm_cvtpd_ps(_mm_loadu_pd(chunk.as_ptr().add(2)));
+            _mm_storeu_ps(out.add(i * 4), _mm_movelh_ps(low, high));
+        }
+        done
+    }
+}
+
+#[cfg(all(feature = "pure", feature = "simd", target_arch = "aarch64"))]
+mod neon {
+    use std::arch::aarch64::*;
+
+    /// Convert whole groups of four values into `out`, returning how many
+    /// were converted
+    pub(super) unsafe fn convert(data: &[f64], out: *mut f32) -> usize {
+        let chunks = data.chunks_exact(4);
+        let done = data.len() - chunks.remainder().len();
+        for (i, chunk) in chunks.enumerate() {
+            let low = vcvt_f32_f64(vld1q_f64(chunk.as_ptr()));
+            let high = vcvt_f32_f64(vld1q_f64(chunk.as_ptr().add(2)));
+            vst1q_f32(out.add(i * 4), vcombine_f32(low, high));
+        }
+        done
+    }
+}
+
+/// IEEE 754 half-precision values, given as their bit patterns, widened to
+/// `f32`
+///
+/// Every half-precision value, including subnormals and infinities, is
+/// exactly representable as `f32`; NaN payloads are kept but quieted.
+pub(crate) fn f16_to_f32(bits: &[u16]) -> Vec<f32> {
+    let mut out = Vec::with_capacity(bits.len());
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    let done = if is_x86_feature_detected!("avx") && is_x86_feature_detected!("f16c") {
+        // SAFETY: F16C is available and `out` has capacity for `bits`
+        unsafe { f16c::convert(bits, out.as_mut_ptr()) }
+    } else {
+        0
+    };
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+    let done = 0;
+
+    // SAFETY: the first `done` values were written above
+    unsafe { out.set_len(done) };
+    out.extend(bits[done..].iter().map(|&h| f16_bits_to_f32(h)));
+    out
+}
+
+fn f16_bits_to_f32(h: u16) -> f32 {
+    let sign = u32::from(h & 0x8000) << 16;
+    let exponent = u32::from(h >> 10) & 0x1f;
+    let mantissa = u32::from(h & 0x3ff);
+    match exponent {
+        // zero and subnormals: mantissa * 2^-24
+        0 => {
+            let magnitude = mantissa as f32 / (1u32 << 24) as f32;
+            f32::from_bits(sign | magnitude.to_bits())
+        }
+        0x1f if mantissa == 0 => f32::from_bits(sign | 0x7f80_0000),
+        // NaN, quieted as F16C does
+        0x1f => f32::from_bits(sign | 0x7fc0_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod f16c {
+    use std::arch::x86_64::*;
+
+    /// Convert whole groups of eight values into `out`, returning how many
+    /// were converted
+    #[target_feature(enable = "avx,f16c")]
+    pub(super) unsafe fn convert(bits: &[u16], out: *mut f32) -> usize {
+        let chunks = bits.chunks_exact(8);
+        let done = bits.len() - chunks.remainder().len();
+        for (i, chunk) in chunks.enumerate() {
+            let half = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+            _mm256_storeu_ps(out.add(i * 8), _mm256_cvtph_ps(half));
+        }
+        done
+    }
+}
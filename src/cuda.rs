@@ -0,0 +1,258 @@
+//! Zero-copy input from CUDA device memory.
+//!
+//! Data is described to XGBoost through the
+//! [`__cuda_array_interface__`](https://numba.readthedocs.io/en/stable/cuda/cuda_array_interface.html)
+//! JSON protocol, so any allocator works: pass the raw device pointer from
+//! `cudarc` (`CudaSlice::device_ptr`), `cust` (`DeviceBuffer::as_device_ptr`)
+//! or your own CUDA code.
+
+#[cfg(xgboost_quantile_dmatrix)]
+use crate::error::{XGBoostError, XGBoostResult};
+#[cfg(xgboost_quantile_dmatrix)]
+use crate::sys;
+#[cfg(xgboost_quantile_dmatrix)]
+use std::ffi::CString;
+use std::marker::PhantomData;
+#[cfg(xgboost_quantile_dmatrix)]
+use std::os::raw::c_int;
+
+/// A dense row-major `f32` matrix in CUDA device memory.
+#[derive(Debug, Clone, Copy)]
+pub struct CudaArray {
+    data: u64,
+    num_rows: usize,
+    num_features: usize,
+    vector: bool,
+    stream: Option<usize>,
+}
+
+impl CudaArray {
+    /// Describe a matrix in device memory
+    ///
+    /// # Arguments
+    /// * `data` - Device pointer to `num_rows * num_features` floats (row-major)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    ///
+    /// # Safety
+    /// `data` must be a valid CUDA device pointer to at least
+    /// `num_rows * num_features` `f32` values that stay allocated and
+    /// unmodified while XGBoost reads them.
+    pub unsafe fn from_raw_parts(data: u64, num_rows: usize, num_features: usize) -> Self {
+        CudaArray {
+            data,
+            num_rows,
+            num_features,
+            vector: false,
+            stream: None,
+        }
+    }
+
+    /// Describe a 1-D vector in device memory, e.g. labels or weights
+    ///
+    /// # Arguments
+    /// * `data` - Device pointer to `len` floats
+    /// * `len` - Number of elements
+    ///
+    /// # Safety
+    /// Same requirements as [`CudaArray::from_raw_parts`] for `len` values.
+    pub unsafe fn vector_from_raw_parts(data: u64, len: usize) -> Self {
+        CudaArray {
+            data,
+            num_rows: len,
+            num_features: 1,
+            vector: true,
+            stream: None,
+        }
+    }
+
+    /// Synchronize on the given CUDA stream before reading the data
+    ///
+    /// Uses the `__cuda_array_interface__` stream convention: `1` is the legacy
+    /// default stream, `2` the per-thread default stream, anything else a
+    /// `cudaStream_t` handle.
+    pub fn with_stream(mut self, stream: usize) -> Self {
+        self.stream = Some(stream);
+        self
+    }
+
+    /// Number of rows in the data
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Number of features per row
+    pub fn num_features(&self) -> usize {
+        self.num_features
+    }
+
+    /// The `__cuda_array_interface__` JSON document for this array.
+    pub(crate) fn array_interface(&self) -> String {
+        let stream = self
+            .stream
+            .map(|s| format!(", \"stream\": {}", s))
+            .unwrap_or_default();
+        let shape = if self.vector {
+            format!("[{}]", self.num_rows)
+        } else {
+            format!("[{}, {}]", self.num_rows, self.num_features)
+        };
+        format!(
+            "{{\"data\": [{}, true], \"shape\": {}, \"typestr\": \"<f4\", \"version\": 3{}}}",
+            self.data, shape, stream
+        )
+    }
+}
+
+/// Predictions left in CUDA device memory by [`Booster::predict_cuda`].
+///
+/// The buffer is owned by XGBoost and is only valid until the next prediction
+/// call on the same booster from the same thread. Borrowing the booster keeps
+/// it from being freed while the predictions are in use.
+///
+/// [`Booster::predict_cuda`]: crate::Booster::predict_cuda
+#[derive(Debug)]
+pub struct CudaPredictions<'a> {
+    pub(crate) data: *const f32,
+    pub(crate) shape: Vec<usize>,
+    pub(crate) _booster: PhantomData<&'a ()>,
+}
+
+impl CudaPredictions<'_> {
+    /// Device pointer to the prediction values (row-major)
+    pub fn device_ptr(&self) -> u64 {
+        self.data as u64
+    }
+
+    /// Shape of the prediction output
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Total number of prediction values
+    pub fn len(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// Whether the output is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// One device-resident batch used to build a `QuantileDMatrix`.
+#[cfg(xgboost_quantile_dmatrix)]
+#[derive(Debug, Clone, Copy)]
+pub struct CudaBatch {
+    /// Feature matrix
+    pub data: CudaArray,
+    /// Labels (one per row)
+    pub label: Option<CudaArray>,
+    /// Instance weights (one per row)
+    pub weight: Option<CudaArray>,
+    /// Base margins (one per row and output group)
+    pub base_margin: Option<CudaArray>,
+}
+
+#[cfg(xgboost_quantile_dmatrix)]
+impl CudaBatch {
+    /// A batch with features only
+    pub fn new(data: CudaArray) -> Self {
+        CudaBatch {
+            data,
+            label: None,
+            weight: None,
+            base_margin: None,
+        }
+    }
+
+    /// Attach labels to the batch
+    pub fn with_label(mut self, label: CudaArray) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Attach instance weights to the batch
+    pub fn with_weight(mut self, weight: CudaArray) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Attach base margins to the batch
+    pub fn with_base_margin(mut self, base_margin: CudaArray) -> Self {
+        self.base_margin = Some(base_margin);
+        self
+    }
+}
+
+/// State behind the `DataIterHandle` passed to the XGBoost iterator callbacks.
+#[cfg(xgboost_quantile_dmatrix)]
+pub(crate) struct BatchIter<'a> {
+    batches: &'a [CudaBatch],
+    position: usize,
+    proxy: sys::DMatrixHandle,
+    pub(crate) error: Option<XGBoostError>,
+}
+
+#[cfg(xgboost_quantile_dmatrix)]
+impl<'a> BatchIter<'a> {
+    pub(crate) fn new(batches: &'a [CudaBatch], proxy: sys::DMatrixHandle) -> Self {
+        BatchIter {
+            batches,
+            position: 0,
+            proxy,
+            error: None,
+        }
+    }
+
+    fn set_batch(&self, batch: &CudaBatch) -> XGBoostResult<()> {
+        let data = CString::new(batch.data.array_interface()).unwrap();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGProxyDMatrixSetDataCudaArrayInterface(self.proxy, data.as_ptr())
+        })?;
+
+        for (field, array) in [
+            ("label", batch.label),
+            ("weight", batch.weight),
+            ("base_margin", batch.base_margin),
+        ] {
+            if let Some(array) = array {
+                let field = CString::new(field).unwrap();
+                let interface = CString::new(array.array_interface()).unwrap();
+                XGBoostError::check_return_value(unsafe {
+                    sys::XGDMatrixSetInfoFromInterface(
+                        self.proxy,
+                        field.as_ptr(),
+                        interface.as_ptr(),
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(xgboost_quantile_dmatrix)]
+pub(crate) unsafe extern "C" fn batch_iter_reset(handle: sys::DataIterHandle) {
+    let iter = &mut *(handle as *mut BatchIter);
+    iter.position = 0;
+}
+
+/// Feed the next batch into the proxy; returns 0 once all batches (or an error) are done.
+#[cfg(xgboost_quantile_dmatrix)]
+pub(crate) unsafe extern "C" fn batch_iter_next(handle: sys::DataIterHandle) -> c_int {
+    let iter = &mut *(handle as *mut BatchIter);
+    if iter.error.is_some() || iter.position >= iter.batches.len() {
+        return 0;
+    }
+    match iter.set_batch(&iter.batches[iter.position]) {
+        Ok(()) => {
+            iter.position += 1;
+            1
+        }
+        Err(e) => {
+            iter.error = Some(e);
+            0
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::dmatrix::DMatrix;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::json;
+use crate::sys;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+/// Histogram bin boundaries of every feature, from
+/// [`DMatrix::quantile_cuts`].
+///
+/// For a numerical feature the cut values are the upper bounds of its
+/// bins, in increasing order: a value falls into the first bin whose bound
+/// is above it, or the last bin. For a categorical feature they are the
+/// categories.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantileCuts {
+    indptr: Vec<u64>,
+    values: Vec<f32>,
+}
+
+impl QuantileCuts {
+    /// Number of features
+    pub fn num_features(&self) -> usize {
+        self.indptr.len().saturating_sub(1)
+    }
+
+    /// Cut values of a feature, empty if it has none or is out of range
+    pub fn feature(&self, feature: usize) -> &[f32] {
+        match (self.indptr.get(feature), self.indptr.get(feature + 1)) {
+            (Some(&start), Some(&end)) => &self.values[start as usize..end as usize],
+            _ => &[],
+        }
+    }
+
+    /// Number of bins of a feature
+    pub fn num_bins(&self, feature: usize) -> usize {
+        self.feature(feature).len()
+    }
+
+    /// Bin of a numerical feature's value, as XGBoost's `hist` method
+    /// assigns it; `None` for NaN (missing) or a feature without cuts
+    pub fn bin(&self, feature: usize, value: f32) -> Option<usize> {
+        let cuts = self.feature(feature);
+        if value.is_nan() || cuts.is_empty() {
+            return None;
+        }
+        Some(
+            cuts.partition_point(|&cut| cut <= value)
+                .min(cuts.len() - 1),
+        )
+    }
+
+    /// Offsets of each feature's cuts into [`QuantileCuts::values`], one
+    /// more than the number of features
+    pub fn indptr(&self) -> &[u64] {
+        &self.indptr
+    }
+
+    /// Cut values of all features, concatenated
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+impl DMatrix {
+    /// Histogram bin boundaries XGBoost computed for this matrix
+    ///
+    /// Available for a `QuantileDMatrix`, and for a `DMatrix` once it has
+    /// been used to train with `tree_method=hist` (or `approx`). Requires
+    /// XGBoost ≥ 2.0.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{train, BoosterParams, DMatrix, TreeMethod};
+    ///
+    /// let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]; // 3 rows, 2 features
+    /// let mut dtrain = DMatrix::from_dense(&data, 3, 2).unwrap();
+    /// dtrain.set_label(&[0.0, 1.0, 0.0]).unwrap();
+    /// let params = BoosterParams::new().tree_method(TreeMethod::Hist);
+    /// train(&params, &dtrain, 1).unwrap();
+    ///
+    /// let cuts = dtrain.quantile_cuts().unwrap();
+    /// println!("feature 0 bins: {:?}", cuts.feature(0));
+    /// println!("2.5 falls into bin {:?}", cuts.bin(0, 2.5));
+    /// ```
+    pub fn quantile_cuts(&self) -> XGBoostResult<QuantileCuts> {
+        let config = CString::new("{}").unwrap();
+        let mut out_indptr: *const c_char = ptr::null();
+        let mut out_data: *const c_char = ptr::null();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixGetQuantileCut(
+                self.handle(),
+                config.as_ptr(),
+                &mut out_indptr,
+                &mut out_data,
+            )
+        })?;
+        if out_indptr.is_null() || out_data.is_null() {
+            return Err(XGBoostError {
+                description: "XGBoost returned no quantile cuts".to_string(),
+            });
+        }
+
+        // the arrays live in the DMatrix; copy them before it changes
+        let indptr: Vec<u64> = unsafe { copy_array(out_indptr, "<u8")? };
+        let values: Vec<f32> = unsafe { copy_array(out_data, "<f4")? };
+        if indptr.last().map_or(0, |&end| end as usize) > values.len() {
+            return Err(XGBoostError {
+                description: "Quantile cut offsets exceed the cut values".to_string(),
+            });
+        }
+        Ok(QuantileCuts { indptr, values })
+    }
+}
+
+/// Copy of a 1-D array described by `__array_interface__` JSON
+///
+/// # Safety
+/// `interface` must be a NUL-terminated description of live data of type
+/// `T`, matching `typestr`.
+unsafe fn copy_array<T: Copy>(interface: *const c_char, typestr: &str) -> XGBoostResult<Vec<T>> {
+    let text = CStr::from_ptr(interface).to_bytes();
+    let invalid = |what: &str| XGBoostError {
+        description: format!(
+            "Unexpected array interface from XGBoost ({}): {}",
+            what,
+            String::from_utf8_lossy(text)
+        ),
+    };
+    let document = json::parse_json(text)?;
+    if document.get("typestr").and_then(|t| t.as_str()) != Some(typestr) {
+        return Err(invalid("type"));
+    }
+    let len = match document.get("shape").and_then(|s| s.as_array()) {
+        Some([len]) => len.as_f64().ok_or_else(|| invalid("shape"))? as usize,
+        _ => return Err(invalid("shape")),
+    };
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    let address = document
+        .get("data")
+        .and_then(|d| d.as_array())
+        .and_then(|d| d.first())
+        .and_then(|a| a.as_f64())
+        .filter(|&a| a != 0.0)
+        .ok_or_else(|| invalid("data"))? as usize;
+    Ok(std::slice::from_raw_parts(address as *const T, len).to_vec())
+}
@@ -0,0 +1,573 @@
+use crate::dmatrix::DMatrix;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::model::{parse_eval_result, Booster};
+use crate::params::BoosterParams;
+use crate::predict_options::PredictOptions;
+use crate::rng::SplitMix64;
+use crate::split::{class_rows, Split};
+use crate::train::{EarlyStopping, EvalHistory};
+use std::collections::BTreeMap;
+
+/// Per-round evaluation results of one fold, dataset → metric → value
+type FoldResults = BTreeMap<String, BTreeMap<String, f64>>;
+
+/// K-fold cross-validation
+///
+/// Shorthand for [`CrossValidation`] with its defaults.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{cv, BoosterParams, DMatrix, Objective};
+///
+/// let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]; // 4 rows, 2 features
+/// let mut dmatrix = DMatrix::from_dense(&data, 4, 2).unwrap();
+/// dmatrix.set_label(&[0.0, 1.0, 0.0, 1.0]).unwrap();
+///
+/// let params = BoosterParams::new().objective(Objective::BinaryLogistic);
+/// let result = cv(&params, &dmatrix, 10, 2).unwrap();
+/// println!("{:?}", result.mean.get("test", "logloss"));
+/// ```
+pub fn cv(
+    params: &BoosterParams,
+    dmatrix: &DMatrix,
+    num_boost_round: usize,
+    nfold: usize,
+) -> XGBoostResult<CvResult> {
+    CrossValidation::new(params, dmatrix)
+        .num_boost_round(num_boost_round)
+        .nfold(nfold)
+        .run()
+}
+
+/// Out-of-fold predictions of K-fold cross-validation
+///
+/// Shorthand for [`CrossValidation::run_predict`] with its defaults.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{cv_predict, BoosterParams, DMatrix, Objective};
+///
+/// let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]; // 4 rows, 2 features
+/// let mut dmatrix = DMatrix::from_dense(&data, 4, 2).unwrap();
+/// dmatrix.set_label(&[0.0, 1.0, 0.0, 1.0]).unwrap();
+///
+/// let params = BoosterParams::new().objective(Objective::BinaryLogistic);
+/// let oof = cv_predict(&params, &dmatrix, 10, 2).unwrap();
+/// assert_eq!(oof.predictions.len(), 4);
+/// ```
+pub fn cv_predict(
+    params: &BoosterParams,
+    dmatrix: &DMatrix,
+    num_boost_round: usize,
+    nfold: usize,
+) -> XGBoostResult<CvPredictions> {
+    CrossValidation::new(params, dmatrix)
+        .num_boost_round(num_boost_round)
+        .nfold(nfold)
+        .run_predict(PredictOptions::empty())
+}
+
+/// K-fold cross-validation with early stopping.
+///
+/// Every fold trains on the other folds and is evaluated as `train` and
+/// `test` after each round; the results are averaged over the folds.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{BoosterParams, CrossValidation, DMatrix, Objective};
+///
+/// # let dmatrix = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+/// let params = BoosterParams::new()
+///     .objective(Objective::BinaryLogistic)
+///     .eval_metric("auc");
+/// let result = CrossValidation::new(&params, &dmatrix)
+///     .nfold(5)
+///     .num_boost_round(500)
+///     .early_stopping_rounds(20)
+///     .seed(42)
+///     .run()
+///     .unwrap();
+/// println!("best round {:?}: {:?}", result.best_iteration, result.best_score);
+/// ```
+#[derive(Clone)]
+pub struct CrossValidation<'a> {
+    params: &'a BoosterParams,
+    dmatrix: &'a DMatrix,
+    nfold: usize,
+    num_boost_round: usize,
+    early_stopping_rounds: Option<usize>,
+    maximize: Option<bool>,
+    shuffle: bool,
+    stratified: bool,
+    folds: Option<Vec<Split>>,
+    seed: u64,
+    #[cfg(xgboost_thread_safe)]
+    parallel: bool,
+}
+
+impl<'a> CrossValidation<'a> {
+    /// 3-fold cross-validation of `params` on `dmatrix` for 10 rounds
+    pub fn new(params: &'a BoosterParams, dmatrix: &'a DMatrix) -> Self {
+        CrossValidation {
+            params,
+            dmatrix,
+            nfold: 3,
+            num_boost_round: 10,
+            early_stopping_rounds: None,
+            maximize: None,
+            shuffle: true,
+            stratified: false,
+            folds: None,
+            seed: 0,
+            #[cfg(xgboost_thread_safe)]
+            parallel: false,
+        }
+    }
+
+    /// Number of folds
+    pub fn nfold(mut self, nfold: usize) -> Self {
+        self.nfold = nfold;
+        self
+    }
+
+    /// Maximum number of boosting rounds
+    pub fn num_boost_round(mut self, num_boost_round: usize) -> Self {
+        self.num_boost_round = num_boost_round;
+        self
+    }
+
+    /// Stop when the mean `test` score has not improved for `rounds`
+    ///
+    /// The metric is chosen as for [`Trainer::early_stopping_rounds`].
+    ///
+    /// [`Trainer::early_stopping_rounds`]: crate::Trainer::early_stopping_rounds
+    pub fn early_stopping_rounds(mut self, rounds: usize) -> Self {
+        self.early_stopping_rounds = Some(rounds);
+        self
+    }
+
+    /// Whether a higher score is better for early stopping
+    pub fn maximize(mut self, maximize: bool) -> Self {
+        self.maximize = Some(maximize);
+        self
+    }
+
+    /// Shuffle rows before splitting them into folds (default `true`)
+    pub fn shuffle(mut self, shuffle: bool) -> Self {
+        self.shuffle = shuffle;
+        self
+    }
+
+    /// Keep the class proportions of the labels in every fold
+    ///
+    /// For classification, and in particular imbalanced data, where plain
+    /// folds can end up with hardly any samples of a rare class.
+    pub fn stratified(mut self, stratified: bool) -> Self {
+        self.stratified = stratified;
+        self
+    }
+
+    /// Use the given train/test splits instead of random folds
+    ///
+    /// For instance the splits of a [`TimeSeriesSplit`], so that no fold is
+    /// evaluated on data that precedes its training data. `nfold`,
+    /// `shuffle` and `stratified` are then ignored.
+    ///
+    /// [`TimeSeriesSplit`]: crate::split::TimeSeriesSplit
+    pub fn folds(mut self, folds: Vec<Split>) -> Self {
+        self.folds = Some(folds);
+        self
+    }
+
+    /// Seed of the shuffle
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Train the folds on separate threads (XGBoost ≥ 1.4)
+    ///
+    /// Each fold still uses `nthread` threads, so consider lowering it.
+    #[cfg(xgboost_thread_safe)]
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Run cross-validation
+    pub fn run(&self) -> XGBoostResult<CvResult> {
+        Ok(self.run_folds()?.0)
+    }
+
+    /// Run cross-validation and predict every row with the model of the
+    /// fold that held it out
+    ///
+    /// Out-of-fold predictions are unbiased estimates of how the model
+    /// scores unseen rows, as needed to fit a stacking meta-model or a
+    /// calibrator. With early stopping, the fold models predict up to the
+    /// best mean round (XGBoost ≥ 1.4). Rows held out by several folds get
+    /// the mean of their predictions and rows held out by none (e.g. the
+    /// first block of a [`TimeSeriesSplit`]) get NaN.
+    ///
+    /// [`TimeSeriesSplit`]: crate::split::TimeSeriesSplit
+    ///
+    /// # Arguments
+    /// * `options` - Prediction options, e.g. `OUTPUT_MARGIN` for stacking
+    ///   on margins
+    pub fn run_predict(&self, options: PredictOptions) -> XGBoostResult<CvPredictions> {
+        let (result, mut folds) = self.run_folds()?;
+        let num_rows = self.dmatrix.num_rows()?;
+        let mut sums: Vec<f64> = Vec::new();
+        let mut counts = vec![0u32; num_rows];
+        let mut num_outputs = 0;
+        for fold in &mut folds {
+            if let Some(best_iteration) = result.best_iteration {
+                fold.booster
+                    .set_attr("best_iteration", &best_iteration.to_string())?;
+            }
+            if fold.test_rows.is_empty() {
+                continue;
+            }
+            let predictions = fold.booster.predict_dmatrix(&fold.dtest, options, false)?;
+            let fold_outputs = predictions.len() / fold.test_rows.len();
+            if num_outputs == 0 {
+                num_outputs = fold_outputs;
+                sums = vec![0.0; num_rows * num_outputs];
+            } else if fold_outputs != num_outputs {
+                return Err(XGBoostError {
+                    description: format!(
+                        "Folds predicted {} and {} outputs per row",
+                        num_outputs, fold_outputs
+                    ),
+                });
+            }
+            for (&row, values) in fold.test_rows.iter().zip(predictions.chunks(num_outputs)) {
+                counts[row] += 1;
+                for (sum, &value) in sums[row * num_outputs..].iter_mut().zip(values) {
+                    *sum += f64::from(value);
+                }
+            }
+        }
+        let predictions = sums
+            .iter()
+            .enumerate()
+            .map(|(i, &sum)| match counts[i / num_outputs] {
+                0 => f32::NAN,
+                count => (sum / f64::from(count)) as f32,
+            })
+            .collect();
+
+        let (folds, models) = folds
+            .into_iter()
+            .map(|fold| (fold.test_rows, fold.booster))
+            .unzip();
+        Ok(CvPredictions {
+            result,
+            predictions,
+            num_outputs,
+            folds,
+            models,
+        })
+    }
+
+    fn run_folds(&self) -> XGBoostResult<(CvResult, Vec<Fold>)> {
+        let mut folds = self.make_folds()?;
+        let mut mean = EvalHistory::default();
+        let mut std = EvalHistory::default();
+        let mut stopper: Option<EarlyStopping> = None;
+        for iteration in 0..self.num_boost_round {
+            let results = self.step(&mut folds, iteration)?;
+            let (round_mean, round_std) = aggregate(&results);
+            mean.push(&round_mean);
+            std.push(&round_std);
+
+            if let Some(rounds) = self.early_stopping_rounds {
+                let test = round_mean.get("test").ok_or_else(|| XGBoostError {
+                    description: format!(
+                        "Early stopping needs a test metric, but round {} reported none; set eval_metric",
+                        iteration
+                    ),
+                })?;
+                let stopper = match &mut stopper {
+                    Some(stopper) => stopper,
+                    None => stopper.insert(EarlyStopping::new(
+                        self.params,
+                        "test",
+                        test,
+                        self.maximize,
+                    )?),
+                };
+                if stopper.update(iteration, "test", test, rounds)? {
+                    break;
+                }
+            }
+        }
+
+        let result = CvResult {
+            mean,
+            std,
+            metric: stopper.as_ref().map(|s| s.metric.clone()),
+            best_iteration: stopper.as_ref().map(|s| s.best_iteration),
+            best_score: stopper.as_ref().map(|s| s.best_score),
+        };
+        Ok((result, folds))
+    }
+
+    fn make_folds(&self) -> XGBoostResult<Vec<Fold>> {
+        if let Some(splits) = &self.folds {
+            if splits.is_empty() {
+                return Err(XGBoostError {
+                    description: "Cross-validation needs at least one fold".to_string(),
+                });
+            }
+            return splits
+                .iter()
+                .map(|split| {
+                    let (dtrain, dtest) = split.dmatrices(self.dmatrix)?;
+                    Fold::new(self.params, dtrain, dtest, split.test.clone())
+                })
+                .collect();
+        }
+
+        let num_rows = self.dmatrix.num_rows()?;
+        if self.nfold < 2 || self.nfold > num_rows {
+            return Err(XGBoostError {
+                description: format!(
+                    "nfold must be between 2 and the number of rows ({}), got {}",
+                    num_rows, self.nfold
+                ),
+            });
+        }
+
+        let labels = self.stratified.then(|| self.dmatrix.label()).transpose()?;
+        let assignment = assign_folds(
+            num_rows,
+            self.nfold,
+            labels.as_deref(),
+            self.shuffle,
+            self.seed,
+        );
+
+        (0..self.nfold)
+            .map(|fold| {
+                let (test, train): (Vec<usize>, Vec<usize>) =
+                    (0..num_rows).partition(|&row| assignment[row] == fold);
+                Fold::new(
+                    self.params,
+                    self.dmatrix.slice(&train)?,
+                    self.dmatrix.slice(&test)?,
+                    test,
+                )
+            })
+            .collect()
+    }
+
+    /// Boost every fold by one round and evaluate it
+    fn step(&self, folds: &mut [Fold], iteration: usize) -> XGBoostResult<Vec<FoldResults>> {
+        #[cfg(xgboost_thread_safe)]
+        if self.parallel {
+            return std::thread::scope(|scope| {
+                let handles: Vec<_> = folds
+                    .iter_mut()
+                    .map(|fold| scope.spawn(move || fold.step(iteration)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                    })
+                    .collect()
+            });
+        }
+        folds.iter_mut().map(|fold| fold.step(iteration)).collect()
+    }
+}
+
+/// The fold holding out each row: contiguous blocks of (shuffled) rows,
+/// or with `labels`, the rows of each class dealt round-robin
+fn assign_folds(
+    num_rows: usize,
+    nfold: usize,
+    labels: Option<&[f32]>,
+    shuffle: bool,
+    seed: u64,
+) -> Vec<usize> {
+    let mut rng = SplitMix64::new(seed);
+    let mut assignment = vec![0; num_rows];
+    if let Some(labels) = labels {
+        // continuing across classes keeps the folds within a row of each other
+        let mut classes = class_rows(labels);
+        let mut position = 0;
+        for rows in &mut classes {
+            if shuffle {
+                rng.shuffle(rows);
+            }
+            for &row in rows.iter() {
+                assignment[row] = position % nfold;
+                position += 1;
+            }
+        }
+    } else {
+        let mut rows: Vec<usize> = (0..num_rows).collect();
+        if shuffle {
+            rng.shuffle(&mut rows);
+        }
+        for (position, &row) in rows.iter().enumerate() {
+            assignment[row] = position * nfold / num_rows;
+        }
+    }
+    assignment
+}
+
+/// Result of cross-validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CvResult {
+    /// Mean over folds of every `train` and `test` metric, per round
+    pub mean: EvalHistory,
+    /// Standard deviation over folds, per round
+    pub std: EvalHistory,
+    /// Metric monitored by early stopping
+    pub metric: Option<String>,
+    /// Round with the best mean `test` score
+    pub best_iteration: Option<usize>,
+    /// Best mean `test` score
+    pub best_score: Option<f64>,
+}
+
+/// Out-of-fold predictions of [`CrossValidation::run_predict`].
+pub struct CvPredictions {
+    /// The cross-validation scores
+    pub result: CvResult,
+    /// Row-major predictions of every row by the model that did not train
+    /// on it, `num_outputs` per row
+    pub predictions: Vec<f32>,
+    /// Predictions per row (e.g. the number of classes)
+    pub num_outputs: usize,
+    /// Rows held out by each fold
+    pub folds: Vec<Vec<usize>>,
+    /// The model of each fold, trained on the other rows
+    pub models: Vec<Booster>,
+}
+
+/// One fold's data and model.
+struct Fold {
+    dtrain: DMatrix,
+    dtest: DMatrix,
+    test_rows: Vec<usize>,
+    booster: Booster,
+}
+
+impl Fold {
+    fn new(
+        params: &BoosterParams,
+        dtrain: DMatrix,
+        dtest: DMatrix,
+        test_rows: Vec<usize>,
+    ) -> XGBoostResult<Self> {
+        let mut booster = Booster::new(&[&dtrain, &dtest])?;
+        booster.set_params(params)?;
+        Ok(Fold {
+            dtrain,
+            dtest,
+            test_rows,
+            booster,
+        })
+    }
+
+    fn step(&mut self, iteration: usize) -> XGBoostResult<FoldResults> {
+        self.booster.update(&self.dtrain, iteration)?;
+        let raw = self
+            .booster
+            .eval_one_iter(&[(&self.dtrain, "train"), (&self.dtest, "test")], iteration)?;
+        parse_eval_result(&raw, &["train", "test"])
+    }
+}
+
+/// Mean and (population) standard deviation of each metric over folds
+fn aggregate(results: &[FoldResults]) -> (FoldResults, FoldResults) {
+    let mut mean = FoldResults::new();
+    let mut std = FoldResults::new();
+    for (dataset, metrics) in &results[0] {
+        for metric in metrics.keys() {
+            let values: Vec<f64> = results
+                .iter()
+                .filter_map(|fold| fold.get(dataset)?.get(metric).copied())
+                .collect();
+            let n = values.len() as f64;
+            let m = values.iter().sum::<f64>() / n;
+            let variance = values.iter().map(|v| (v - m) * (v - m)).sum::<f64>() / n;
+            mean.entry(dataset.clone())
+                .or_default()
+                .insert(metric.clone(), m);
+            std.entry(dataset.clone())
+                .or_default()
+                .insert(metric.clone(), variance.sqrt());
+        }
+    }
+    (mean, std)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fold_sizes(assignment: &[usize], nfold: usize) -> Vec<usize> {
+        (0..nfold)
+            .map(|fold| assignment.iter().filter(|&&f| f == fold).count())
+            .collect()
+    }
+
+    #[test]
+    fn folds_are_contiguous_without_shuffling() {
+        assert_eq!(
+            assign_folds(10, 3, None, false, 0),
+            [0, 0, 0, 0, 1, 1, 1, 2, 2, 2]
+        );
+    }
+
+    #[test]
+    fn shuffled_folds_are_balanced_and_seeded() {
+        let assignment = assign_folds(10, 3, None, true, 42);
+        let mut sizes = fold_sizes(&assignment, 3);
+        sizes.sort_unstable();
+        assert_eq!(sizes, [3, 3, 4]);
+        assert_eq!(assignment, assign_folds(10, 3, None, true, 42));
+        assert_ne!(assignment, assign_folds(10, 3, None, false, 42));
+    }
+
+    #[test]
+    fn stratified_folds_deal_each_class() {
+        let labels = [1.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+        assert_eq!(
+            assign_folds(6, 2, Some(&labels), false, 0),
+            [1, 0, 0, 1, 0, 1]
+        );
+
+        let labels: Vec<f32> = (0..30).map(|i| (i % 3 == 0) as u8 as f32).collect();
+        let assignment = assign_folds(30, 5, Some(&labels), true, 7);
+        assert_eq!(fold_sizes(&assignment, 5), [6; 5]);
+        for fold in 0..5 {
+            let positives = (0..30)
+                .filter(|&row| assignment[row] == fold && labels[row] == 1.0)
+                .count();
+            assert_eq!(positives, 2);
+        }
+    }
+
+    #[test]
+    fn metrics_are_averaged_over_folds() {
+        let fold = |rmse: f64| {
+            let mut results = FoldResults::new();
+            results
+                .entry("test".to_string())
+                .or_default()
+                .insert("rmse".to_string(), rmse);
+            results
+        };
+        let (mean, std) = aggregate(&[fold(1.0), fold(3.0)]);
+        assert_eq!(mean["test"]["rmse"], 2.0);
+        assert_eq!(std["test"]["rmse"], 1.0);
+    }
+}
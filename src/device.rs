@@ -0,0 +1,88 @@
+use crate::error::XGBoostError;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Device used to run predictions.
+///
+/// Parses from the same strings XGBoost accepts for its `device` parameter:
+/// `"cpu"`, `"cuda"`, `"gpu"` (both meaning the first GPU) and `"cuda:<ordinal>"`.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::Device;
+///
+/// assert_eq!("cuda:1".parse::<Device>().unwrap(), Device::Cuda(1));
+/// assert_eq!(Device::Cuda(0).to_string(), "cuda:0");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Device {
+    /// Run on the CPU
+    #[default]
+    Cpu,
+    /// Run on the CUDA device with the given ordinal
+    Cuda(usize),
+}
+
+impl Device {
+    /// Whether this is a GPU device
+    pub fn is_cuda(&self) -> bool {
+        matches!(self, Device::Cuda(_))
+    }
+}
+
+impl fmt::Display for Device {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Device::Cpu => write!(f, "cpu"),
+            Device::Cuda(ordinal) => write!(f, "cuda:{}", ordinal),
+        }
+    }
+}
+
+impl FromStr for Device {
+    type Err = XGBoostError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        match lower.as_str() {
+            "cpu" => return Ok(Device::Cpu),
+            "cuda" | "gpu" => return Ok(Device::Cuda(0)),
+            _ => {}
+        }
+        lower
+            .strip_prefix("cuda:")
+            .or_else(|| lower.strip_prefix("gpu:"))
+            .and_then(|ordinal| ordinal.parse().ok())
+            .map(Device::Cuda)
+            .ok_or_else(|| XGBoostError {
+                description: format!(
+                    "Invalid device '{}': expected \"cpu\", \"cuda\" or \"cuda:<ordinal>\"",
+                    s
+                ),
+            })
+    }
+}
+
+impl TryFrom<&str> for Device {
+    type Error = XGBoostError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for Device {
+    type Error = XGBoostError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+// Lets `set_device` accept a `Device` directly as well as strings
+impl From<Infallible> for XGBoostError {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::error::XGBoostResult;
+use crate::gbm::{GradientBooster, Learner, Tree};
+use crate::Booster;
+
+/// A value that differs between two models (`None` where it is absent).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueChange {
+    pub name: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// How one tree differs between two models.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeDiff {
+    /// Index of the tree in both models
+    pub tree: usize,
+    /// Number of nodes in the old and new tree
+    pub num_nodes: (usize, usize),
+    /// Whether the shape or the split features differ; when they do, the
+    /// counts below are not computed
+    pub structure_changed: bool,
+    /// Number of split thresholds (or category sets) that differ
+    pub changed_splits: usize,
+    /// Number of leaf values that differ
+    pub changed_leaves: usize,
+    /// Largest absolute change of a leaf value
+    pub max_leaf_delta: f32,
+}
+
+/// Differences between two models, from [`compare_models`].
+///
+/// Its `Display` implementation prints a human-readable report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelDiff {
+    /// Model parameters (`learner_model_param`, objective and booster
+    /// settings) that differ, by dotted path
+    pub params: Vec<ValueChange>,
+    /// Model attributes (e.g. `best_iteration`) that differ
+    pub attributes: Vec<ValueChange>,
+    /// Number of trees of the old and new model
+    pub num_trees: (usize, usize),
+    /// Number of input features of the old and new model
+    pub num_features: (usize, usize),
+    /// Features split on only by the old model
+    pub features_removed: Vec<String>,
+    /// Features split on only by the new model
+    pub features_added: Vec<String>,
+    /// Trees present in both models that differ, in order
+    pub changed_trees: Vec<TreeDiff>,
+    /// Number of `gblinear` weights that differ
+    pub changed_linear_weights: usize,
+}
+
+impl ModelDiff {
+    /// Whether the models are equivalent
+    pub fn is_identical(&self) -> bool {
+        self.params.is_empty()
+            && self.attributes.is_empty()
+            && self.num_trees.0 == self.num_trees.1
+            && self.num_features.0 == self.num_features.1
+            && self.features_removed.is_empty()
+            && self.features_added.is_empty()
+            && self.changed_trees.is_empty()
+            && self.changed_linear_weights == 0
+    }
+}
+
+impl fmt::Display for ModelDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_identical() {
+            return writeln!(f, "models are identical");
+        }
+        let show = |value: &Option<String>| value.clone().unwrap_or_else(|| "<none>".to_string());
+        for (title, changes) in [("parameter", &self.params), ("attribute", &self.attributes)] {
+            for change in changes {
+                writeln!(
+                    f,
+                    "{} {}: {} -> {}",
+                    title,
+                    change.name,
+                    show(&change.old),
+                    show(&change.new)
+                )?;
+            }
+        }
+        if self.num_features.0 != self.num_features.1 {
+            writeln!(
+                f,
+                "features: {} -> {}",
+                self.num_features.0, self.num_features.1
+            )?;
+        }
+        if !self.features_removed.is_empty() {
+            writeln!(f, "no longer used: {}", self.features_removed.join(", "))?;
+        }
+        if !self.features_added.is_empty() {
+            writeln!(f, "newly used: {}", self.features_added.join(", "))?;
+        }
+        if self.num_trees.0 != self.num_trees.1 {
+            writeln!(f, "trees: {} -> {}", self.num_trees.0, self.num_trees.1)?;
+        }
+        for tree in &self.changed_trees {
+            if tree.structure_changed {
+                writeln!(
+                    f,
+                    "tree {}: structure changed ({} -> {} nodes)",
+                    tree.tree, tree.num_nodes.0, tree.num_nodes.1
+                )?;
+            } else {
+                writeln!(
+                    f,
+                    "tree {}: {} splits and {} leaves changed (max leaf delta {})",
+                    tree.tree, tree.changed_splits, tree.changed_leaves, tree.max_leaf_delta
+                )?;
+            }
+        }
+        if self.changed_linear_weights > 0 {
+            writeln!(f, "linear weights changed: {}", self.changed_linear_weights)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare two models, e.g. two nightly versions
+///
+/// Reports differing model parameters and attributes, the number of trees,
+/// the features the models split on, and per-tree structural changes for
+/// the trees both models have. Training-only parameters (`eta`,
+/// `max_depth`, …) are not stored in model files and cannot be compared.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{compare_models, Booster};
+///
+/// let old = Booster::load("model-2024-05-01.json").unwrap();
+/// let new = Booster::load("model-2024-05-02.json").unwrap();
+/// let diff = compare_models(&old, &new).unwrap();
+/// print!("{}", diff);
+/// ```
+pub fn compare_models(old: &Booster, new: &Booster) -> XGBoostResult<ModelDiff> {
+    Ok(compare_learners(&old.learner()?, &new.learner()?))
+}
+
+fn compare_learners(old: &Learner, new: &Learner) -> ModelDiff {
+    let (old_trees, new_trees) = (trees(old), trees(new));
+    let changed_trees = old_trees
+        .iter()
+        .zip(new_trees)
+        .enumerate()
+        .filter_map(|(i, (a, b))| compare_trees(i, a, b))
+        .collect();
+
+    let (old_features, new_features) = (used_features(old), used_features(new));
+    let changed_linear_weights = match (&old.booster, &new.booster) {
+        (GradientBooster::Linear(a), GradientBooster::Linear(b)) => {
+            if a.weights.len() == b.weights.len() {
+                a.weights
+                    .iter()
+                    .zip(&b.weights)
+                    .filter(|(x, y)| x != y)
+                    .count()
+            } else {
+                a.weights.len().max(b.weights.len())
+            }
+        }
+        _ => 0,
+    };
+
+    ModelDiff {
+        params: compare_values(&old.params, &new.params),
+        attributes: compare_values(&old.attributes, &new.attributes),
+        num_trees: (old_trees.len(), new_trees.len()),
+        num_features: (old.num_feature, new.num_feature),
+        features_removed: old_features.difference(&new_features).cloned().collect(),
+        features_added: new_features.difference(&old_features).cloned().collect(),
+        changed_trees,
+        changed_linear_weights,
+    }
+}
+
+fn trees(learner: &Learner) -> &[Tree] {
+    match &learner.booster {
+        GradientBooster::Tree(ensemble) => &ensemble.trees,
+        GradientBooster::Linear(_) => &[],
+    }
+}
+
+/// Names of the features a model splits on (`f<index>` without feature names)
+fn used_features(learner: &Learner) -> BTreeSet<String> {
+    trees(learner)
+        .iter()
+        .flat_map(|tree| tree.nodes.iter().filter(|node| !node.is_leaf()))
+        .map(|node| {
+            let index = node.split_index as usize;
+            learner
+                .feature_names
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| format!("f{}", index))
+        })
+        .collect()
+}
+
+fn compare_values(old: &[(String, String)], new: &[(String, String)]) -> Vec<ValueChange> {
+    let old: BTreeMap<&str, &str> = old.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    let new: BTreeMap<&str, &str> = new.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    let names: BTreeSet<&str> = old.keys().chain(new.keys()).copied().collect();
+    names
+        .into_iter()
+        .filter(|name| old.get(name) != new.get(name))
+        .map(|name| ValueChange {
+            name: name.to_string(),
+            old: old.get(name).map(|v| v.to_string()),
+            new: new.get(name).map(|v| v.to_string()),
+        })
+        .collect()
+}
+
+fn compare_trees(index: usize, old: &Tree, new: &Tree) -> Option<TreeDiff> {
+    let mut diff = TreeDiff {
+        tree: index,
+        num_nodes: (old.nodes.len(), new.nodes.len()),
+        structure_changed: false,
+        changed_splits: 0,
+        changed_leaves: 0,
+        max_leaf_delta: 0.0,
+    };
+    let same_shape = old.nodes.len() == new.nodes.len()
+        && old.nodes.iter().zip(&new.nodes).all(|(a, b)| {
+            (a.left, a.right) == (b.left, b.right)
+                && (a.is_leaf() || a.split_index == b.split_index)
+        });
+    if !same_shape {
+        diff.structure_changed = true;
+        return Some(diff);
+    }
+
+    for (a, b) in old.nodes.iter().zip(&new.nodes) {
+        if a.is_leaf() {
+            if a.split_condition != b.split_condition {
+                diff.changed_leaves += 1;
+                diff.max_leaf_delta = diff
+                    .max_leaf_delta
+                    .max((a.split_condition - b.split_condition).abs());
+            }
+        } else if a.split_condition != b.split_condition
+            || a.default_left != b.default_left
+            || a.categories != b.categories
+        {
+            diff.changed_splits += 1;
+        }
+    }
+    (diff.changed_splits > 0 || diff.changed_leaves > 0).then_some(diff)
+}
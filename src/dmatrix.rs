@@ -0,0 +1,1145 @@
+use crate::backend::check_dense_len;
+use crate::convert;
+#[cfg(feature = "cuda")]
+use crate::cuda::CudaArray;
+#[cfg(all(feature = "cuda", xgboost_quantile_dmatrix))]
+use crate::cuda::{self, CudaBatch};
+use crate::error::{XGBoostError, XGBoostResult};
+#[cfg(xgboost_feature_score)]
+use crate::features::FeatureType;
+use crate::sys;
+use std::ffi::CString;
+use std::ptr;
+
+/// XGBoost's internal data matrix.
+///
+/// Building a `DMatrix` once and passing it to [`Booster::predict_dmatrix`]
+/// avoids re-copying the data on every prediction call.
+///
+/// [`Booster::predict_dmatrix`]: crate::Booster::predict_dmatrix
+pub struct DMatrix {
+    handle: sys::DMatrixHandle,
+}
+
+// A DMatrix is not tied to the thread that created it, so it can be moved to
+// (e.g. a cross-validation fold trained on) another thread.
+unsafe impl Send for DMatrix {}
+
+impl DMatrix {
+    /// Create a DMatrix from a dense row-major array
+    ///
+    /// `NaN` values are treated as missing.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let dmatrix = DMatrix::from_dense(&data, 2, 2).unwrap();
+    /// ```
+    pub fn from_dense(data: &[f32], num_rows: usize, num_features: usize) -> XGBoostResult<Self> {
+        Self::from_dense_with_missing(data, num_rows, num_features, f32::NAN)
+    }
+
+    /// Create a DMatrix from dense data with a custom missing value sentinel
+    ///
+    /// Entries equal to `missing` (e.g. `-999.0`) are treated as missing.
+    /// When `missing` is not NaN, the data must not contain NaN values.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `missing` - Value that marks a missing entry
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let data = vec![1.0, -999.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let dmatrix = DMatrix::from_dense_with_missing(&data, 2, 2, -999.0).unwrap();
+    /// ```
+    pub fn from_dense_with_missing(
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        missing: f32,
+    ) -> XGBoostResult<Self> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+
+        let mut handle: sys::DMatrixHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixCreateFromMat(
+                data.as_ptr(),
+                num_rows as u64,
+                num_features as u64,
+                missing,
+                &mut handle,
+            )
+        })?;
+
+        Ok(DMatrix { handle })
+    }
+
+    /// Create a DMatrix from rows, e.g. `Vec<Vec<f32>>` or `&[&[f32]]`
+    ///
+    /// All rows must have the same length, the number of features. `NaN`
+    /// values are treated as missing.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let rows = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+    /// let dmatrix = DMatrix::from_rows(&rows).unwrap();
+    /// ```
+    pub fn from_rows<R: AsRef<[f32]>>(rows: &[R]) -> XGBoostResult<Self> {
+        let num_features = rows.first().map_or(0, |row| row.as_ref().len());
+        let mut data = Vec::with_capacity(rows.len() * num_features);
+        for (i, row) in rows.iter().enumerate() {
+            let row = row.as_ref();
+            if row.len() != num_features {
+                return Err(XGBoostError {
+                    description: format!(
+                        "Row {} has {} values, but row 0 has {}",
+                        i,
+                        row.len(),
+                        num_features
+                    ),
+                });
+            }
+            data.extend_from_slice(row);
+        }
+        Self::from_dense(&data, rows.len(), num_features)
+    }
+
+    /// Create a DMatrix from dense `f64` data without converting it up front
+    ///
+    /// The data is handed to XGBoost through the array interface. Requires
+    /// XGBoost ≥ 1.7.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let data = vec![1.0f64, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let dmatrix = DMatrix::from_dense_f64(&data, 2, 2).unwrap();
+    /// ```
+    #[cfg(xgboost_array_interface)]
+    pub fn from_dense_f64(
+        data: &[f64],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Self> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+        let interface = f64_array_interface(data, num_rows, num_features);
+        let config = CString::new("{\"missing\": NaN, \"nthread\": 0}").unwrap();
+
+        let mut handle: sys::DMatrixHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixCreateFromDense(interface.as_ptr(), config.as_ptr(), &mut handle)
+        })?;
+
+        Ok(DMatrix { handle })
+    }
+
+    /// Create a DMatrix from dense half-precision data
+    ///
+    /// Values are IEEE 754 binary16 bit patterns, as stored by `half::f16`
+    /// (`bytemuck::cast_slice` turns a `&[half::f16]` into a `&[u16]`
+    /// without copying) or f16 columns of Arrow and Polars. They are widened
+    /// to `f32` in one pass, exactly; NaN is treated as missing.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let data: Vec<u16> = vec![0x3c00, 0x4000, 0x4200, 0x4400]; // 1.0, 2.0, 3.0, 4.0
+    /// let dmatrix = DMatrix::from_dense_f16(&data, 2, 2).unwrap();
+    /// ```
+    pub fn from_dense_f16(
+        data: &[u16],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Self> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+        Self::from_dense(&convert::f16_to_f32(data), num_rows, num_features)
+    }
+
+    /// Create a DMatrix from data in CUDA device memory
+    ///
+    /// The data is read directly on the device and never copied to the host.
+    /// `NaN` values are treated as missing. Requires the `cuda` feature and a
+    /// CUDA-enabled libxgboost.
+    ///
+    /// # Arguments
+    /// * `array` - Device-resident matrix
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{CudaArray, DMatrix};
+    ///
+    /// # let device_ptr: u64 = 0;
+    /// // `device_ptr` points to 100 x 8 floats allocated with cudaMalloc
+    /// let array = unsafe { CudaArray::from_raw_parts(device_ptr, 100, 8) };
+    /// let dmatrix = DMatrix::from_cuda_array(&array).unwrap();
+    /// ```
+    #[cfg(feature = "cuda")]
+    pub fn from_cuda_array(array: &CudaArray) -> XGBoostResult<Self> {
+        let interface = CString::new(array.array_interface()).unwrap();
+        let config = CString::new("{\"missing\": NaN, \"nthread\": 0}").unwrap();
+
+        let mut handle: sys::DMatrixHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixCreateFromCudaArrayInterface(
+                interface.as_ptr(),
+                config.as_ptr(),
+                &mut handle,
+            )
+        })?;
+
+        Ok(DMatrix { handle })
+    }
+
+    /// Build a `QuantileDMatrix` from batches in CUDA device memory
+    ///
+    /// The features are sketched into histogram bins batch by batch on the
+    /// GPU, so the full dataset is never materialized as a regular DMatrix.
+    /// This is the memory-efficient input for `tree_method=hist` with
+    /// `device=cuda`. Requires the `cuda` feature and XGBoost ≥ 1.7.
+    ///
+    /// # Arguments
+    /// * `batches` - Device-resident batches (features plus optional label, weight and base margin)
+    /// * `max_bin` - Maximum number of histogram bins per feature (XGBoost's default is 256)
+    /// * `reference` - Training QuantileDMatrix whose cuts to reuse, for validation data
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{CudaArray, CudaBatch, DMatrix};
+    ///
+    /// # let (features_ptr, labels_ptr): (u64, u64) = (0, 0);
+    /// let batch = unsafe {
+    ///     CudaBatch::new(CudaArray::from_raw_parts(features_ptr, 1000, 8))
+    ///         .with_label(CudaArray::vector_from_raw_parts(labels_ptr, 1000))
+    /// };
+    /// let dtrain = DMatrix::quantile_from_cuda_batches(&[batch], 256, None).unwrap();
+    /// ```
+    #[cfg(all(feature = "cuda", xgboost_quantile_dmatrix))]
+    pub fn quantile_from_cuda_batches(
+        batches: &[CudaBatch],
+        max_bin: usize,
+        reference: Option<&DMatrix>,
+    ) -> XGBoostResult<Self> {
+        if batches.is_empty() {
+            return Err(XGBoostError {
+                description: "Cannot build a QuantileDMatrix from zero batches".to_string(),
+            });
+        }
+
+        let mut proxy: sys::DMatrixHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe { sys::XGProxyDMatrixCreate(&mut proxy) })?;
+        let proxy = DMatrix { handle: proxy };
+
+        let config = CString::new(format!(
+            "{{\"missing\": NaN, \"nthread\": 0, \"max_bin\": {}}}",
+            max_bin
+        ))
+        .unwrap();
+        let mut iter = cuda::BatchIter::new(batches, proxy.handle);
+        let mut handle: sys::DMatrixHandle = ptr::null_mut();
+        let result = XGBoostError::check_return_value(unsafe {
+            sys::XGQuantileDMatrixCreateFromCallback(
+                &mut iter as *mut cuda::BatchIter as sys::DataIterHandle,
+                proxy.handle,
+                reference.map_or(ptr::null_mut(), |r| r.handle),
+                Some(cuda::batch_iter_reset),
+                Some(cuda::batch_iter_next),
+                config.as_ptr(),
+                &mut handle,
+            )
+        });
+
+        let dmatrix = (!handle.is_null()).then_some(DMatrix { handle });
+        // Errors raised while feeding batches take precedence over XGBoost's own
+        if let Some(e) = iter.error {
+            return Err(e);
+        }
+        result?;
+        dmatrix.ok_or_else(|| XGBoostError {
+            description: "XGBoost returned a null QuantileDMatrix".to_string(),
+        })
+    }
+
+    /// Set a float meta-information field (`label`, `weight`, `base_margin`, ...)
+    ///
+    /// # Arguments
+    /// * `field` - Name of the field
+    /// * `values` - One value per row (or per row and output group for `base_margin`)
+    pub fn set_float_info(&mut self, field: &str, values: &[f32]) -> XGBoostResult<()> {
+        let field_c_str = CString::new(field).map_err(|e| XGBoostError {
+            description: format!("Field name contains NUL byte: {}", e),
+        })?;
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixSetFloatInfo(
+                self.handle,
+                field_c_str.as_ptr(),
+                values.as_ptr(),
+                values.len() as u64,
+            )
+        })
+    }
+
+    /// Get a float meta-information field
+    ///
+    /// # Arguments
+    /// * `field` - Name of the field
+    pub fn get_float_info(&self, field: &str) -> XGBoostResult<Vec<f32>> {
+        let field_c_str = CString::new(field).map_err(|e| XGBoostError {
+            description: format!("Field name contains NUL byte: {}", e),
+        })?;
+        let mut out_len: u64 = 0;
+        let mut out_dptr: *const f32 = ptr::null();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixGetFloatInfo(
+                self.handle,
+                field_c_str.as_ptr(),
+                &mut out_len,
+                &mut out_dptr,
+            )
+        })?;
+        if out_dptr.is_null() || out_len == 0 {
+            return Ok(Vec::new());
+        }
+        Ok(unsafe { std::slice::from_raw_parts(out_dptr, out_len as usize).to_vec() })
+    }
+
+    /// Set a per-row field, checking that there is exactly one value per row
+    fn set_row_info(&mut self, field: &str, values: &[f32]) -> XGBoostResult<()> {
+        let num_rows = self.num_rows()?;
+        if values.len() != num_rows {
+            return Err(XGBoostError {
+                description: format!(
+                    "{} length mismatch: expected {} values (one per row), got {}",
+                    field,
+                    num_rows,
+                    values.len()
+                ),
+            });
+        }
+        self.set_float_info(field, values)
+    }
+
+    /// Set the training labels
+    pub fn set_label(&mut self, labels: &[f32]) -> XGBoostResult<()> {
+        self.set_row_info("label", labels)
+    }
+
+    /// Set a label matrix for multi-output models, row-major with
+    /// `num_targets` labels per row
+    ///
+    /// Trains one output per target: with `multi_strategy` set to
+    /// [`MultiStrategy::MultiOutputTree`] a single tree predicts all targets
+    /// through vector leaves, otherwise each target gets its own trees.
+    /// Requires XGBoost ≥ 1.7 (multi-output trees ≥ 2.0).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{train, BoosterParams, DMatrix, MultiStrategy, TreeMethod};
+    ///
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let targets = vec![0.5, 1.5, 2.5, 3.5]; // 2 rows, 2 targets
+    /// let mut dtrain = DMatrix::from_dense(&data, 2, 2).unwrap();
+    /// dtrain.set_label_matrix(&targets, 2).unwrap();
+    ///
+    /// let params = BoosterParams::new()
+    ///     .tree_method(TreeMethod::Hist)
+    ///     .multi_strategy(MultiStrategy::MultiOutputTree);
+    /// let booster = train(&params, &dtrain, 10).unwrap();
+    /// let predictions = booster.predict_multi_output(&dtrain).unwrap();
+    /// assert_eq!(predictions.shape(), &[2, 2]);
+    /// ```
+    ///
+    /// [`MultiStrategy::MultiOutputTree`]: crate::MultiStrategy::MultiOutputTree
+    #[cfg(xgboost_array_interface)]
+    pub fn set_label_matrix(&mut self, labels: &[f32], num_targets: usize) -> XGBoostResult<()> {
+        let num_rows = self.num_rows()?;
+        if num_targets == 0 || num_rows.checked_mul(num_targets) != Some(labels.len()) {
+            return Err(XGBoostError {
+                description: format!(
+                    "label length mismatch: expected {}×{} values, got {}",
+                    num_rows,
+                    num_targets,
+                    labels.len()
+                ),
+            });
+        }
+        let interface = CString::new(format!(
+            "{{\"data\": [{}, true], \"shape\": [{}, {}], \"typestr\": \"<f4\", \"version\": 3}}",
+            labels.as_ptr() as usize,
+            num_rows,
+            num_targets
+        ))
+        .unwrap();
+        let field = CString::new("label").unwrap();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixSetInfoFromInterface(self.handle, field.as_ptr(), interface.as_ptr())
+        })
+    }
+
+    /// Get the training labels
+    pub fn label(&self) -> XGBoostResult<Vec<f32>> {
+        self.get_float_info("label")
+    }
+
+    /// Set per-instance weights
+    ///
+    /// Weights scale each row's contribution to the gradient, e.g. to
+    /// express importance or to counter class imbalance. They must be
+    /// finite and non-negative.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]; // 3 rows, 2 features
+    /// let mut dtrain = DMatrix::from_dense(&data, 3, 2).unwrap();
+    /// dtrain.set_label(&[0.0, 0.0, 1.0]).unwrap();
+    /// dtrain.set_weight(&[1.0, 1.0, 2.0]).unwrap();
+    /// ```
+    pub fn set_weight(&mut self, weights: &[f32]) -> XGBoostResult<()> {
+        if let Some(w) = weights.iter().find(|w| !w.is_finite() || **w < 0.0) {
+            return Err(XGBoostError {
+                description: format!("Weights must be finite and non-negative, got {}", w),
+            });
+        }
+        self.set_row_info("weight", weights)
+    }
+
+    /// Get the per-instance weights (empty if none were set)
+    pub fn weight(&self) -> XGBoostResult<Vec<f32>> {
+        self.get_float_info("weight")
+    }
+
+    /// Weight rows inversely to the frequency of their label
+    ///
+    /// Each row of class `c` gets `num_rows / (num_classes * count(c))`, so
+    /// every class contributes equally to training. Labels must be set first.
+    pub fn set_balanced_weight(&mut self) -> XGBoostResult<()> {
+        let labels = self.label()?;
+        if labels.is_empty() {
+            return Err(XGBoostError {
+                description: "Labels must be set before computing balanced weights".to_string(),
+            });
+        }
+        let mut counts: Vec<(f32, usize)> = Vec::new();
+        for &label in &labels {
+            match counts.iter_mut().find(|(l, _)| *l == label) {
+                Some(entry) => entry.1 += 1,
+                None => counts.push((label, 1)),
+            }
+        }
+        let scale = labels.len() as f32 / counts.len() as f32;
+        let weights: Vec<f32> = labels
+            .iter()
+            .map(|label| {
+                let count = counts.iter().find(|(l, _)| l == label).map_or(1, |c| c.1);
+                scale / count as f32
+            })
+            .collect();
+        self.set_weight(&weights)
+    }
+
+    /// `scale_pos_weight` that balances a binary classification problem
+    ///
+    /// Returns the total weight of the negative rows (label 0) divided by
+    /// that of the positive rows (label 1), using instance weights if set.
+    /// Pass it to [`BoosterParams::scale_pos_weight`]; for multi-class
+    /// problems use [`set_balanced_weight`](Self::set_balanced_weight).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{BoosterParams, DMatrix, Objective};
+    ///
+    /// # let dtrain = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+    /// let params = BoosterParams::new()
+    ///     .objective(Objective::BinaryLogistic)
+    ///     .scale_pos_weight(dtrain.scale_pos_weight().unwrap());
+    /// ```
+    ///
+    /// [`BoosterParams::scale_pos_weight`]: crate::BoosterParams::scale_pos_weight
+    pub fn scale_pos_weight(&self) -> XGBoostResult<f64> {
+        let labels = self.label()?;
+        let weights = self.weight()?;
+        let (mut negative, mut positive) = (0.0f64, 0.0f64);
+        for (i, &label) in labels.iter().enumerate() {
+            let weight = weights.get(i).map_or(1.0, |&w| w as f64);
+            if label == 0.0 {
+                negative += weight;
+            } else if label == 1.0 {
+                positive += weight;
+            } else {
+                return Err(XGBoostError {
+                    description: format!(
+                        "scale_pos_weight needs binary 0/1 labels, found {}",
+                        label
+                    ),
+                });
+            }
+        }
+        if negative == 0.0 || positive == 0.0 {
+            return Err(XGBoostError {
+                description: "scale_pos_weight needs both positive and negative rows".to_string(),
+            });
+        }
+        Ok(negative / positive)
+    }
+
+    /// Set per-row base margins, the initial (untransformed) prediction
+    ///
+    /// Base margins replace the model's `base_score` both during training and
+    /// prediction, so the margin output of a prior model can be used as an
+    /// offset. Multi-output models take `num_rows × num_groups` values in
+    /// row-major order.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, DMatrix, PredictOptions};
+    ///
+    /// let first = Booster::load("stage1.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let mut dmatrix = DMatrix::from_dense(&data, 2, 2).unwrap();
+    /// let offset = first
+    ///     .predict_dmatrix(&dmatrix, PredictOptions::OUTPUT_MARGIN, false)
+    ///     .unwrap();
+    /// dmatrix.set_base_margin(&offset).unwrap();
+    /// ```
+    pub fn set_base_margin(&mut self, base_margin: &[f32]) -> XGBoostResult<()> {
+        let num_rows = self.num_rows()?;
+        if num_rows == 0 || base_margin.len() % num_rows != 0 || base_margin.is_empty() {
+            return Err(XGBoostError {
+                description: format!(
+                    "base_margin length mismatch: expected a multiple of {} values (rows × groups), got {}",
+                    num_rows,
+                    base_margin.len()
+                ),
+            });
+        }
+        self.set_float_info("base_margin", base_margin)
+    }
+
+    /// Get the base margins (empty if none were set)
+    pub fn base_margin(&self) -> XGBoostResult<Vec<f32>> {
+        self.get_float_info("base_margin")
+    }
+
+    /// Set the lower bounds of interval-censored labels for `survival:aft`
+    ///
+    /// Use the observed time for both bounds of uncensored rows, 0 as the
+    /// lower bound for left-censored rows and `f32::INFINITY` as the upper
+    /// bound for right-censored rows.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]; // 3 rows, 2 features
+    /// let mut dtrain = DMatrix::from_dense(&data, 3, 2).unwrap();
+    /// // uncensored at 5, right-censored after 3, interval-censored in [2, 7]
+    /// dtrain.set_label_lower_bound(&[5.0, 3.0, 2.0]).unwrap();
+    /// dtrain.set_label_upper_bound(&[5.0, f32::INFINITY, 7.0]).unwrap();
+    /// ```
+    pub fn set_label_lower_bound(&mut self, lower_bound: &[f32]) -> XGBoostResult<()> {
+        self.set_row_info("label_lower_bound", lower_bound)
+    }
+
+    /// Set the upper bounds of interval-censored labels for `survival:aft`
+    ///
+    /// See [`DMatrix::set_label_lower_bound`].
+    pub fn set_label_upper_bound(&mut self, upper_bound: &[f32]) -> XGBoostResult<()> {
+        self.set_row_info("label_upper_bound", upper_bound)
+    }
+
+    /// Set query groups for learning to rank, as the number of rows in each
+    /// consecutive group
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let data = vec![1.0, 2.0, 3.0, 4.0, 5.0]; // 5 rows, 1 feature
+    /// let mut dmatrix = DMatrix::from_dense(&data, 5, 1).unwrap();
+    /// dmatrix.set_label(&[2.0, 1.0, 0.0, 1.0, 0.0]).unwrap();
+    /// dmatrix.set_group(&[3, 2]).unwrap(); // rows 0-2 and 3-4
+    /// ```
+    pub fn set_group(&mut self, group_sizes: &[u32]) -> XGBoostResult<()> {
+        let num_rows = self.num_rows()?;
+        let total: u64 = group_sizes.iter().map(|&size| size as u64).sum();
+        if total != num_rows as u64 {
+            return Err(XGBoostError {
+                description: format!(
+                    "Group sizes add up to {} rows, but the DMatrix has {}",
+                    total, num_rows
+                ),
+            });
+        }
+        // XGBoost stores the first row of every group as a 32-bit integer
+        if total > u64::from(u32::MAX) {
+            return Err(XGBoostError {
+                description: format!(
+                    "Query groups are limited to {} rows in total, got {}",
+                    u32::MAX,
+                    total
+                ),
+            });
+        }
+        let field = CString::new("group").unwrap();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixSetUIntInfo(
+                self.handle,
+                field.as_ptr(),
+                group_sizes.as_ptr(),
+                group_sizes.len() as u64,
+            )
+        })
+    }
+
+    /// Concatenate the rows of several matrices into a new one
+    ///
+    /// Labels, weights, base margins, label bounds and query groups are
+    /// concatenated too; each of them must be set on all of the matrices or
+    /// on none. The result has as many features as the widest input.
+    /// Requires XGBoost ≥ 2.0.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let mut first = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+    /// first.set_label(&[0.0]).unwrap();
+    /// let mut second = DMatrix::from_dense(&[3.0, 4.0, 5.0, 6.0], 2, 2).unwrap();
+    /// second.set_label(&[1.0, 0.0]).unwrap();
+    ///
+    /// let combined = DMatrix::concat(&[&first, &second]).unwrap();
+    /// assert_eq!(combined.num_rows().unwrap(), 3);
+    /// ```
+    #[cfg(xgboost_get_data)]
+    pub fn concat(matrices: &[&DMatrix]) -> XGBoostResult<DMatrix> {
+        if matrices.is_empty() {
+            return Err(XGBoostError {
+                description: "Cannot concatenate zero matrices".to_string(),
+            });
+        }
+
+        let mut num_features = 0;
+        let mut indptr = vec![0usize];
+        let (mut indices, mut values) = (Vec::new(), Vec::new());
+        for matrix in matrices {
+            num_features = num_features.max(matrix.num_cols()?);
+            let (rows, row_indices, row_values) = matrix.csr_data()?;
+            let offset = values.len();
+            indptr.extend(rows[1..].iter().map(|&end| offset + end as usize));
+            indices.extend(row_indices);
+            values.extend(row_values);
+        }
+        let mut combined = DMatrix::from_csr(&indptr, &indices, &values, num_features)?;
+
+        for field in ROW_FIELDS {
+            let parts = matrices
+                .iter()
+                .map(|matrix| matrix.get_float_info(field))
+                .collect::<XGBoostResult<Vec<_>>>()?;
+            if let Some(values) = concat_info(field, parts)? {
+                combined.set_float_info(field, &values)?;
+            }
+        }
+
+        let groups = matrices
+            .iter()
+            .map(|matrix| {
+                // group_ptr holds the first row of every group and the row count
+                let pointers = matrix.get_uint_info("group_ptr")?;
+                Ok(pointers.windows(2).map(|w| w[1] - w[0]).collect())
+            })
+            .collect::<XGBoostResult<Vec<_>>>()?;
+        if let Some(sizes) = concat_info("group", groups)? {
+            combined.set_group(&sizes)?;
+        }
+        Ok(combined)
+    }
+
+    /// The stored entries as CSR: row pointers, feature indices and values
+    #[cfg(xgboost_get_data)]
+    fn csr_data(&self) -> XGBoostResult<(Vec<u64>, Vec<u32>, Vec<f32>)> {
+        let num_rows = self.num_rows()?;
+        let mut num_values: u64 = 0;
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixNumNonMissing(self.handle, &mut num_values)
+        })?;
+
+        let mut indptr = vec![0u64; num_rows + 1];
+        let mut indices = vec![0u32; num_values as usize];
+        let mut values = vec![0f32; num_values as usize];
+        let config = CString::new("{}").unwrap();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixGetDataAsCSR(
+                self.handle,
+                config.as_ptr(),
+                indptr.as_mut_ptr(),
+                indices.as_mut_ptr(),
+                values.as_mut_ptr(),
+            )
+        })?;
+        Ok((indptr, indices, values))
+    }
+
+    /// Get an unsigned integer meta-information field (`group_ptr`, ...)
+    #[cfg(xgboost_get_data)]
+    fn get_uint_info(&self, field: &str) -> XGBoostResult<Vec<u32>> {
+        let field_c_str = CString::new(field).unwrap();
+        let mut out_len: u64 = 0;
+        let mut out_dptr: *const u32 = ptr::null();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixGetUIntInfo(
+                self.handle,
+                field_c_str.as_ptr(),
+                &mut out_len,
+                &mut out_dptr,
+            )
+        })?;
+        if out_dptr.is_null() || out_len == 0 {
+            return Ok(Vec::new());
+        }
+        Ok(unsafe { std::slice::from_raw_parts(out_dptr, out_len as usize).to_vec() })
+    }
+
+    /// New DMatrix with the given rows, in order, including their labels and weights
+    ///
+    /// Matrices with query groups cannot be sliced. XGBoost's slicing takes
+    /// 32-bit row indices; rows past `i32::MAX` are instead copied out
+    /// through the CSR data, which requires XGBoost ≥ 2.0 and drops feature
+    /// names and types.
+    pub fn slice(&self, rows: &[usize]) -> XGBoostResult<DMatrix> {
+        let num_rows = self.num_rows()?;
+        if let Some(&row) = rows.iter().find(|&&row| row >= num_rows) {
+            return Err(XGBoostError {
+                description: format!("Row index {} out of range for {} rows", row, num_rows),
+            });
+        }
+        let indices = match rows
+            .iter()
+            .map(|&row| std::os::raw::c_int::try_from(row))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(indices) => indices,
+            #[cfg(xgboost_get_data)]
+            Err(_) => return self.slice_csr(rows),
+            #[cfg(not(xgboost_get_data))]
+            Err(_) => {
+                return Err(XGBoostError {
+                    description: format!(
+                        "Row indices above {} need XGBoost >= 2.0 to slice",
+                        std::os::raw::c_int::MAX
+                    ),
+                })
+            }
+        };
+
+        let mut handle: sys::DMatrixHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixSliceDMatrix(
+                self.handle,
+                indices.as_ptr(),
+                indices.len() as u64,
+                &mut handle,
+            )
+        })?;
+        Ok(DMatrix { handle })
+    }
+
+    /// [`DMatrix::slice`] by copying the rows' entries and meta-information
+    #[cfg(xgboost_get_data)]
+    fn slice_csr(&self, rows: &[usize]) -> XGBoostResult<DMatrix> {
+        if !self.get_uint_info("group_ptr")?.is_empty() {
+            return Err(XGBoostError {
+                description: "Matrices with query groups cannot be sliced".to_string(),
+            });
+        }
+        let (indptr, indices, values) = self.csr_data()?;
+        let mut sliced_indptr = Vec::with_capacity(rows.len() + 1);
+        sliced_indptr.push(0);
+        let (mut sliced_indices, mut sliced_values) = (Vec::new(), Vec::new());
+        for &row in rows {
+            let (start, end) = (indptr[row] as usize, indptr[row + 1] as usize);
+            sliced_indices.extend_from_slice(&indices[start..end]);
+            sliced_values.extend_from_slice(&values[start..end]);
+            sliced_indptr.push(sliced_values.len());
+        }
+        let mut sliced = DMatrix::from_csr(
+            &sliced_indptr,
+            &sliced_indices,
+            &sliced_values,
+            self.num_cols()?,
+        )?;
+
+        let num_rows = indptr.len() - 1;
+        for field in ROW_FIELDS {
+            let info = self.get_float_info(field)?;
+            if info.is_empty() {
+                continue;
+            }
+            // labels and base margins may hold several values per row
+            let width = info.len() / num_rows;
+            let picked: Vec<f32> = rows
+                .iter()
+                .flat_map(|&row| info[row * width..(row + 1) * width].iter().copied())
+                .collect();
+            if field == "label" && width > 1 {
+                sliced.set_label_matrix(&picked, width)?;
+            } else {
+                sliced.set_float_info(field, &picked)?;
+            }
+        }
+        Ok(sliced)
+    }
+
+    pub(crate) fn handle(&self) -> sys::DMatrixHandle {
+        self.handle
+    }
+
+    /// Get the number of rows
+    pub fn num_rows(&self) -> XGBoostResult<usize> {
+        let mut out: u64 = 0;
+        XGBoostError::check_return_value(unsafe { sys::XGDMatrixNumRow(self.handle, &mut out) })?;
+        Ok(out as usize)
+    }
+
+    /// Get the number of columns
+    pub fn num_cols(&self) -> XGBoostResult<usize> {
+        let mut out: u64 = 0;
+        XGBoostError::check_return_value(unsafe { sys::XGDMatrixNumCol(self.handle, &mut out) })?;
+        Ok(out as usize)
+    }
+
+    /// Name the features; training stores the names in the model, and
+    /// prediction checks that they match (XGBoost ≥ 1.5)
+    #[cfg(xgboost_feature_score)]
+    pub fn set_feature_names<S: AsRef<str>>(&mut self, names: &[S]) -> XGBoostResult<()> {
+        self.set_str_feature_info("feature_name", names)
+    }
+
+    /// Set the type of every feature (XGBoost ≥ 1.5)
+    ///
+    /// Values of a categorical feature are category codes, non-negative
+    /// integers, and trees split them into sets of categories instead of
+    /// comparing them against a threshold; `NaN` is missing. This is what
+    /// `enable_categorical` does in the Python package. Categorical
+    /// features need `tree_method` `hist` or `approx`, and the types are
+    /// stored in the trained model. See also
+    /// [`BoosterParams::max_cat_to_onehot`] and
+    /// [`BoosterParams::max_cat_threshold`].
+    ///
+    /// [`BoosterParams::max_cat_to_onehot`]: crate::BoosterParams::max_cat_to_onehot
+    /// [`BoosterParams::max_cat_threshold`]: crate::BoosterParams::max_cat_threshold
+    #[cfg(xgboost_feature_score)]
+    pub fn set_feature_types(&mut self, types: &[FeatureType]) -> XGBoostResult<()> {
+        let num_cols = self.num_cols()?;
+        if types.len() != num_cols {
+            return Err(XGBoostError {
+                description: format!(
+                    "{} feature types given for {} features",
+                    types.len(),
+                    num_cols
+                ),
+            });
+        }
+        let names: Vec<&str> = types.iter().map(FeatureType::as_str).collect();
+        self.set_str_feature_info("feature_type", &names)
+    }
+
+    /// Mark the given features categorical and all others numerical
+    /// (XGBoost ≥ 1.5)
+    ///
+    /// See [`DMatrix::set_feature_types`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{train, BoosterParams, DMatrix, TreeMethod};
+    ///
+    /// // feature 0: numerical, feature 1: category codes 0..3
+    /// let data = vec![0.5, 0.0, 1.5, 2.0, 2.5, 1.0, 3.5, 2.0];
+    /// let mut dtrain = DMatrix::from_dense(&data, 4, 2).unwrap();
+    /// dtrain.set_label(&[0.0, 1.0, 0.0, 1.0]).unwrap();
+    /// dtrain.set_categorical_features(&[1]).unwrap();
+    ///
+    /// let params = BoosterParams::new()
+    ///     .tree_method(TreeMethod::Hist)
+    ///     .max_cat_to_onehot(4);
+    /// let booster = train(&params, &dtrain, 10).unwrap();
+    /// ```
+    #[cfg(xgboost_feature_score)]
+    pub fn set_categorical_features(&mut self, features: &[usize]) -> XGBoostResult<()> {
+        let num_cols = self.num_cols()?;
+        let mut types = vec![FeatureType::Numerical; num_cols];
+        for &feature in features {
+            *types.get_mut(feature).ok_or_else(|| XGBoostError {
+                description: format!(
+                    "Feature index {} out of range for {} features",
+                    feature, num_cols
+                ),
+            })? = FeatureType::Categorical;
+        }
+        self.set_feature_types(&types)
+    }
+
+    #[cfg(xgboost_feature_score)]
+    fn set_str_feature_info<S: AsRef<str>>(
+        &mut self,
+        field: &str,
+        values: &[S],
+    ) -> XGBoostResult<()> {
+        let values = values
+            .iter()
+            .map(|value| CString::new(value.as_ref()))
+            .collect::<Result<Vec<CString>, _>>()
+            .map_err(|e| XGBoostError {
+                description: format!("{} contains NUL byte: {}", field, e),
+            })?;
+        let mut pointers: Vec<*const std::os::raw::c_char> =
+            values.iter().map(|value| value.as_ptr()).collect();
+        let field = CString::new(field).unwrap();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixSetStrFeatureInfo(
+                self.handle,
+                field.as_ptr(),
+                pointers.as_mut_ptr(),
+                pointers.len() as u64,
+            )
+        })
+    }
+}
+
+/// Incremental construction of a DMatrix, one row at a time.
+///
+/// Rows are appended to compact sparse (CSR) buffers that only hold the
+/// non-missing values, so the data never has to be collected into one
+/// dense `Vec` first.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::DMatrixBuilder;
+///
+/// let mut builder = DMatrixBuilder::new(3);
+/// builder.push_row(&[1.0, f32::NAN, 3.0]).unwrap();
+/// builder.push_sparse_row(&[(0, 4.0), (2, 6.0)]).unwrap();
+/// builder
+///     .extend_rows((0..1000).map(|i| vec![i as f32, 2.0 * i as f32, 0.5]))
+///     .unwrap();
+/// let dmatrix = builder.build().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct DMatrixBuilder {
+    num_features: usize,
+    missing: f32,
+    indptr: Vec<usize>,
+    indices: Vec<u32>,
+    values: Vec<f32>,
+}
+
+impl DMatrixBuilder {
+    /// An empty matrix with `num_features` columns
+    pub fn new(num_features: usize) -> Self {
+        DMatrixBuilder {
+            num_features,
+            missing: f32::NAN,
+            indptr: vec![0],
+            indices: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Treat dense entries equal to `missing` as missing (NaN always is)
+    pub fn missing(mut self, missing: f32) -> Self {
+        self.missing = missing;
+        self
+    }
+
+    /// Pre-allocate room for `num_rows` more rows with `values_per_row`
+    /// non-missing values each
+    pub fn reserve(&mut self, num_rows: usize, values_per_row: usize) {
+        self.indptr.reserve(num_rows);
+        self.indices.reserve(num_rows * values_per_row);
+        self.values.reserve(num_rows * values_per_row);
+    }
+
+    /// Append a dense row of `num_features` values
+    pub fn push_row(&mut self, row: &[f32]) -> XGBoostResult<()> {
+        check_num_features(self.num_features)?;
+        if row.len() != self.num_features {
+            return Err(XGBoostError {
+                description: format!(
+                    "Row {} has {} values, expected {}",
+                    self.num_rows(),
+                    row.len(),
+                    self.num_features
+                ),
+            });
+        }
+        for (index, &value) in row.iter().enumerate() {
+            if !value.is_nan() && value != self.missing {
+                self.indices.push(index as u32);
+                self.values.push(value);
+            }
+        }
+        self.indptr.push(self.values.len());
+        Ok(())
+    }
+
+    /// Append a sparse row of `(feature index, value)` entries; absent
+    /// features are missing
+    pub fn push_sparse_row(&mut self, entries: &[(usize, f32)]) -> XGBoostResult<()> {
+        check_num_features(self.num_features)?;
+        if let Some(&(index, _)) = entries.iter().find(|(i, _)| *i >= self.num_features) {
+            return Err(XGBoostError {
+                description: format!(
+                    "Feature index {} in row {} out of range for {} features",
+                    index,
+                    self.num_rows(),
+                    self.num_features
+                ),
+            });
+        }
+        for &(index, value) in entries {
+            self.indices.push(index as u32);
+            self.values.push(value);
+        }
+        self.indptr.push(self.values.len());
+        Ok(())
+    }
+
+    /// Append every dense row of an iterator
+    ///
+    /// Stops at the first row of the wrong width; the rows before it are kept.
+    pub fn extend_rows<I, R>(&mut self, rows: I) -> XGBoostResult<()>
+    where
+        I: IntoIterator<Item = R>,
+        R: AsRef<[f32]>,
+    {
+        rows.into_iter()
+            .try_for_each(|row| self.push_row(row.as_ref()))
+    }
+
+    /// Number of rows appended so far
+    pub fn num_rows(&self) -> usize {
+        self.indptr.len() - 1
+    }
+
+    /// Create the DMatrix
+    pub fn build(self) -> XGBoostResult<DMatrix> {
+        DMatrix::from_csr(&self.indptr, &self.indices, &self.values, self.num_features)
+    }
+}
+
+impl DMatrix {
+    /// Create a DMatrix from compressed sparse rows; absent entries are missing
+    pub(crate) fn from_csr(
+        indptr: &[usize],
+        indices: &[u32],
+        values: &[f32],
+        num_features: usize,
+    ) -> XGBoostResult<Self> {
+        check_num_features(num_features)?;
+        let mut handle: sys::DMatrixHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixCreateFromCSREx(
+                indptr.as_ptr(),
+                indices.as_ptr(),
+                values.as_ptr(),
+                indptr.len(),
+                values.len(),
+                num_features,
+                &mut handle,
+            )
+        })?;
+        Ok(DMatrix { handle })
+    }
+}
+
+/// Check that feature indices fit the 32-bit column indices of XGBoost's
+/// CSR input
+pub(crate) fn check_num_features(num_features: usize) -> XGBoostResult<()> {
+    if num_features > u32::MAX as usize {
+        return Err(XGBoostError {
+            description: format!(
+                "Too many features: {} (sparse input supports at most {})",
+                num_features,
+                u32::MAX
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Float meta-information fields holding values per row
+#[cfg(xgboost_get_data)]
+pub(crate) const ROW_FIELDS: [&str; 5] = [
+    "label",
+    "weight",
+    "base_margin",
+    "label_lower_bound",
+    "label_upper_bound",
+];
+
+/// Concatenated values of a meta-information field, or `None` if no part has it
+#[cfg(xgboost_get_data)]
+fn concat_info<T>(field: &str, parts: Vec<Vec<T>>) -> XGBoostResult<Option<Vec<T>>> {
+    let present = parts.iter().filter(|part| !part.is_empty()).count();
+    if present == 0 {
+        return Ok(None);
+    }
+    if present < parts.len() {
+        return Err(XGBoostError {
+            description: format!(
+                "'{}' is set on only {} of {} matrices",
+                field,
+                present,
+                parts.len()
+            ),
+        });
+    }
+    Ok(Some(parts.into_iter().flatten().collect()))
+}
+
+/// `__array_interface__` JSON of a row-major host `f64` matrix
+#[cfg(xgboost_array_interface)]
+pub(crate) fn f64_array_interface(data: &[f64], num_rows: usize, num_features: usize) -> CString {
+    CString::new(format!(
+        "{{\"data\": [{}, true], \"shape\": [{}, {}], \"typestr\": \"<f8\", \"version\": 3}}",
+        data.as_ptr() as usize,
+        num_rows,
+        num_features
+    ))
+    .unwrap()
+}
+
+impl Drop for DMatrix {
+    fn drop(&mut self) {
+        unsafe {
+            sys::XGDMatrixFree(self.handle);
+        }
+    }
+}
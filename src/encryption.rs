@@ -0,0 +1,129 @@
+//! Models encrypted at rest with AES-256-GCM.
+//!
+//! An encrypted model is a short header, a random 96-bit nonce and the
+//! sealed model bytes, so it is decrypted and authenticated in one step, in
+//! memory. Any model file (compressed or not) can be encrypted with
+//! [`encrypt`] and loaded with `Booster::load_encrypted`.
+//!
+//! # Example
+//! ```no_run
+//! use std::path::Path;
+//! use xgboost_rust::encryption::{self, KeyProvider};
+//! use xgboost_rust::{Booster, XGBoostError, XGBoostResult};
+//!
+//! // look the key up in a secret store; here, a hex environment variable
+//! let key = |_: &Path| -> XGBoostResult<[u8; 32]> {
+//!     let hex = std::env::var("MODEL_KEY").map_err(|e| XGBoostError {
+//!         description: format!("MODEL_KEY: {}", e),
+//!     })?;
+//!     encryption::parse_key(&hex)
+//! };
+//!
+//! let model = std::fs::read("model.ubj").unwrap();
+//! let sealed = encryption::encrypt(&model, &key.key(Path::new("model.ubj.enc")).unwrap()).unwrap();
+//! std::fs::write("model.ubj.enc", sealed).unwrap();
+//!
+//! let booster = Booster::load_encrypted("model.ubj.enc", &key).unwrap();
+//! ```
+
+use crate::error::{XGBoostError, XGBoostResult};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::path::Path;
+
+/// Start of every encrypted model, also authenticated with it
+const HEADER: &[u8] = b"XGBENC1\n";
+
+/// Supplies the AES-256 key of an encrypted model.
+///
+/// Implemented for keys and for closures taking the model path, which can
+/// fetch the key from a secret manager or KMS.
+pub trait KeyProvider {
+    /// The 256-bit key of the model at `path`
+    fn key(&self, path: &Path) -> XGBoostResult<[u8; 32]>;
+}
+
+impl KeyProvider for [u8; 32] {
+    fn key(&self, _path: &Path) -> XGBoostResult<[u8; 32]> {
+        Ok(*self)
+    }
+}
+
+impl<F: Fn(&Path) -> XGBoostResult<[u8; 32]>> KeyProvider for F {
+    fn key(&self, path: &Path) -> XGBoostResult<[u8; 32]> {
+        self(path)
+    }
+}
+
+/// Parse a key from 64 hex digits
+pub fn parse_key(hex: &str) -> XGBoostResult<[u8; 32]> {
+    let hex = hex.trim();
+    let mut key = [0u8; 32];
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(invalid_key());
+    }
+    for (byte, digits) in key.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        let digits = std::str::from_utf8(digits).map_err(|_| invalid_key())?;
+        *byte = u8::from_str_radix(digits, 16).map_err(|_| invalid_key())?;
+    }
+    Ok(key)
+}
+
+fn invalid_key() -> XGBoostError {
+    XGBoostError {
+        description: "An AES-256 key must be 64 hex digits".to_string(),
+    }
+}
+
+fn cipher(key: &[u8; 32]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).expect("AES-256 keys are 32 bytes"))
+}
+
+/// Encrypt serialized model bytes with a fresh random nonce
+pub fn encrypt(model: &[u8], key: &[u8; 32]) -> XGBoostResult<Vec<u8>> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| XGBoostError {
+            description: "Failed to generate a nonce".to_string(),
+        })?;
+    let mut sealed = model.to_vec();
+    cipher(key)
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(HEADER),
+            &mut sealed,
+        )
+        .map_err(|_| XGBoostError {
+            description: "Failed to encrypt the model".to_string(),
+        })?;
+    let mut out = Vec::with_capacity(HEADER.len() + NONCE_LEN + sealed.len());
+    out.extend_from_slice(HEADER);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+/// Decrypt a model made by [`encrypt`], failing if it was modified or the
+/// key is wrong
+pub fn decrypt(encrypted: &[u8], key: &[u8; 32]) -> XGBoostResult<Vec<u8>> {
+    let body = encrypted.strip_prefix(HEADER).ok_or_else(|| XGBoostError {
+        description: "Not an encrypted model".to_string(),
+    })?;
+    if body.len() < NONCE_LEN {
+        return Err(XGBoostError {
+            description: "Encrypted model is truncated".to_string(),
+        });
+    }
+    let (nonce, sealed) = body.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).expect("nonce has NONCE_LEN bytes");
+    let mut model = sealed.to_vec();
+    let len = cipher(key)
+        .open_in_place(nonce, Aad::from(HEADER), &mut model)
+        .map_err(|_| XGBoostError {
+            description: "Failed to decrypt the model: wrong key or corrupted file".to_string(),
+        })?
+        .len();
+    model.truncate(len);
+    Ok(model)
+}
@@ -0,0 +1,205 @@
+use crate::backend::Backend;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::predict_options::PredictOptions;
+use crate::Booster;
+
+/// How an [`Ensemble`] combines the predictions of its members.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Combination {
+    /// Weighted mean of the predictions
+    #[default]
+    Mean,
+    /// Weighted mean of the normalized ranks (0 to 1) of the predictions
+    /// within the batch, for members whose scores are not on the same
+    /// scale. Only meaningful for batches of many rows.
+    RankMean,
+}
+
+/// Several models predicting the same target, e.g. trained with different
+/// seeds, whose predictions are blended.
+///
+/// All members must take the same features in the same order, which is
+/// checked when the ensemble is created, and produce the same number of
+/// outputs per row.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{Booster, Ensemble, PredictOptions};
+///
+/// let ensemble = Ensemble::weighted(vec![
+///     (Booster::load("seed1.json").unwrap(), 0.5),
+///     (Booster::load("seed2.json").unwrap(), 0.3),
+///     (Booster::load("seed3.json").unwrap(), 0.2),
+/// ])
+/// .unwrap();
+/// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+/// let predictions = ensemble.predict(&data, 2, 2, PredictOptions::empty()).unwrap();
+/// ```
+pub struct Ensemble {
+    members: Vec<Booster>,
+    weights: Vec<f64>,
+    combination: Combination,
+}
+
+impl Ensemble {
+    /// An equally weighted ensemble
+    pub fn new(members: Vec<Booster>) -> XGBoostResult<Self> {
+        Self::weighted(members.into_iter().map(|m| (m, 1.0)).collect())
+    }
+
+    /// An ensemble with a weight per member; weights are normalized to sum
+    /// to one
+    pub fn weighted(members: Vec<(Booster, f64)>) -> XGBoostResult<Self> {
+        let Some((first, _)) = members.first() else {
+            return Err(XGBoostError {
+                description: "An ensemble needs at least one model".to_string(),
+            });
+        };
+        if members.iter().any(|(_, w)| !w.is_finite() || *w < 0.0) {
+            return Err(XGBoostError {
+                description: "Ensemble weights must be finite and non-negative".to_string(),
+            });
+        }
+        let total: f64 = members.iter().map(|(_, w)| w).sum();
+        if total <= 0.0 {
+            return Err(XGBoostError {
+                description: "Ensemble weights must not all be zero".to_string(),
+            });
+        }
+
+        let num_features = first.num_features()?;
+        let names = first.model_feature_names()?;
+        for (i, (member, _)) in members.iter().enumerate().skip(1) {
+            if member.num_features()? != num_features {
+                return Err(XGBoostError {
+                    description: format!(
+                        "Ensemble member {} takes {} features but member 0 takes {}",
+                        i,
+                        member.num_features()?,
+                        num_features
+                    ),
+                });
+            }
+            let member_names = member.model_feature_names()?;
+            if !names.is_empty() && !member_names.is_empty() && member_names != names {
+                let position = names
+                    .iter()
+                    .zip(&member_names)
+                    .position(|(a, b)| a != b)
+                    .unwrap_or(0);
+                return Err(XGBoostError {
+                    description: format!(
+                        "Ensemble member {} has feature '{}' where member 0 has '{}'",
+                        i, member_names[position], names[position]
+                    ),
+                });
+            }
+        }
+
+        let (members, weights) = members.into_iter().map(|(m, w)| (m, w / total)).unzip();
+        Ok(Ensemble {
+            members,
+            weights,
+            combination: Combination::Mean,
+        })
+    }
+
+    /// Set how predictions are combined (default [`Combination::Mean`])
+    pub fn combination(mut self, combination: Combination) -> Self {
+        self.combination = combination;
+        self
+    }
+
+    /// The members of the ensemble
+    pub fn members(&self) -> &[Booster] {
+        &self.members
+    }
+
+    /// The normalized weights of the members
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Predict with every member and combine the predictions
+    ///
+    /// Leaf indices cannot be combined; with [`Combination::RankMean`] only
+    /// normal predictions and margins are supported. Feature contributions
+    /// are averaged like predictions.
+    pub fn predict(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        if options.contains(PredictOptions::PRED_LEAF) {
+            return Err(XGBoostError {
+                description: "Leaf indices of ensemble members cannot be combined".to_string(),
+            });
+        }
+        if self.combination == Combination::RankMean
+            && !(options - PredictOptions::OUTPUT_MARGIN).is_empty()
+        {
+            return Err(XGBoostError {
+                description: "Rank averaging only combines predictions or margins".to_string(),
+            });
+        }
+
+        let mut combined: Vec<f64> = Vec::new();
+        for (i, (member, &weight)) in self.members.iter().zip(&self.weights).enumerate() {
+            let mut predictions = member.predict(data, num_rows, num_features, options, false)?;
+            if i == 0 {
+                combined = vec![0.0; predictions.len()];
+            } else if predictions.len() != combined.len() {
+                return Err(XGBoostError {
+                    description: format!(
+                        "Ensemble member {} produced {} outputs but member 0 produced {}",
+                        i,
+                        predictions.len(),
+                        combined.len()
+                    ),
+                });
+            }
+            if self.combination == Combination::RankMean {
+                rank_columns(&mut predictions, num_rows);
+            }
+            for (sum, &p) in combined.iter_mut().zip(&predictions) {
+                *sum += weight * f64::from(p);
+            }
+        }
+        Ok(combined.into_iter().map(|p| p as f32).collect())
+    }
+}
+
+/// Replace each output column of row-major predictions by the ranks of its
+/// values scaled to 0–1, ties sharing their mean rank
+fn rank_columns(predictions: &mut [f32], num_rows: usize) {
+    if num_rows == 0 {
+        return;
+    }
+    let num_outputs = predictions.len() / num_rows;
+    let mut order: Vec<usize> = Vec::with_capacity(num_rows);
+    let mut ranks = vec![0.0f32; num_rows];
+    for column in 0..num_outputs {
+        let value = |row: usize| predictions[row * num_outputs + column];
+        order.clear();
+        order.extend(0..num_rows);
+        order.sort_by(|&a, &b| value(a).total_cmp(&value(b)));
+        let scale = (num_rows - 1).max(1) as f32;
+        let mut start = 0;
+        while start < num_rows {
+            let mut end = start + 1;
+            while end < num_rows && value(order[end]) == value(order[start]) {
+                end += 1;
+            }
+            let rank = (start + end - 1) as f32 / 2.0 / scale;
+            for &row in &order[start..end] {
+                ranks[row] = rank;
+            }
+            start = end;
+        }
+        for (row, &rank) in ranks.iter().enumerate() {
+            predictions[row * num_outputs + column] = rank;
+        }
+    }
+}
@@ -1,4 +1,6 @@
+#[cfg(feature = "native")]
 use crate::sys;
+#[cfg(feature = "native")]
 use std::ffi::CStr;
 use std::fmt;
 
@@ -9,6 +11,7 @@ pub struct XGBoostError {
     pub description: String,
 }
 
+#[cfg(feature = "native")]
 impl XGBoostError {
     /// Check the return value from an XGBoost FFI call, and return the last error message on error.
     /// Return values of 0 are treated as success, non-zero values are treated as errors.
@@ -0,0 +1,270 @@
+//! Hashing named features into a fixed number of columns.
+//!
+//! [`FeatureHasher`] turns `(name, value)` pairs, such as categoricals with
+//! too many levels to keep a vocabulary of, into sparse rows. It hashes
+//! like scikit-learn's `FeatureHasher`, so a model trained on features
+//! hashed in Python can be served from Rust with the same columns.
+
+#[cfg(feature = "native")]
+use crate::dmatrix::{DMatrix, DMatrixBuilder};
+use crate::error::{XGBoostError, XGBoostResult};
+
+/// Value of a named feature passed to a [`FeatureHasher`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeatureValue<'a> {
+    /// A category; `name=category` is hashed and the value is 1
+    Category(&'a str),
+    /// A number; `name` is hashed and the value is the number
+    Number(f32),
+}
+
+impl<'a> From<&'a str> for FeatureValue<'a> {
+    fn from(category: &'a str) -> Self {
+        FeatureValue::Category(category)
+    }
+}
+
+impl<'a> From<&'a String> for FeatureValue<'a> {
+    fn from(category: &'a String) -> Self {
+        FeatureValue::Category(category)
+    }
+}
+
+impl From<f32> for FeatureValue<'_> {
+    fn from(value: f32) -> Self {
+        FeatureValue::Number(value)
+    }
+}
+
+impl From<f64> for FeatureValue<'_> {
+    fn from(value: f64) -> Self {
+        FeatureValue::Number(value as f32)
+    }
+}
+
+/// Maps named features, such as high-cardinality categoricals, into a
+/// fixed number of columns without a vocabulary.
+///
+/// The hash is the same as scikit-learn's `FeatureHasher`, so both produce
+/// the same columns:
+///
+/// * the key is `name=category` for categories and `name` for numbers,
+/// * its UTF-8 bytes are hashed with 32-bit MurmurHash3 (x86) and seed 0,
+/// * the column is the absolute value of the hash, as a signed integer,
+///   modulo the number of features,
+/// * with `alternate_sign` (the default) the value is negated if the hash
+///   is negative, so collisions tend to cancel rather than add up.
+///
+/// Values that land in the same column are summed. Columns no feature
+/// lands in are missing, so hashed rows go straight into
+/// `Booster::predict_sparse_rows`.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::FeatureHasher;
+///
+/// let hasher = FeatureHasher::new(1 << 18).unwrap();
+/// let rows = hasher.transform([
+///     [("user_id", "u_8812"), ("country", "DE")],
+///     [("user_id", "u_0042"), ("country", "FR")],
+/// ]);
+/// assert!(rows.iter().all(|row| row.len() == 2));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureHasher {
+    num_features: u32,
+    alternate_sign: bool,
+}
+
+impl FeatureHasher {
+    /// Hash into `num_features` columns
+    pub fn new(num_features: u32) -> XGBoostResult<Self> {
+        if num_features == 0 {
+            return Err(XGBoostError {
+                description: "FeatureHasher needs at least one feature".to_string(),
+            });
+        }
+        Ok(FeatureHasher {
+            num_features,
+            alternate_sign: true,
+        })
+    }
+
+    /// Negate values whose hash is negative (default true)
+    pub fn alternate_sign(mut self, alternate_sign: bool) -> Self {
+        self.alternate_sign = alternate_sign;
+        self
+    }
+
+    /// Number of columns
+    pub fn num_features(&self) -> u32 {
+        self.num_features
+    }
+
+    /// Column and sign of a key (`name` or `name=category`)
+    pub fn index(&self, key: &str) -> (u32, f32) {
+        let hash = murmur3_32(key.as_bytes(), 0) as i32;
+        // abs(i32::MIN) overflows, so scikit-learn defines its column as
+        // (i32::MAX - (num_features - 1)) % num_features
+        let index = if hash == i32::MIN {
+            (i32::MAX as u32 - (self.num_features - 1)) % self.num_features
+        } else {
+            hash.unsigned_abs() % self.num_features
+        };
+        let sign = if self.alternate_sign && hash < 0 {
+            -1.0
+        } else {
+            1.0
+        };
+        (index, sign)
+    }
+
+    /// Hash one row of `(name, value)` pairs into `(column, value)` entries,
+    /// sorted by column
+    pub fn transform_row<'v, I, N, V>(&self, row: I) -> Vec<(u32, f32)>
+    where
+        I: IntoIterator<Item = (N, V)>,
+        N: AsRef<str>,
+        V: Into<FeatureValue<'v>>,
+    {
+        let mut entries: Vec<(u32, f32)> = row
+            .into_iter()
+            .map(|(name, value)| {
+                let name = name.as_ref();
+                let ((index, sign), value) = match value.into() {
+                    FeatureValue::Category(category) => {
+                        (self.index(&format!("{}={}", name, category)), 1.0)
+                    }
+                    FeatureValue::Number(value) => (self.index(name), value),
+                };
+                (index, sign * value)
+            })
+            .collect();
+        entries.sort_by_key(|&(index, _)| index);
+        entries.dedup_by(|next, kept| {
+            let same = next.0 == kept.0;
+            if same {
+                kept.1 += next.1;
+            }
+            same
+        });
+        entries
+    }
+
+    /// Hash several rows
+    pub fn transform<'v, R, I, N, V>(&self, rows: R) -> Vec<Vec<(u32, f32)>>
+    where
+        R: IntoIterator<Item = I>,
+        I: IntoIterator<Item = (N, V)>,
+        N: AsRef<str>,
+        V: Into<FeatureValue<'v>>,
+    {
+        rows.into_iter()
+            .map(|row| self.transform_row(row))
+            .collect()
+    }
+
+    /// Build a DMatrix, e.g. for training, from hashed rows
+    #[cfg(feature = "native")]
+    pub fn to_dmatrix(&self, rows: &[Vec<(u32, f32)>]) -> XGBoostResult<DMatrix> {
+        let mut builder = DMatrixBuilder::new(self.num_features as usize);
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.clear();
+            entries.extend(row.iter().map(|&(index, value)| (index as usize, value)));
+            builder.push_sparse_row(&entries)?;
+        }
+        builder.build()
+    }
+}
+
+/// 32-bit MurmurHash3 (x86 variant)
+fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    let mut hash = seed;
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        let mut k = u32::from_le_bytes([block[0], block[1], block[2], block[3]]);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+        hash = hash
+            .rotate_left(13)
+            .wrapping_mul(5)
+            .wrapping_add(0xe654_6b64);
+    }
+
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0u32;
+        for (i, &byte) in tail.iter().enumerate() {
+            k |= (byte as u32) << (8 * i);
+        }
+        hash ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^ (hash >> 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn murmur3_matches_scikit_learn() {
+        // sklearn.utils.murmurhash3_32
+        assert_eq!(murmur3_32(b"foo", 0) as i32, -156_908_512);
+        assert_eq!(murmur3_32(b"foo", 42) as i32, -1_322_301_282);
+        assert_eq!(murmur3_32(b"foo", 0), 4_138_058_784);
+        assert_eq!(murmur3_32(b"", 0), 0);
+    }
+
+    #[test]
+    fn rows_match_scikit_learn() {
+        // The examples in the scikit-learn FeatureHasher docs
+        let hasher = FeatureHasher::new(10).unwrap();
+        let rows = hasher.transform([
+            vec![("dog", 1.0), ("cat", 2.0), ("elephant", 4.0)],
+            vec![("dog", 2.0), ("run", 5.0)],
+        ]);
+        assert_eq!(rows[0], [(2, -4.0), (3, -1.0), (9, 2.0)]);
+        assert_eq!(rows[1], [(3, -2.0), (4, -5.0)]);
+
+        let hasher = FeatureHasher::new(8).unwrap();
+        let rows = hasher.transform([
+            vec![("dog", 1.0), ("cat", 1.0), ("snake", 1.0)],
+            vec![("snake", 1.0), ("dog", 1.0)],
+            vec![("cat", 1.0), ("bird", 1.0)],
+        ]);
+        assert_eq!(rows[0], [(3, -1.0), (5, -1.0), (7, 1.0)]);
+        assert_eq!(rows[1], [(3, -1.0), (5, -1.0)]);
+        assert_eq!(rows[2], [(1, -1.0), (7, 1.0)]);
+    }
+
+    #[test]
+    fn collisions_cancel_with_alternate_sign() {
+        // scikit-learn's test_hash_collisions
+        let letters: Vec<String> = "Thequickbrownfoxjumped".chars().map(String::from).collect();
+        let row = || letters.iter().map(|letter| (letter, 1.0));
+        let hasher = FeatureHasher::new(1).unwrap();
+        let signed = hasher.transform_row(row());
+        assert!(signed[0].1.abs() < letters.len() as f32);
+        let unsigned = hasher.alternate_sign(false).transform_row(row());
+        assert_eq!(unsigned, [(0, letters.len() as f32)]);
+    }
+
+    #[test]
+    fn most_negative_hash_is_placed_like_scikit_learn() {
+        assert_eq!(murmur3_32(b"g66hr2", 0) as i32, i32::MIN);
+        let hasher = FeatureHasher::new(10).unwrap();
+        assert_eq!(hasher.index("g66hr2"), (8, -1.0));
+        let hasher = FeatureHasher::new(1 << 18).unwrap();
+        assert_eq!(hasher.index("g66hr2"), (0, -1.0));
+    }
+}
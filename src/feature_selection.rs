@@ -0,0 +1,227 @@
+//! Feature selection by model importance (XGBoost ≥ 1.5).
+//!
+//! Like scikit-learn's `SelectFromModel`: train (or take) a model, keep the
+//! features whose importance reaches a threshold, and project data onto
+//! them to retrain a smaller model.
+//!
+//! # Example
+//! ```no_run
+//! use xgboost_rust::feature_selection::{SelectFromModel, Threshold};
+//! use xgboost_rust::{train, BoosterParams, DMatrix, ImportanceType};
+//!
+//! let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]; // 3 rows, 2 features
+//! let mut dtrain = DMatrix::from_dense(&data, 3, 2).unwrap();
+//! dtrain.set_label(&[0.0, 1.0, 1.0]).unwrap();
+//! let params = BoosterParams::new();
+//!
+//! let selector = SelectFromModel::new()
+//!     .importance_type(ImportanceType::Gain)
+//!     .threshold(Threshold::Mean)
+//!     .fit(&params, &dtrain, 50)
+//!     .unwrap();
+//! println!("kept features {:?}", selector.selected());
+//!
+//! let mut dsmall = selector.transform_dmatrix(&data, 3).unwrap();
+//! dsmall.set_label(&[0.0, 1.0, 1.0]).unwrap();
+//! let smaller = train(&params, &dsmall, 50).unwrap();
+//! ```
+
+use crate::backend::check_dense_len;
+use crate::dmatrix::DMatrix;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::importance::ImportanceType;
+use crate::model::Booster;
+use crate::params::BoosterParams;
+use crate::train::train;
+
+/// Minimum importance a feature needs to be selected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+    /// A fixed importance value
+    Value(f32),
+    /// The mean importance over all features
+    Mean,
+    /// The median importance over all features
+    Median,
+    /// A multiple of the mean importance, e.g. `ScaledMean(1.25)`
+    ScaledMean(f32),
+}
+
+/// Selection rule: importance type, threshold and an optional feature cap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelectFromModel {
+    importance_type: ImportanceType,
+    threshold: Threshold,
+    max_features: Option<usize>,
+}
+
+impl Default for SelectFromModel {
+    fn default() -> Self {
+        SelectFromModel {
+            importance_type: ImportanceType::Gain,
+            threshold: Threshold::Mean,
+            max_features: None,
+        }
+    }
+}
+
+impl SelectFromModel {
+    /// Keep features whose gain is at least the mean gain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How importance is measured
+    pub fn importance_type(mut self, importance_type: ImportanceType) -> Self {
+        self.importance_type = importance_type;
+        self
+    }
+
+    /// Minimum importance of a selected feature
+    pub fn threshold(mut self, threshold: Threshold) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Keep at most the `max_features` most important features
+    pub fn max_features(mut self, max_features: usize) -> Self {
+        self.max_features = Some(max_features);
+        self
+    }
+
+    /// Train a model and select features from it
+    pub fn fit(
+        &self,
+        params: &BoosterParams,
+        dtrain: &DMatrix,
+        num_boost_round: usize,
+    ) -> XGBoostResult<FeatureSelector> {
+        let booster = train(params, dtrain, num_boost_round)?;
+        self.select(&booster)
+    }
+
+    /// Select features from an already trained model
+    pub fn select(&self, booster: &Booster) -> XGBoostResult<FeatureSelector> {
+        let num_features = booster.num_features()?;
+        let names = booster.feature_names()?;
+        let mut scores = vec![0.0f32; num_features];
+        for (name, score) in booster.feature_importance(self.importance_type)? {
+            let index = feature_index(&name, &names).ok_or_else(|| XGBoostError {
+                description: format!("Unknown feature '{}' in importance scores", name),
+            })?;
+            if index >= num_features {
+                return Err(XGBoostError {
+                    description: format!(
+                        "Feature '{}' out of range for {} features",
+                        name, num_features
+                    ),
+                });
+            }
+            scores[index] = score;
+        }
+
+        let threshold = self.threshold_value(&scores);
+        let mut ranked: Vec<usize> = (0..num_features)
+            .filter(|&i| scores[i] >= threshold)
+            .collect();
+        ranked.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+        if let Some(max_features) = self.max_features {
+            ranked.truncate(max_features);
+        }
+        ranked.sort_unstable();
+
+        Ok(FeatureSelector {
+            selected: ranked,
+            scores,
+            names,
+        })
+    }
+
+    fn threshold_value(&self, scores: &[f32]) -> f32 {
+        let mean = || scores.iter().sum::<f32>() / scores.len().max(1) as f32;
+        match self.threshold {
+            Threshold::Value(value) => value,
+            Threshold::Mean => mean(),
+            Threshold::ScaledMean(scale) => scale * mean(),
+            Threshold::Median => {
+                let mut sorted = scores.to_vec();
+                sorted.sort_by(f32::total_cmp);
+                match sorted.len() {
+                    0 => 0.0,
+                    n if n % 2 == 1 => sorted[n / 2],
+                    n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0,
+                }
+            }
+        }
+    }
+}
+
+/// Index of an importance entry: the position of a model feature name, or
+/// the number in XGBoost's default `f<index>` names.
+fn feature_index(name: &str, names: &[String]) -> Option<usize> {
+    if names.is_empty() {
+        name.strip_prefix('f')?.parse().ok()
+    } else {
+        names.iter().position(|n| n == name)
+    }
+}
+
+/// Selected features, and a projection of data onto them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureSelector {
+    selected: Vec<usize>,
+    scores: Vec<f32>,
+    names: Vec<String>,
+}
+
+impl FeatureSelector {
+    /// Indices of the selected features, in their original order
+    pub fn selected(&self) -> &[usize] {
+        &self.selected
+    }
+
+    /// Names of the selected features, if the model has feature names
+    pub fn selected_names(&self) -> Option<Vec<&str>> {
+        if self.names.is_empty() {
+            return None;
+        }
+        Some(
+            self.selected
+                .iter()
+                .map(|&i| self.names[i].as_str())
+                .collect(),
+        )
+    }
+
+    /// Importance of every original feature (0 for unused features)
+    pub fn scores(&self) -> &[f32] {
+        &self.scores
+    }
+
+    /// Number of features of the original data
+    pub fn num_input_features(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// Keep only the selected columns of a row-major dense matrix
+    ///
+    /// `data` must have [`num_input_features`](Self::num_input_features)
+    /// columns; the result has `selected().len()` columns.
+    pub fn transform(&self, data: &[f32], num_rows: usize) -> XGBoostResult<Vec<f32>> {
+        let num_features = self.num_input_features();
+        check_dense_len(data.len(), num_rows, num_features)?;
+        let mut out = Vec::with_capacity(num_rows * self.selected.len());
+        for row in data.chunks(num_features.max(1)).take(num_rows) {
+            out.extend(self.selected.iter().map(|&i| row[i]));
+        }
+        Ok(out)
+    }
+
+    /// [`transform`](Self::transform) into a new DMatrix (NaN is missing)
+    ///
+    /// Labels and weights have to be set on the result again.
+    pub fn transform_dmatrix(&self, data: &[f32], num_rows: usize) -> XGBoostResult<DMatrix> {
+        let projected = self.transform(data, num_rows)?;
+        DMatrix::from_dense(&projected, num_rows, self.selected.len())
+    }
+}
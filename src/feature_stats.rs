@@ -0,0 +1,260 @@
+use crate::backend::Backend;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::Booster;
+
+/// Model attribute holding the statistics
+const ATTRIBUTE: &str = "feature_stats";
+
+/// Per-feature minimum, maximum, mean and missing fraction of the training
+/// data, to check production inputs against.
+///
+/// Compute the statistics from the training matrix, store them in the
+/// model with `save_to` and, when scoring, flag batches whose values fall
+/// outside the training range or that are missing features which were
+/// never missing in training.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{Booster, FeatureStats};
+///
+/// let booster = Booster::load("model.json").unwrap();
+/// let stats = FeatureStats::from_booster(&booster).unwrap().expect("model has feature stats");
+///
+/// let batch = vec![0.5, f32::NAN, 120.0, 3.0]; // 2 rows, 2 features
+/// let report = stats.validate(&batch, 2, 2).unwrap();
+/// for drift in report.features() {
+///     eprintln!(
+///         "feature {}: {} below min, {} above max, {} missing",
+///         drift.feature, drift.below_min, drift.above_max, drift.missing
+///     );
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureStats {
+    min: Vec<f32>,
+    max: Vec<f32>,
+    mean: Vec<f64>,
+    missing: Vec<f64>,
+}
+
+/// How a batch deviates from the training data on one feature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureDrift {
+    /// Feature index
+    pub feature: usize,
+    /// Rows below the training minimum
+    pub below_min: usize,
+    /// Rows above the training maximum
+    pub above_max: usize,
+    /// Rows missing the feature
+    pub missing: usize,
+    /// Whether the feature is missing here but never was in training
+    pub newly_missing: bool,
+    /// Mean of the present values of the batch (NaN if none)
+    pub mean: f64,
+}
+
+/// Result of [`FeatureStats::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputReport {
+    num_rows: usize,
+    features: Vec<FeatureDrift>,
+}
+
+impl InputReport {
+    /// Whether every value was in range and no feature was newly missing
+    pub fn is_ok(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// Features with out-of-range or newly missing values
+    pub fn features(&self) -> &[FeatureDrift] {
+        &self.features
+    }
+
+    /// Number of rows checked
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+}
+
+impl FeatureStats {
+    /// Statistics of row-major dense data; NaN is a missing value
+    pub fn compute(data: &[f32], num_rows: usize, num_features: usize) -> XGBoostResult<Self> {
+        check_len(data, num_rows, num_features)?;
+        let mut stats = FeatureStats {
+            min: vec![f32::INFINITY; num_features],
+            max: vec![f32::NEG_INFINITY; num_features],
+            mean: vec![0.0; num_features],
+            missing: vec![0.0; num_features],
+        };
+        let mut present = vec![0usize; num_features];
+        for row in data.chunks(num_features.max(1)) {
+            for (feature, &value) in row.iter().enumerate() {
+                if value.is_nan() {
+                    stats.missing[feature] += 1.0;
+                    continue;
+                }
+                stats.min[feature] = stats.min[feature].min(value);
+                stats.max[feature] = stats.max[feature].max(value);
+                stats.mean[feature] += f64::from(value);
+                present[feature] += 1;
+            }
+        }
+        for (mean, &present) in stats.mean.iter_mut().zip(&present) {
+            *mean = match present {
+                0 => f64::NAN,
+                n => *mean / n as f64,
+            };
+        }
+        for missing in &mut stats.missing {
+            *missing /= num_rows.max(1) as f64;
+        }
+        Ok(stats)
+    }
+
+    /// Number of features
+    pub fn num_features(&self) -> usize {
+        self.min.len()
+    }
+
+    /// Smallest value of a feature (infinity if it was always missing)
+    pub fn min(&self, feature: usize) -> f32 {
+        self.min[feature]
+    }
+
+    /// Largest value of a feature (negative infinity if it was always
+    /// missing)
+    pub fn max(&self, feature: usize) -> f32 {
+        self.max[feature]
+    }
+
+    /// Mean of the present values of a feature (NaN if it was always
+    /// missing)
+    pub fn mean(&self, feature: usize) -> f64 {
+        self.mean[feature]
+    }
+
+    /// Fraction of rows missing a feature
+    pub fn missing_fraction(&self, feature: usize) -> f64 {
+        self.missing[feature]
+    }
+
+    /// Compare a batch of row-major dense data with the statistics
+    pub fn validate(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<InputReport> {
+        if num_features != self.num_features() {
+            return Err(XGBoostError {
+                description: format!(
+                    "Data has {} features but the statistics cover {}",
+                    num_features,
+                    self.num_features()
+                ),
+            });
+        }
+        check_len(data, num_rows, num_features)?;
+        let mut drift: Vec<FeatureDrift> = (0..num_features)
+            .map(|feature| FeatureDrift {
+                feature,
+                below_min: 0,
+                above_max: 0,
+                missing: 0,
+                newly_missing: false,
+                mean: 0.0,
+            })
+            .collect();
+        for row in data.chunks(num_features.max(1)) {
+            for (feature, &value) in row.iter().enumerate() {
+                let drift = &mut drift[feature];
+                if value.is_nan() {
+                    drift.missing += 1;
+                } else {
+                    drift.below_min += usize::from(value < self.min[feature]);
+                    drift.above_max += usize::from(value > self.max[feature]);
+                    drift.mean += f64::from(value);
+                }
+            }
+        }
+        for drift in &mut drift {
+            let present = num_rows - drift.missing;
+            drift.mean = if present == 0 {
+                f64::NAN
+            } else {
+                drift.mean / present as f64
+            };
+            drift.newly_missing = drift.missing > 0 && self.missing[drift.feature] == 0.0;
+        }
+        drift.retain(|d| d.below_min > 0 || d.above_max > 0 || d.newly_missing);
+        Ok(InputReport {
+            num_rows,
+            features: drift,
+        })
+    }
+
+    /// Store the statistics as a model attribute, saved with the model
+    #[cfg(feature = "native")]
+    pub fn save_to(&self, booster: &mut Booster) -> XGBoostResult<()> {
+        let fields: Vec<String> = (0..self.num_features())
+            .map(|f| {
+                format!(
+                    "{},{},{},{}",
+                    self.min[f], self.max[f], self.mean[f], self.missing[f]
+                )
+            })
+            .collect();
+        booster.set_attr(ATTRIBUTE, &fields.join(","))
+    }
+
+    /// Restore the statistics stored with `save_to`, or `None` if the model
+    /// has none
+    pub fn from_booster(booster: &Booster) -> XGBoostResult<Option<Self>> {
+        Self::from_model(booster)
+    }
+
+    /// [`FeatureStats::from_booster`] for either backend
+    pub(crate) fn from_model<B: Backend>(booster: &B) -> XGBoostResult<Option<Self>> {
+        let Some(value) = booster.attr(ATTRIBUTE)? else {
+            return Ok(None);
+        };
+        let invalid = || XGBoostError {
+            description: "Invalid feature statistics stored in the model".to_string(),
+        };
+        let fields: Vec<&str> = value.split(',').filter(|f| !f.is_empty()).collect();
+        if fields.len() % 4 != 0 {
+            return Err(invalid());
+        }
+        let mut stats = FeatureStats {
+            min: Vec::new(),
+            max: Vec::new(),
+            mean: Vec::new(),
+            missing: Vec::new(),
+        };
+        for feature in fields.chunks(4) {
+            stats.min.push(feature[0].parse().map_err(|_| invalid())?);
+            stats.max.push(feature[1].parse().map_err(|_| invalid())?);
+            stats.mean.push(feature[2].parse().map_err(|_| invalid())?);
+            stats
+                .missing
+                .push(feature[3].parse().map_err(|_| invalid())?);
+        }
+        Ok(Some(stats))
+    }
+}
+
+fn check_len(data: &[f32], num_rows: usize, num_features: usize) -> XGBoostResult<()> {
+    if num_rows.checked_mul(num_features) != Some(data.len()) {
+        return Err(XGBoostError {
+            description: format!(
+                "Data length mismatch: expected {}×{} elements, got {}",
+                num_rows,
+                num_features,
+                data.len()
+            ),
+        });
+    }
+    Ok(())
+}
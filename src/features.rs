@@ -0,0 +1,223 @@
+use crate::error::{XGBoostError, XGBoostResult};
+#[cfg(any(feature = "pure", xgboost_feature_score))]
+use std::borrow::Borrow;
+#[cfg(any(feature = "pure", xgboost_feature_score))]
+use std::collections::HashMap;
+#[cfg(any(feature = "pure", xgboost_feature_score))]
+use std::hash::Hash;
+
+/// How XGBoost treats the values of a feature (`feature_types`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FeatureType {
+    /// Numbers compared against split thresholds (`q`)
+    #[default]
+    Numerical,
+    /// Category codes `0, 1, 2, …`, split into sets of categories (`c`)
+    Categorical,
+}
+
+impl FeatureType {
+    /// The XGBoost name of the type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeatureType::Numerical => "q",
+            FeatureType::Categorical => "c",
+        }
+    }
+
+    /// Parse an XGBoost type name; `int`, `float`, `i` and `q` are all
+    /// numerical
+    #[cfg(any(feature = "pure", xgboost_feature_score))]
+    pub(crate) fn from_name(name: &str) -> Self {
+        if name == "c" {
+            FeatureType::Categorical
+        } else {
+            FeatureType::Numerical
+        }
+    }
+}
+
+/// A value that can be used as a feature.
+///
+/// Implemented for the primitive numbers, `bool` (0 or 1) and `Option`s of
+/// those, where `None` is a missing value.
+pub trait AsFeature {
+    /// The feature value
+    fn as_feature(&self) -> f32;
+}
+
+macro_rules! impl_as_feature {
+    ($($ty:ty),*) => {
+        $(
+            impl AsFeature for $ty {
+                fn as_feature(&self) -> f32 {
+                    *self as f32
+                }
+            }
+        )*
+    };
+}
+
+impl_as_feature!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl AsFeature for bool {
+    fn as_feature(&self) -> f32 {
+        f32::from(u8::from(*self))
+    }
+}
+
+impl<T: AsFeature> AsFeature for Option<T> {
+    fn as_feature(&self) -> f32 {
+        self.as_ref().map_or(f32::NAN, AsFeature::as_feature)
+    }
+}
+
+impl<T: AsFeature + ?Sized> AsFeature for &T {
+    fn as_feature(&self) -> f32 {
+        (**self).as_feature()
+    }
+}
+
+/// A type that describes one row of features.
+///
+/// Usually derived: with the `derive` feature, `#[derive(XgbFeatures)]`
+/// makes every field of a struct a feature, in declaration order and named
+/// after the field. `#[xgb(rename = "name")]` changes a feature's name and
+/// `#[xgb(skip)]` leaves a field out. Rows are scored with
+/// `Booster::predict_structs`, which checks the names against the model.
+///
+/// # Example
+/// ```no_run
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use xgboost_rust::{Booster, PredictOptions, XgbFeatures};
+///
+/// #[derive(XgbFeatures)]
+/// struct Customer {
+///     age: u32,
+///     #[xgb(rename = "monthly_spend")]
+///     spend: f64,
+///     churned_before: bool,
+///     days_since_login: Option<f32>,
+///     #[xgb(skip)]
+///     id: String,
+/// }
+///
+/// assert_eq!(
+///     Customer::FEATURE_NAMES,
+///     ["age", "monthly_spend", "churned_before", "days_since_login"]
+/// );
+///
+/// let booster = Booster::load("churn.json").unwrap();
+/// let customers = vec![Customer {
+///     age: 41,
+///     spend: 89.5,
+///     churned_before: false,
+///     days_since_login: None,
+///     id: "c-1".to_string(),
+/// }];
+/// let predictions = booster.predict_structs(&customers, PredictOptions::empty()).unwrap();
+/// # }
+/// ```
+pub trait XgbFeatures {
+    /// Feature names, in the order the values are written
+    const FEATURE_NAMES: &'static [&'static str];
+
+    /// Append the row's feature values to `out`
+    fn write_features(&self, out: &mut Vec<f32>);
+
+    /// The row's feature values
+    fn to_features(&self) -> Vec<f32> {
+        let mut out = Vec::with_capacity(Self::FEATURE_NAMES.len());
+        self.write_features(&mut out);
+        out
+    }
+}
+
+/// Check the features of `T` against a model and lay out `rows` row-major
+///
+/// The model's feature names are only compared when it has them.
+pub(crate) fn to_dense<T: XgbFeatures>(
+    rows: &[T],
+    num_features: usize,
+    model_names: &[String],
+) -> XGBoostResult<Vec<f32>> {
+    let names = T::FEATURE_NAMES;
+    if names.len() != num_features {
+        return Err(XGBoostError {
+            description: format!(
+                "Rows have {} features but the model expects {}",
+                names.len(),
+                num_features
+            ),
+        });
+    }
+    if !model_names.is_empty() {
+        let mismatch = names
+            .iter()
+            .zip(model_names)
+            .position(|(name, model_name)| name != model_name);
+        if let Some(index) = mismatch {
+            return Err(XGBoostError {
+                description: format!(
+                    "Feature {} is '{}' in the rows but '{}' in the model",
+                    index, names[index], model_names[index]
+                ),
+            });
+        }
+    }
+    let mut data = Vec::with_capacity(rows.len() * num_features);
+    for row in rows {
+        row.write_features(&mut data);
+    }
+    if data.len() != rows.len() * num_features {
+        return Err(XGBoostError {
+            description: format!(
+                "write_features wrote {} values for {} rows of {} features",
+                data.len(),
+                rows.len(),
+                num_features
+            ),
+        });
+    }
+    Ok(data)
+}
+
+/// One row with the model's feature order from values keyed by name;
+/// features without a value are missing
+#[cfg(any(feature = "pure", xgboost_feature_score))]
+pub(crate) fn named_row<K>(
+    features: &HashMap<K, f32>,
+    model_names: &[String],
+) -> XGBoostResult<Vec<f32>>
+where
+    K: Borrow<str> + Hash + Eq,
+{
+    if model_names.is_empty() {
+        return Err(XGBoostError {
+            description: "The model has no feature names".to_string(),
+        });
+    }
+    let mut found = 0;
+    let row: Vec<f32> = model_names
+        .iter()
+        .map(|name| match features.get(name.as_str()) {
+            Some(&value) => {
+                found += 1;
+                value
+            }
+            None => f32::NAN,
+        })
+        .collect();
+    if found < features.len() {
+        let unknown = features
+            .keys()
+            .map(Borrow::borrow)
+            .find(|name: &&str| !model_names.iter().any(|n| n == name))
+            .unwrap_or_default();
+        return Err(XGBoostError {
+            description: format!("The model has no feature named '{}'", unknown),
+        });
+    }
+    Ok(row)
+}
@@ -0,0 +1,785 @@
+//! In-memory representation of an XGBoost learner parsed from its JSON/UBJSON
+//! model document, together with a pure-Rust evaluator for it.
+
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::json::Value;
+
+fn invalid_model(message: impl Into<String>) -> XGBoostError {
+    XGBoostError {
+        description: format!("Invalid model: {}", message.into()),
+    }
+}
+
+fn field<'a>(value: &'a Value, path: &[&str]) -> XGBoostResult<&'a Value> {
+    let mut current = value;
+    for key in path {
+        current = current
+            .get(key)
+            .ok_or_else(|| invalid_model(format!("missing field '{}'", path.join("."))))?;
+    }
+    Ok(current)
+}
+
+fn number(value: &Value, name: &str) -> XGBoostResult<f64> {
+    value
+        .as_f64()
+        .ok_or_else(|| invalid_model(format!("field '{}' is not a number", name)))
+}
+
+fn number_array(value: &Value, name: &str) -> XGBoostResult<Vec<f64>> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid_model(format!("field '{}' is not an array", name)))?
+        .iter()
+        .map(|v| number(v, name))
+        .collect()
+}
+
+fn string_array(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Collect the scalar fields of a JSON object as `prefix.key` pairs, skipping
+/// arrays and the tree models themselves.
+fn flatten_params(prefix: &str, value: &Value, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(entries) => {
+            for (key, value) in entries {
+                if key != "model" {
+                    flatten_params(&format!("{}.{}", prefix, key), value, out);
+                }
+            }
+        }
+        Value::String(s) => out.push((prefix.to_string(), s.clone())),
+        Value::Number(n) => out.push((prefix.to_string(), n.to_string())),
+        Value::Bool(b) => out.push((prefix.to_string(), b.to_string())),
+        Value::Null | Value::Array(_) => {}
+    }
+}
+
+/// Iterate over the rows of a row-major dense matrix.
+#[cfg(feature = "pure")]
+fn rows(data: &[f32], num_rows: usize, num_features: usize) -> impl Iterator<Item = &[f32]> {
+    (0..num_rows).map(move |r| &data[r * num_features..(r + 1) * num_features])
+}
+
+/// The learning objective of a model, which determines how raw margins are
+/// transformed into predictions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Objective {
+    pub(crate) name: String,
+}
+
+impl Objective {
+    /// Convert a `base_score` stored in prediction space into margin space.
+    fn prob_to_margin(&self, base_score: f32) -> f32 {
+        match self.name.as_str() {
+            "binary:logistic" | "reg:logistic" | "binary:logitraw" => {
+                -(1.0 / base_score - 1.0).ln()
+            }
+            "count:poisson" | "reg:gamma" | "reg:tweedie" | "survival:cox" | "survival:aft" => {
+                base_score.ln()
+            }
+            _ => base_score,
+        }
+    }
+
+    /// Transform row-major raw margins (`num_rows x num_groups`) into predictions.
+    #[cfg(feature = "pure")]
+    fn transform(&self, mut margins: Vec<f32>, num_groups: usize) -> Vec<f32> {
+        match self.name.as_str() {
+            "binary:logistic" | "reg:logistic" => {
+                margins
+                    .iter_mut()
+                    .for_each(|m| *m = 1.0 / (1.0 + (-*m).exp()));
+                margins
+            }
+            "count:poisson" | "reg:gamma" | "reg:tweedie" | "survival:cox" | "survival:aft" => {
+                margins.iter_mut().for_each(|m| *m = m.exp());
+                margins
+            }
+            "binary:hinge" => {
+                margins
+                    .iter_mut()
+                    .for_each(|m| *m = if *m > 0.0 { 1.0 } else { 0.0 });
+                margins
+            }
+            "multi:softprob" => {
+                for row in margins.chunks_mut(num_groups) {
+                    let max = row.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                    let mut sum = 0.0;
+                    for m in row.iter_mut() {
+                        *m = (*m - max).exp();
+                        sum += *m;
+                    }
+                    row.iter_mut().for_each(|m| *m /= sum);
+                }
+                margins
+            }
+            "multi:softmax" => margins
+                .chunks(num_groups)
+                .map(|row| {
+                    let mut best = 0;
+                    for (i, m) in row.iter().enumerate() {
+                        if *m > row[best] {
+                            best = i;
+                        }
+                    }
+                    best as f32
+                })
+                .collect(),
+            _ => margins,
+        }
+    }
+}
+
+/// A single node of a regression tree.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Node {
+    /// Index of the left child, or `-1` for leaves.
+    pub(crate) left: i32,
+    pub(crate) right: i32,
+    pub(crate) split_index: u32,
+    /// Split threshold for internal nodes, leaf value for leaves.
+    pub(crate) split_condition: f32,
+    pub(crate) default_left: bool,
+    /// Categories sent to the right child for categorical splits.
+    pub(crate) categories: Option<Vec<u32>>,
+}
+
+impl Node {
+    pub(crate) fn is_leaf(&self) -> bool {
+        self.left < 0
+    }
+}
+
+/// A regression tree stored as a flat node array rooted at index 0.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Tree {
+    pub(crate) nodes: Vec<Node>,
+}
+
+impl Tree {
+    fn from_value(value: &Value) -> XGBoostResult<Self> {
+        if let Some(size) = value
+            .get("tree_param")
+            .and_then(|p| p.get("size_leaf_vector"))
+            .and_then(Value::as_f64)
+        {
+            if size > 1.0 {
+                return Err(invalid_model(
+                    "multi-output trees (multi_strategy=multi_output_tree) are not supported",
+                ));
+            }
+        }
+
+        let left = number_array(field(value, &["left_children"])?, "left_children")?;
+        let right = number_array(field(value, &["right_children"])?, "right_children")?;
+        let split_indices = number_array(field(value, &["split_indices"])?, "split_indices")?;
+        let split_conditions =
+            number_array(field(value, &["split_conditions"])?, "split_conditions")?;
+        let default_left = number_array(field(value, &["default_left"])?, "default_left")?;
+
+        let num_nodes = left.len();
+        if [
+            right.len(),
+            split_indices.len(),
+            split_conditions.len(),
+            default_left.len(),
+        ]
+        .iter()
+        .any(|len| *len != num_nodes)
+            || num_nodes == 0
+        {
+            return Err(invalid_model("tree node arrays have inconsistent lengths"));
+        }
+
+        let mut nodes: Vec<Node> = (0..num_nodes)
+            .map(|i| Node {
+                left: left[i] as i32,
+                right: right[i] as i32,
+                split_index: split_indices[i] as u32,
+                split_condition: split_conditions[i] as f32,
+                default_left: default_left[i] != 0.0,
+                categories: None,
+            })
+            .collect();
+
+        // Children always come after their parent, which also rules out cycles.
+        for (nid, node) in nodes.iter().enumerate() {
+            let valid_child = |child: i32| child as usize > nid && (child as usize) < num_nodes;
+            if !node.is_leaf() && (!valid_child(node.left) || !valid_child(node.right)) {
+                return Err(invalid_model("tree child index out of range"));
+            }
+        }
+
+        // Categorical splits are stored as a CSR-like list of category sets.
+        if let Some(split_type) = value.get("split_type") {
+            let split_type = number_array(split_type, "split_type")?;
+            if split_type.contains(&1.0) {
+                let categories = number_array(field(value, &["categories"])?, "categories")?;
+                let cat_nodes =
+                    number_array(field(value, &["categories_nodes"])?, "categories_nodes")?;
+                let segments = number_array(
+                    field(value, &["categories_segments"])?,
+                    "categories_segments",
+                )?;
+                let sizes = number_array(field(value, &["categories_sizes"])?, "categories_sizes")?;
+                for ((nid, begin), size) in cat_nodes.iter().zip(&segments).zip(&sizes) {
+                    let (nid, begin, size) = (*nid as usize, *begin as usize, *size as usize);
+                    let set = categories
+                        .get(begin..begin + size)
+                        .ok_or_else(|| invalid_model("category segment out of range"))?;
+                    let node = nodes
+                        .get_mut(nid)
+                        .ok_or_else(|| invalid_model("categorical node out of range"))?;
+                    let mut set: Vec<u32> = set.iter().map(|c| *c as u32).collect();
+                    set.sort_unstable();
+                    node.categories = Some(set);
+                }
+            }
+        }
+
+        Ok(Tree { nodes })
+    }
+
+    /// Walk the tree for one row and return the index of the reached leaf.
+    #[cfg(feature = "pure")]
+    pub(crate) fn leaf_index(&self, row: &[f32]) -> usize {
+        let mut nid = 0;
+        loop {
+            let node = &self.nodes[nid];
+            if node.is_leaf() {
+                return nid;
+            }
+            let fvalue = row
+                .get(node.split_index as usize)
+                .copied()
+                .unwrap_or(f32::NAN);
+            let go_left = if fvalue.is_nan() {
+                node.default_left
+            } else if let Some(categories) = &node.categories {
+                // Same rule as XGBoost: invalid or unseen categories go left,
+                // categories in the split set go right.
+                fvalue < 0.0 || categories.binary_search(&(fvalue as u32)).is_err()
+            } else {
+                fvalue < node.split_condition
+            };
+            nid = if go_left { node.left } else { node.right } as usize;
+        }
+    }
+
+    #[cfg(feature = "pure")]
+    pub(crate) fn leaf_value(&self, row: &[f32]) -> f32 {
+        self.nodes[self.leaf_index(row)].split_condition
+    }
+}
+
+/// A tree ensemble (`gbtree` or `dart`).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TreeEnsemble {
+    pub(crate) trees: Vec<Tree>,
+    /// Output group each tree contributes to.
+    pub(crate) tree_groups: Vec<usize>,
+    /// Per-tree scaling; all ones except for DART models.
+    pub(crate) tree_weights: Vec<f32>,
+    /// Index of the first tree of each boosting round, followed by the number of trees.
+    pub(crate) iteration_indptr: Vec<usize>,
+}
+
+impl TreeEnsemble {
+    fn from_value(
+        model: &Value,
+        weight_drop: Option<&Value>,
+        num_groups: usize,
+    ) -> XGBoostResult<Self> {
+        let trees = field(model, &["trees"])?
+            .as_array()
+            .ok_or_else(|| invalid_model("field 'trees' is not an array"))?
+            .iter()
+            .map(Tree::from_value)
+            .collect::<XGBoostResult<Vec<_>>>()?;
+        let tree_groups: Vec<usize> = number_array(field(model, &["tree_info"])?, "tree_info")?
+            .into_iter()
+            .map(|g| g as usize)
+            .collect();
+        if tree_groups.len() != trees.len() {
+            return Err(invalid_model(
+                "'tree_info' length does not match number of trees",
+            ));
+        }
+        let tree_weights = match weight_drop {
+            Some(weights) => {
+                let weights: Vec<f32> = number_array(weights, "weight_drop")?
+                    .into_iter()
+                    .map(|w| w as f32)
+                    .collect();
+                if weights.len() != trees.len() {
+                    return Err(invalid_model(
+                        "'weight_drop' length does not match number of trees",
+                    ));
+                }
+                weights
+            }
+            None => vec![1.0; trees.len()],
+        };
+        // XGBoost >= 2.0 stores the round boundaries; older models have a
+        // fixed number of trees per round.
+        let iteration_indptr: Vec<usize> = match model.get("iteration_indptr") {
+            Some(indptr) => number_array(indptr, "iteration_indptr")?
+                .into_iter()
+                .map(|i| i as usize)
+                .collect(),
+            None => {
+                let num_parallel_tree = model
+                    .get("gbtree_model_param")
+                    .and_then(|p| p.get("num_parallel_tree"))
+                    .and_then(Value::as_f64)
+                    .unwrap_or(1.0)
+                    .max(1.0) as usize;
+                let per_round = num_parallel_tree * num_groups;
+                (0..=trees.len() / per_round)
+                    .map(|round| round * per_round)
+                    .collect()
+            }
+        };
+        Ok(TreeEnsemble {
+            trees,
+            tree_groups,
+            tree_weights,
+            iteration_indptr,
+        })
+    }
+}
+
+#[cfg(feature = "pure")]
+impl TreeEnsemble {
+    /// Number of trees in the first `iteration_end` rounds (all trees for `None`).
+    fn num_trees(&self, iteration_end: Option<usize>) -> usize {
+        iteration_end
+            .and_then(|end| self.iteration_indptr.get(end).copied())
+            .unwrap_or(self.trees.len())
+            .min(self.trees.len())
+    }
+}
+
+/// A linear booster (`gblinear`).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LinearModel {
+    /// Feature-major weights `(num_feature + 1) x num_groups`; the last row is the bias.
+    pub(crate) weights: Vec<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum GradientBooster {
+    Tree(TreeEnsemble),
+    Linear(LinearModel),
+}
+
+/// A parsed XGBoost learner.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Learner {
+    pub(crate) objective: Objective,
+    pub(crate) num_feature: usize,
+    pub(crate) num_groups: usize,
+    /// Base score per output group, already converted to margin space.
+    pub(crate) base_margin: Vec<f32>,
+    pub(crate) booster: GradientBooster,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) feature_names: Vec<String>,
+    pub(crate) feature_types: Vec<String>,
+    /// Scalar model parameters (`learner_model_param`, objective and booster
+    /// settings) as dotted paths, e.g. `objective.reg_loss_param.scale_pos_weight`.
+    pub(crate) params: Vec<(String, String)>,
+}
+
+impl Learner {
+    /// Build a learner from a decoded model document.
+    pub(crate) fn from_document(document: &Value) -> XGBoostResult<Self> {
+        let learner = field(document, &["learner"])?;
+        let params = field(learner, &["learner_model_param"])?;
+
+        let num_feature = number(field(params, &["num_feature"])?, "num_feature")? as usize;
+        let num_class = params
+            .get("num_class")
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0);
+        let num_target = params
+            .get("num_target")
+            .and_then(Value::as_f64)
+            .unwrap_or(1.0);
+        let num_groups = num_class.max(num_target).max(1.0) as usize;
+
+        let objective = Objective {
+            name: field(learner, &["objective", "name"])?
+                .as_str()
+                .ok_or_else(|| invalid_model("objective name is not a string"))?
+                .to_string(),
+        };
+
+        // XGBoost >= 3.1 stores the intercept as a vector ("[5E-1]").
+        let base_score = field(params, &["base_score"])?;
+        let base_scores: Vec<f32> = match base_score {
+            Value::String(s) => s
+                .trim_matches(|c| c == '[' || c == ']')
+                .split(',')
+                .map(|v| v.trim().parse::<f32>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| invalid_model("field 'base_score' is not a number"))?,
+            other => vec![number(other, "base_score")? as f32],
+        };
+        let base_margin: Vec<f32> = match base_scores.len() {
+            1 => vec![objective.prob_to_margin(base_scores[0]); num_groups],
+            n if n == num_groups => base_scores
+                .iter()
+                .map(|b| objective.prob_to_margin(*b))
+                .collect(),
+            _ => {
+                return Err(invalid_model(
+                    "'base_score' length does not match output groups",
+                ))
+            }
+        };
+
+        let gbm = field(learner, &["gradient_booster"])?;
+        let booster = match field(gbm, &["name"])?.as_str() {
+            Some("gbtree") => GradientBooster::Tree(TreeEnsemble::from_value(
+                field(gbm, &["model"])?,
+                None,
+                num_groups,
+            )?),
+            Some("dart") => GradientBooster::Tree(TreeEnsemble::from_value(
+                field(gbm, &["gbtree", "model"])?,
+                Some(field(gbm, &["weight_drop"])?),
+                num_groups,
+            )?),
+            Some("gblinear") => {
+                let weights: Vec<f32> =
+                    number_array(field(gbm, &["model", "weights"])?, "weights")?
+                        .into_iter()
+                        .map(|w| w as f32)
+                        .collect();
+                if weights.len() != (num_feature + 1) * num_groups {
+                    return Err(invalid_model(
+                        "gblinear weight count does not match model shape",
+                    ));
+                }
+                GradientBooster::Linear(LinearModel { weights })
+            }
+            other => {
+                return Err(invalid_model(format!(
+                    "unsupported booster type {:?}",
+                    other.unwrap_or("<missing>")
+                )))
+            }
+        };
+
+        let attributes = learner
+            .get("attributes")
+            .and_then(Value::as_object)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut flat_params = Vec::new();
+        flatten_params("learner_model_param", params, &mut flat_params);
+        flatten_params(
+            "objective",
+            field(learner, &["objective"])?,
+            &mut flat_params,
+        );
+        flatten_params("gradient_booster", gbm, &mut flat_params);
+
+        Ok(Learner {
+            objective,
+            num_feature,
+            num_groups,
+            base_margin,
+            booster,
+            attributes,
+            feature_names: string_array(learner.get("feature_names")),
+            feature_types: string_array(learner.get("feature_types")),
+            params: flat_params,
+        })
+    }
+
+    /// Raw margins, row-major `num_rows x num_groups`.
+    ///
+    /// A per-row `base_margin` (same layout as the output) replaces the
+    /// model's `base_score`.
+    #[cfg(feature = "pure")]
+    pub(crate) fn predict_margin(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        base_margin: Option<&[f32]>,
+        iteration_end: Option<usize>,
+    ) -> Vec<f32> {
+        let mut margins: Vec<f32> = match base_margin {
+            Some(base_margin) => base_margin.to_vec(),
+            None => (0..num_rows)
+                .flat_map(|_| self.base_margin.iter().copied())
+                .collect(),
+        };
+
+        match &self.booster {
+            GradientBooster::Tree(ensemble) => {
+                for (row, out) in
+                    rows(data, num_rows, num_features).zip(margins.chunks_mut(self.num_groups))
+                {
+                    for ((tree, group), weight) in ensemble
+                        .trees
+                        .iter()
+                        .zip(&ensemble.tree_groups)
+                        .zip(&ensemble.tree_weights)
+                        .take(ensemble.num_trees(iteration_end))
+                    {
+                        out[*group] += weight * tree.leaf_value(row);
+                    }
+                }
+            }
+            GradientBooster::Linear(linear) => {
+                let bias = &linear.weights[self.num_feature * self.num_groups..];
+                for (row, out) in
+                    rows(data, num_rows, num_features).zip(margins.chunks_mut(self.num_groups))
+                {
+                    for (group, value) in out.iter_mut().enumerate() {
+                        let mut sum = bias[group];
+                        for (fid, x) in row.iter().enumerate().take(self.num_feature) {
+                            if !x.is_nan() {
+                                sum += x * linear.weights[fid * self.num_groups + group];
+                            }
+                        }
+                        *value += sum;
+                    }
+                }
+            }
+        }
+        margins
+    }
+
+    /// The `best_iteration` attribute set by early stopping, if any.
+    #[cfg(feature = "pure")]
+    pub(crate) fn best_iteration(&self) -> Option<usize> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == "best_iteration")
+            .and_then(|(_, v)| v.parse().ok())
+    }
+
+    /// Transformed predictions according to the model objective.
+    #[cfg(feature = "pure")]
+    pub(crate) fn predict(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        base_margin: Option<&[f32]>,
+        iteration_end: Option<usize>,
+    ) -> Vec<f32> {
+        let margins = self.predict_margin(data, num_rows, num_features, base_margin, iteration_end);
+        self.objective.transform(margins, self.num_groups)
+    }
+
+    /// Leaf indices, row-major `num_rows x num_trees`.
+    #[cfg(feature = "pure")]
+    pub(crate) fn predict_leaf(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        iteration_end: Option<usize>,
+    ) -> XGBoostResult<Vec<f32>> {
+        match &self.booster {
+            GradientBooster::Tree(ensemble) => {
+                let num_trees = ensemble.num_trees(iteration_end);
+                Ok(rows(data, num_rows, num_features)
+                    .flat_map(|row| {
+                        ensemble.trees[..num_trees]
+                            .iter()
+                            .map(move |tree| tree.leaf_index(row) as f32)
+                    })
+                    .collect())
+            }
+            GradientBooster::Linear(_) => Err(XGBoostError {
+                description: "gblinear models do not support leaf index prediction".to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::parse_json;
+
+    /// `f0 < 0.5`, missing goes left; leaves -1 and 1
+    const NUMERICAL: &str = r#"{"left_children": [1, -1, -1], "right_children": [2, -1, -1],
+        "split_indices": [0, 0, 0], "split_conditions": [0.5, -1.0, 1.0],
+        "default_left": [1, 0, 0]}"#;
+    /// `f1` in {2, 3} goes right, missing goes right; leaves -0.5 and 0.5
+    const CATEGORICAL: &str = r#"{"left_children": [1, -1, -1], "right_children": [2, -1, -1],
+        "split_indices": [1, 0, 0], "split_conditions": [NaN, -0.5, 0.5],
+        "default_left": [0, 0, 0], "split_type": [1, 0, 0], "categories": [3, 2],
+        "categories_nodes": [0], "categories_segments": [0], "categories_sizes": [2]}"#;
+
+    fn learner(objective: &str, num_class: usize, gradient_booster: &str) -> Learner {
+        let document = format!(
+            r#"{{"learner": {{
+                "learner_model_param": {{"num_feature": "2", "num_class": "{}", "base_score": "5E-1"}},
+                "objective": {{"name": "{}"}},
+                "gradient_booster": {}
+            }}}}"#,
+            num_class, objective, gradient_booster
+        );
+        Learner::from_document(&parse_json(document.as_bytes()).unwrap()).unwrap()
+    }
+
+    fn gbtree(trees: &[&str], tree_info: &str) -> String {
+        format!(
+            r#"{{"name": "gbtree", "model": {{"trees": [{}], "tree_info": {}}}}}"#,
+            trees.join(", "),
+            tree_info
+        )
+    }
+
+    fn tree(json: &str) -> XGBoostResult<Tree> {
+        Tree::from_value(&parse_json(json.as_bytes()).unwrap())
+    }
+
+    #[test]
+    fn base_score_is_stored_in_margin_space() {
+        let trees = gbtree(&[NUMERICAL], "[0]");
+        assert_eq!(learner("reg:squarederror", 0, &trees).base_margin, [0.5]);
+        assert_eq!(learner("binary:logistic", 0, &trees).base_margin, [0.0]);
+        assert_eq!(
+            learner("count:poisson", 0, &trees).base_margin,
+            [0.5f32.ln()]
+        );
+    }
+
+    #[test]
+    fn categories_are_sorted() {
+        let tree = tree(CATEGORICAL).unwrap();
+        assert_eq!(tree.nodes[0].categories, Some(vec![2, 3]));
+        assert_eq!(tree.nodes[1].categories, None);
+    }
+
+    #[test]
+    fn malformed_trees_are_rejected() {
+        let error = |json: &str| tree(json).unwrap_err().description;
+        assert!(error(&NUMERICAL.replace("[2, -1, -1]", "[0, -1, -1]"))
+            .contains("child index out of range"));
+        assert!(
+            error(&NUMERICAL.replace("[2, -1, -1]", "[2, -1]")).contains("inconsistent lengths")
+        );
+        assert!(error(
+            &CATEGORICAL.replace("\"categories_sizes\": [2]", "\"categories_sizes\": [3]")
+        )
+        .contains("category segment out of range"));
+        let multi_output = NUMERICAL.replace('}', r#", "tree_param": {"size_leaf_vector": "2"}}"#);
+        assert!(error(&multi_output).contains("multi-output trees"));
+    }
+
+    #[cfg(feature = "pure")]
+    #[test]
+    fn numerical_splits() {
+        let tree = tree(NUMERICAL).unwrap();
+        assert_eq!(tree.leaf_index(&[0.2, 0.0]), 1);
+        assert_eq!(tree.leaf_index(&[0.5, 0.0]), 2);
+        assert_eq!(tree.leaf_index(&[f32::NAN, 0.0]), 1);
+        // features past the end of the row are missing
+        assert_eq!(tree.leaf_index(&[]), 1);
+        assert_eq!(tree.leaf_value(&[0.7]), 1.0);
+    }
+
+    #[cfg(feature = "pure")]
+    #[test]
+    fn categorical_splits() {
+        let tree = tree(CATEGORICAL).unwrap();
+        let leaf = |category: f32| tree.leaf_index(&[0.0, category]);
+        assert_eq!(leaf(2.0), 2);
+        assert_eq!(leaf(3.0), 2);
+        assert_eq!(leaf(f32::NAN), 2);
+        // unseen, negative and out-of-range codes go left
+        assert_eq!(leaf(1.0), 1);
+        assert_eq!(leaf(-2.0), 1);
+        assert_eq!(leaf(1e9), 1);
+    }
+
+    #[cfg(feature = "pure")]
+    #[test]
+    fn margins_sum_the_trees() {
+        let learner = learner(
+            "reg:squarederror",
+            0,
+            &gbtree(&[NUMERICAL, CATEGORICAL], "[0, 0]"),
+        );
+        let data = [0.2, 2.0, 0.7, 1.0];
+        assert_eq!(learner.predict_margin(&data, 2, 2, None, None), [0.0, 1.0]);
+        // one tree per round when the model has no iteration_indptr
+        assert_eq!(
+            learner.predict_margin(&data, 2, 2, None, Some(1)),
+            [-0.5, 1.5]
+        );
+        assert_eq!(
+            learner.predict_margin(&data, 2, 2, Some(&[1.0, -1.0]), None),
+            [0.5, -0.5]
+        );
+        assert_eq!(
+            learner.predict_leaf(&data, 2, 2, None).unwrap(),
+            [1.0, 2.0, 2.0, 1.0]
+        );
+    }
+
+    #[cfg(feature = "pure")]
+    #[test]
+    fn trees_add_to_their_output_group() {
+        let learner = learner(
+            "multi:softprob",
+            2,
+            &gbtree(&[NUMERICAL, CATEGORICAL], "[0, 1]"),
+        );
+        let data = [0.2, 2.0];
+        assert_eq!(learner.predict_margin(&data, 1, 2, None, None), [-0.5, 1.0]);
+        let probabilities = learner.predict(&data, 1, 2, None, None);
+        assert!((probabilities[0] - 1.0 / (1.0 + 1.5f32.exp())).abs() < 1e-6);
+        assert!((probabilities.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+    }
+
+    #[cfg(feature = "pure")]
+    #[test]
+    fn dart_scales_the_trees() {
+        let dart = format!(
+            r#"{{"name": "dart", "weight_drop": [0.5, 2.0], "gbtree": {}}}"#,
+            gbtree(&[NUMERICAL, CATEGORICAL], "[0, 0]")
+        );
+        let learner = learner("reg:squarederror", 0, &dart);
+        assert_eq!(learner.predict_margin(&[0.2, 2.0], 1, 2, None, None), [1.0]);
+    }
+
+    #[cfg(feature = "pure")]
+    #[test]
+    fn linear_models_skip_missing_features() {
+        let linear = r#"{"name": "gblinear", "model": {"weights": [2.0, 3.0, 0.25]}}"#;
+        let learner = learner("reg:squarederror", 0, linear);
+        assert_eq!(
+            learner.predict_margin(&[1.0, f32::NAN, 1.0, 1.0], 2, 2, None, None),
+            [2.75, 5.75]
+        );
+        assert!(learner.predict_leaf(&[1.0, 1.0], 1, 2, None).is_err());
+    }
+}
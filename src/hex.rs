@@ -0,0 +1,18 @@
+//! Lowercase hexadecimal encoding of digests and signatures.
+
+/// `bytes` as lowercase hex
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The bytes of a hex string, or `None` if it is not one
+#[cfg(feature = "integrity")]
+pub(crate) fn decode(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,38 @@
+use std::fmt;
+
+/// How feature importance is measured.
+///
+/// The same definitions as XGBoost's `importance_type`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ImportanceType {
+    /// Number of splits on the feature
+    #[default]
+    Weight,
+    /// Average loss reduction of the feature's splits
+    Gain,
+    /// Average number of training rows (hessian) covered by the feature's splits
+    Cover,
+    /// Total loss reduction of the feature's splits
+    TotalGain,
+    /// Total coverage of the feature's splits
+    TotalCover,
+}
+
+impl ImportanceType {
+    /// The `importance_type` string XGBoost uses
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportanceType::Weight => "weight",
+            ImportanceType::Gain => "gain",
+            ImportanceType::Cover => "cover",
+            ImportanceType::TotalGain => "total_gain",
+            ImportanceType::TotalCover => "total_cover",
+        }
+    }
+}
+
+impl fmt::Display for ImportanceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
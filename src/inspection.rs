@@ -0,0 +1,182 @@
+//! Model inspection: partial dependence of predictions on features.
+//!
+//! Partial dependence sets a feature to each value of a grid on every row of
+//! a dataset and averages the model's predictions, showing how predictions
+//! change with that feature on average.
+//!
+//! # Example
+//! ```no_run
+//! use xgboost_rust::inspection::{partial_dependence, quantile_grid};
+//! use xgboost_rust::Booster;
+//!
+//! let booster = Booster::load("model.json").unwrap();
+//! let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]; // 3 rows, 2 features
+//!
+//! let grid = quantile_grid(&data, 3, 2, 0, 20).unwrap();
+//! for (value, mean) in partial_dependence(&booster, &data, 3, 2, 0, &grid).unwrap() {
+//!     println!("f0 = {}: {}", value, mean);
+//! }
+//! ```
+
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::predict_options::PredictOptions;
+use crate::Booster;
+
+/// Mean prediction over all rows of `data` with `feature` set to each grid value
+///
+/// # Arguments
+/// * `booster` - A model with one output per row
+/// * `data` - Row-major dense data (num_rows x num_features)
+/// * `feature` - Index of the feature to vary
+/// * `grid` - Values to set the feature to
+///
+/// # Returns
+/// `(value, mean prediction)` for every grid value, in grid order
+pub fn partial_dependence(
+    booster: &Booster,
+    data: &[f32],
+    num_rows: usize,
+    num_features: usize,
+    feature: usize,
+    grid: &[f32],
+) -> XGBoostResult<Vec<(f32, f64)>> {
+    check_features(data, num_rows, num_features, &[feature])?;
+    let mut perturbed = data.to_vec();
+    grid.iter()
+        .map(|&value| {
+            set_column(&mut perturbed, num_features, feature, value);
+            let mean = mean_prediction(booster, &perturbed, num_rows, num_features)?;
+            Ok((value, mean))
+        })
+        .collect()
+}
+
+/// Two-feature partial dependence, to show interactions between them
+///
+/// # Returns
+/// `(value_a, value_b, mean prediction)` for every pair of grid values,
+/// with `grids.1` varying fastest
+pub fn partial_dependence_2d(
+    booster: &Booster,
+    data: &[f32],
+    num_rows: usize,
+    num_features: usize,
+    features: (usize, usize),
+    grids: (&[f32], &[f32]),
+) -> XGBoostResult<Vec<(f32, f32, f64)>> {
+    check_features(data, num_rows, num_features, &[features.0, features.1])?;
+    if features.0 == features.1 {
+        return Err(XGBoostError {
+            description: format!("Both features are {}", features.0),
+        });
+    }
+    let mut perturbed = data.to_vec();
+    let mut out = Vec::with_capacity(grids.0.len() * grids.1.len());
+    for &a in grids.0 {
+        set_column(&mut perturbed, num_features, features.0, a);
+        for &b in grids.1 {
+            set_column(&mut perturbed, num_features, features.1, b);
+            let mean = mean_prediction(booster, &perturbed, num_rows, num_features)?;
+            out.push((a, b, mean));
+        }
+    }
+    Ok(out)
+}
+
+/// Grid of `num_points` evenly spaced percentiles (5th to 95th) of a feature
+///
+/// Missing (NaN) values are ignored; duplicate grid values are removed, so
+/// discrete features yield fewer points.
+pub fn quantile_grid(
+    data: &[f32],
+    num_rows: usize,
+    num_features: usize,
+    feature: usize,
+    num_points: usize,
+) -> XGBoostResult<Vec<f32>> {
+    check_features(data, num_rows, num_features, &[feature])?;
+    let mut values: Vec<f32> = (0..num_rows)
+        .map(|row| data[row * num_features + feature])
+        .filter(|v| !v.is_nan())
+        .collect();
+    if values.is_empty() {
+        return Err(XGBoostError {
+            description: format!("Feature {} has no non-missing values", feature),
+        });
+    }
+    values.sort_by(f32::total_cmp);
+
+    let percentile = |p: f64| {
+        let position = p * (values.len() - 1) as f64;
+        let (low, frac) = (position.floor() as usize, position.fract() as f32);
+        let high = (low + 1).min(values.len() - 1);
+        values[low] + frac * (values[high] - values[low])
+    };
+    let mut grid: Vec<f32> = match num_points {
+        0 => Vec::new(),
+        1 => vec![percentile(0.5)],
+        n => (0..n)
+            .map(|i| percentile(0.05 + 0.9 * i as f64 / (n - 1) as f64))
+            .collect(),
+    };
+    grid.dedup();
+    Ok(grid)
+}
+
+fn check_features(
+    data: &[f32],
+    num_rows: usize,
+    num_features: usize,
+    features: &[usize],
+) -> XGBoostResult<()> {
+    if num_rows.checked_mul(num_features) != Some(data.len()) {
+        return Err(XGBoostError {
+            description: format!(
+                "Data length mismatch: expected {}×{} elements, got {}",
+                num_rows,
+                num_features,
+                data.len()
+            ),
+        });
+    }
+    if num_rows == 0 {
+        return Err(XGBoostError {
+            description: "Partial dependence needs at least one row".to_string(),
+        });
+    }
+    if let Some(&feature) = features.iter().find(|&&f| f >= num_features) {
+        return Err(XGBoostError {
+            description: format!(
+                "Feature {} out of range for {} features",
+                feature, num_features
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn set_column(data: &mut [f32], num_features: usize, feature: usize, value: f32) {
+    for row in data.chunks_mut(num_features) {
+        row[feature] = value;
+    }
+}
+
+fn mean_prediction(
+    booster: &Booster,
+    data: &[f32],
+    num_rows: usize,
+    num_features: usize,
+) -> XGBoostResult<f64> {
+    let predictions =
+        booster.predict(data, num_rows, num_features, PredictOptions::empty(), false)?;
+    if predictions.len() != num_rows {
+        return Err(XGBoostError {
+            description: format!(
+                "Partial dependence needs one output per row, got {} predictions for {} rows",
+                predictions.len(),
+                num_rows
+            ),
+        });
+    }
+    Ok(predictions.iter().map(|&p| p as f64).sum::<f64>() / num_rows as f64)
+}
@@ -0,0 +1,486 @@
+//! Latency and throughput instrumentation for prediction.
+//!
+//! [`InstrumentedBooster`] times every prediction and reports it to a
+//! [`PredictRecorder`]. The built-in [`PredictStats`] keeps latency and
+//! batch-size histograms and renders them in the Prometheus text format
+//! for a `/metrics` endpoint; implement the trait to forward measurements
+//! to another metrics system instead, or enable the `metrics` feature
+//! and use `MetricsRecorder` to report through the `metrics` crate.
+//!
+//! # Example
+//! ```no_run
+//! use std::sync::Arc;
+//! use xgboost_rust::instrument::{InstrumentedBooster, PredictStats};
+//! use xgboost_rust::{Booster, PredictOptions};
+//!
+//! let stats = Arc::new(PredictStats::new());
+//! let booster = InstrumentedBooster::new(Booster::load("model.json").unwrap(), Arc::clone(&stats));
+//! let predictions = booster.predict(&[1.0, 2.0], 1, 2, PredictOptions::empty(), false).unwrap();
+//!
+//! // in the /metrics handler
+//! let body = stats.to_prometheus("churn_model");
+//! ```
+
+#[cfg(feature = "native")]
+use crate::dmatrix::DMatrix;
+use crate::error::XGBoostResult;
+use crate::features::XgbFeatures;
+use crate::predict_options::PredictOptions;
+use crate::records;
+use crate::Booster;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Receives a measurement for every prediction call.
+pub trait PredictRecorder {
+    /// A successful call predicting `num_rows` rows in `latency`
+    fn record(&self, num_rows: usize, latency: Duration);
+
+    /// A call that failed
+    fn record_error(&self) {}
+}
+
+impl<R: PredictRecorder + ?Sized> PredictRecorder for Arc<R> {
+    fn record(&self, num_rows: usize, latency: Duration) {
+        (**self).record(num_rows, latency)
+    }
+
+    fn record_error(&self) {
+        (**self).record_error()
+    }
+}
+
+/// Upper bounds of the latency buckets, in seconds
+const LATENCY_BUCKETS: [f64; 14] = [
+    0.000_05, 0.000_1, 0.000_25, 0.000_5, 0.001, 0.002_5, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5,
+    1.0,
+];
+
+/// Upper bounds of the batch size buckets, in rows
+const BATCH_BUCKETS: [usize; 13] = [1, 2, 4, 8, 16, 32, 64, 128, 256, 1024, 4096, 16_384, 65_536];
+
+/// Histograms of prediction latency and batch size, safe to update from
+/// many threads.
+#[derive(Debug, Default)]
+pub struct PredictStats {
+    // one count per bucket plus one for larger values, not cumulative
+    latency_counts: [AtomicU64; LATENCY_BUCKETS.len() + 1],
+    batch_counts: [AtomicU64; BATCH_BUCKETS.len() + 1],
+    latency_nanos: AtomicU64,
+    rows: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl PredictStats {
+    /// Empty histograms
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of successful prediction calls
+    pub fn calls(&self) -> u64 {
+        self.latency_counts
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Number of failed prediction calls
+    pub fn errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// Rows predicted by successful calls
+    pub fn rows(&self) -> u64 {
+        self.rows.load(Ordering::Relaxed)
+    }
+
+    /// Time spent in successful calls
+    pub fn total_latency(&self) -> Duration {
+        Duration::from_nanos(self.latency_nanos.load(Ordering::Relaxed))
+    }
+
+    /// Rows per second of prediction time (not wall-clock time; compute
+    /// that from the change in rows between two scrapes)
+    pub fn rows_per_second(&self) -> f64 {
+        let seconds = self.total_latency().as_secs_f64();
+        if seconds > 0.0 {
+            self.rows() as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// Upper bound of the histogram bucket holding the `q` quantile of
+    /// latency (e.g. 0.99), or `None` before the first call or if it falls
+    /// above the largest bucket (1 s)
+    pub fn latency_quantile(&self, q: f64) -> Option<Duration> {
+        let counts: Vec<u64> = self
+            .latency_counts
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let rank = (q.clamp(0.0, 1.0) * total as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (bound, count) in LATENCY_BUCKETS.iter().zip(&counts) {
+            seen += count;
+            if seen >= rank {
+                return Some(Duration::from_secs_f64(*bound));
+            }
+        }
+        None
+    }
+
+    /// The histograms and counters in the Prometheus text exposition
+    /// format, with metric names starting with `prefix`
+    pub fn to_prometheus(&self, prefix: &str) -> String {
+        let mut out = String::new();
+        let latency: Vec<String> = LATENCY_BUCKETS.iter().map(f64::to_string).collect();
+        write_histogram(
+            &mut out,
+            &format!("{}_predict_latency_seconds", prefix),
+            "Latency of prediction calls",
+            &latency,
+            &self.latency_counts,
+            self.total_latency().as_secs_f64(),
+        );
+        let batch: Vec<String> = BATCH_BUCKETS.iter().map(usize::to_string).collect();
+        write_histogram(
+            &mut out,
+            &format!("{}_predict_batch_rows", prefix),
+            "Rows per prediction call",
+            &batch,
+            &self.batch_counts,
+            self.rows() as f64,
+        );
+        for (name, help, value) in [
+            ("predict_rows_total", "Rows predicted", self.rows()),
+            (
+                "predict_errors_total",
+                "Failed prediction calls",
+                self.errors(),
+            ),
+        ] {
+            let _ = writeln!(out, "# HELP {}_{} {}", prefix, name, help);
+            let _ = writeln!(out, "# TYPE {}_{} counter", prefix, name);
+            let _ = writeln!(out, "{}_{} {}", prefix, name, value);
+        }
+        out
+    }
+}
+
+impl PredictRecorder for PredictStats {
+    fn record(&self, num_rows: usize, latency: Duration) {
+        let seconds = latency.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|&bound| seconds <= bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.latency_counts[bucket].fetch_add(1, Ordering::Relaxed);
+        let bucket = BATCH_BUCKETS
+            .iter()
+            .position(|&bound| num_rows <= bound)
+            .unwrap_or(BATCH_BUCKETS.len());
+        self.batch_counts[bucket].fetch_add(1, Ordering::Relaxed);
+        let nanos = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        self.latency_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.rows.fetch_add(num_rows as u64, Ordering::Relaxed);
+    }
+
+    fn record_error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Write a histogram with cumulative bucket counts
+fn write_histogram(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    bounds: &[String],
+    counts: &[AtomicU64],
+    sum: f64,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} histogram", name);
+    let mut cumulative = 0;
+    let labels = bounds.iter().map(String::as_str).chain(["+Inf"]);
+    for (bound, count) in labels.zip(counts) {
+        cumulative += count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+    }
+    let _ = writeln!(out, "{}_sum {}", name, sum);
+    let _ = writeln!(out, "{}_count {}", name, cumulative);
+}
+
+/// Forwards measurements to the [`metrics`](https://docs.rs/metrics) facade,
+/// and from there to whichever exporter the application installed.
+///
+/// Reports the histograms `<prefix>_predict_latency_seconds` and
+/// `<prefix>_predict_batch_rows` and the counters
+/// `<prefix>_predict_rows_total` and `<prefix>_predict_errors_total`,
+/// named like the output of [`PredictStats::to_prometheus`].
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::instrument::{InstrumentedBooster, MetricsRecorder};
+/// use xgboost_rust::Booster;
+///
+/// // after installing an exporter, e.g. metrics-exporter-prometheus
+/// let booster = InstrumentedBooster::new(
+///     Booster::load("model.json").unwrap(),
+///     MetricsRecorder::new("churn_model"),
+/// );
+/// ```
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone)]
+pub struct MetricsRecorder {
+    latency: String,
+    batch_rows: String,
+    rows: String,
+    errors: String,
+}
+
+#[cfg(feature = "metrics")]
+impl MetricsRecorder {
+    /// Report metrics with names starting with `prefix`
+    pub fn new(prefix: &str) -> Self {
+        MetricsRecorder {
+            latency: format!("{}_predict_latency_seconds", prefix),
+            batch_rows: format!("{}_predict_batch_rows", prefix),
+            rows: format!("{}_predict_rows_total", prefix),
+            errors: format!("{}_predict_errors_total", prefix),
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl PredictRecorder for MetricsRecorder {
+    fn record(&self, num_rows: usize, latency: Duration) {
+        metrics::histogram!(self.latency.clone()).record(latency.as_secs_f64());
+        metrics::histogram!(self.batch_rows.clone()).record(num_rows as f64);
+        metrics::counter!(self.rows.clone()).increment(num_rows as u64);
+    }
+
+    fn record_error(&self) {
+        metrics::counter!(self.errors.clone()).increment(1);
+    }
+}
+
+/// A booster whose predictions are timed and reported to a recorder.
+///
+/// Every prediction method shared by the native and `pure` backends is
+/// wrapped and recorded. Other methods, including the native-only
+/// prediction variants, are reached through [`booster`](Self::booster)
+/// and are not recorded.
+pub struct InstrumentedBooster<R = Arc<PredictStats>> {
+    booster: Booster,
+    recorder: R,
+}
+
+impl<R: PredictRecorder> InstrumentedBooster<R> {
+    /// Report the predictions of `booster` to `recorder`
+    pub fn new(booster: Booster, recorder: R) -> Self {
+        InstrumentedBooster { booster, recorder }
+    }
+
+    /// The recorder
+    pub fn recorder(&self) -> &R {
+        &self.recorder
+    }
+
+    /// The booster, without instrumentation
+    pub fn booster(&self) -> &Booster {
+        &self.booster
+    }
+
+    /// The booster, without instrumentation
+    pub fn into_inner(self) -> Booster {
+        self.booster
+    }
+
+    /// [`Booster::predict`], recorded
+    pub fn predict(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.timed(num_rows, || {
+            self.booster
+                .predict(data, num_rows, num_features, options, training)
+        })
+    }
+
+    /// [`Booster::predict_margin`], recorded
+    pub fn predict_margin(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.timed(num_rows, || {
+            self.booster.predict_margin(data, num_rows, num_features)
+        })
+    }
+
+    /// [`Booster::predict_f64`], recorded
+    #[cfg(any(not(feature = "native"), xgboost_array_interface))]
+    pub fn predict_f64(
+        &self,
+        data: &[f64],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.timed(num_rows, || {
+            self.booster
+                .predict_f64(data, num_rows, num_features, options, training)
+        })
+    }
+
+    /// [`Booster::predict_f16`], recorded
+    pub fn predict_f16(
+        &self,
+        data: &[u16],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.timed(num_rows, || {
+            self.booster
+                .predict_f16(data, num_rows, num_features, options, training)
+        })
+    }
+
+    /// [`Booster::predict_with_missing`], recorded
+    pub fn predict_with_missing(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        missing: f32,
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.timed(num_rows, || {
+            self.booster
+                .predict_with_missing(data, num_rows, num_features, missing, options)
+        })
+    }
+
+    /// [`Booster::predict_with_base_margin`], recorded
+    pub fn predict_with_base_margin(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        base_margin: &[f32],
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.timed(num_rows, || {
+            self.booster.predict_with_base_margin(
+                data,
+                num_rows,
+                num_features,
+                base_margin,
+                options,
+            )
+        })
+    }
+
+    /// [`Booster::predict_structs`], recorded
+    pub fn predict_structs<T: XgbFeatures>(
+        &self,
+        rows: &[T],
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.timed(rows.len(), || self.booster.predict_structs(rows, options))
+    }
+
+    /// [`Booster::predict_named`], recorded as one row
+    #[cfg(any(not(feature = "native"), xgboost_feature_score))]
+    pub fn predict_named<K>(
+        &self,
+        features: &std::collections::HashMap<K, f32>,
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>>
+    where
+        K: std::borrow::Borrow<str> + std::hash::Hash + Eq,
+    {
+        self.timed(1, || self.booster.predict_named(features, options))
+    }
+
+    /// [`Booster::predict_json`], recorded; the time includes parsing
+    pub fn predict_json<S: AsRef<str>>(
+        &self,
+        records: &[u8],
+        feature_order: &[S],
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.timed_rows(|| {
+            let num_features = self.booster.num_features()?;
+            let (data, num_rows) = records::to_dense(records, feature_order, num_features)?;
+            let predictions =
+                self.booster
+                    .predict(&data, num_rows, num_features, options, false)?;
+            Ok((predictions, num_rows))
+        })
+    }
+
+    /// [`Booster::predict_json_values`], recorded
+    #[cfg(feature = "serde_json")]
+    pub fn predict_json_values<S: AsRef<str>>(
+        &self,
+        records: &[serde_json::Value],
+        feature_order: &[S],
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.timed(records.len(), || {
+            self.booster
+                .predict_json_values(records, feature_order, options)
+        })
+    }
+
+    /// [`Booster::predict_dmatrix`], recorded
+    #[cfg(feature = "native")]
+    pub fn predict_dmatrix(
+        &self,
+        dmatrix: &DMatrix,
+        options: PredictOptions,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        let num_rows = dmatrix.num_rows()?;
+        self.timed(num_rows, || {
+            self.booster.predict_dmatrix(dmatrix, options, training)
+        })
+    }
+
+    fn timed<T>(&self, num_rows: usize, f: impl FnOnce() -> XGBoostResult<T>) -> XGBoostResult<T> {
+        self.timed_rows(|| f().map(|value| (value, num_rows)))
+    }
+
+    /// Time `f`, which also returns the number of rows it predicted
+    fn timed_rows<T>(&self, f: impl FnOnce() -> XGBoostResult<(T, usize)>) -> XGBoostResult<T> {
+        let start = Instant::now();
+        match f() {
+            Ok((value, num_rows)) => {
+                self.recorder.record(num_rows, start.elapsed());
+                Ok(value)
+            }
+            Err(error) => {
+                self.recorder.record_error();
+                Err(error)
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Checksums and signatures written next to saved models.
+//!
+//! `model.json` gets a `model.json.sha256` file in `sha256sum` format and,
+//! when signed, a `model.json.sig` file holding the hex HMAC-SHA256 of the
+//! model under a secret key. Both cover the bytes on disk, so they also
+//! verify compressed models.
+
+use crate::error::XGBoostError;
+use crate::hex;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Error of a model that failed verification when loaded.
+///
+/// `Booster::load` reports these as an [`XGBoostError`] with the same
+/// message; `Booster::load_verified` and `Booster::load_signed` return them
+/// as is, so corruption can be told apart from other failures.
+#[derive(Debug)]
+pub enum IntegrityError {
+    /// The checksum or signature file is absent
+    Missing {
+        /// The expected checksum or signature file
+        path: PathBuf,
+    },
+    /// The model does not match its checksum, e.g. after a truncated upload
+    ChecksumMismatch {
+        /// The model file
+        path: PathBuf,
+        /// SHA-256 recorded in the checksum file
+        expected: String,
+        /// SHA-256 of the model file
+        actual: String,
+    },
+    /// The signature was not made with the key for this model
+    InvalidSignature {
+        /// The model file
+        path: PathBuf,
+    },
+    /// Reading the files or loading the model failed
+    Load(XGBoostError),
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::Missing { path } => {
+                write!(f, "Integrity file {} does not exist", path.display())
+            }
+            IntegrityError::ChecksumMismatch {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Model file {} is corrupt: SHA-256 is {} but {} was recorded",
+                path.display(),
+                actual,
+                expected
+            ),
+            IntegrityError::InvalidSignature { path } => {
+                write!(f, "Model file {} has an invalid signature", path.display())
+            }
+            IntegrityError::Load(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IntegrityError::Load(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<XGBoostError> for IntegrityError {
+    fn from(e: XGBoostError) -> Self {
+        IntegrityError::Load(e)
+    }
+}
+
+impl From<IntegrityError> for XGBoostError {
+    fn from(e: IntegrityError) -> Self {
+        match e {
+            IntegrityError::Load(e) => e,
+            e => XGBoostError {
+                description: e.to_string(),
+            },
+        }
+    }
+}
+
+/// `model.json` → `model.json.<extension>`
+fn sidecar(path: &Path, extension: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+/// Path of the checksum file of a model
+pub(crate) fn checksum_path(path: &Path) -> PathBuf {
+    sidecar(path, "sha256")
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, IntegrityError> {
+    fs::read(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            IntegrityError::Missing {
+                path: path.to_path_buf(),
+            }
+        } else {
+            IntegrityError::Load(XGBoostError {
+                description: format!("Failed to read {}: {}", path.display(), e),
+            })
+        }
+    })
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<(), XGBoostError> {
+    fs::write(path, contents).map_err(|e| XGBoostError {
+        description: format!("Failed to write {}: {}", path.display(), e),
+    })
+}
+
+/// Write the checksum file of a saved model, returning the model bytes
+pub(crate) fn write_checksum(path: &Path) -> Result<Vec<u8>, XGBoostError> {
+    let model = read(path)?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let line = format!("{}  {}\n", hex::encode(&Sha256::digest(&model)), name);
+    write(&checksum_path(path), line.as_bytes())?;
+    Ok(model)
+}
+
+/// Write the checksum and signature files of a saved model
+pub(crate) fn write_signature(path: &Path, key: &[u8]) -> Result<(), XGBoostError> {
+    let model = write_checksum(path)?;
+    let signature = hex::encode(&mac(key, &model).finalize().into_bytes());
+    write(&sidecar(path, "sig"), format!("{}\n", signature).as_bytes())
+}
+
+/// Check a model file against its checksum file, returning the model bytes
+pub(crate) fn verify_checksum(path: &Path) -> Result<Vec<u8>, IntegrityError> {
+    let recorded = read(&checksum_path(path))?;
+    let model = read(path)?;
+    // `sha256sum` format: the digest, then the file name
+    let expected = String::from_utf8_lossy(&recorded)
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let actual = hex::encode(&Sha256::digest(&model));
+    if expected != actual {
+        return Err(IntegrityError::ChecksumMismatch {
+            path: path.to_path_buf(),
+            expected,
+            actual,
+        });
+    }
+    Ok(model)
+}
+
+/// Check a model file against its checksum and signature files
+pub(crate) fn verify_signature(path: &Path, key: &[u8]) -> Result<Vec<u8>, IntegrityError> {
+    let model = verify_checksum(path)?;
+    let recorded = read(&sidecar(path, "sig"))?;
+    let invalid = || IntegrityError::InvalidSignature {
+        path: path.to_path_buf(),
+    };
+    let signature = hex::decode(String::from_utf8_lossy(&recorded).trim()).ok_or_else(invalid)?;
+    mac(key, &model)
+        .verify_slice(&signature)
+        .map_err(|_| invalid())?;
+    Ok(model)
+}
+
+fn mac(key: &[u8], data: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac
+}
@@ -0,0 +1,4 @@
+//! Reading and writing datasets in text formats.
+
+pub mod csv;
+pub mod libsvm;
@@ -0,0 +1,633 @@
+//! Numeric CSV files, parsed in Rust.
+//!
+//! Every column except an optional label column and any key columns is a
+//! feature. Empty fields and `NA`, `NaN` or `null` are missing values;
+//! fields may be enclosed in double quotes. With
+//! [`missing_indicators`](CsvReader::missing_indicators), every feature gets
+//! a 0/1 column marking its missing values.
+//!
+//! Key columns (e.g. IDs) are kept as text next to the features, so
+//! predictions can be written out with the keys of their rows. Large files
+//! can be read in batches of rows with
+//! [`read_file_batches`](CsvReader::read_file_batches).
+//!
+//! # Example
+//! ```no_run
+//! use xgboost_rust::io::csv::CsvReader;
+//!
+//! let data = CsvReader::new().label_name("target").read_file("train.csv").unwrap();
+//! println!("{} rows, features {:?}", data.num_rows(), data.feature_names());
+//! let dtrain = data.to_dmatrix().unwrap(); // with labels and feature names
+//! ```
+
+use crate::dmatrix::DMatrix;
+use crate::error::{XGBoostError, XGBoostResult};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Lines};
+use std::iter::Enumerate;
+use std::path::Path;
+
+/// Column selected by index or header name
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl Column {
+    fn resolve(&self, header: Option<&[String]>) -> XGBoostResult<usize> {
+        match (self, header) {
+            (Column::Index(index), _) => Ok(*index),
+            (Column::Name(name), Some(header)) => {
+                header
+                    .iter()
+                    .position(|h| h == name)
+                    .ok_or_else(|| XGBoostError {
+                        description: format!("No CSV column named '{}'", name),
+                    })
+            }
+            (Column::Name(_), None) => Err(XGBoostError {
+                description: "Columns can only be named with a header row".to_string(),
+            }),
+        }
+    }
+}
+
+/// What a column holds, resolved once per file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Feature,
+    Label,
+    /// Position among the key columns
+    Key(usize),
+}
+
+/// Rows preallocated per batch, so an unbounded batch doesn't reserve
+/// memory for the whole file
+const RESERVED_ROWS: usize = 65_536;
+
+/// Parser options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvReader {
+    delimiter: char,
+    has_header: bool,
+    label: Option<Column>,
+    keys: Vec<Column>,
+    missing_indicators: bool,
+}
+
+impl Default for CsvReader {
+    fn default() -> Self {
+        CsvReader {
+            delimiter: ',',
+            has_header: true,
+            label: None,
+            keys: Vec::new(),
+            missing_indicators: false,
+        }
+    }
+}
+
+impl CsvReader {
+    /// Comma-separated with a header row and no label column
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Field separator, e.g. `'\t'` or `';'`
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Whether the first row holds column names (the default)
+    pub fn has_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    /// Read labels from the column at `index`
+    pub fn label_column(mut self, index: usize) -> Self {
+        self.label = Some(Column::Index(index));
+        self
+    }
+
+    /// Read labels from the column named `name` in the header
+    pub fn label_name(mut self, name: &str) -> Self {
+        self.label = Some(Column::Name(name.to_string()));
+        self
+    }
+
+    /// Keep the column at `index` as a text key instead of a feature; may
+    /// be called for several columns
+    pub fn key_column(mut self, index: usize) -> Self {
+        self.keys.push(Column::Index(index));
+        self
+    }
+
+    /// Keep the column named `name` as a text key instead of a feature; may
+    /// be called for several columns
+    pub fn key_name(mut self, name: &str) -> Self {
+        self.keys.push(Column::Name(name.to_string()));
+        self
+    }
+
+    /// Append a `<name>_missing` column per feature that is 1 where the
+    /// feature is missing and 0 elsewhere (default false)
+    ///
+    /// The features keep their `NaN`s, so trees can still route missing
+    /// values, while the indicators also let missingness be used as a
+    /// signal of its own, e.g. by linear models or after imputation.
+    pub fn missing_indicators(mut self, missing_indicators: bool) -> Self {
+        self.missing_indicators = missing_indicators;
+        self
+    }
+
+    /// Parse a file
+    pub fn read_file<P: AsRef<Path>>(&self, path: P) -> XGBoostResult<CsvData> {
+        self.read(open(path.as_ref())?)
+    }
+
+    /// Parse from a reader
+    pub fn read<R: BufRead>(&self, reader: R) -> XGBoostResult<CsvData> {
+        let mut batches = self.read_batches(reader, usize::MAX)?;
+        match batches.next() {
+            Some(batch) => batch,
+            None => Ok(batches.finish(batches.new_batch())),
+        }
+    }
+
+    /// Parse a file in batches of at most `batch_rows` rows
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::io::csv::CsvReader;
+    /// use xgboost_rust::{Booster, PredictOptions};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let reader = CsvReader::new().key_name("customer_id");
+    /// for batch in reader.read_file_batches("customers.csv", 65_536).unwrap() {
+    ///     let batch = batch.unwrap();
+    ///     let predictions = booster
+    ///         .predict(batch.data(), batch.num_rows(), batch.num_features(), PredictOptions::empty(), false)
+    ///         .unwrap();
+    ///     for (row, prediction) in predictions.iter().enumerate() {
+    ///         println!("{},{}", batch.keys(row)[0], prediction);
+    ///     }
+    /// }
+    /// ```
+    pub fn read_file_batches<P: AsRef<Path>>(
+        &self,
+        path: P,
+        batch_rows: usize,
+    ) -> XGBoostResult<CsvBatches<BufReader<File>>> {
+        self.read_batches(open(path.as_ref())?, batch_rows)
+    }
+
+    /// Parse from a reader in batches of at most `batch_rows` rows
+    ///
+    /// The header is read immediately; rows are read as the returned
+    /// iterator is advanced.
+    pub fn read_batches<R: BufRead>(
+        &self,
+        reader: R,
+        batch_rows: usize,
+    ) -> XGBoostResult<CsvBatches<R>> {
+        let mut lines = reader.lines().enumerate();
+        let mut header: Option<Vec<String>> = None;
+        if self.has_header {
+            if let Some((number, line)) = next_line(&mut lines) {
+                header = Some(split_fields(&read_line(number, line)?, self.delimiter));
+            }
+        }
+
+        let label = self
+            .label
+            .as_ref()
+            .map(|column| column.resolve(header.as_deref()))
+            .transpose()?;
+        let keys = self
+            .keys
+            .iter()
+            .map(|column| column.resolve(header.as_deref()))
+            .collect::<XGBoostResult<Vec<usize>>>()?;
+        if label.is_some_and(|label| keys.contains(&label)) {
+            return Err(XGBoostError {
+                description: "The label column cannot also be a key column".to_string(),
+            });
+        }
+
+        let mut batches = CsvBatches {
+            lines,
+            delimiter: self.delimiter,
+            missing_indicators: self.missing_indicators,
+            batch_rows: batch_rows.max(1),
+            label,
+            keys,
+            num_columns: header.as_ref().map(Vec::len),
+            roles: Vec::new(),
+            feature_names: Vec::new(),
+            key_names: Vec::new(),
+        };
+        if let Some(header) = header {
+            batches.key_names = batches
+                .keys
+                .iter()
+                .map(|&k| header.get(k).cloned().unwrap_or_default())
+                .collect();
+            batches.feature_names = header
+                .into_iter()
+                .enumerate()
+                .filter(|(column, _)| Some(*column) != label && !batches.keys.contains(column))
+                .map(|(_, name)| name)
+                .collect();
+        }
+        Ok(batches)
+    }
+}
+
+fn open(path: &Path) -> XGBoostResult<BufReader<File>> {
+    let file = File::open(path).map_err(|e| XGBoostError {
+        description: format!("Failed to open {}: {}", path.display(), e),
+    })?;
+    Ok(BufReader::new(file))
+}
+
+/// The next line that is not blank
+fn next_line<R: BufRead>(
+    lines: &mut Enumerate<Lines<R>>,
+) -> Option<(usize, std::io::Result<String>)> {
+    lines.find(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+}
+
+fn read_line(number: usize, line: std::io::Result<String>) -> XGBoostResult<String> {
+    line.map_err(|e| XGBoostError {
+        description: format!("Failed to read CSV line {}: {}", number + 1, e),
+    })
+}
+
+/// Split a line into fields, removing enclosing double quotes
+/// (`""` inside quotes is a literal quote)
+fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().unwrap();
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(String::new()),
+            c => field.push(c),
+        }
+    }
+    fields
+        .into_iter()
+        .map(|field| field.trim().to_string())
+        .collect()
+}
+
+fn parse_value(field: &str) -> Option<f32> {
+    match field {
+        "" | "NA" | "NaN" | "nan" | "null" => Some(f32::NAN),
+        _ => field.parse().ok(),
+    }
+}
+
+/// Batches of rows of a CSV file, from [`CsvReader::read_batches`].
+pub struct CsvBatches<R> {
+    lines: Enumerate<Lines<R>>,
+    delimiter: char,
+    missing_indicators: bool,
+    batch_rows: usize,
+    label: Option<usize>,
+    keys: Vec<usize>,
+    num_columns: Option<usize>,
+    roles: Vec<Role>,
+    feature_names: Vec<String>,
+    key_names: Vec<String>,
+}
+
+impl<R> CsvBatches<R> {
+    /// Rows with the column layout of the file
+    fn finish(&self, mut batch: CsvData) -> CsvData {
+        let num_excluded = self.keys.len() + usize::from(self.label.is_some());
+        batch.num_features = self.num_columns.unwrap_or(0).saturating_sub(num_excluded);
+        batch.feature_names = self.feature_names.clone();
+        batch.key_names = self.key_names.clone();
+        if self.missing_indicators {
+            batch.append_missing_indicators();
+        }
+        batch
+    }
+
+    fn new_batch(&self) -> CsvData {
+        let rows = self.batch_rows.min(RESERVED_ROWS);
+        let num_features = self.roles.iter().filter(|&&r| r == Role::Feature).count();
+        CsvData {
+            data: Vec::with_capacity(rows * num_features),
+            labels: self.label.map(|_| Vec::with_capacity(rows)),
+            keys: Vec::with_capacity(rows * self.keys.len()),
+            num_rows: 0,
+            num_features: 0,
+            feature_names: Vec::new(),
+            key_names: Vec::new(),
+        }
+    }
+
+    fn parse_row(&mut self, batch: &mut CsvData, number: usize, line: &str) -> XGBoostResult<()> {
+        let fields = split_fields(line, self.delimiter);
+        let expected = *self.num_columns.get_or_insert(fields.len());
+        if fields.len() != expected {
+            return Err(XGBoostError {
+                description: format!(
+                    "CSV line {}: expected {} fields, got {}",
+                    number + 1,
+                    expected,
+                    fields.len()
+                ),
+            });
+        }
+        if self.roles.len() != expected {
+            self.roles = self.column_roles(expected)?;
+        }
+        let start = batch.keys.len();
+        batch.keys.resize(start + self.keys.len(), String::new());
+        for (field, &role) in fields.into_iter().zip(&self.roles) {
+            if let Role::Key(key) = role {
+                batch.keys[start + key] = field;
+                continue;
+            }
+            let value = parse_value(&field).ok_or_else(|| XGBoostError {
+                description: format!("CSV line {}: invalid number '{}'", number + 1, field),
+            })?;
+            match (role, &mut batch.labels) {
+                (Role::Label, Some(labels)) => labels.push(value),
+                _ => batch.data.push(value),
+            }
+        }
+        batch.num_rows += 1;
+        Ok(())
+    }
+
+    /// The role of each of `num_columns` columns
+    fn column_roles(&self, num_columns: usize) -> XGBoostResult<Vec<Role>> {
+        let out_of_range = self
+            .label
+            .iter()
+            .chain(&self.keys)
+            .any(|&c| c >= num_columns);
+        if out_of_range {
+            return Err(XGBoostError {
+                description: format!(
+                    "Label or key column out of range for {} columns",
+                    num_columns
+                ),
+            });
+        }
+        let mut roles = vec![Role::Feature; num_columns];
+        if let Some(label) = self.label {
+            roles[label] = Role::Label;
+        }
+        for (key, &column) in self.keys.iter().enumerate() {
+            roles[column] = Role::Key(key);
+        }
+        Ok(roles)
+    }
+}
+
+impl<R: BufRead> Iterator for CsvBatches<R> {
+    type Item = XGBoostResult<CsvData>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut batch = self.new_batch();
+        while batch.num_rows < self.batch_rows {
+            let Some((number, line)) = next_line(&mut self.lines) else {
+                break;
+            };
+            let parsed =
+                read_line(number, line).and_then(|line| self.parse_row(&mut batch, number, &line));
+            if let Err(error) = parsed {
+                return Some(Err(error));
+            }
+        }
+        if batch.num_rows == 0 {
+            return None;
+        }
+        Some(Ok(self.finish(batch)))
+    }
+}
+
+/// A dense row-major matrix with optional labels, keys and column names.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvData {
+    data: Vec<f32>,
+    labels: Option<Vec<f32>>,
+    keys: Vec<String>,
+    num_rows: usize,
+    num_features: usize,
+    feature_names: Vec<String>,
+    key_names: Vec<String>,
+}
+
+impl CsvData {
+    /// Number of rows
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Number of feature columns
+    pub fn num_features(&self) -> usize {
+        self.num_features
+    }
+
+    /// Row-major features, `NaN` where missing
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+
+    /// One label per row, if a label column was selected
+    pub fn labels(&self) -> Option<&[f32]> {
+        self.labels.as_deref()
+    }
+
+    /// Names of the feature columns (empty without a header)
+    pub fn feature_names(&self) -> &[String] {
+        &self.feature_names
+    }
+
+    /// Names of the key columns, in the order they were selected (empty
+    /// without a header)
+    pub fn key_names(&self) -> &[String] {
+        &self.key_names
+    }
+
+    /// Key fields of a row, in the order the key columns were selected
+    pub fn keys(&self, row: usize) -> &[String] {
+        let num_keys = self.keys.len().checked_div(self.num_rows).unwrap_or(0);
+        &self.keys[row * num_keys..(row + 1) * num_keys]
+    }
+
+    /// Create a DMatrix with the labels and, on XGBoost ≥ 1.5, the
+    /// feature names
+    pub fn to_dmatrix(&self) -> XGBoostResult<DMatrix> {
+        let mut dmatrix = DMatrix::from_dense(&self.data, self.num_rows, self.num_features)?;
+        if let Some(labels) = &self.labels {
+            dmatrix.set_label(labels)?;
+        }
+        #[cfg(xgboost_feature_score)]
+        if !self.feature_names.is_empty() {
+            dmatrix.set_feature_names(&self.feature_names)?;
+        }
+        Ok(dmatrix)
+    }
+
+    fn append_missing_indicators(&mut self) {
+        let num_features = self.num_features;
+        let mut data = Vec::with_capacity(self.data.len() * 2);
+        for row in self.data.chunks(num_features.max(1)) {
+            data.extend_from_slice(row);
+            data.extend(row.iter().map(|value| f32::from(u8::from(value.is_nan()))));
+        }
+        self.data = data;
+        self.num_features = num_features * 2;
+        let indicators: Vec<String> = self
+            .feature_names
+            .iter()
+            .map(|name| format!("{}_missing", name))
+            .collect();
+        self.feature_names.extend(indicators);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(reader: &CsvReader, text: &str) -> XGBoostResult<CsvData> {
+        reader.read(text.as_bytes())
+    }
+
+    /// NaN-aware comparison of feature values
+    fn assert_values(data: &[f32], expected: &[f32]) {
+        assert_eq!(data.len(), expected.len());
+        for (value, expected) in data.iter().zip(expected) {
+            assert!(
+                value == expected || (value.is_nan() && expected.is_nan()),
+                "{:?} != {:?}",
+                data,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn fields_are_unquoted() {
+        assert_eq!(
+            split_fields(r#"a, "b,c" ,"say ""hi""""#, ','),
+            ["a", "b,c", r#"say "hi""#]
+        );
+        assert_eq!(split_fields("1\t\t2", '\t'), ["1", "", "2"]);
+    }
+
+    #[test]
+    fn missing_values() {
+        for missing in ["", "NA", "NaN", "nan", "null"] {
+            assert!(parse_value(missing).unwrap().is_nan());
+        }
+        assert_eq!(parse_value("1e3"), Some(1000.0));
+        assert_eq!(parse_value("-0.5"), Some(-0.5));
+        assert_eq!(parse_value("n/a"), None);
+    }
+
+    #[test]
+    fn labels_keys_and_features() {
+        let reader = CsvReader::new().label_name("target").key_name("id");
+        let data = read(&reader, "id,x,y,target\na,1,,0\n\n\"b\",NA,3.5,1\n").unwrap();
+        assert_eq!(data.num_rows(), 2);
+        assert_eq!(data.num_features(), 2);
+        assert_values(data.data(), &[1.0, f32::NAN, f32::NAN, 3.5]);
+        assert_eq!(data.labels(), Some(&[0.0, 1.0][..]));
+        assert_eq!(data.feature_names(), ["x", "y"]);
+        assert_eq!(data.key_names(), ["id"]);
+        assert_eq!(data.keys(1), ["b"]);
+    }
+
+    #[test]
+    fn columns_by_index_without_header() {
+        let reader = CsvReader::new()
+            .has_header(false)
+            .delimiter(';')
+            .label_column(0);
+        let data = read(&reader, "1;2;3\n0;4;5\n").unwrap();
+        assert_eq!(data.labels(), Some(&[1.0, 0.0][..]));
+        assert_eq!(data.data(), [2.0, 3.0, 4.0, 5.0]);
+        assert!(data.feature_names().is_empty());
+        assert!(data.keys(0).is_empty());
+    }
+
+    #[test]
+    fn missing_indicator_columns() {
+        let reader = CsvReader::new().missing_indicators(true);
+        let data = read(&reader, "x,y\n1,\nNA,2\n").unwrap();
+        assert_eq!(data.num_features(), 4);
+        assert_eq!(data.feature_names(), ["x", "y", "x_missing", "y_missing"]);
+        assert_values(
+            data.data(),
+            &[1.0, f32::NAN, 0.0, 1.0, f32::NAN, 2.0, 1.0, 0.0],
+        );
+    }
+
+    #[test]
+    fn empty_files() {
+        let data = read(&CsvReader::new(), "x,y\n").unwrap();
+        assert_eq!((data.num_rows(), data.num_features()), (0, 2));
+        assert_eq!(data.feature_names(), ["x", "y"]);
+        assert_eq!(read(&CsvReader::new(), "").unwrap().num_rows(), 0);
+    }
+
+    #[test]
+    fn batches_of_rows() {
+        let text = "x,y\n1,2\n3,4\n5,6\n7,8\n9,10\n";
+        let batches: Vec<CsvData> = CsvReader::new()
+            .read_batches(text.as_bytes(), 2)
+            .unwrap()
+            .collect::<XGBoostResult<_>>()
+            .unwrap();
+        let sizes: Vec<usize> = batches.iter().map(CsvData::num_rows).collect();
+        assert_eq!(sizes, [2, 2, 1]);
+        assert_eq!(batches[2].data(), [9.0, 10.0]);
+        assert!(batches
+            .iter()
+            .all(|batch| batch.feature_names() == ["x", "y"]));
+    }
+
+    #[test]
+    fn malformed_files() {
+        let error = |reader: CsvReader, text: &str| read(&reader, text).unwrap_err().description;
+        assert_eq!(
+            error(CsvReader::new(), "x,y\n1,2\n3,4,5\n"),
+            "CSV line 3: expected 2 fields, got 3"
+        );
+        assert_eq!(
+            error(CsvReader::new(), "x,y\n1,two\n"),
+            "CSV line 2: invalid number 'two'"
+        );
+        assert_eq!(
+            error(CsvReader::new().label_name("z"), "x,y\n1,2\n"),
+            "No CSV column named 'z'"
+        );
+        assert!(
+            error(CsvReader::new().has_header(false).label_name("x"), "1,2\n")
+                .contains("only be named with a header")
+        );
+        assert!(
+            error(CsvReader::new().label_column(0).key_column(0), "x,y\n1,2\n")
+                .contains("cannot also be a key column")
+        );
+        assert!(error(CsvReader::new().label_column(5), "x,y\n1,2\n").contains("out of range"));
+    }
+}
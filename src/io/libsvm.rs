@@ -0,0 +1,427 @@
+//! The libsvm / SVMlight text format, parsed in Rust.
+//!
+//! Each line holds one row: `label[:weight] [qid:<query>] index:value ...`,
+//! optionally followed by a `# comment`. Absent features are missing.
+//!
+//! # Example
+//! ```no_run
+//! use xgboost_rust::io::libsvm::LibsvmReader;
+//!
+//! let data = LibsvmReader::new().one_based(true).read_file("train.svm").unwrap();
+//! println!("{} rows, {} features", data.num_rows(), data.num_features());
+//! let dtrain = data.to_dmatrix().unwrap(); // with labels, weights and query groups
+//! ```
+
+use crate::backend::check_dense_len;
+use crate::dmatrix::{check_num_features, DMatrix};
+use crate::error::{XGBoostError, XGBoostResult};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Parser options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LibsvmReader {
+    one_based: bool,
+    num_features: Option<usize>,
+}
+
+impl LibsvmReader {
+    /// Zero-based feature indices, number of features inferred from the data
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether feature indices start at 1 (as in LIBSVM's own tools)
+    pub fn one_based(mut self, one_based: bool) -> Self {
+        self.one_based = one_based;
+        self
+    }
+
+    /// Fix the number of features instead of inferring it from the
+    /// largest index, e.g. to match a model trained on wider data
+    pub fn num_features(mut self, num_features: usize) -> Self {
+        self.num_features = Some(num_features);
+        self
+    }
+
+    /// Parse a file
+    pub fn read_file<P: AsRef<Path>>(&self, path: P) -> XGBoostResult<LibsvmData> {
+        let file = File::open(path.as_ref()).map_err(|e| XGBoostError {
+            description: format!("Failed to open {}: {}", path.as_ref().display(), e),
+        })?;
+        self.read(BufReader::new(file))
+    }
+
+    /// Parse from a reader
+    pub fn read<R: BufRead>(&self, reader: R) -> XGBoostResult<LibsvmData> {
+        let mut data = LibsvmData {
+            labels: Vec::new(),
+            weights: Vec::new(),
+            qids: Vec::new(),
+            indptr: vec![0],
+            indices: Vec::new(),
+            values: Vec::new(),
+            num_features: 0,
+        };
+        for (number, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| XGBoostError {
+                description: format!("Failed to read libsvm line {}: {}", number + 1, e),
+            })?;
+            let content = line.split('#').next().unwrap_or("").trim();
+            if content.is_empty() {
+                continue;
+            }
+            self.parse_line(content, &mut data)
+                .map_err(|description| XGBoostError {
+                    description: format!("libsvm line {}: {}", number + 1, description),
+                })?;
+        }
+
+        if !data.qids.is_empty() && data.qids.len() != data.labels.len() {
+            return Err(XGBoostError {
+                description: "Either all or no libsvm rows must have a qid".to_string(),
+            });
+        }
+        if !data.weights.is_empty() && data.weights.len() != data.labels.len() {
+            return Err(XGBoostError {
+                description: "Either all or no libsvm rows must have a weight".to_string(),
+            });
+        }
+        let inferred = data.indices.iter().max().map_or(0, |&i| i as usize + 1);
+        data.num_features = match self.num_features {
+            Some(n) if n < inferred => {
+                return Err(XGBoostError {
+                    description: format!(
+                        "Feature index {} out of range for {} features",
+                        inferred - 1,
+                        n
+                    ),
+                })
+            }
+            Some(n) => n,
+            None => inferred,
+        };
+        Ok(data)
+    }
+
+    fn parse_line(&self, line: &str, data: &mut LibsvmData) -> Result<(), String> {
+        let mut tokens = line.split_whitespace();
+        let label = tokens.next().unwrap_or_default();
+        let (label, weight) = match label.split_once(':') {
+            Some((label, weight)) => (label, Some(weight)),
+            None => (label, None),
+        };
+        data.labels.push(parse(label, "label")?);
+        if let Some(weight) = weight {
+            data.weights.push(parse(weight, "weight")?);
+        }
+
+        let mut previous: Option<u32> = None;
+        for token in tokens {
+            let (key, value) = token
+                .split_once(':')
+                .ok_or_else(|| format!("expected index:value, got '{}'", token))?;
+            if key == "qid" {
+                if previous.is_some() || data.qids.len() == data.labels.len() {
+                    return Err("qid must come right after the label".to_string());
+                }
+                data.qids.push(parse(value, "qid")?);
+                continue;
+            }
+            let mut index: u32 = parse(key, "feature index")?;
+            if self.one_based {
+                index = index
+                    .checked_sub(1)
+                    .ok_or("feature index 0 with one-based indexing")?;
+            }
+            if previous.is_some_and(|p| index <= p) {
+                return Err(format!("feature indices not increasing at '{}'", token));
+            }
+            previous = Some(index);
+            data.indices.push(index);
+            data.values.push(parse(value, "value")?);
+        }
+        data.indptr.push(data.values.len());
+        Ok(())
+    }
+}
+
+fn parse<T: std::str::FromStr>(text: &str, what: &str) -> Result<T, String> {
+    text.parse()
+        .map_err(|_| format!("invalid {} '{}'", what, text))
+}
+
+/// Rows in sparse form with their labels and optional weights and query ids.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibsvmData {
+    labels: Vec<f32>,
+    weights: Vec<f32>,
+    qids: Vec<u64>,
+    indptr: Vec<usize>,
+    indices: Vec<u32>,
+    values: Vec<f32>,
+    num_features: usize,
+}
+
+impl LibsvmData {
+    /// Rows of a dense row-major matrix; `NaN` values are left out
+    pub fn from_dense(
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        labels: &[f32],
+    ) -> XGBoostResult<Self> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+        if labels.len() != num_rows {
+            return Err(XGBoostError {
+                description: format!(
+                    "Length mismatch: {} labels, {} rows",
+                    labels.len(),
+                    num_rows
+                ),
+            });
+        }
+        check_num_features(num_features)?;
+        let mut indptr = Vec::with_capacity(num_rows + 1);
+        let (mut indices, mut values) = (Vec::new(), Vec::new());
+        indptr.push(0);
+        for row in data.chunks(num_features.max(1)).take(num_rows) {
+            for (index, &value) in row.iter().enumerate() {
+                if !value.is_nan() {
+                    indices.push(index as u32);
+                    values.push(value);
+                }
+            }
+            indptr.push(values.len());
+        }
+        Ok(LibsvmData {
+            labels: labels.to_vec(),
+            weights: Vec::new(),
+            qids: Vec::new(),
+            indptr,
+            indices,
+            values,
+            num_features,
+        })
+    }
+
+    /// Attach one weight per row
+    pub fn with_weights(mut self, weights: &[f32]) -> XGBoostResult<Self> {
+        self.check_row_len(weights.len(), "weights")?;
+        self.weights = weights.to_vec();
+        Ok(self)
+    }
+
+    /// Attach one query id per row (rows of a query must be consecutive)
+    pub fn with_qids(mut self, qids: &[u64]) -> XGBoostResult<Self> {
+        self.check_row_len(qids.len(), "qids")?;
+        self.qids = qids.to_vec();
+        Ok(self)
+    }
+
+    fn check_row_len(&self, len: usize, what: &str) -> XGBoostResult<()> {
+        if len != self.num_rows() {
+            return Err(XGBoostError {
+                description: format!(
+                    "Length mismatch: {} {}, {} rows",
+                    len,
+                    what,
+                    self.num_rows()
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Number of rows
+    pub fn num_rows(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Number of features
+    pub fn num_features(&self) -> usize {
+        self.num_features
+    }
+
+    /// One label per row
+    pub fn labels(&self) -> &[f32] {
+        &self.labels
+    }
+
+    /// One weight per row, if the data has weights
+    pub fn weights(&self) -> Option<&[f32]> {
+        (!self.weights.is_empty()).then_some(&self.weights[..])
+    }
+
+    /// One query id per row, if the data has query ids
+    pub fn qids(&self) -> Option<&[u64]> {
+        (!self.qids.is_empty()).then_some(&self.qids[..])
+    }
+
+    /// `(feature index, value)` entries of a row
+    pub fn row(&self, row: usize) -> impl Iterator<Item = (usize, f32)> + '_ {
+        let range = self.indptr[row]..self.indptr[row + 1];
+        self.indices[range.clone()]
+            .iter()
+            .zip(&self.values[range])
+            .map(|(&index, &value)| (index as usize, value))
+    }
+
+    /// Number of rows in each run of equal query ids
+    pub fn group_sizes(&self) -> Option<Vec<u32>> {
+        let qids = self.qids()?;
+        let mut sizes: Vec<u32> = Vec::new();
+        for (i, qid) in qids.iter().enumerate() {
+            match sizes.last_mut() {
+                Some(size) if qids[i - 1] == *qid => *size += 1,
+                _ => sizes.push(1),
+            }
+        }
+        Some(sizes)
+    }
+
+    /// Create a DMatrix with the labels, weights and query groups
+    pub fn to_dmatrix(&self) -> XGBoostResult<DMatrix> {
+        let mut dmatrix =
+            DMatrix::from_csr(&self.indptr, &self.indices, &self.values, self.num_features)?;
+        dmatrix.set_label(&self.labels)?;
+        if let Some(weights) = self.weights() {
+            dmatrix.set_weight(weights)?;
+        }
+        if let Some(groups) = self.group_sizes() {
+            dmatrix.set_group(&groups)?;
+        }
+        Ok(dmatrix)
+    }
+
+    /// Write the rows in libsvm format with zero-based feature indices
+    pub fn write<W: Write>(&self, mut writer: W) -> XGBoostResult<()> {
+        let mut line = String::new();
+        for row in 0..self.num_rows() {
+            line.clear();
+            line.push_str(&self.labels[row].to_string());
+            if let Some(weights) = self.weights() {
+                line.push(':');
+                line.push_str(&weights[row].to_string());
+            }
+            if let Some(qids) = self.qids() {
+                line.push_str(&format!(" qid:{}", qids[row]));
+            }
+            for (index, value) in self.row(row) {
+                line.push_str(&format!(" {}:{}", index, value));
+            }
+            line.push('\n');
+            writer.write_all(line.as_bytes()).map_err(write_error)?;
+        }
+        writer.flush().map_err(write_error)
+    }
+
+    /// Write the rows to a file, see [`write`](Self::write)
+    pub fn write_file<P: AsRef<Path>>(&self, path: P) -> XGBoostResult<()> {
+        let file = File::create(path.as_ref()).map_err(|e| XGBoostError {
+            description: format!("Failed to create {}: {}", path.as_ref().display(), e),
+        })?;
+        self.write(BufWriter::new(file))
+    }
+}
+
+fn write_error(e: std::io::Error) -> XGBoostError {
+    XGBoostError {
+        description: format!("Failed to write libsvm data: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(reader: LibsvmReader, text: &str) -> XGBoostResult<LibsvmData> {
+        reader.read(text.as_bytes())
+    }
+
+    #[test]
+    fn weights_qids_and_comments() {
+        let data = read(
+            LibsvmReader::new(),
+            "1:2 qid:3 0:1.5 4:2 # first\n\n# only a comment\n0:1 qid:3 2:-1\n",
+        )
+        .unwrap();
+        assert_eq!(data.num_rows(), 2);
+        assert_eq!(data.num_features(), 5);
+        assert_eq!(data.labels(), [1.0, 0.0]);
+        assert_eq!(data.weights(), Some(&[2.0, 1.0][..]));
+        assert_eq!(data.qids(), Some(&[3, 3][..]));
+        assert_eq!(data.row(0).collect::<Vec<_>>(), [(0, 1.5), (4, 2.0)]);
+        assert_eq!(data.row(1).collect::<Vec<_>>(), [(2, -1.0)]);
+        assert_eq!(data.group_sizes(), Some(vec![2]));
+    }
+
+    #[test]
+    fn one_based_indices() {
+        let data = read(LibsvmReader::new().one_based(true), "1 1:5 3:6").unwrap();
+        assert_eq!(data.row(0).collect::<Vec<_>>(), [(0, 5.0), (2, 6.0)]);
+        assert_eq!(data.num_features(), 3);
+        assert_eq!(data.weights(), None);
+        assert_eq!(data.group_sizes(), None);
+
+        let error = read(LibsvmReader::new().one_based(true), "1 0:5").unwrap_err();
+        assert_eq!(
+            error.description,
+            "libsvm line 1: feature index 0 with one-based indexing"
+        );
+    }
+
+    #[test]
+    fn fixed_number_of_features() {
+        let reader = LibsvmReader::new().num_features(10);
+        assert_eq!(read(reader, "1 4:1").unwrap().num_features(), 10);
+        let error = read(LibsvmReader::new().num_features(3), "1 4:1").unwrap_err();
+        assert_eq!(
+            error.description,
+            "Feature index 4 out of range for 3 features"
+        );
+    }
+
+    #[test]
+    fn malformed_lines() {
+        let error = |text: &str| read(LibsvmReader::new(), text).unwrap_err().description;
+        assert_eq!(
+            error("1 0:1\n0 2:1 1:1"),
+            "libsvm line 2: feature indices not increasing at '1:1'"
+        );
+        assert!(error("1 0:1 qid:2").contains("qid must come right after the label"));
+        assert!(error("1 0:x").contains("invalid value 'x'"));
+        assert!(error("yes 0:1").contains("invalid label 'yes'"));
+        assert!(error("1 0").contains("expected index:value"));
+        assert!(error("1 qid:1 0:1\n0 0:1").contains("all or no libsvm rows must have a qid"));
+        assert!(error("1:1 0:1\n0 0:1").contains("all or no libsvm rows must have a weight"));
+    }
+
+    #[test]
+    fn dense_rows_round_trip() {
+        let data = LibsvmData::from_dense(&[1.0, f32::NAN, 0.0, 2.0], 2, 2, &[1.0, 0.0])
+            .unwrap()
+            .with_weights(&[0.5, 1.0])
+            .unwrap()
+            .with_qids(&[7, 7])
+            .unwrap();
+        let mut text = Vec::new();
+        data.write(&mut text).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert_eq!(text, "1:0.5 qid:7 0:1\n0:1 qid:7 0:0 1:2\n");
+        assert_eq!(read(LibsvmReader::new(), &text).unwrap(), data);
+
+        assert!(data.clone().with_weights(&[1.0]).is_err());
+        assert!(LibsvmData::from_dense(&[1.0, 2.0], 2, 1, &[0.0]).is_err());
+    }
+
+    #[test]
+    fn groups_are_runs_of_equal_qids() {
+        let data = LibsvmData::from_dense(&[0.0; 4], 4, 1, &[0.0; 4])
+            .unwrap()
+            .with_qids(&[1, 1, 2, 1])
+            .unwrap();
+        assert_eq!(data.group_sizes(), Some(vec![2, 1, 1]));
+    }
+}
@@ -0,0 +1,646 @@
+//! Minimal JSON and UBJSON readers for XGBoost model documents.
+//!
+//! XGBoost serializes models either as JSON (`.json`) or as Universal Binary
+//! JSON (`.ubj`). Both decode into the same [`Value`] tree so that the rest of
+//! the crate does not need to care which format a model was stored in.
+
+use crate::error::{XGBoostError, XGBoostResult};
+
+/// A decoded JSON value.
+///
+/// Numbers are kept as `f64`, which is lossless for every integer and float
+/// XGBoost writes into its model files.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Look up a key in an object. Returns `None` for non-objects.
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_object(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// Interpret the value as a number.
+    ///
+    /// XGBoost stores most scalar parameters as strings (`"num_feature": "4"`),
+    /// so numeric strings and booleans are accepted as well.
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a model document, detecting whether it is JSON or UBJSON.
+pub(crate) fn parse_model_document(buffer: &[u8]) -> XGBoostResult<Value> {
+    match detect_format(buffer) {
+        Some(ModelFormat::Json) => parse_json(buffer),
+        Some(ModelFormat::Ubjson) => parse_ubjson(buffer),
+        None => Err(XGBoostError {
+            description: "Unsupported model format: expected a JSON or UBJSON model \
+                          (the legacy binary format is not supported)"
+                .to_string(),
+        }),
+    }
+}
+
+/// Serialization format of an XGBoost model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelFormat {
+    /// JSON (`.json`)
+    Json,
+    /// Universal Binary JSON (`.ubj`), the default format since XGBoost 2.1
+    Ubjson,
+}
+
+impl ModelFormat {
+    /// The name XGBoost uses for this format in its configuration.
+    #[cfg(feature = "native")]
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ModelFormat::Json => "json",
+            ModelFormat::Ubjson => "ubj",
+        }
+    }
+}
+
+/// Sniff the format of a model document, the same way XGBoost does: both
+/// formats start with `{`, but JSON follows it with a quote, whitespace or `}`.
+pub(crate) fn detect_format(buffer: &[u8]) -> Option<ModelFormat> {
+    let start = buffer.iter().position(|b| !b.is_ascii_whitespace())?;
+    if buffer[start] != b'{' {
+        return None;
+    }
+    if start > 0 {
+        return Some(ModelFormat::Json);
+    }
+    match buffer.get(1) {
+        Some(b'"') | Some(b'}') | None => Some(ModelFormat::Json),
+        Some(b) if b.is_ascii_whitespace() => Some(ModelFormat::Json),
+        Some(_) => Some(ModelFormat::Ubjson),
+    }
+}
+
+/// Deepest nesting of arrays and objects a document may have; XGBoost's
+/// own documents stay below ten levels, and the recursive parsers must not
+/// overflow the stack on hostile input.
+const MAX_DEPTH: usize = 128;
+
+fn syntax_error(format: &str, offset: usize, message: &str) -> XGBoostError {
+    XGBoostError {
+        description: format!("Invalid {} at byte {}: {}", format, offset, message),
+    }
+}
+
+/// Parse a JSON document.
+pub(crate) fn parse_json(buffer: &[u8]) -> XGBoostResult<Value> {
+    let mut parser = JsonParser {
+        buf: buffer,
+        pos: 0,
+        depth: 0,
+    };
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.pos != buffer.len() {
+        return Err(parser.error("trailing characters after document"));
+    }
+    Ok(value)
+}
+
+struct JsonParser<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl JsonParser<'_> {
+    fn error(&self, message: &str) -> XGBoostError {
+        syntax_error("JSON", self.pos, message)
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> XGBoostResult<Value>) -> XGBoostResult<Value> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(&format!("nesting deeper than {} levels", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.buf.len() && self.buf[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.buf.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> XGBoostResult<()> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn consume_literal(&mut self, literal: &str) -> bool {
+        if self.buf[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_value(&mut self) -> XGBoostResult<Value> {
+        match self.peek() {
+            Some(b'{') => self.nested(Self::parse_object),
+            Some(b'[') => self.nested(Self::parse_array),
+            Some(b'"') => Ok(Value::String(self.parse_string()?)),
+            Some(b't') if self.consume_literal("true") => Ok(Value::Bool(true)),
+            Some(b'f') if self.consume_literal("false") => Ok(Value::Bool(false)),
+            Some(b'n') if self.consume_literal("null") => Ok(Value::Null),
+            // XGBoost writes non-finite floats as bare words.
+            Some(b'N') if self.consume_literal("NaN") => Ok(Value::Number(f64::NAN)),
+            Some(b'I') if self.consume_literal("Infinity") => Ok(Value::Number(f64::INFINITY)),
+            Some(b'-') if self.consume_literal("-Infinity") => Ok(Value::Number(f64::NEG_INFINITY)),
+            Some(b) if b == b'-' || b.is_ascii_digit() => self.parse_number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_object(&mut self) -> XGBoostResult<Value> {
+        self.expect(b'{')?;
+        let mut entries = Vec::new();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Value::Object(entries));
+        }
+        loop {
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected object key"));
+            }
+            let key = self.parse_string()?;
+            self.expect(b':')?;
+            let value = self.parse_value()?;
+            entries.push((key, value));
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(entries));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> XGBoostResult<Value> {
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> XGBoostResult<Value> {
+        let start = self.pos;
+        while self.pos < self.buf.len()
+            && matches!(
+                self.buf[self.pos],
+                b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'
+            )
+        {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.buf[start..self.pos])
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .map(Value::Number)
+            .ok_or_else(|| syntax_error("JSON", start, "invalid number"))
+    }
+
+    fn parse_hex4(&mut self) -> XGBoostResult<u32> {
+        let digits = self
+            .buf
+            .get(self.pos..self.pos + 4)
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u32::from_str_radix(d, 16).ok())
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(digits)
+    }
+
+    fn parse_string(&mut self) -> XGBoostResult<String> {
+        self.expect(b'"')?;
+        let mut out = Vec::new();
+        loop {
+            let byte = *self
+                .buf
+                .get(self.pos)
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escape = *self
+                        .buf
+                        .get(self.pos)
+                        .ok_or_else(|| self.error("unterminated escape"))?;
+                    self.pos += 1;
+                    match escape {
+                        b'"' => out.push(b'"'),
+                        b'\\' => out.push(b'\\'),
+                        b'/' => out.push(b'/'),
+                        b'b' => out.push(0x08),
+                        b'f' => out.push(0x0c),
+                        b'n' => out.push(b'\n'),
+                        b'r' => out.push(b'\r'),
+                        b't' => out.push(b'\t'),
+                        b'u' => {
+                            let mut code = self.parse_hex4()?;
+                            if (0xD800..0xDC00).contains(&code) && self.consume_literal("\\u") {
+                                let low = self.parse_hex4()?;
+                                code =
+                                    0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00));
+                            }
+                            let ch = char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER);
+                            let mut tmp = [0u8; 4];
+                            out.extend_from_slice(ch.encode_utf8(&mut tmp).as_bytes());
+                        }
+                        _ => return Err(self.error("invalid escape sequence")),
+                    }
+                }
+                _ => out.push(byte),
+            }
+        }
+        String::from_utf8(out).map_err(|_| self.error("string is not valid UTF-8"))
+    }
+}
+
+/// Parse a UBJSON document (as written by XGBoost's `.ubj` model format).
+pub(crate) fn parse_ubjson(buffer: &[u8]) -> XGBoostResult<Value> {
+    let mut parser = UbjsonParser {
+        buf: buffer,
+        pos: 0,
+        depth: 0,
+    };
+    parser.parse_value()
+}
+
+struct UbjsonParser<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl UbjsonParser<'_> {
+    fn error(&self, message: &str) -> XGBoostError {
+        syntax_error("UBJSON", self.pos, message)
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> XGBoostResult<Value>) -> XGBoostResult<Value> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(&format!("nesting deeper than {} levels", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn take(&mut self, len: usize) -> XGBoostResult<&[u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.buf.len())
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn next_marker(&mut self) -> XGBoostResult<u8> {
+        loop {
+            let marker = self.take(1)?[0];
+            // 'N' is a no-op marker that may appear anywhere between values.
+            if marker != b'N' {
+                return Ok(marker);
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.buf.get(self.pos).copied()
+    }
+
+    fn parse_value(&mut self) -> XGBoostResult<Value> {
+        let marker = self.next_marker()?;
+        self.parse_typed(marker)
+    }
+
+    fn parse_typed(&mut self, marker: u8) -> XGBoostResult<Value> {
+        match marker {
+            b'Z' => Ok(Value::Null),
+            b'T' => Ok(Value::Bool(true)),
+            b'F' => Ok(Value::Bool(false)),
+            b'i' | b'U' | b'I' | b'l' | b'L' => {
+                Ok(Value::Number(self.parse_integer(marker)? as f64))
+            }
+            b'd' => {
+                let bytes: [u8; 4] = self.take(4)?.try_into().unwrap();
+                Ok(Value::Number(f32::from_be_bytes(bytes) as f64))
+            }
+            b'D' => {
+                let bytes: [u8; 8] = self.take(8)?.try_into().unwrap();
+                Ok(Value::Number(f64::from_be_bytes(bytes)))
+            }
+            b'C' => Ok(Value::String((self.take(1)?[0] as char).to_string())),
+            b'S' => Ok(Value::String(self.parse_string_body()?)),
+            b'H' => {
+                let text = self.parse_string_body()?;
+                text.parse()
+                    .map(Value::Number)
+                    .map_err(|_| self.error("invalid high-precision number"))
+            }
+            b'[' => self.nested(Self::parse_array),
+            b'{' => self.nested(Self::parse_object),
+            _ => Err(self.error(&format!("unknown type marker '{}'", marker as char))),
+        }
+    }
+
+    fn parse_integer(&mut self, marker: u8) -> XGBoostResult<i64> {
+        Ok(match marker {
+            b'i' => self.take(1)?[0] as i8 as i64,
+            b'U' => self.take(1)?[0] as i64,
+            b'I' => i16::from_be_bytes(self.take(2)?.try_into().unwrap()) as i64,
+            b'l' => i32::from_be_bytes(self.take(4)?.try_into().unwrap()) as i64,
+            b'L' => i64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            _ => return Err(self.error("expected an integer length")),
+        })
+    }
+
+    fn parse_length(&mut self) -> XGBoostResult<usize> {
+        let marker = self.next_marker()?;
+        let len = self.parse_integer(marker)?;
+        usize::try_from(len).map_err(|_| self.error("negative length"))
+    }
+
+    fn parse_string_body(&mut self) -> XGBoostResult<String> {
+        let len = self.parse_length()?;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| self.error("string is not valid UTF-8"))
+    }
+
+    /// Parse the optional `$type` and `#count` headers of an optimized container.
+    fn parse_container_header(&mut self) -> XGBoostResult<(Option<u8>, Option<usize>)> {
+        let mut element_type = None;
+        if self.peek() == Some(b'$') {
+            self.pos += 1;
+            let marker = self.take(1)?[0];
+            // Elements of these types take no bytes, so a count alone could
+            // stand for any number of them.
+            if matches!(marker, b'Z' | b'T' | b'F' | b'N') {
+                return Err(
+                    self.error(&format!("container of zero-size type '{}'", marker as char))
+                );
+            }
+            element_type = Some(marker);
+        }
+        let mut count = None;
+        if self.peek() == Some(b'#') {
+            self.pos += 1;
+            let len = self.parse_length()?;
+            // Every element takes at least one byte.
+            if len > self.buf.len() - self.pos {
+                return Err(self.error("container count exceeds the remaining input"));
+            }
+            count = Some(len);
+        } else if element_type.is_some() {
+            return Err(self.error("typed container without a count"));
+        }
+        Ok((element_type, count))
+    }
+
+    fn parse_array(&mut self) -> XGBoostResult<Value> {
+        let (element_type, count) = self.parse_container_header()?;
+        match count {
+            Some(count) => {
+                let mut items = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let marker = match element_type {
+                        Some(t) => t,
+                        None => self.next_marker()?,
+                    };
+                    items.push(self.parse_typed(marker)?);
+                }
+                Ok(Value::Array(items))
+            }
+            None => {
+                let mut items = Vec::new();
+                loop {
+                    let marker = self.next_marker()?;
+                    if marker == b']' {
+                        return Ok(Value::Array(items));
+                    }
+                    items.push(self.parse_typed(marker)?);
+                }
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> XGBoostResult<Value> {
+        let (element_type, count) = self.parse_container_header()?;
+        let mut entries = Vec::new();
+        match count {
+            Some(count) => {
+                for _ in 0..count {
+                    let key = self.parse_string_body()?;
+                    let marker = match element_type {
+                        Some(t) => t,
+                        None => self.next_marker()?,
+                    };
+                    entries.push((key, self.parse_typed(marker)?));
+                }
+            }
+            None => loop {
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    break;
+                }
+                let key = self.parse_string_body()?;
+                let value = self.parse_value()?;
+                entries.push((key, value));
+            },
+        }
+        Ok(Value::Object(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_values() {
+        let value = parse_json(
+            br#" {"a": [1, -2.5E-1, true, null], "b": {"c": "x\"\u00e9\ud83d\ude00"}, "d": NaN} "#,
+        )
+        .unwrap();
+        assert_eq!(
+            value.get("a").unwrap().as_array().unwrap(),
+            [
+                Value::Number(1.0),
+                Value::Number(-0.25),
+                Value::Bool(true),
+                Value::Null
+            ]
+        );
+        assert_eq!(
+            value.get("b").and_then(|b| b.get("c")).unwrap().as_str(),
+            Some("x\"é😀")
+        );
+        assert!(value.get("d").unwrap().as_f64().unwrap().is_nan());
+        assert_eq!(value.get("e"), None);
+    }
+
+    #[test]
+    fn numeric_strings_are_numbers() {
+        assert_eq!(Value::String(" 4 ".to_string()).as_f64(), Some(4.0));
+        assert_eq!(Value::String("5E-1".to_string()).as_f64(), Some(0.5));
+        assert_eq!(Value::Bool(true).as_f64(), Some(1.0));
+        assert_eq!(Value::String("four".to_string()).as_f64(), None);
+        assert_eq!(Value::Null.as_f64(), None);
+    }
+
+    #[test]
+    fn json_errors_report_the_offset() {
+        let error = parse_json(b"{\"a\": [1, 2}").unwrap_err();
+        assert_eq!(
+            error.description,
+            "Invalid JSON at byte 11: expected ',' or ']'"
+        );
+        assert!(parse_json(b"{} x").is_err());
+        assert!(parse_json(b"{\"a\": \"unterminated").is_err());
+        assert!(parse_json(b"").is_err());
+    }
+
+    #[test]
+    fn nesting_is_capped() {
+        let nested = |depth: usize| {
+            let mut doc = "[".repeat(depth);
+            doc.push_str(&"]".repeat(depth));
+            doc
+        };
+        assert!(parse_json(nested(MAX_DEPTH).as_bytes()).is_ok());
+        let error = parse_json(nested(MAX_DEPTH + 1).as_bytes()).unwrap_err();
+        assert!(error.description.contains("nesting deeper than 128 levels"));
+        // deep enough to overflow the stack without the cap
+        assert!(parse_json(nested(1_000_000).as_bytes()).is_err());
+        assert!(parse_ubjson(&vec![b'['; 1_000_000]).is_err());
+    }
+
+    #[test]
+    fn ubjson_values() {
+        // {"a": [0.5, -2.0], "b": [1, 300], "s": "hi", "t": true}, where "a" is
+        // a typed, counted array and "b" has a no-op marker before its end
+        let mut doc = b"{U\x01a[$d#U\x02".to_vec();
+        doc.extend_from_slice(&0.5f32.to_be_bytes());
+        doc.extend_from_slice(&(-2.0f32).to_be_bytes());
+        doc.extend_from_slice(b"U\x01b[U\x01I\x01\x2cN]U\x01sSU\x02hiU\x01tT}");
+        let value = parse_ubjson(&doc).unwrap();
+        assert_eq!(
+            value.get("a").unwrap().as_array().unwrap(),
+            [Value::Number(0.5), Value::Number(-2.0)]
+        );
+        assert_eq!(
+            value.get("b").unwrap().as_array().unwrap(),
+            [Value::Number(1.0), Value::Number(300.0)]
+        );
+        assert_eq!(value.get("s").unwrap().as_str(), Some("hi"));
+        assert_eq!(value.get("t"), Some(&Value::Bool(true)));
+    }
+
+    #[test]
+    fn ubjson_errors() {
+        assert!(parse_ubjson(b"{U\x01a[$d]").is_err());
+        assert!(parse_ubjson(b"SU\x05ab").is_err());
+        assert!(parse_ubjson(b"Si\xff").is_err());
+        assert!(parse_ubjson(b"X").is_err());
+    }
+
+    #[test]
+    fn ubjson_counts_are_bounded_by_the_input() {
+        // a count of 2^62 nulls or empty arrays would never finish
+        let huge = b"L\x40\x00\x00\x00\x00\x00\x00\x00";
+        for header in [&b"[$Z#"[..], b"[$T#", b"{$N#", b"[#", b"[$[#", b"{#"] {
+            let doc = [header, huge].concat();
+            assert!(parse_ubjson(&doc).is_err());
+        }
+        let error = parse_ubjson(b"[$Z#U\x01").unwrap_err();
+        assert!(error.description.contains("zero-size type 'Z'"));
+        assert!(parse_ubjson(b"[#U\x02ZZ").is_ok());
+        assert!(parse_ubjson(b"[#U\x03ZZ").is_err());
+    }
+
+    #[test]
+    fn formats_are_sniffed() {
+        assert_eq!(detect_format(b"{\"learner\": {}}"), Some(ModelFormat::Json));
+        assert_eq!(detect_format(b"{}"), Some(ModelFormat::Json));
+        assert_eq!(detect_format(b"  {U\x07learner"), Some(ModelFormat::Json));
+        assert_eq!(detect_format(b"{U\x07learner"), Some(ModelFormat::Ubjson));
+        assert_eq!(detect_format(b"binf"), None);
+        assert!(parse_model_document(b"binf").is_err());
+    }
+}
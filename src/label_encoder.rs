@@ -0,0 +1,222 @@
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::predict_options::PredictOptions;
+use crate::Booster;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Model attribute holding the encoded classes
+const ATTRIBUTE: &str = "label_encoder_classes";
+
+/// Maps arbitrary class labels to the indices `0..num_classes` XGBoost
+/// trains on, and predicted indices back to labels.
+///
+/// Classes are numbered in sorted order, as in scikit-learn. Saving the
+/// encoder into the model with `save_to` keeps the mapping
+/// next to the trees, so a service that loads the model can restore it
+/// with [`from_booster`](Self::from_booster).
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{Booster, LabelEncoder};
+///
+/// let booster = Booster::load("model.json").unwrap();
+/// let encoder: LabelEncoder = LabelEncoder::from_booster(&booster).unwrap().unwrap();
+/// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+/// let species: Vec<String> = encoder.predict_class(&booster, &data, 2, 2).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelEncoder<T = String> {
+    classes: Vec<T>,
+}
+
+impl<T: Ord + Clone> LabelEncoder<T> {
+    /// Learn the distinct classes of `labels`
+    pub fn fit(labels: &[T]) -> XGBoostResult<Self> {
+        let mut classes = labels.to_vec();
+        classes.sort();
+        classes.dedup();
+        Self::from_classes(classes)
+    }
+
+    /// An encoder for known classes, numbered in sorted order
+    pub fn from_classes(mut classes: Vec<T>) -> XGBoostResult<Self> {
+        classes.sort();
+        if classes.is_empty() {
+            return Err(XGBoostError {
+                description: "LabelEncoder needs at least one class".to_string(),
+            });
+        }
+        if classes.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(XGBoostError {
+                description: "LabelEncoder classes must be distinct".to_string(),
+            });
+        }
+        Ok(LabelEncoder { classes })
+    }
+
+    /// The classes, in index order
+    pub fn classes(&self) -> &[T] {
+        &self.classes
+    }
+
+    /// Number of classes (the `num_class` training parameter)
+    pub fn num_classes(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Class indices of `labels`, as training labels
+    pub fn transform(&self, labels: &[T]) -> XGBoostResult<Vec<f32>> {
+        labels
+            .iter()
+            .map(|label| {
+                self.classes
+                    .binary_search(label)
+                    .map(|index| index as f32)
+                    .map_err(|_| XGBoostError {
+                        description: "Label not seen when fitting the LabelEncoder".to_string(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Labels of class indices, e.g. the output of `multi:softmax`
+    pub fn inverse_transform(&self, indices: &[f32]) -> XGBoostResult<Vec<T>> {
+        indices
+            .iter()
+            .map(|&index| {
+                let class = index as usize;
+                if index < 0.0 || index.fract() != 0.0 || class >= self.classes.len() {
+                    return Err(XGBoostError {
+                        description: format!(
+                            "Class index {} out of range for {} classes",
+                            index,
+                            self.classes.len()
+                        ),
+                    });
+                }
+                Ok(self.classes[class].clone())
+            })
+            .collect()
+    }
+
+    /// Predict the label of each row
+    ///
+    /// Handles models with one probability per class (`multi:softprob`),
+    /// one class index per row (`multi:softmax`), and binary classifiers
+    /// with one probability per row, thresholded at 0.5.
+    pub fn predict_class(
+        &self,
+        booster: &Booster,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Vec<T>> {
+        let predictions = booster.predict(
+            data,
+            num_rows,
+            num_features,
+            PredictOptions::default(),
+            false,
+        )?;
+        let num_classes = self.classes.len();
+        let indices: Vec<f32> = if predictions.len() == num_rows * num_classes && num_classes > 1 {
+            predictions
+                .chunks(num_classes)
+                .map(|row| {
+                    let mut best = 0;
+                    for (class, &p) in row.iter().enumerate() {
+                        if p > row[best] {
+                            best = class;
+                        }
+                    }
+                    best as f32
+                })
+                .collect()
+        } else if predictions.len() == num_rows && num_classes == 2 {
+            predictions
+                .iter()
+                .map(|&p| if p > 0.5 { 1.0 } else { 0.0 })
+                .collect()
+        } else if predictions.len() == num_rows {
+            predictions
+        } else {
+            return Err(XGBoostError {
+                description: format!(
+                    "Got {} predictions for {} rows and {} classes",
+                    predictions.len(),
+                    num_rows,
+                    num_classes
+                ),
+            });
+        };
+        self.inverse_transform(&indices)
+    }
+}
+
+impl<T: Ord + Clone + Display> LabelEncoder<T> {
+    /// Store the classes as a model attribute, saved with the model
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{train, BoosterParams, DMatrix, LabelEncoder, Objective};
+    ///
+    /// let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]; // 3 rows, 2 features
+    /// let species = ["setosa", "virginica", "setosa"].map(String::from);
+    /// let encoder = LabelEncoder::fit(&species).unwrap();
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&data, 3, 2).unwrap();
+    /// dtrain.set_label(&encoder.transform(&species).unwrap()).unwrap();
+    /// let num_class = encoder.num_classes() as u32;
+    /// let params = BoosterParams::new().objective(Objective::MultiSoftprob(num_class));
+    /// let mut booster = train(&params, &dtrain, 10).unwrap();
+    /// encoder.save_to(&mut booster).unwrap();
+    /// booster.save("model.json").unwrap();
+    /// ```
+    #[cfg(feature = "native")]
+    pub fn save_to(&self, booster: &mut Booster) -> XGBoostResult<()> {
+        let classes: Vec<String> = self
+            .classes
+            .iter()
+            .map(|class| escape(&class.to_string()))
+            .collect();
+        booster.set_attr(ATTRIBUTE, &classes.join(","))
+    }
+}
+
+impl<T: Ord + Clone + FromStr> LabelEncoder<T> {
+    /// Restore an encoder stored with `save_to`, or `None`
+    /// if the model has none
+    pub fn from_booster(booster: &Booster) -> XGBoostResult<Option<Self>> {
+        let Some(value) = booster.attr(ATTRIBUTE)? else {
+            return Ok(None);
+        };
+        let classes = split_escaped(&value)
+            .iter()
+            .map(|class| {
+                class.parse().map_err(|_| XGBoostError {
+                    description: format!("Invalid label encoder class '{}'", class),
+                })
+            })
+            .collect::<XGBoostResult<Vec<T>>>()?;
+        Self::from_classes(classes).map(Some)
+    }
+}
+
+/// Escape `\` and `,` so classes can be joined with commas
+#[cfg(feature = "native")]
+pub(crate) fn escape(class: &str) -> String {
+    class.replace('\\', "\\\\").replace(',', "\\,")
+}
+
+pub(crate) fn split_escaped(value: &str) -> Vec<String> {
+    let mut classes = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => classes.last_mut().unwrap().extend(chars.next()),
+            ',' => classes.push(String::new()),
+            _ => classes.last_mut().unwrap().push(c),
+        }
+    }
+    classes
+}
@@ -0,0 +1,198 @@
+use crate::cv::CrossValidation;
+use crate::dmatrix::DMatrix;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::params::BoosterParams;
+use crate::rng::SplitMix64;
+use crate::split::Split;
+use crate::train::EvalHistory;
+use std::collections::BTreeMap;
+
+/// Metric name → value
+type Scores = BTreeMap<String, f64>;
+
+/// Learning curve over the given fractions of the training rows
+///
+/// Shorthand for [`LearningCurve`] with its defaults.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{learning_curve, BoosterParams, DMatrix, Objective};
+///
+/// # let dmatrix = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+/// let params = BoosterParams::new()
+///     .objective(Objective::BinaryLogistic)
+///     .eval_metric("auc");
+/// for point in learning_curve(&params, &dmatrix, &[0.1, 0.25, 0.5, 1.0]).unwrap() {
+///     println!(
+///         "{} rows: train auc {:?}, test auc {:?}",
+///         point.num_train_rows,
+///         point.train.get("auc"),
+///         point.test.get("auc")
+///     );
+/// }
+/// ```
+pub fn learning_curve(
+    params: &BoosterParams,
+    dmatrix: &DMatrix,
+    fractions: &[f64],
+) -> XGBoostResult<Vec<LearningCurvePoint>> {
+    LearningCurve::new(params, dmatrix)
+        .fractions(fractions)
+        .run()
+}
+
+/// Train and test scores for growing subsets of the training data.
+///
+/// Rows are split into folds once; for every fraction, each fold trains on
+/// that fraction of its training rows and is scored on the training subset
+/// and its full test fold. A gap that stays wide as rows are added points
+/// to too much capacity (regularize), while test scores still improving at
+/// the full data mean more data would help.
+#[derive(Clone)]
+pub struct LearningCurve<'a> {
+    params: &'a BoosterParams,
+    dmatrix: &'a DMatrix,
+    fractions: Vec<f64>,
+    nfold: usize,
+    num_boost_round: usize,
+    early_stopping_rounds: Option<usize>,
+    seed: u64,
+}
+
+/// Scores at one training set size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LearningCurvePoint {
+    /// Fraction of each fold's training rows used
+    pub fraction: f64,
+    /// Training rows of the first fold (folds differ by at most one row)
+    pub num_train_rows: usize,
+    /// Mean score on the training rows, per metric
+    pub train: Scores,
+    /// Mean score on the test folds, per metric
+    pub test: Scores,
+    /// Standard deviation of the test score over folds, per metric
+    pub test_std: Scores,
+}
+
+impl<'a> LearningCurve<'a> {
+    /// A 3-fold learning curve at 10%, 32.5%, 55%, 77.5% and 100% of the
+    /// training rows, training for 10 rounds
+    pub fn new(params: &'a BoosterParams, dmatrix: &'a DMatrix) -> Self {
+        LearningCurve {
+            params,
+            dmatrix,
+            fractions: vec![0.1, 0.325, 0.55, 0.775, 1.0],
+            nfold: 3,
+            num_boost_round: 10,
+            early_stopping_rounds: None,
+            seed: 0,
+        }
+    }
+
+    /// Fractions of the training rows, each in `(0, 1]`
+    pub fn fractions(mut self, fractions: &[f64]) -> Self {
+        self.fractions = fractions.to_vec();
+        self
+    }
+
+    /// Number of folds
+    pub fn nfold(mut self, nfold: usize) -> Self {
+        self.nfold = nfold;
+        self
+    }
+
+    /// Maximum number of boosting rounds
+    pub fn num_boost_round(mut self, num_boost_round: usize) -> Self {
+        self.num_boost_round = num_boost_round;
+        self
+    }
+
+    /// Stop each size when the mean test score has not improved for
+    /// `rounds`, and report the scores of its best round
+    pub fn early_stopping_rounds(mut self, rounds: usize) -> Self {
+        self.early_stopping_rounds = Some(rounds);
+        self
+    }
+
+    /// Seed of the fold assignment and subsets
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Train and score every size, in the order of the fractions
+    pub fn run(&self) -> XGBoostResult<Vec<LearningCurvePoint>> {
+        if let Some(f) = self.fractions.iter().find(|f| !(**f > 0.0 && **f <= 1.0)) {
+            return Err(XGBoostError {
+                description: format!("Learning curve fractions must be in (0, 1], got {}", f),
+            });
+        }
+        let num_rows = self.dmatrix.num_rows()?;
+        if self.nfold < 2 || self.nfold > num_rows {
+            return Err(XGBoostError {
+                description: format!(
+                    "nfold must be between 2 and the number of rows ({}), got {}",
+                    num_rows, self.nfold
+                ),
+            });
+        }
+
+        // shuffled once, so smaller subsets are contained in larger ones
+        let mut rows: Vec<usize> = (0..num_rows).collect();
+        SplitMix64::new(self.seed).shuffle(&mut rows);
+        let folds: Vec<Split> = (0..self.nfold)
+            .map(|fold| {
+                let (test, train): (Vec<usize>, Vec<usize>) =
+                    (0..num_rows).partition(|&position| position * self.nfold / num_rows == fold);
+                Split {
+                    train: train.into_iter().map(|p| rows[p]).collect(),
+                    test: test.into_iter().map(|p| rows[p]).collect(),
+                }
+            })
+            .collect();
+
+        self.fractions
+            .iter()
+            .map(|&fraction| {
+                let subsets: Vec<Split> = folds
+                    .iter()
+                    .map(|split| {
+                        let len = ((split.train.len() as f64 * fraction).round() as usize).max(1);
+                        Split {
+                            train: split.train[..len].to_vec(),
+                            test: split.test.clone(),
+                        }
+                    })
+                    .collect();
+                let num_train_rows = subsets[0].train.len();
+                let mut cv = CrossValidation::new(self.params, self.dmatrix)
+                    .folds(subsets)
+                    .num_boost_round(self.num_boost_round);
+                if let Some(rounds) = self.early_stopping_rounds {
+                    cv = cv.early_stopping_rounds(rounds);
+                }
+                let result = cv.run()?;
+                let round = result
+                    .best_iteration
+                    .unwrap_or_else(|| result.mean.num_rounds().saturating_sub(1));
+                Ok(LearningCurvePoint {
+                    fraction,
+                    num_train_rows,
+                    train: scores_at(&result.mean, "train", round),
+                    test: scores_at(&result.mean, "test", round),
+                    test_std: scores_at(&result.std, "test", round),
+                })
+            })
+            .collect()
+    }
+}
+
+fn scores_at(history: &EvalHistory, dataset: &str, round: usize) -> Scores {
+    history
+        .metrics(dataset)
+        .filter_map(|metric| {
+            let value = *history.get(dataset, metric)?.get(round)?;
+            Some((metric.to_string(), value))
+        })
+        .collect()
+}
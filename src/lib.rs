@@ -1,22 +1,229 @@
+#[cfg(not(any(feature = "native", feature = "pure")))]
+compile_error!("enable the `native` feature (libxgboost) or the `pure` feature (pure Rust)");
+
 // Include the XGBoost C API bindings
+#[cfg(feature = "native")]
 mod sys;
 
 mod error;
 pub use crate::error::{XGBoostError, XGBoostResult};
 
+mod device;
+pub use crate::device::Device;
+
+mod quantile;
+pub use crate::quantile::{pinball_loss, QuantilePredictions};
+
+pub mod metrics;
+
+#[cfg(feature = "native")]
 mod model;
-pub use crate::model::Booster;
-
-// Re-export prediction option constants for convenience
-pub mod predict_option {
-    /// Normal prediction, output is the transformed probability
-    pub const OUTPUT_MARGIN: u32 = 0x01;
-    /// Output the untransformed margin value
-    pub const PRED_LEAF: u32 = 0x02;
-    /// Output the leaf index of trees
-    pub const PRED_CONTRIBS: u32 = 0x04;
-    /// Output feature contributions (SHAP values)
-    pub const PRED_APPROX_CONTRIBS: u32 = 0x08;
-    /// Output feature interaction contributions
-    pub const PRED_INTERACTIONS: u32 = 0x10;
-}
+#[cfg(feature = "native")]
+pub use crate::model::{parse_eval_line, Booster, EvalRecord};
+
+// Runtime report of the libxgboost build configuration
+#[cfg(all(xgboost_build_info, feature = "native"))]
+mod build_info;
+#[cfg(all(xgboost_build_info, feature = "native"))]
+pub use crate::build_info::{build_info, xgboost_version, BuildInfo};
+
+#[cfg(feature = "native")]
+mod dmatrix;
+#[cfg(feature = "native")]
+pub use crate::dmatrix::{DMatrix, DMatrixBuilder};
+#[cfg(feature = "native")]
+mod memory;
+#[cfg(feature = "native")]
+pub use crate::memory::estimate_dmatrix_bytes;
+#[cfg(all(xgboost_get_data, feature = "native"))]
+pub use crate::memory::MemoryInfo;
+#[cfg(all(xgboost_get_data, feature = "native"))]
+mod cuts;
+#[cfg(all(xgboost_get_data, feature = "native"))]
+pub use crate::cuts::QuantileCuts;
+#[cfg(feature = "native")]
+pub mod io;
+
+// Training
+#[cfg(feature = "native")]
+mod params;
+#[cfg(all(xgboost_device_param, feature = "native"))]
+pub use crate::params::MultiStrategy;
+#[cfg(feature = "native")]
+pub use crate::params::{
+    AftDistribution, BoosterParams, BoosterType, DartNormalizeType, DartSampleType, Objective,
+    TreeMethod,
+};
+#[cfg(feature = "native")]
+mod train;
+#[cfg(feature = "native")]
+pub use crate::train::{
+    train, EvalHistory, RoundEvent, StopReason, TrainOutput, Trainer, TrainingObserver,
+};
+#[cfg(feature = "native")]
+mod cancel;
+#[cfg(feature = "native")]
+pub use crate::cancel::CancellationToken;
+#[cfg(feature = "native")]
+mod cv;
+#[cfg(feature = "native")]
+pub use crate::cv::{cv, cv_predict, CrossValidation, CvPredictions, CvResult};
+#[cfg(feature = "native")]
+mod learning_curve;
+#[cfg(feature = "native")]
+pub use crate::learning_curve::{learning_curve, LearningCurve, LearningCurvePoint};
+#[cfg(feature = "native")]
+mod stacking;
+#[cfg(feature = "native")]
+pub use crate::stacking::{StackedModel, Stacker};
+#[cfg(feature = "native")]
+pub mod split;
+#[cfg(feature = "native")]
+pub mod tuning;
+
+#[cfg(all(xgboost_feature_score, feature = "native"))]
+mod importance;
+#[cfg(all(xgboost_feature_score, feature = "native"))]
+pub use crate::importance::ImportanceType;
+#[cfg(all(xgboost_feature_score, feature = "native"))]
+pub mod feature_selection;
+
+// Zero-copy input from CUDA device memory
+#[cfg(all(feature = "cuda", feature = "native"))]
+mod cuda;
+#[cfg(all(feature = "cuda", xgboost_quantile_dmatrix, feature = "native"))]
+pub use crate::cuda::CudaBatch;
+#[cfg(all(feature = "cuda", feature = "native"))]
+pub use crate::cuda::{CudaArray, CudaPredictions};
+
+// Model document parsing, shared by the pure-Rust predictor and the exporters
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+mod gbm;
+// only JSON request records are parsed without these
+#[cfg_attr(
+    not(any(feature = "pure", xgboost_save_to_buffer, xgboost_build_info)),
+    allow(dead_code)
+)]
+mod json;
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+pub use crate::json::ModelFormat;
+
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+mod diff;
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+mod linear;
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+mod onnx;
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+mod pmml;
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+mod sql;
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+pub use crate::diff::{compare_models, ModelDiff, TreeDiff, ValueChange};
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+pub use crate::linear::LinearCoefficients;
+#[cfg(any(feature = "pure", xgboost_save_to_buffer))]
+pub use crate::sql::SqlDialect;
+
+// Distributed training across workers
+#[cfg(all(xgboost_collective, feature = "native"))]
+pub mod collective;
+
+// Versioned model sources (directories, URLs)
+pub mod registry;
+
+// Prediction latency and throughput metrics
+pub mod instrument;
+
+// Scoring a candidate model next to the production model
+pub mod shadow;
+
+// Blending the predictions of several models
+mod ensemble;
+pub use crate::ensemble::{Combination, Ensemble};
+
+// Reloading a model when its file changes
+#[cfg(any(not(feature = "native"), xgboost_thread_safe))]
+mod watch;
+#[cfg(any(not(feature = "native"), xgboost_thread_safe))]
+pub use crate::watch::{WatchedBooster, WatchedBoosterBuilder};
+
+// Model download from object stores, for Booster::load_from_url
+#[cfg(feature = "object_store")]
+mod object_store;
+
+// Request batching for scoring services
+#[cfg(all(feature = "serve", any(not(feature = "native"), xgboost_thread_safe)))]
+pub mod serve;
+
+// Treelite/TL2cgen export via the Python tooling
+#[cfg(feature = "tl2cgen")]
+pub mod tl2cgen;
+
+// Pure-Rust model evaluation, the `Booster` when the `native` feature is off
+#[cfg(feature = "pure")]
+mod pure;
+#[cfg(feature = "pure")]
+pub use crate::pure::PureBooster;
+#[cfg(all(feature = "pure", not(feature = "native")))]
+pub use crate::pure::PureBooster as Booster;
+mod backend;
+mod convert;
+
+mod date;
+#[cfg(any(feature = "integrity", feature = "object_store"))]
+mod hex;
+mod rng;
+
+pub mod inspection;
+pub mod shap;
+
+mod calibration;
+pub use crate::calibration::{CalibratedBooster, CalibrationMethod};
+
+mod label_encoder;
+pub use crate::label_encoder::LabelEncoder;
+
+mod feature_hasher;
+pub use crate::feature_hasher::{FeatureHasher, FeatureValue};
+
+mod target_encoder;
+pub use crate::target_encoder::TargetEncoder;
+
+mod feature_stats;
+pub use crate::feature_stats::{FeatureDrift, FeatureStats, InputReport};
+
+mod model_card;
+pub use crate::model_card::ModelCard;
+
+mod compression;
+pub use crate::compression::Compression;
+
+// Encrypted models
+#[cfg(feature = "encryption")]
+pub mod encryption;
+
+// Checksums and signatures of saved models
+#[cfg(feature = "integrity")]
+mod integrity;
+#[cfg(feature = "integrity")]
+pub use crate::integrity::IntegrityError;
+
+mod predict_options;
+pub use crate::predict_options::PredictOptions;
+
+mod features;
+mod records;
+pub use crate::features::{AsFeature, FeatureType, XgbFeatures};
+#[cfg(feature = "derive")]
+pub use xgboost_rust_derive::XgbFeatures;
+
+mod batches;
+pub use crate::batches::{PredictBatches, RowBatch};
+#[cfg(feature = "arrow")]
+mod record_batches;
+
+#[cfg(all(xgboost_predict_config, feature = "native"))]
+mod predict_config;
+#[cfg(all(xgboost_predict_config, feature = "native"))]
+pub use crate::predict_config::{PredictConfig, PredictType, Predictions};
@@ -0,0 +1,64 @@
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::gbm::{GradientBooster, Learner};
+
+/// Coefficients of a `gblinear` model, from [`Booster::linear_coefficients`].
+///
+/// The margin of output group `g` for a row `x` is
+/// `base_margin[g] + bias[g] + Σ weights[g][i] * x[i]`, with missing
+/// values contributing nothing.
+///
+/// [`Booster::linear_coefficients`]: crate::Booster::linear_coefficients
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearCoefficients {
+    /// One weight per feature for each output group (class)
+    pub weights: Vec<Vec<f32>>,
+    /// Bias of each output group
+    pub bias: Vec<f32>,
+    /// Base score of each output group, converted to margin space
+    pub base_margin: Vec<f32>,
+    /// Feature names stored in the model (empty if it has none)
+    pub feature_names: Vec<String>,
+}
+
+impl LinearCoefficients {
+    pub(crate) fn from_learner(learner: &Learner) -> XGBoostResult<Self> {
+        let GradientBooster::Linear(linear) = &learner.booster else {
+            return Err(XGBoostError {
+                description: "Coefficients are only defined for gblinear models".to_string(),
+            });
+        };
+        let num_groups = learner.num_groups;
+        // feature-major: row i holds the weights of feature i for every group
+        let weights = (0..num_groups)
+            .map(|group| {
+                (0..learner.num_feature)
+                    .map(|fid| linear.weights[fid * num_groups + group])
+                    .collect()
+            })
+            .collect();
+        Ok(LinearCoefficients {
+            weights,
+            bias: linear.weights[learner.num_feature * num_groups..].to_vec(),
+            base_margin: learner.base_margin.clone(),
+            feature_names: learner.feature_names.clone(),
+        })
+    }
+
+    /// Margins of one row of features, one per output group
+    pub fn margin(&self, row: &[f32]) -> Vec<f32> {
+        self.weights
+            .iter()
+            .zip(&self.bias)
+            .zip(&self.base_margin)
+            .map(|((weights, bias), base)| {
+                let dot: f32 = weights
+                    .iter()
+                    .zip(row)
+                    .filter(|(_, x)| !x.is_nan())
+                    .map(|(w, x)| w * x)
+                    .sum();
+                base + bias + dot
+            })
+            .collect()
+    }
+}
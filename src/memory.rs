@@ -0,0 +1,139 @@
+#[cfg(xgboost_get_data)]
+use crate::dmatrix::{DMatrix, ROW_FIELDS};
+#[cfg(xgboost_get_data)]
+use crate::error::{XGBoostError, XGBoostResult};
+#[cfg(xgboost_get_data)]
+use crate::sys;
+#[cfg(xgboost_get_data)]
+use std::ffi::CString;
+#[cfg(xgboost_get_data)]
+use std::ptr;
+
+/// Row offsets of XGBoost's sparse pages (`size_t`)
+const OFFSET_BYTES: u64 = 8;
+/// One stored entry: a `u32` feature index and an `f32` value
+const ENTRY_BYTES: u64 = 8;
+/// One value of a float meta-information field
+#[cfg(xgboost_get_data)]
+const INFO_BYTES: u64 = 4;
+
+/// Estimated bytes XGBoost holds for a DMatrix of `num_rows` rows with
+/// `num_non_missing` stored values, before any labels or weights
+///
+/// XGBoost stores every non-missing value with its feature index plus an
+/// offset per row, whatever the input layout, so a dense input with no
+/// missing values needs about twice its own size. Add 4 bytes per row for
+/// each of labels, weights and base margins (times the number of targets
+/// or classes), and the input itself while it is being converted. At most
+/// `num_rows × num_cols` values are counted.
+///
+/// Training adds the histogram index on top: about one byte per stored
+/// value for `tree_method=hist` with up to 256 bins.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::estimate_dmatrix_bytes;
+///
+/// // 100M rows, 50 features, 30% missing
+/// let bytes = estimate_dmatrix_bytes(100_000_000, 50, 3_500_000_000);
+/// println!("{:.1} GiB", bytes as f64 / (1u64 << 30) as f64);
+/// ```
+pub fn estimate_dmatrix_bytes(num_rows: usize, num_cols: usize, num_non_missing: usize) -> u64 {
+    let num_rows = num_rows as u64;
+    let num_values = (num_non_missing as u64).min(num_rows.saturating_mul(num_cols as u64));
+    (num_rows + 1)
+        .saturating_mul(OFFSET_BYTES)
+        .saturating_add(num_values.saturating_mul(ENTRY_BYTES))
+}
+
+/// Size of a DMatrix, from [`DMatrix::memory_info`].
+#[cfg(xgboost_get_data)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryInfo {
+    /// Number of rows
+    pub num_rows: usize,
+    /// Number of columns
+    pub num_cols: usize,
+    /// Number of stored (non-missing) values
+    pub num_non_missing: usize,
+    /// Estimated bytes of the stored values and row offsets
+    pub data_bytes: u64,
+    /// Bytes of the labels, weights, base margins and label bounds
+    pub info_bytes: u64,
+}
+
+#[cfg(xgboost_get_data)]
+impl MemoryInfo {
+    /// Estimated bytes of the whole matrix
+    pub fn total_bytes(&self) -> u64 {
+        self.data_bytes + self.info_bytes
+    }
+
+    /// Fraction of the `num_rows × num_cols` cells that hold a value
+    pub fn density(&self) -> f64 {
+        let cells = self.num_rows as f64 * self.num_cols as f64;
+        if cells == 0.0 {
+            0.0
+        } else {
+            self.num_non_missing as f64 / cells
+        }
+    }
+}
+
+#[cfg(xgboost_get_data)]
+impl DMatrix {
+    /// Number of values and estimated memory of this matrix
+    ///
+    /// The data size is [`estimate_dmatrix_bytes`] for the stored values;
+    /// meta-information is counted exactly. On a sample of a larger job,
+    /// scale the result by the row count to size chunks or machines.
+    /// Requires XGBoost ≥ 2.0.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let dmatrix = DMatrix::from_dense(&[1.0, f32::NAN, 3.0, 4.0], 2, 2).unwrap();
+    /// let info = dmatrix.memory_info().unwrap();
+    /// println!("{} values, {} bytes", info.num_non_missing, info.total_bytes());
+    /// ```
+    pub fn memory_info(&self) -> XGBoostResult<MemoryInfo> {
+        let num_rows = self.num_rows()?;
+        let num_cols = self.num_cols()?;
+        let mut num_non_missing: u64 = 0;
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixNumNonMissing(self.handle(), &mut num_non_missing)
+        })?;
+        let num_non_missing = num_non_missing as usize;
+
+        let mut info_values: u64 = 0;
+        for field in ROW_FIELDS {
+            info_values += float_info_len(self, field)?;
+        }
+
+        Ok(MemoryInfo {
+            num_rows,
+            num_cols,
+            num_non_missing,
+            data_bytes: estimate_dmatrix_bytes(num_rows, num_cols, num_non_missing),
+            info_bytes: info_values * INFO_BYTES,
+        })
+    }
+}
+
+/// Number of values of a float meta-information field, without copying them
+#[cfg(xgboost_get_data)]
+fn float_info_len(dmatrix: &DMatrix, field: &str) -> XGBoostResult<u64> {
+    let field = CString::new(field).unwrap();
+    let mut out_len: u64 = 0;
+    let mut out_dptr: *const f32 = ptr::null();
+    XGBoostError::check_return_value(unsafe {
+        sys::XGDMatrixGetFloatInfo(
+            dmatrix.handle(),
+            field.as_ptr(),
+            &mut out_len,
+            &mut out_dptr,
+        )
+    })?;
+    Ok(if out_dptr.is_null() { 0 } else { out_len })
+}
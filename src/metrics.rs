@@ -0,0 +1,879 @@
+//! Evaluation metrics computed from predictions and labels.
+//!
+//! The definitions follow the XGBoost metrics of the same name, so values
+//! match what `eval_metric` reports during training. Optional weights are
+//! per row, except for the ranking metrics where they are per query group.
+//!
+//! # Example
+//! ```no_run
+//! use xgboost_rust::metrics;
+//!
+//! let labels = [0.0, 1.0, 1.0, 0.0];
+//! let predictions = [0.1, 0.8, 0.6, 0.4];
+//! let auc = metrics::auc(&labels, &predictions, None).unwrap();
+//! let logloss = metrics::logloss(&labels, &predictions, None).unwrap();
+//! ```
+
+use crate::error::{XGBoostError, XGBoostResult};
+use std::fmt;
+
+pub use crate::quantile::pinball_loss;
+
+/// Root mean squared error (`rmse`)
+pub fn rmse(labels: &[f32], predictions: &[f32], weights: Option<&[f32]>) -> XGBoostResult<f64> {
+    weighted_mean(labels, predictions, weights, |y, p| (y - p) * (y - p)).map(f64::sqrt)
+}
+
+/// Mean absolute error (`mae`)
+pub fn mae(labels: &[f32], predictions: &[f32], weights: Option<&[f32]>) -> XGBoostResult<f64> {
+    weighted_mean(labels, predictions, weights, |y, p| (y - p).abs())
+}
+
+/// Negative log-likelihood of binary predictions (`logloss`)
+///
+/// Predictions are probabilities; they are clipped away from 0 and 1.
+pub fn logloss(labels: &[f32], predictions: &[f32], weights: Option<&[f32]>) -> XGBoostResult<f64> {
+    const EPS: f64 = 1e-16;
+    weighted_mean(labels, predictions, weights, |y, p| {
+        let p = p.clamp(EPS, 1.0 - EPS);
+        -(y * p.ln() + (1.0 - y) * (1.0 - p).ln())
+    })
+}
+
+/// Area under the ROC curve of binary predictions (`auc`)
+///
+/// Tied predictions contribute the trapezoid between their endpoints.
+pub fn auc(labels: &[f32], predictions: &[f32], weights: Option<&[f32]>) -> XGBoostResult<f64> {
+    check_lengths(labels, predictions, weights)?;
+    let mut order: Vec<usize> = (0..labels.len()).collect();
+    order.sort_by(|&a, &b| predictions[b].total_cmp(&predictions[a]));
+
+    let weight = |i: usize| weights.map_or(1.0, |w| w[i] as f64);
+    let (mut tp, mut fp, mut area) = (0.0, 0.0, 0.0);
+    let mut start = 0;
+    while start < order.len() {
+        let score = predictions[order[start]];
+        let end = start
+            + order[start..]
+                .iter()
+                .take_while(|&&i| predictions[i] == score)
+                .count();
+        let (tp_prev, fp_prev) = (tp, fp);
+        for &i in &order[start..end] {
+            let label = labels[i] as f64;
+            tp += weight(i) * label;
+            fp += weight(i) * (1.0 - label);
+        }
+        area += (fp - fp_prev) * (tp + tp_prev) / 2.0;
+        start = end;
+    }
+
+    if tp <= 0.0 || fp <= 0.0 {
+        return Err(XGBoostError {
+            description: "AUC requires both positive and negative samples".to_string(),
+        });
+    }
+    Ok(area / (tp * fp))
+}
+
+/// Normalized discounted cumulative gain (`ndcg`, `ndcg@k`)
+///
+/// Uses the exponential gain `2^label - 1`. Queries without any relevant
+/// document score 1, as in XGBoost.
+///
+/// # Arguments
+/// * `labels` - Relevance degrees
+/// * `predictions` - Ranking scores
+/// * `group_sizes` - Number of documents in each query, in order
+/// * `k` - Only consider the top `k` documents of each query
+/// * `weights` - Optional weight per query
+pub fn ndcg(
+    labels: &[f32],
+    predictions: &[f32],
+    group_sizes: &[usize],
+    k: Option<usize>,
+    weights: Option<&[f32]>,
+) -> XGBoostResult<f64> {
+    ranking_mean(
+        labels,
+        predictions,
+        group_sizes,
+        weights,
+        |labels, ranked| {
+            let k = k.unwrap_or(usize::MAX);
+            let dcg = |order: &mut dyn Iterator<Item = f32>| -> f64 {
+                order
+                    .take(k)
+                    .enumerate()
+                    .map(|(i, label)| (2f64.powf(label as f64) - 1.0) / ((i + 2) as f64).log2())
+                    .sum()
+            };
+            let mut ideal = labels.to_vec();
+            ideal.sort_by(|a, b| b.total_cmp(a));
+            let idcg = dcg(&mut ideal.into_iter());
+            if idcg == 0.0 {
+                return 1.0;
+            }
+            dcg(&mut ranked.iter().map(|&i| labels[i])) / idcg
+        },
+    )
+}
+
+/// Mean average precision (`map`, `map@k`)
+///
+/// Labels greater than zero are relevant. Queries without any relevant
+/// document score 1, as in XGBoost.
+///
+/// # Arguments
+/// * `labels` - Relevance labels
+/// * `predictions` - Ranking scores
+/// * `group_sizes` - Number of documents in each query, in order
+/// * `k` - Only consider the top `k` documents of each query
+/// * `weights` - Optional weight per query
+pub fn map(
+    labels: &[f32],
+    predictions: &[f32],
+    group_sizes: &[usize],
+    k: Option<usize>,
+    weights: Option<&[f32]>,
+) -> XGBoostResult<f64> {
+    ranking_mean(
+        labels,
+        predictions,
+        group_sizes,
+        weights,
+        |labels, ranked| {
+            let k = k.unwrap_or(usize::MAX);
+            let (mut relevant, mut hits, mut precision_sum) = (0usize, 0usize, 0.0);
+            for (position, &i) in ranked.iter().enumerate() {
+                if labels[i] > 0.0 {
+                    relevant += 1;
+                    if position < k {
+                        hits += 1;
+                        precision_sum += hits as f64 / (position + 1) as f64;
+                    }
+                }
+            }
+            if relevant == 0 {
+                1.0
+            } else {
+                precision_sum / relevant as f64
+            }
+        },
+    )
+}
+
+/// `ndcg@k` of queries given by their boundaries
+///
+/// Same as [`ndcg`], with the queries described like XGBoost's
+/// `group_ptr`: `group_ptr[q]..group_ptr[q + 1]` are the rows of query `q`.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::metrics;
+///
+/// let labels = [2.0, 0.0, 1.0, 0.0, 1.0];
+/// let scores = [0.9, 0.7, 0.2, 0.1, 0.8];
+/// let group_ptr = [0, 3, 5]; // rows 0-2 and 3-4
+/// let ndcg = metrics::ndcg_at_k(&labels, &scores, &group_ptr, 10).unwrap();
+/// let map = metrics::map_at_k(&labels, &scores, &group_ptr, 10).unwrap();
+/// let mrr = metrics::mrr(&labels, &scores, &group_ptr).unwrap();
+/// ```
+pub fn ndcg_at_k(
+    labels: &[f32],
+    predictions: &[f32],
+    group_ptr: &[usize],
+    k: usize,
+) -> XGBoostResult<f64> {
+    ndcg(labels, predictions, &group_sizes(group_ptr)?, Some(k), None)
+}
+
+/// `map@k` of queries given by their boundaries
+///
+/// Same as [`map`], with the queries described as in [`ndcg_at_k`].
+pub fn map_at_k(
+    labels: &[f32],
+    predictions: &[f32],
+    group_ptr: &[usize],
+    k: usize,
+) -> XGBoostResult<f64> {
+    map(labels, predictions, &group_sizes(group_ptr)?, Some(k), None)
+}
+
+/// Mean reciprocal rank of the first relevant document of each query
+///
+/// Labels greater than zero are relevant; queries without any relevant
+/// document score 0. XGBoost has no such metric. The queries are
+/// described as in [`ndcg_at_k`].
+pub fn mrr(labels: &[f32], predictions: &[f32], group_ptr: &[usize]) -> XGBoostResult<f64> {
+    ranking_mean(
+        labels,
+        predictions,
+        &group_sizes(group_ptr)?,
+        None,
+        |labels, ranked| {
+            ranked
+                .iter()
+                .position(|&i| labels[i] > 0.0)
+                .map_or(0.0, |position| 1.0 / (position + 1) as f64)
+        },
+    )
+}
+
+/// What [`tune_threshold`] optimizes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThresholdObjective {
+    /// Harmonic mean of precision and recall
+    F1,
+    /// Highest precision with at least the given recall
+    PrecisionAtRecall(f64),
+    /// Youden's J statistic: true positive rate minus false positive rate
+    YoudenJ,
+}
+
+/// Rates when rows scoring at least `threshold` are predicted positive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThresholdPoint {
+    /// Lowest prediction classified positive
+    pub threshold: f32,
+    /// Fraction of predicted positives that are positive
+    pub precision: f64,
+    /// Fraction of positives predicted positive (true positive rate)
+    pub recall: f64,
+    /// Fraction of negatives predicted positive
+    pub false_positive_rate: f64,
+}
+
+impl ThresholdPoint {
+    /// Harmonic mean of precision and recall
+    pub fn f1(&self) -> f64 {
+        if self.precision + self.recall == 0.0 {
+            0.0
+        } else {
+            2.0 * self.precision * self.recall / (self.precision + self.recall)
+        }
+    }
+
+    /// True positive rate minus false positive rate
+    pub fn youden_j(&self) -> f64 {
+        self.recall - self.false_positive_rate
+    }
+}
+
+/// Best threshold found by [`tune_threshold`], with the whole curve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdTuning {
+    /// The point maximizing the objective
+    pub best: ThresholdPoint,
+    /// Value of the objective at the best point
+    pub score: f64,
+    /// Every distinct prediction as a threshold, from the highest to the
+    /// lowest: the precision-recall (and ROC) curve
+    pub curve: Vec<ThresholdPoint>,
+}
+
+/// Sweep the decision threshold of binary predictions and pick the one
+/// that maximizes `objective`
+///
+/// Labels are 0 or 1 (positive above 0.5). Every distinct prediction is
+/// tried as a threshold, rows scoring at least the threshold counting as
+/// positive; ties go to the higher threshold.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::metrics::{self, ThresholdObjective};
+///
+/// let labels = [0.0, 0.0, 1.0, 1.0, 0.0, 1.0];
+/// let predictions = [0.1, 0.4, 0.35, 0.8, 0.6, 0.9];
+/// let tuning = metrics::tune_threshold(&labels, &predictions, ThresholdObjective::F1).unwrap();
+/// println!("predict positive from {} (F1 {})", tuning.best.threshold, tuning.score);
+///
+/// let strict =
+///     metrics::tune_threshold(&labels, &predictions, ThresholdObjective::PrecisionAtRecall(0.6))
+///         .unwrap();
+/// ```
+pub fn tune_threshold(
+    labels: &[f32],
+    predictions: &[f32],
+    objective: ThresholdObjective,
+) -> XGBoostResult<ThresholdTuning> {
+    check_lengths(labels, predictions, None)?;
+    if predictions.iter().any(|p| p.is_nan()) {
+        return Err(XGBoostError {
+            description: "Predictions must not be NaN".to_string(),
+        });
+    }
+    let num_positive = labels.iter().filter(|&&y| y > 0.5).count();
+    let num_negative = labels.len() - num_positive;
+    if num_positive == 0 || num_negative == 0 {
+        return Err(XGBoostError {
+            description: "Threshold tuning requires both positive and negative samples".to_string(),
+        });
+    }
+
+    let mut order: Vec<usize> = (0..labels.len()).collect();
+    order.sort_by(|&a, &b| predictions[b].total_cmp(&predictions[a]));
+    let mut curve = Vec::new();
+    let (mut tp, mut fp) = (0usize, 0usize);
+    let mut start = 0;
+    while start < order.len() {
+        let threshold = predictions[order[start]];
+        let end = start
+            + order[start..]
+                .iter()
+                .take_while(|&&i| predictions[i] == threshold)
+                .count();
+        for &i in &order[start..end] {
+            if labels[i] > 0.5 {
+                tp += 1;
+            } else {
+                fp += 1;
+            }
+        }
+        curve.push(ThresholdPoint {
+            threshold,
+            precision: tp as f64 / end as f64,
+            recall: tp as f64 / num_positive as f64,
+            false_positive_rate: fp as f64 / num_negative as f64,
+        });
+        start = end;
+    }
+
+    let score = |point: &ThresholdPoint| match objective {
+        ThresholdObjective::F1 => Some(point.f1()),
+        ThresholdObjective::PrecisionAtRecall(recall) => {
+            (point.recall >= recall).then_some(point.precision)
+        }
+        ThresholdObjective::YoudenJ => Some(point.youden_j()),
+    };
+    let mut best: Option<(ThresholdPoint, f64)> = None;
+    for point in &curve {
+        if let Some(value) = score(point) {
+            if best.map_or(true, |(_, best)| value > best) {
+                best = Some((*point, value));
+            }
+        }
+    }
+    let (best, score) = best.ok_or_else(|| XGBoostError {
+        description: format!("No threshold satisfies {:?}", objective),
+    })?;
+    Ok(ThresholdTuning { best, score, curve })
+}
+
+/// Per-class scores of a [`ClassificationReport`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassMetrics {
+    /// Fraction of rows predicted as the class that belong to it
+    pub precision: f64,
+    /// Fraction of the class's rows predicted as the class
+    pub recall: f64,
+    /// Harmonic mean of precision and recall
+    pub f1: f64,
+    /// Number of rows labeled with the class
+    pub support: usize,
+}
+
+/// Confusion matrix and per-class scores, from [`classification_report`].
+///
+/// Classes are the label values `0, 1, 2, …`. Scores of a class that is
+/// never predicted (or never present) are 0. Formats as a table like
+/// scikit-learn's `classification_report`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassificationReport {
+    /// Row counts, indexed by `[label][predicted class]`
+    pub confusion_matrix: Vec<Vec<usize>>,
+    /// Scores of each class
+    pub classes: Vec<ClassMetrics>,
+    /// Fraction of rows predicted correctly
+    pub accuracy: f64,
+}
+
+impl ClassificationReport {
+    /// Number of classes
+    pub fn num_classes(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Unweighted mean of the per-class F1 scores
+    pub fn macro_f1(&self) -> f64 {
+        self.classes.iter().map(|c| c.f1).sum::<f64>() / self.classes.len() as f64
+    }
+
+    /// Mean of the per-class F1 scores, weighted by support
+    pub fn weighted_f1(&self) -> f64 {
+        let total: usize = self.classes.iter().map(|c| c.support).sum();
+        self.classes
+            .iter()
+            .map(|c| c.f1 * c.support as f64)
+            .sum::<f64>()
+            / total as f64
+    }
+}
+
+impl fmt::Display for ClassificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>8} {:>9} {:>9} {:>9} {:>9}",
+            "class", "precision", "recall", "f1", "support"
+        )?;
+        for (class, metrics) in self.classes.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>8} {:>9.4} {:>9.4} {:>9.4} {:>9}",
+                class, metrics.precision, metrics.recall, metrics.f1, metrics.support
+            )?;
+        }
+        writeln!(
+            f,
+            "accuracy {:.4}, macro f1 {:.4}",
+            self.accuracy,
+            self.macro_f1()
+        )?;
+        writeln!(f, "confusion matrix (rows: label, columns: predicted):")?;
+        for row in &self.confusion_matrix {
+            let counts: Vec<String> = row.iter().map(|n| n.to_string()).collect();
+            writeln!(f, "  {}", counts.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Confusion matrix, per-class precision, recall and F1, and accuracy of
+/// a classifier's predictions
+///
+/// Labels are class indices `0, 1, 2, …`. As in
+/// [`LabelEncoder::predict_class`](crate::LabelEncoder::predict_class),
+/// predictions are one probability per class and row (`multi:softprob`,
+/// the most probable class wins), or one value per row: probabilities of a
+/// binary classifier, positive above 0.5, or class indices
+/// (`multi:softmax`).
+///
+/// The classes are those of the labels, or one per probability column for
+/// `multi:softprob`; a predicted class or label outside them is an error.
+///
+/// # Example
+/// ```
+/// use xgboost_rust::metrics;
+///
+/// let labels = [0.0, 1.0, 2.0, 1.0];
+/// let predictions = [0.0, 1.0, 1.0, 1.0]; // multi:softmax
+/// let report = metrics::classification_report(&labels, &predictions).unwrap();
+/// assert_eq!(report.confusion_matrix[2][1], 1);
+/// assert!(report.accuracy >= 0.75);
+/// println!("{}", report);
+/// ```
+pub fn classification_report(
+    labels: &[f32],
+    predictions: &[f32],
+) -> XGBoostResult<ClassificationReport> {
+    if labels.is_empty() {
+        return Err(XGBoostError {
+            description: "Cannot evaluate a metric on empty data".to_string(),
+        });
+    }
+    let num_rows = labels.len();
+    let actual = labels
+        .iter()
+        .map(|&y| {
+            if y >= 0.0 && y.fract() == 0.0 {
+                Ok(y as usize)
+            } else {
+                Err(XGBoostError {
+                    description: format!("Labels must be class indices, got {}", y),
+                })
+            }
+        })
+        .collect::<XGBoostResult<Vec<usize>>>()?;
+    let label_classes = actual.iter().max().map_or(0, |&c| c + 1).max(2);
+    // The matrix is sized by the label classes, or by the row width of
+    // softprob output, never by a predicted value
+    let (num_classes, predicted) = if predictions.len() == num_rows {
+        let predicted: Vec<usize> = if predictions.iter().all(|&p| (0.0..=1.0).contains(&p)) {
+            predictions.iter().map(|&p| usize::from(p > 0.5)).collect()
+        } else if let Some(&p) = predictions.iter().find(|p| p.is_nan() || **p < 0.0) {
+            return Err(XGBoostError {
+                description: format!("Predicted classes must not be negative, got {}", p),
+            });
+        } else if let Some(&p) = predictions
+            .iter()
+            .find(|p| p.round() >= label_classes as f32)
+        {
+            return Err(XGBoostError {
+                description: format!(
+                    "Predicted class {} is not among the {} classes of the labels",
+                    p, label_classes
+                ),
+            });
+        } else {
+            predictions.iter().map(|&p| p.round() as usize).collect()
+        };
+        (label_classes, predicted)
+    } else if predictions.len() % num_rows == 0 {
+        let width = predictions.len() / num_rows;
+        if label_classes > width {
+            return Err(XGBoostError {
+                description: format!(
+                    "Label {} is out of range for {} predicted classes",
+                    label_classes - 1,
+                    width
+                ),
+            });
+        }
+        let predicted = predictions
+            .chunks(width)
+            .map(|row| {
+                let mut best = 0;
+                for (class, &p) in row.iter().enumerate() {
+                    if p > row[best] {
+                        best = class;
+                    }
+                }
+                best
+            })
+            .collect();
+        (width, predicted)
+    } else {
+        return Err(XGBoostError {
+            description: format!(
+                "Length mismatch: {} labels, {} predictions",
+                num_rows,
+                predictions.len()
+            ),
+        });
+    };
+
+    let mut confusion_matrix = vec![vec![0usize; num_classes]; num_classes];
+    for (&y, &p) in actual.iter().zip(&predicted) {
+        confusion_matrix[y][p] += 1;
+    }
+    let ratio = |n: usize, d: usize| if d == 0 { 0.0 } else { n as f64 / d as f64 };
+    let classes = (0..num_classes)
+        .map(|class| {
+            let correct = confusion_matrix[class][class];
+            let support: usize = confusion_matrix[class].iter().sum();
+            let num_predicted: usize = confusion_matrix.iter().map(|row| row[class]).sum();
+            let precision = ratio(correct, num_predicted);
+            let recall = ratio(correct, support);
+            let f1 = if precision + recall == 0.0 {
+                0.0
+            } else {
+                2.0 * precision * recall / (precision + recall)
+            };
+            ClassMetrics {
+                precision,
+                recall,
+                f1,
+                support,
+            }
+        })
+        .collect();
+    let correct: usize = (0..num_classes).map(|c| confusion_matrix[c][c]).sum();
+    Ok(ClassificationReport {
+        confusion_matrix,
+        classes,
+        accuracy: ratio(correct, num_rows),
+    })
+}
+
+/// Query sizes from boundaries `[0, end of query 0, end of query 1, …]`
+fn group_sizes(group_ptr: &[usize]) -> XGBoostResult<Vec<usize>> {
+    if group_ptr.first() != Some(&0) {
+        return Err(XGBoostError {
+            description: "Group boundaries must start at 0".to_string(),
+        });
+    }
+    group_ptr
+        .windows(2)
+        .map(|w| {
+            w[1].checked_sub(w[0]).ok_or_else(|| XGBoostError {
+                description: format!("Group boundaries decrease from {} to {}", w[0], w[1]),
+            })
+        })
+        .collect()
+}
+
+fn check_lengths(
+    labels: &[f32],
+    predictions: &[f32],
+    weights: Option<&[f32]>,
+) -> XGBoostResult<()> {
+    if labels.len() != predictions.len() {
+        return Err(XGBoostError {
+            description: format!(
+                "Length mismatch: {} labels, {} predictions",
+                labels.len(),
+                predictions.len()
+            ),
+        });
+    }
+    if labels.is_empty() {
+        return Err(XGBoostError {
+            description: "Cannot evaluate a metric on empty data".to_string(),
+        });
+    }
+    if let Some(weights) = weights {
+        if weights.len() != labels.len() {
+            return Err(XGBoostError {
+                description: format!(
+                    "Length mismatch: {} labels, {} weights",
+                    labels.len(),
+                    weights.len()
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Weighted mean of a per-row loss.
+fn weighted_mean(
+    labels: &[f32],
+    predictions: &[f32],
+    weights: Option<&[f32]>,
+    loss: impl Fn(f64, f64) -> f64,
+) -> XGBoostResult<f64> {
+    check_lengths(labels, predictions, weights)?;
+    let (mut total, mut weight_sum) = (0.0, 0.0);
+    for (i, (&y, &p)) in labels.iter().zip(predictions).enumerate() {
+        let w = weights.map_or(1.0, |w| w[i] as f64);
+        total += w * loss(y as f64, p as f64);
+        weight_sum += w;
+    }
+    if weight_sum <= 0.0 {
+        return Err(XGBoostError {
+            description: "Sum of weights must be positive".to_string(),
+        });
+    }
+    Ok(total / weight_sum)
+}
+
+/// Weighted mean over query groups of a per-query score.
+///
+/// `score` receives the labels of one query and the indices of its documents
+/// sorted by descending prediction.
+fn ranking_mean(
+    labels: &[f32],
+    predictions: &[f32],
+    group_sizes: &[usize],
+    weights: Option<&[f32]>,
+    score: impl Fn(&[f32], &[usize]) -> f64,
+) -> XGBoostResult<f64> {
+    check_lengths(labels, predictions, None)?;
+    if group_sizes.iter().sum::<usize>() != labels.len() {
+        return Err(XGBoostError {
+            description: format!(
+                "Group sizes sum to {}, but there are {} rows",
+                group_sizes.iter().sum::<usize>(),
+                labels.len()
+            ),
+        });
+    }
+    if let Some(weights) = weights {
+        if weights.len() != group_sizes.len() {
+            return Err(XGBoostError {
+                description: format!(
+                    "Length mismatch: {} groups, {} weights",
+                    group_sizes.len(),
+                    weights.len()
+                ),
+            });
+        }
+    }
+
+    let (mut total, mut weight_sum) = (0.0, 0.0);
+    let mut start = 0;
+    for (group, &size) in group_sizes.iter().enumerate() {
+        let labels = &labels[start..start + size];
+        let predictions = &predictions[start..start + size];
+        start += size;
+        if size == 0 {
+            continue;
+        }
+        let mut ranked: Vec<usize> = (0..size).collect();
+        ranked.sort_by(|&a, &b| predictions[b].total_cmp(&predictions[a]));
+        let w = weights.map_or(1.0, |w| w[group] as f64);
+        total += w * score(labels, &ranked);
+        weight_sum += w;
+    }
+    if weight_sum <= 0.0 {
+        return Err(XGBoostError {
+            description: "Sum of weights must be positive".to_string(),
+        });
+    }
+    Ok(total / weight_sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn regression_metrics() {
+        let (labels, predictions) = ([1.0, 2.0, 3.0], [1.0, 2.0, 5.0]);
+        assert!(close(
+            rmse(&labels, &predictions, None).unwrap(),
+            (4.0f64 / 3.0).sqrt()
+        ));
+        assert!(close(
+            rmse(&labels, &predictions, Some(&[1.0, 1.0, 2.0])).unwrap(),
+            2f64.sqrt()
+        ));
+        assert!(close(mae(&labels, &predictions, None).unwrap(), 2.0 / 3.0));
+        assert!(mae(&labels, &predictions, Some(&[0.0, 0.0, 0.0])).is_err());
+        assert!(mae(&labels, &predictions[..2], None).is_err());
+        assert!(mae(&[], &[], None).is_err());
+    }
+
+    #[test]
+    fn logloss_clips_probabilities() {
+        assert!(close(
+            logloss(&[1.0, 0.0], &[0.75, 0.25], None).unwrap(),
+            -0.75f64.ln()
+        ));
+        let certain_and_wrong = logloss(&[0.0], &[1.0], None).unwrap();
+        assert!(certain_and_wrong.is_finite() && certain_and_wrong > 36.0);
+    }
+
+    #[test]
+    fn auc_counts_ties_and_weights() {
+        let labels = [0.0, 1.0, 1.0, 0.0];
+        assert_eq!(auc(&labels, &[0.1, 0.8, 0.6, 0.4], None).unwrap(), 1.0);
+        assert_eq!(auc(&[0.0, 1.0], &[0.5, 0.5], None).unwrap(), 0.5);
+        // one correctly and one wrongly ordered pair, the latter heavier
+        let weighted = auc(&[1.0, 0.0, 1.0], &[0.9, 0.5, 0.1], Some(&[1.0, 1.0, 3.0]));
+        assert_eq!(weighted.unwrap(), 0.25);
+        assert!(auc(&[1.0, 1.0], &[0.2, 0.4], None).is_err());
+    }
+
+    #[test]
+    fn ranking_metrics() {
+        let labels = [2.0, 0.0, 1.0];
+        let scores = [0.9, 0.7, 0.2];
+        let ideal = 3.0 + 1.0 / 3f64.log2();
+        assert!(close(
+            ndcg(&labels, &scores, &[3], None, None).unwrap(),
+            3.5 / ideal
+        ));
+        assert_eq!(ndcg(&labels, &scores, &[3], Some(1), None).unwrap(), 1.0);
+        assert_eq!(
+            ndcg(&[0.0, 0.0], &[0.1, 0.2], &[2], None, None).unwrap(),
+            1.0
+        );
+
+        let relevant = [1.0, 0.0, 1.0];
+        let scores = [0.9, 0.8, 0.7];
+        assert!(close(
+            map(&relevant, &scores, &[3], None, None).unwrap(),
+            5.0 / 6.0
+        ));
+        assert_eq!(map(&relevant, &scores, &[3], Some(2), None).unwrap(), 0.5);
+
+        // the second query is weighted out
+        let labels = [0.0, 1.0, 0.0, 0.0, 1.0];
+        let scores = [0.9, 0.8, 0.1, 0.5, 0.4];
+        assert_eq!(
+            map(&labels, &scores, &[3, 2], None, Some(&[1.0, 0.0])).unwrap(),
+            0.5
+        );
+        assert_eq!(mrr(&labels, &scores, &[0, 3, 5]).unwrap(), 0.5);
+        assert_eq!(mrr(&labels[..4], &scores[..4], &[0, 3, 4]).unwrap(), 0.25);
+    }
+
+    #[test]
+    fn group_boundaries_are_checked() {
+        assert_eq!(group_sizes(&[0, 3, 5]).unwrap(), [3, 2]);
+        assert!(group_sizes(&[1, 3]).is_err());
+        assert!(group_sizes(&[0, 3, 2]).is_err());
+        let error = ndcg_at_k(&[1.0, 0.0], &[0.1, 0.2], &[0, 3], 5).unwrap_err();
+        assert_eq!(
+            error.description,
+            "Group sizes sum to 3, but there are 2 rows"
+        );
+    }
+
+    #[test]
+    fn threshold_tuning() {
+        let labels = [0.0, 1.0, 1.0, 0.0];
+        let predictions = [0.1, 0.8, 0.6, 0.4];
+        let tuning = tune_threshold(&labels, &predictions, ThresholdObjective::F1).unwrap();
+        assert_eq!(tuning.best.threshold, 0.6);
+        assert_eq!(tuning.score, 1.0);
+        let thresholds: Vec<f32> = tuning.curve.iter().map(|p| p.threshold).collect();
+        assert_eq!(thresholds, [0.8, 0.6, 0.4, 0.1]);
+
+        let youden = tune_threshold(&labels, &predictions, ThresholdObjective::YoudenJ);
+        assert_eq!(youden.unwrap().best.threshold, 0.6);
+        // every recall reaches 0.4, and the first point has the best precision
+        let strict = tune_threshold(
+            &labels,
+            &predictions,
+            ThresholdObjective::PrecisionAtRecall(0.4),
+        );
+        assert_eq!(strict.unwrap().best.threshold, 0.8);
+        assert!(tune_threshold(
+            &labels,
+            &predictions,
+            ThresholdObjective::PrecisionAtRecall(1.5)
+        )
+        .is_err());
+        assert!(
+            tune_threshold(&labels, &[0.1, f32::NAN, 0.6, 0.4], ThresholdObjective::F1).is_err()
+        );
+        assert!(tune_threshold(&[1.0, 1.0], &[0.1, 0.2], ThresholdObjective::F1).is_err());
+    }
+
+    #[test]
+    fn classification_report_classes_come_from_the_labels() {
+        // probabilities, so one prediction per row is a binary decision
+        let report = classification_report(&[0.0, 1.0, 2.0], &[0.2, 0.6, 0.4]).unwrap();
+        assert_eq!(
+            report.confusion_matrix,
+            [vec![1, 0, 0], vec![0, 1, 0], vec![1, 0, 0]]
+        );
+        assert_eq!(report.classes[2].support, 1);
+        assert_eq!(report.classes[2].recall, 0.0);
+        assert!(close(report.classes[0].f1, 2.0 / 3.0));
+        assert!(close(report.macro_f1(), 5.0 / 9.0));
+        assert!(close(report.accuracy, 2.0 / 3.0));
+
+        let softmax = classification_report(&[0.0, 1.0, 2.0], &[0.0, 2.0, 2.0]).unwrap();
+        assert_eq!(softmax.num_classes(), 3);
+        assert_eq!(softmax.confusion_matrix[1][2], 1);
+    }
+
+    #[test]
+    fn classification_report_softprob() {
+        let predictions = [0.7, 0.2, 0.1, 0.1, 0.3, 0.6];
+        let report = classification_report(&[0.0, 1.0], &predictions).unwrap();
+        assert_eq!(report.num_classes(), 3);
+        assert_eq!(report.confusion_matrix[1][2], 1);
+        assert_eq!(report.classes[2].precision, 0.0);
+        assert_eq!(report.accuracy, 0.5);
+    }
+
+    #[test]
+    fn classification_report_bounds() {
+        let error = |labels: &[f32], predictions: &[f32]| {
+            classification_report(labels, predictions)
+                .unwrap_err()
+                .description
+        };
+        assert_eq!(
+            error(&[0.0, 1.0], &[0.0, 5.0]),
+            "Predicted class 5 is not among the 2 classes of the labels"
+        );
+        assert_eq!(
+            error(&[0.0, 2.0], &[0.7, 0.3, 0.4, 0.6]),
+            "Label 2 is out of range for 2 predicted classes"
+        );
+        assert!(error(&[0.0, 1.0], &[0.0, -2.0]).contains("must not be negative"));
+        assert!(error(&[0.0, 1.5], &[0.0, 1.0]).contains("class indices"));
+        assert!(error(&[0.0, 1.0], &[0.0, 1.0, 1.0]).contains("Length mismatch"));
+        assert!(error(&[], &[]).contains("empty"));
+    }
+}
@@ -1,10 +1,49 @@
+#[cfg(all(feature = "encryption", xgboost_save_to_buffer))]
+use crate::backend::encrypted_path;
+use crate::backend::{check_dense_len, Backend};
+use crate::batches;
+#[cfg(xgboost_build_info)]
+use crate::build_info;
+use crate::compression::{self, Compression};
+use crate::convert;
+#[cfg(feature = "cuda")]
+use crate::cuda::{CudaArray, CudaPredictions};
+use crate::device::Device;
+#[cfg(xgboost_array_interface)]
+use crate::dmatrix::f64_array_interface;
+use crate::dmatrix::{DMatrix, DMatrixBuilder};
+#[cfg(all(feature = "encryption", xgboost_save_to_buffer))]
+use crate::encryption::{self, KeyProvider};
 use crate::error::{XGBoostError, XGBoostResult};
+#[cfg(xgboost_feature_score)]
+use crate::features::FeatureType;
+#[cfg(xgboost_save_to_buffer)]
+use crate::gbm::Learner;
+#[cfg(xgboost_feature_score)]
+use crate::importance::ImportanceType;
+#[cfg(feature = "integrity")]
+use crate::integrity;
+#[cfg(xgboost_save_to_buffer)]
+use crate::json::{self, ModelFormat};
+use crate::params::{check_param_name, BoosterParams};
+#[cfg(xgboost_predict_config)]
+use crate::predict_config::{PredictConfig, PredictType, Predictions};
+use crate::predict_options::PredictOptions;
+use crate::quantile::QuantilePredictions;
+use crate::shap::Contributions;
 use crate::sys;
-use std::ffi::CString;
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+#[cfg(xgboost_save_to_buffer)]
+use std::fs;
+#[cfg(feature = "cuda")]
+use std::marker::PhantomData;
+use std::os::raw::c_char;
 use std::path::Path;
 use std::ptr;
 
-/// An XGBoost Booster for making predictions.
+/// An XGBoost Booster for training and making predictions.
 ///
 /// # Thread Safety
 ///
@@ -48,6 +87,11 @@ use std::ptr;
 ///    ```
 pub struct Booster {
     handle: sys::BoosterHandle,
+    // The `best_iteration` attribute, read on load and whenever it is set so
+    // that predictions do not query it through the C API
+    best_iteration: Option<usize>,
+    use_best_iteration: bool,
+    max_chunk_rows: Option<usize>,
 }
 
 // Thread safety implementation based on XGBoost version
@@ -63,50 +107,76 @@ unsafe impl Sync for Booster {}
 // Users should wrap in Arc<Mutex<Booster>> or use one Booster per thread.
 
 impl Booster {
-    /// Load a model from a file
+    /// Create an untrained booster for the given datasets
+    ///
+    /// The datasets are cached by XGBoost for training and evaluation.
     ///
     /// # Arguments
-    /// * `path` - Path to the model file (can be JSON, binary, or deprecated text format)
+    /// * `cache` - Training (and evaluation) datasets
     ///
     /// # Example
     /// ```no_run
-    /// use xgboost_rust::Booster;
+    /// use xgboost_rust::{Booster, DMatrix};
     ///
-    /// let booster = Booster::load("model.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let mut dtrain = DMatrix::from_dense(&data, 2, 2).unwrap();
+    /// dtrain.set_label(&[0.0, 1.0]).unwrap();
+    /// let mut booster = Booster::new(&[&dtrain]).unwrap();
+    /// booster.update(&dtrain, 0).unwrap();
     /// ```
-    pub fn load<P: AsRef<Path>>(path: P) -> XGBoostResult<Self> {
-        let path_str = path.as_ref().to_str().ok_or_else(|| XGBoostError {
-            description: "Path contains invalid UTF-8 characters".to_string(),
-        })?;
-        let path_c_str = CString::new(path_str).map_err(|e| XGBoostError {
-            description: format!("Path contains NUL byte: {}", e),
-        })?;
-
-        // Create a booster first
+    pub fn new(cache: &[&DMatrix]) -> XGBoostResult<Self> {
+        let handles: Vec<sys::DMatrixHandle> = cache.iter().map(|d| d.handle()).collect();
         let mut handle: sys::BoosterHandle = ptr::null_mut();
         XGBoostError::check_return_value(unsafe {
-            sys::XGBoosterCreate(ptr::null(), 0, &mut handle)
+            sys::XGBoosterCreate(handles.as_ptr(), handles.len() as u64, &mut handle)
         })?;
+        Ok(Booster {
+            handle,
+            best_iteration: None,
+            use_best_iteration: true,
+            max_chunk_rows: None,
+        })
+    }
 
-        // Load model into the booster
-        let result = XGBoostError::check_return_value(unsafe {
-            sys::XGBoosterLoadModel(handle, path_c_str.as_ptr())
-        });
-
-        if let Err(e) = result {
-            unsafe {
-                sys::XGBoosterFree(handle);
-            }
-            return Err(e);
+    /// Load a model from a file
+    ///
+    /// # Arguments
+    /// * `path` - Path to the model file (can be JSON, binary, or deprecated text format),
+    ///   decompressed first if it ends in `.gz` or `.zst` (see [`Compression`])
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// ```
+    ///
+    /// With the `integrity` feature, a model with a `.sha256` checksum file
+    /// (see `Booster::save_with_checksum`) is verified against it first.
+    pub fn load<P: AsRef<Path>>(path: P) -> XGBoostResult<Self> {
+        #[cfg(feature = "integrity")]
+        if integrity::checksum_path(path.as_ref()).exists() {
+            return Ok(Self::load_verified(path)?);
         }
-
-        Ok(Booster { handle })
+        if Compression::from_path(path.as_ref()).is_some() {
+            let buffer = std::fs::read(path.as_ref()).map_err(|e| XGBoostError {
+                description: format!(
+                    "Failed to read model file {}: {}",
+                    path.as_ref().display(),
+                    e
+                ),
+            })?;
+            return Self::load_from_buffer(&buffer);
+        }
+        let mut booster = Self::new(&[])?;
+        booster.load_model(path)?;
+        Ok(booster)
     }
 
     /// Load a model from a memory buffer
     ///
     /// # Arguments
-    /// * `buffer` - Model content as bytes
+    /// * `buffer` - Model content as bytes, possibly gzip or zstd compressed
     ///
     /// # Example
     /// ```no_run
@@ -117,29 +187,121 @@ impl Booster {
     /// let booster = Booster::load_from_buffer(&buffer).unwrap();
     /// ```
     pub fn load_from_buffer(buffer: &[u8]) -> XGBoostResult<Self> {
-        // Create a booster first
-        let mut handle: sys::BoosterHandle = ptr::null_mut();
-        XGBoostError::check_return_value(unsafe {
-            sys::XGBoosterCreate(ptr::null(), 0, &mut handle)
+        let buffer = compression::decompress(buffer)?;
+        let mut booster = Self::new(&[])?;
+        booster.load_model_from_buffer(&buffer)?;
+        Ok(booster)
+    }
+
+    /// Load a saved model and train it for `extra_rounds` more rounds
+    ///
+    /// The objective and booster stored in the model are kept; `params` are
+    /// applied on top (training hyperparameters such as `eta` are not saved
+    /// with the model, so pass them again). New rounds are numbered after the
+    /// saved ones, and a stale `best_iteration` from early stopping is
+    /// removed. Requires XGBoost ≥ 1.4.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the saved model
+    /// * `params` - Training parameters
+    /// * `dtrain` - Training data (with labels)
+    /// * `extra_rounds` - Number of boosting rounds to add
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, BoosterParams, DMatrix};
+    ///
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let mut dtrain = DMatrix::from_dense(&data, 2, 2).unwrap();
+    /// dtrain.set_label(&[0.0, 1.0]).unwrap();
+    ///
+    /// let params = BoosterParams::new().eta(0.05);
+    /// let booster = Booster::train_continue("model.json", &params, &dtrain, 20).unwrap();
+    /// booster.save("model.json").unwrap();
+    /// ```
+    #[cfg(xgboost_boosted_rounds)]
+    pub fn train_continue<P: AsRef<Path>>(
+        path: P,
+        params: &BoosterParams,
+        dtrain: &DMatrix,
+        extra_rounds: usize,
+    ) -> XGBoostResult<Self> {
+        let mut booster = Self::new(&[dtrain])?;
+        booster.load_model(path)?;
+        booster.continue_training(params, dtrain, extra_rounds)?;
+        Ok(booster)
+    }
+
+    /// Like [`Booster::train_continue`], with the model in a memory buffer
+    #[cfg(xgboost_boosted_rounds)]
+    pub fn train_continue_from_buffer(
+        buffer: &[u8],
+        params: &BoosterParams,
+        dtrain: &DMatrix,
+        extra_rounds: usize,
+    ) -> XGBoostResult<Self> {
+        let mut booster = Self::new(&[dtrain])?;
+        booster.load_model_from_buffer(buffer)?;
+        booster.continue_training(params, dtrain, extra_rounds)?;
+        Ok(booster)
+    }
+
+    #[cfg(xgboost_boosted_rounds)]
+    fn continue_training(
+        &mut self,
+        params: &BoosterParams,
+        dtrain: &DMatrix,
+        extra_rounds: usize,
+    ) -> XGBoostResult<()> {
+        self.remove_attr("best_iteration")?;
+        self.remove_attr("best_score")?;
+        self.set_params(params)?;
+        let start = self.num_boosted_rounds()?;
+        for iteration in start..start + extra_rounds {
+            self.update(dtrain, iteration)?;
+        }
+        Ok(())
+    }
+
+    fn load_model<P: AsRef<Path>>(&mut self, path: P) -> XGBoostResult<()> {
+        let path_str = path.as_ref().to_str().ok_or_else(|| XGBoostError {
+            description: "Path contains invalid UTF-8 characters".to_string(),
+        })?;
+        let path_c_str = CString::new(path_str).map_err(|e| XGBoostError {
+            description: format!("Path contains NUL byte: {}", e),
         })?;
 
-        // Load model from buffer into the booster
+        let result = XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterLoadModel(self.handle, path_c_str.as_ptr())
+        });
+        #[cfg(xgboost_build_info)]
+        let result = result.map_err(|e| match std::fs::read(path.as_ref()) {
+            Ok(model) => build_info::explain_load_error(e, &model),
+            Err(_) => e,
+        });
+        result?;
+        self.read_best_iteration()
+    }
+
+    fn load_model_from_buffer(&mut self, buffer: &[u8]) -> XGBoostResult<()> {
         let result = XGBoostError::check_return_value(unsafe {
             sys::XGBoosterLoadModelFromBuffer(
-                handle,
+                self.handle,
                 buffer.as_ptr() as *const std::os::raw::c_void,
                 buffer.len() as u64,
             )
         });
+        #[cfg(xgboost_build_info)]
+        let result = result.map_err(|e| build_info::explain_load_error(e, buffer));
+        result?;
+        self.read_best_iteration()
+    }
 
-        if let Err(e) = result {
-            unsafe {
-                sys::XGBoosterFree(handle);
-            }
-            return Err(e);
-        }
-
-        Ok(Booster { handle })
+    fn read_best_iteration(&mut self) -> XGBoostResult<()> {
+        self.best_iteration = self
+            .attr("best_iteration")?
+            .and_then(|value| value.parse().ok());
+        Ok(())
     }
 
     /// Make predictions on data
@@ -148,83 +310,240 @@ impl Booster {
     /// * `data` - 2D array of features (row-major, num_rows x num_features)
     /// * `num_rows` - Number of rows in the data
     /// * `num_features` - Number of features per row
-    /// * `option_mask` - Prediction options (see `predict_option` module)
-    /// * `training` - Whether this is for training (false for inference)
+    /// * `options` - Prediction options
+    /// * `training` - Whether this is for training (false for inference; true
+    ///   applies DART dropout)
     ///
     /// # Returns
     /// A vector of prediction values
     ///
     /// # Example
     /// ```no_run
-    /// use xgboost_rust::Booster;
+    /// use xgboost_rust::{Booster, PredictOptions};
     ///
     /// let booster = Booster::load("model.json").unwrap();
     /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
-    /// let predictions = booster.predict(&data, 2, 2, 0, false).unwrap();
+    /// let predictions = booster.predict(&data, 2, 2, PredictOptions::empty(), false).unwrap();
     /// ```
     pub fn predict(
         &self,
         data: &[f32],
         num_rows: usize,
         num_features: usize,
-        option_mask: u32,
+        options: PredictOptions,
         training: bool,
     ) -> XGBoostResult<Vec<f32>> {
-        // Validate input dimensions
-        let expected_len = num_rows
-            .checked_mul(num_features)
-            .ok_or_else(|| XGBoostError {
-                description: format!(
-                    "Integer overflow: num_rows ({}) * num_features ({}) exceeds usize::MAX",
-                    num_rows, num_features
-                ),
-            })?;
+        check_dense_len(data.len(), num_rows, num_features)?;
+        batches::predict_chunked(num_rows, self.max_chunk_rows, |rows| {
+            let chunk = &data[rows.start * num_features..rows.end * num_features];
+            let dmatrix = DMatrix::from_dense(chunk, rows.len(), num_features)?;
+            self.predict_dmatrix(&dmatrix, options, training)
+        })
+    }
+
+    /// Predict SHAP feature contributions
+    ///
+    /// With `approx`, uses the faster approximation that attributes each
+    /// split's gain to its feature along the prediction path (Saabas)
+    /// instead of exact TreeSHAP. See [`Contributions`] for aggregating
+    /// the result.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let contributions = booster.predict_contributions(&data, 2, 2, false).unwrap();
+    /// println!("f0 pushed row 0 by {}", contributions.get(0, 0, 0));
+    /// ```
+    pub fn predict_contributions(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        approx: bool,
+    ) -> XGBoostResult<Contributions<'static>> {
+        let mut options = PredictOptions::PRED_CONTRIBS;
+        options.set(PredictOptions::PRED_APPROX_CONTRIBS, approx);
+        let values = self.predict(data, num_rows, num_features, options, false)?;
+        Contributions::from_vec(values, num_rows, num_features)
+    }
+
+    /// Make predictions on data that encodes missing values with a sentinel
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `missing` - Value that marks a missing entry (e.g. `-999.0`)
+    /// * `options` - Prediction options
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, PredictOptions};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data = vec![1.0, -999.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let predictions = booster.predict_with_missing(&data, 2, 2, -999.0, PredictOptions::empty()).unwrap();
+    /// ```
+    pub fn predict_with_missing(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        missing: f32,
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+        batches::predict_chunked(num_rows, self.max_chunk_rows, |rows| {
+            let chunk = &data[rows.start * num_features..rows.end * num_features];
+            let dmatrix =
+                DMatrix::from_dense_with_missing(chunk, rows.len(), num_features, missing)?;
+            self.predict_dmatrix(&dmatrix, options, false)
+        })
+    }
 
-        if data.len() != expected_len {
+    /// Make predictions on data, starting from per-row base margins
+    ///
+    /// The base margins replace the model's `base_score`, so the output of
+    /// a previous model (with `OUTPUT_MARGIN`) can be used as an offset.
+    /// See [`DMatrix::set_base_margin`].
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `base_margin` - Initial margins (row-major, num_rows x num_groups)
+    /// * `options` - Prediction options
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, PredictOptions};
+    ///
+    /// let first = Booster::load("stage1.json").unwrap();
+    /// let second = Booster::load("stage2.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let offset = first.predict(&data, 2, 2, PredictOptions::OUTPUT_MARGIN, false).unwrap();
+    /// let predictions = second.predict_with_base_margin(&data, 2, 2, &offset, PredictOptions::empty()).unwrap();
+    /// ```
+    pub fn predict_with_base_margin(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        base_margin: &[f32],
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+        let margins_per_row = base_margin.len().checked_div(num_rows).unwrap_or(0);
+        if margins_per_row * num_rows != base_margin.len() {
             return Err(XGBoostError {
                 description: format!(
-                    "Data length mismatch: expected {} elements ({}×{}), got {}",
-                    expected_len,
-                    num_rows,
-                    num_features,
-                    data.len()
+                    "Base margin length mismatch: {} values for {} rows",
+                    base_margin.len(),
+                    num_rows
                 ),
             });
         }
+        batches::predict_chunked(num_rows, self.max_chunk_rows, |rows| {
+            let chunk = &data[rows.start * num_features..rows.end * num_features];
+            let mut dmatrix = DMatrix::from_dense(chunk, rows.len(), num_features)?;
+            dmatrix.set_base_margin(
+                &base_margin[rows.start * margins_per_row..rows.end * margins_per_row],
+            )?;
+            self.predict_dmatrix(&dmatrix, options, false)
+        })
+    }
 
-        // Create DMatrix from data
-        let mut dmatrix_handle: sys::DMatrixHandle = ptr::null_mut();
-
-        XGBoostError::check_return_value(unsafe {
-            sys::XGDMatrixCreateFromMat(
-                data.as_ptr(),
-                num_rows as u64,
-                num_features as u64,
-                f32::NAN,
-                &mut dmatrix_handle,
-            )
-        })?;
-
-        // RAII guard to ensure DMatrix is always freed
-        struct DMatrixGuard(sys::DMatrixHandle);
-        impl Drop for DMatrixGuard {
-            fn drop(&mut self) {
-                unsafe {
-                    sys::XGDMatrixFree(self.0);
-                }
+    /// Make predictions on sparse rows, given as `(feature index, value)` pairs
+    ///
+    /// Each row only lists its known features (e.g. a `HashMap<u32, f32>`
+    /// from a feature store); all others are missing. Indices must be below
+    /// the model's number of features.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::collections::HashMap;
+    /// use xgboost_rust::{Booster, PredictOptions};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let rows: Vec<HashMap<u32, f32>> = vec![
+    ///     HashMap::from([(0, 1.0), (3, 0.5)]),
+    ///     HashMap::from([(2, -1.0)]),
+    /// ];
+    /// let predictions = booster.predict_sparse_rows(&rows, PredictOptions::empty()).unwrap();
+    /// ```
+    pub fn predict_sparse_rows<R, K, V>(
+        &self,
+        rows: R,
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>>
+    where
+        R: IntoIterator,
+        R::Item: IntoIterator<Item = (K, V)>,
+        K: Borrow<u32>,
+        V: Borrow<f32>,
+    {
+        let num_features = self.num_features()?;
+        let rows: Vec<Vec<(usize, f32)>> = rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|(index, value)| (*index.borrow() as usize, *value.borrow()))
+                    .collect()
+            })
+            .collect();
+        batches::predict_chunked(rows.len(), self.max_chunk_rows, |range| {
+            let mut builder = DMatrixBuilder::new(num_features);
+            for row in &rows[range] {
+                builder.push_sparse_row(row)?;
             }
+            self.predict_dmatrix(&builder.build()?, options, false)
+        })
+    }
+
+    /// Make predictions on an existing DMatrix
+    ///
+    /// # Arguments
+    /// * `dmatrix` - Data to predict on
+    /// * `options` - Prediction options
+    /// * `training` - Whether this is for training (false for inference; true
+    ///   applies DART dropout)
+    ///
+    /// # Returns
+    /// A vector of prediction values
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, DMatrix, PredictOptions};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let dmatrix = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+    /// let predictions = booster.predict_dmatrix(&dmatrix, PredictOptions::empty(), false).unwrap();
+    /// ```
+    pub fn predict_dmatrix(
+        &self,
+        dmatrix: &DMatrix,
+        options: PredictOptions,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        #[cfg(xgboost_predict_config)]
+        if self.use_best_iteration && self.best_iteration()?.is_some() {
+            let config = PredictConfig::new()
+                .predict_type(PredictType::from_options(options))
+                .training(training);
+            return Ok(self.predict_with_config(dmatrix, &config)?.into_vec());
         }
-        let _guard = DMatrixGuard(dmatrix_handle);
 
-        // Make prediction
         let mut out_len: u64 = 0;
         let mut out_result: *const f32 = ptr::null();
 
         XGBoostError::check_return_value(unsafe {
             sys::XGBoosterPredict(
                 self.handle,
-                dmatrix_handle,
-                option_mask as i32,
+                dmatrix.handle(),
+                options.bits() as i32,
                 0, // ntree_limit (0 means use all trees)
                 training as i32,
                 &mut out_len,
@@ -242,49 +561,1194 @@ impl Booster {
         // Copy results to a Vec
         let results = unsafe { std::slice::from_raw_parts(out_result, out_len as usize).to_vec() };
 
-        // DMatrix will be automatically freed when _guard goes out of scope
-
         Ok(results)
     }
 
-    /// Get the number of features the model expects
+    /// Make predictions on a DMatrix with a [`PredictConfig`]
     ///
-    /// # Returns
-    /// The number of features
-    pub fn num_features(&self) -> XGBoostResult<usize> {
-        let mut out_num_features: u64 = 0;
+    /// Unlike [`Booster::predict_dmatrix`], this can restrict prediction to a
+    /// range of boosting rounds and reports the shape of the output.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, DMatrix, PredictConfig, PredictType};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let dmatrix = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+    /// let config = PredictConfig::new()
+    ///     .predict_type(PredictType::Margin)
+    ///     .iteration_range(0, 10);
+    /// let predictions = booster.predict_with_config(&dmatrix, &config).unwrap();
+    /// println!("shape {:?}: {:?}", predictions.shape(), predictions.row(0));
+    /// ```
+    #[cfg(xgboost_predict_config)]
+    pub fn predict_with_config(
+        &self,
+        dmatrix: &DMatrix,
+        config: &PredictConfig,
+    ) -> XGBoostResult<Predictions> {
+        let config = CString::new(self.default_iteration_range(config)?.to_json()).unwrap();
+        let mut out_shape: *const u64 = ptr::null();
+        let mut out_dim: u64 = 0;
+        let mut out_result: *const f32 = ptr::null();
 
         XGBoostError::check_return_value(unsafe {
-            sys::XGBoosterGetNumFeature(self.handle, &mut out_num_features)
+            sys::XGBoosterPredictFromDMatrix(
+                self.handle,
+                dmatrix.handle(),
+                config.as_ptr(),
+                &mut out_shape,
+                &mut out_dim,
+                &mut out_result,
+            )
         })?;
 
-        Ok(out_num_features as usize)
+        if out_result.is_null() || out_shape.is_null() {
+            return Err(XGBoostError {
+                description: "XGBoost returned null prediction result".to_string(),
+            });
+        }
+
+        let shape: Vec<usize> = unsafe { std::slice::from_raw_parts(out_shape, out_dim as usize) }
+            .iter()
+            .map(|&d| d as usize)
+            .collect();
+        let len = shape.iter().product();
+        let values = unsafe { std::slice::from_raw_parts(out_result, len).to_vec() };
+        Ok(Predictions::new(values, shape))
     }
 
-    /// Save the model to a file
+    /// Predict every output of a multi-output model, shaped `(rows, outputs)`
+    ///
+    /// The outputs are the targets of a model trained on a label matrix
+    /// (see [`DMatrix::set_label_matrix`]), the class probabilities of a
+    /// `multi:softprob` model, or the levels of a quantile model; other
+    /// models have one output. Requires XGBoost ≥ 1.4.
+    #[cfg(xgboost_predict_config)]
+    pub fn predict_multi_output(&self, dmatrix: &DMatrix) -> XGBoostResult<Predictions> {
+        self.predict_with_config(dmatrix, &PredictConfig::new().strict_shape(true))
+    }
+
+    /// Limit a config without an explicit iteration range to the best iteration
+    #[cfg(xgboost_predict_config)]
+    fn default_iteration_range(&self, config: &PredictConfig) -> XGBoostResult<PredictConfig> {
+        if config.has_iteration_range() || !self.use_best_iteration {
+            return Ok(config.clone());
+        }
+        Ok(match self.best_iteration()? {
+            Some(best) => config.clone().iteration_range(0, best + 1),
+            None => config.clone(),
+        })
+    }
+
+    /// Make predictions on dense `f64` data
+    ///
+    /// Uses inplace prediction, so the data is read directly through the
+    /// array interface instead of being copied into an `f32` buffer first.
+    /// Only the default output and `OUTPUT_MARGIN` are supported. Requires
+    /// XGBoost ≥ 1.7.
     ///
     /// # Arguments
-    /// * `path` - Path where to save the model
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `options` - Prediction options
+    /// * `training` - Whether this is for training (false for inference; true
+    ///   applies DART dropout)
     ///
     /// # Example
     /// ```no_run
-    /// use xgboost_rust::Booster;
+    /// use xgboost_rust::{Booster, PredictOptions};
     ///
     /// let booster = Booster::load("model.json").unwrap();
-    /// booster.save("model_copy.json").unwrap();
+    /// let data = vec![1.0f64, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let predictions = booster.predict_f64(&data, 2, 2, PredictOptions::empty(), false).unwrap();
     /// ```
-    pub fn save<P: AsRef<Path>>(&self, path: P) -> XGBoostResult<()> {
-        let path_str = path.as_ref().to_str().ok_or_else(|| XGBoostError {
-            description: "Path contains invalid UTF-8 characters".to_string(),
-        })?;
-        let path_c_str = CString::new(path_str).map_err(|e| XGBoostError {
-            description: format!("Path contains NUL byte: {}", e),
-        })?;
+    #[cfg(xgboost_array_interface)]
+    pub fn predict_f64(
+        &self,
+        data: &[f64],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+        if num_rows == 0 {
+            return Err(XGBoostError {
+                description: "Cannot predict on empty data".to_string(),
+            });
+        }
+        let interface = f64_array_interface(data, num_rows, num_features);
+        let config = self.default_iteration_range(&inplace_predict_config(options, training)?)?;
+        let config = CString::new(config.to_inplace_json()).unwrap();
+
+        let mut out_shape: *const u64 = ptr::null();
+        let mut out_dim: u64 = 0;
+        let mut out_result: *const f32 = ptr::null();
 
         XGBoostError::check_return_value(unsafe {
-            sys::XGBoosterSaveModel(self.handle, path_c_str.as_ptr())
+            sys::XGBoosterPredictFromDense(
+                self.handle,
+                interface.as_ptr(),
+                config.as_ptr(),
+                ptr::null_mut(),
+                &mut out_shape,
+                &mut out_dim,
+                &mut out_result,
+            )
+        })?;
+
+        if out_result.is_null() || out_shape.is_null() {
+            return Err(XGBoostError {
+                description: "XGBoost returned null prediction result".to_string(),
+            });
+        }
+
+        let len: u64 = unsafe { std::slice::from_raw_parts(out_shape, out_dim as usize) }
+            .iter()
+            .product();
+        Ok(unsafe { std::slice::from_raw_parts(out_result, len as usize).to_vec() })
+    }
+
+    /// Make predictions on dense half-precision data
+    ///
+    /// Values are IEEE 754 binary16 bit patterns, as stored by `half::f16`
+    /// (`bytemuck::cast_slice` turns a `&[half::f16]` into a `&[u16]`
+    /// without copying) or f16 columns of Arrow and Polars. They are widened
+    /// to `f32` in one pass, exactly, before predicting.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `options` - Prediction options
+    /// * `training` - Whether this is for training (false for inference; true
+    ///   applies DART dropout)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, PredictOptions};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data: Vec<u16> = vec![0x3c00, 0x4000, 0x4200, 0x4400]; // 1.0, 2.0, 3.0, 4.0
+    /// let predictions = booster.predict_f16(&data, 2, 2, PredictOptions::empty(), false).unwrap();
+    /// ```
+    pub fn predict_f16(
+        &self,
+        data: &[u16],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+        batches::predict_chunked(num_rows, self.max_chunk_rows, |rows| {
+            let chunk = &data[rows.start * num_features..rows.end * num_features];
+            let chunk = convert::f16_to_f32(chunk);
+            let dmatrix = DMatrix::from_dense(&chunk, rows.len(), num_features)?;
+            self.predict_dmatrix(&dmatrix, options, training)
         })
     }
+
+    /// Run inplace prediction on data in CUDA device memory
+    ///
+    /// Neither the input nor the output leave the GPU: the returned
+    /// [`CudaPredictions`] point to device memory owned by XGBoost. Only the
+    /// default output and `OUTPUT_MARGIN` are supported. Requires the `cuda`
+    /// feature and a CUDA-enabled libxgboost; select the GPU first with
+    /// [`Booster::set_device`].
+    ///
+    /// # Arguments
+    /// * `array` - Device-resident matrix
+    /// * `options` - Prediction options
+    /// * `training` - Whether this is for training (false for inference; true
+    ///   applies DART dropout)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, CudaArray, PredictOptions};
+    ///
+    /// # let device_ptr: u64 = 0;
+    /// let mut booster = Booster::load("model.json").unwrap();
+    /// booster.set_device("cuda:0").unwrap();
+    /// let array = unsafe { CudaArray::from_raw_parts(device_ptr, 100, 8) };
+    /// let predictions = booster.predict_cuda(&array, PredictOptions::empty(), false).unwrap();
+    /// println!("{} predictions at {:#x}", predictions.len(), predictions.device_ptr());
+    /// ```
+    #[cfg(feature = "cuda")]
+    pub fn predict_cuda(
+        &self,
+        array: &CudaArray,
+        options: PredictOptions,
+        training: bool,
+    ) -> XGBoostResult<CudaPredictions<'_>> {
+        let interface = CString::new(array.array_interface()).unwrap();
+        let config = self.default_iteration_range(&inplace_predict_config(options, training)?)?;
+        let config = CString::new(config.to_inplace_json()).unwrap();
+
+        let mut out_shape: *const u64 = ptr::null();
+        let mut out_dim: u64 = 0;
+        let mut out_result: *const f32 = ptr::null();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterPredictFromCudaArray(
+                self.handle,
+                interface.as_ptr(),
+                config.as_ptr(),
+                ptr::null_mut(),
+                &mut out_shape,
+                &mut out_dim,
+                &mut out_result,
+            )
+        })?;
+
+        if out_result.is_null() || out_shape.is_null() {
+            return Err(XGBoostError {
+                description: "XGBoost returned null prediction result".to_string(),
+            });
+        }
+
+        let shape = unsafe { std::slice::from_raw_parts(out_shape, out_dim as usize) }
+            .iter()
+            .map(|&d| d as usize)
+            .collect();
+        Ok(CudaPredictions {
+            data: out_result,
+            shape,
+            _booster: PhantomData,
+        })
+    }
+
+    /// Run inplace prediction on a raw row-major `f32` buffer in CUDA device
+    /// memory
+    ///
+    /// Shorthand for [`Booster::predict_cuda`] with a [`CudaArray`] built
+    /// from the pointer, for feature pipelines that only hand out device
+    /// pointers. The output stays on the device.
+    ///
+    /// # Arguments
+    /// * `data` - Device pointer to `num_rows * num_features` floats
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `stream` - CUDA stream XGBoost synchronizes on before reading the
+    ///   data, following the `__cuda_array_interface__` convention (`1`
+    ///   legacy default, `2` per-thread default, otherwise a `cudaStream_t`)
+    /// * `options` - Prediction options
+    ///
+    /// # Safety
+    /// Same requirements as [`CudaArray::from_raw_parts`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, PredictOptions};
+    ///
+    /// # let (device_ptr, stream): (u64, usize) = (0, 2);
+    /// let mut booster = Booster::load("model.json").unwrap();
+    /// booster.set_device("cuda:0").unwrap();
+    /// let predictions = unsafe {
+    ///     booster.predict_from_device(device_ptr, 100, 8, Some(stream), PredictOptions::empty())
+    /// }
+    /// .unwrap();
+    /// ```
+    #[cfg(feature = "cuda")]
+    pub unsafe fn predict_from_device(
+        &self,
+        data: u64,
+        num_rows: usize,
+        num_features: usize,
+        stream: Option<usize>,
+        options: PredictOptions,
+    ) -> XGBoostResult<CudaPredictions<'_>> {
+        let mut array = CudaArray::from_raw_parts(data, num_rows, num_features);
+        if let Some(stream) = stream {
+            array = array.with_stream(stream);
+        }
+        self.predict_cuda(&array, options, false)
+    }
+
+    /// Predict all quantiles of a multi-quantile (`reg:quantileerror`) model
+    ///
+    /// # Arguments
+    /// * `dmatrix` - Data to predict on
+    /// * `alphas` - The `quantile_alpha` values the model was trained with
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, DMatrix};
+    ///
+    /// let booster = Booster::load("quantile.json").unwrap();
+    /// let dmatrix = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+    /// let predictions = booster.predict_quantiles(&dmatrix, &[0.1, 0.5, 0.9]).unwrap();
+    /// let median = predictions.for_alpha(0.5).unwrap();
+    /// ```
+    pub fn predict_quantiles(
+        &self,
+        dmatrix: &DMatrix,
+        alphas: &[f64],
+    ) -> XGBoostResult<QuantilePredictions> {
+        let predictions = self.predict_dmatrix(dmatrix, PredictOptions::empty(), false)?;
+        let num_rows = dmatrix.num_rows()?;
+        if predictions.len() != num_rows * alphas.len() {
+            return Err(XGBoostError {
+                description: format!(
+                    "Model returned {} predictions for {} rows, expected {} quantiles per row",
+                    predictions.len(),
+                    num_rows,
+                    alphas.len()
+                ),
+            });
+        }
+        QuantilePredictions::new(predictions, alphas)
+    }
+
+    /// Get a model attribute
+    ///
+    /// # Arguments
+    /// * `key` - Attribute name
+    pub fn attr(&self, key: &str) -> XGBoostResult<Option<String>> {
+        let key_c_str = CString::new(key).map_err(|e| XGBoostError {
+            description: format!("Attribute name contains NUL byte: {}", e),
+        })?;
+        let mut out: *const c_char = ptr::null();
+        let mut success: i32 = 0;
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterGetAttr(self.handle, key_c_str.as_ptr(), &mut out, &mut success)
+        })?;
+        if success == 0 || out.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(
+            unsafe { CStr::from_ptr(out) }
+                .to_string_lossy()
+                .into_owned(),
+        ))
+    }
+
+    /// Set a model attribute; attributes are saved with the model
+    ///
+    /// # Arguments
+    /// * `key` - Attribute name
+    /// * `value` - Attribute value
+    pub fn set_attr(&mut self, key: &str, value: &str) -> XGBoostResult<()> {
+        let key_c_str = CString::new(key).map_err(|e| XGBoostError {
+            description: format!("Attribute name contains NUL byte: {}", e),
+        })?;
+        let value_c_str = CString::new(value).map_err(|e| XGBoostError {
+            description: format!("Attribute value contains NUL byte: {}", e),
+        })?;
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSetAttr(self.handle, key_c_str.as_ptr(), value_c_str.as_ptr())
+        })?;
+        if key == "best_iteration" {
+            self.read_best_iteration()?;
+        }
+        Ok(())
+    }
+
+    /// Remove a model attribute
+    pub fn remove_attr(&mut self, key: &str) -> XGBoostResult<()> {
+        let key_c_str = CString::new(key).map_err(|e| XGBoostError {
+            description: format!("Attribute name contains NUL byte: {}", e),
+        })?;
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSetAttr(self.handle, key_c_str.as_ptr(), ptr::null())
+        })?;
+        if key == "best_iteration" {
+            self.best_iteration = None;
+        }
+        Ok(())
+    }
+
+    /// Number of boosting rounds the model has been trained for (XGBoost ≥ 1.4)
+    #[cfg(xgboost_boosted_rounds)]
+    pub fn num_boosted_rounds(&self) -> XGBoostResult<usize> {
+        let mut out: i32 = 0;
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterBoostedRounds(self.handle, &mut out)
+        })?;
+        Ok(out as usize)
+    }
+
+    /// The best boosting round found by early stopping (`best_iteration` attribute)
+    pub fn best_iteration(&self) -> XGBoostResult<Option<usize>> {
+        Ok(self.best_iteration)
+    }
+
+    /// Whether predictions stop at [`Booster::best_iteration`] (the default)
+    ///
+    /// Models trained with early stopping keep the rounds after the best
+    /// one; by default they are ignored, as in the Python `XGBModel`
+    /// interface. Pass `false` to predict with all trees. Requires
+    /// XGBoost ≥ 1.4; older versions always use all trees.
+    pub fn set_use_best_iteration(&mut self, use_best_iteration: bool) {
+        self.use_best_iteration = use_best_iteration;
+    }
+
+    /// Predict inputs of more than `max_chunk_rows` rows in chunks of at
+    /// most that many rows (default `None`, all at once)
+    ///
+    /// Outputs are concatenated in row order, so results are unchanged,
+    /// but the copy XGBoost makes of the input and the converted f16 or
+    /// struct data held at a time are bounded by the chunk size rather
+    /// than the input. Applies to [`Booster::predict`],
+    /// [`Booster::predict_f16`], [`Booster::predict_with_missing`],
+    /// [`Booster::predict_with_base_margin`],
+    /// [`Booster::predict_sparse_rows`], [`Booster::predict_structs`] and
+    /// the methods built on them.
+    pub fn set_max_chunk_rows(&mut self, max_chunk_rows: Option<usize>) {
+        self.max_chunk_rows = max_chunk_rows.map(|rows| rows.max(1));
+    }
+
+    /// Get the number of features the model expects
+    ///
+    /// # Returns
+    /// The number of features
+    pub fn num_features(&self) -> XGBoostResult<usize> {
+        let mut out_num_features: u64 = 0;
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterGetNumFeature(self.handle, &mut out_num_features)
+        })?;
+
+        Ok(out_num_features as usize)
+    }
+
+    /// Feature names stored in the model, empty if it has none (XGBoost ≥ 1.5)
+    #[cfg(xgboost_feature_score)]
+    pub fn feature_names(&self) -> XGBoostResult<Vec<String>> {
+        let field = CString::new("feature_name").unwrap();
+        let mut len: u64 = 0;
+        let mut out: *mut *const c_char = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterGetStrFeatureInfo(self.handle, field.as_ptr(), &mut len, &mut out)
+        })?;
+        Ok(c_strings(out, len as usize))
+    }
+
+    /// Feature types stored in the model, empty if it has none (XGBoost ≥ 1.5)
+    ///
+    /// Set on the training data with [`DMatrix::set_feature_types`].
+    #[cfg(xgboost_feature_score)]
+    pub fn feature_types(&self) -> XGBoostResult<Vec<FeatureType>> {
+        let field = CString::new("feature_type").unwrap();
+        let mut len: u64 = 0;
+        let mut out: *mut *const c_char = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterGetStrFeatureInfo(self.handle, field.as_ptr(), &mut len, &mut out)
+        })?;
+        Ok(c_strings(out, len as usize)
+            .iter()
+            .map(|name| FeatureType::from_name(name))
+            .collect())
+    }
+
+    /// Importance score of every feature used by the model (XGBoost ≥ 1.5)
+    ///
+    /// Features are named by the model's feature names, or `f0`, `f1`, …
+    /// if it has none. Features that are never split on are omitted. Only
+    /// tree boosters are supported.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, ImportanceType};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// for (feature, gain) in booster.feature_importance(ImportanceType::Gain).unwrap() {
+    ///     println!("{}: {}", feature, gain);
+    /// }
+    /// ```
+    #[cfg(xgboost_feature_score)]
+    pub fn feature_importance(
+        &self,
+        importance_type: ImportanceType,
+    ) -> XGBoostResult<Vec<(String, f32)>> {
+        let config =
+            CString::new(format!("{{\"importance_type\": \"{}\"}}", importance_type)).unwrap();
+        let mut num_features: u64 = 0;
+        let mut features: *mut *const c_char = ptr::null_mut();
+        let mut dim: u64 = 0;
+        let mut shape: *const u64 = ptr::null();
+        let mut scores: *const f32 = ptr::null();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterFeatureScore(
+                self.handle,
+                config.as_ptr(),
+                &mut num_features,
+                &mut features,
+                &mut dim,
+                &mut shape,
+                &mut scores,
+            )
+        })?;
+        if dim != 1 {
+            return Err(XGBoostError {
+                description: format!(
+                    "Expected one importance score per feature, got a {}-dimensional result",
+                    dim
+                ),
+            });
+        }
+
+        let names = c_strings(features, num_features as usize);
+        let scores = if scores.is_null() || names.is_empty() {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(scores, names.len()) }
+        };
+        Ok(names.into_iter().zip(scores.iter().copied()).collect())
+    }
+
+    /// Select the device used for prediction
+    ///
+    /// Requesting a CUDA device checks that the linked libxgboost was built
+    /// with CUDA support and returns an error otherwise, instead of silently
+    /// falling back to the CPU. With XGBoost < 2.0 this sets `gpu_id` and the
+    /// `gpu_predictor`/`cpu_predictor` instead of `device`.
+    ///
+    /// # Arguments
+    /// * `device` - A [`Device`] or a device string such as `"cpu"` or `"cuda:0"`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, Device};
+    ///
+    /// let mut booster = Booster::load("model.json").unwrap();
+    /// booster.set_device("cuda:0").unwrap();
+    /// booster.set_device(Device::Cpu).unwrap();
+    /// ```
+    pub fn set_device<D>(&mut self, device: D) -> XGBoostResult<()>
+    where
+        D: TryInto<Device>,
+        D::Error: Into<XGBoostError>,
+    {
+        let device = device.try_into().map_err(Into::into)?;
+        if device.is_cuda() {
+            check_cuda_available()?;
+        }
+
+        #[cfg(xgboost_device_param)]
+        {
+            self.set_param("device", &device.to_string())
+        }
+        #[cfg(not(xgboost_device_param))]
+        {
+            match device {
+                Device::Cpu => self.set_param("predictor", "cpu_predictor"),
+                Device::Cuda(ordinal) => {
+                    self.set_param("gpu_id", &ordinal.to_string())?;
+                    self.set_param("predictor", "gpu_predictor")
+                }
+            }
+        }
+    }
+
+    /// Set a single training or prediction parameter
+    ///
+    /// XGBoost accepts unknown parameter names and at most warns that they
+    /// are unused, so a typo silently trains with the default. Names are
+    /// therefore checked against XGBoost's documented parameters, and an
+    /// unknown name is an error that suggests the closest known one. Use
+    /// [`set_param_unchecked`](Self::set_param_unchecked) for parameters of
+    /// plugins or newer XGBoost versions.
+    ///
+    /// # Arguments
+    /// * `name` - Parameter name, e.g. `"eta"`
+    /// * `value` - Parameter value
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let mut booster = Booster::load("model.json").unwrap();
+    /// booster.set_param("nthread", "4").unwrap();
+    ///
+    /// let error = booster.set_param("learning_rte", "0.1").unwrap_err();
+    /// assert!(error.description.contains("did you mean 'learning_rate'"));
+    /// ```
+    pub fn set_param(&mut self, name: &str, value: &str) -> XGBoostResult<()> {
+        check_param_name(name)?;
+        self.set_param_unchecked(name, value)
+    }
+
+    /// Set a parameter without checking its name
+    pub fn set_param_unchecked(&mut self, name: &str, value: &str) -> XGBoostResult<()> {
+        let name_c_str = CString::new(name).map_err(|e| XGBoostError {
+            description: format!("Parameter name contains NUL byte: {}", e),
+        })?;
+        let value_c_str = CString::new(value).map_err(|e| XGBoostError {
+            description: format!("Parameter value contains NUL byte: {}", e),
+        })?;
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSetParam(self.handle, name_c_str.as_ptr(), value_c_str.as_ptr())
+        })
+    }
+
+    /// Apply a set of typed parameters, in order
+    ///
+    /// # Arguments
+    /// * `params` - Parameters to apply
+    pub fn set_params(&mut self, params: &BoosterParams) -> XGBoostResult<()> {
+        params.iter().try_for_each(|(name, value)| {
+            if params.is_unchecked(name) {
+                self.set_param_unchecked(name, value)
+            } else {
+                self.set_param(name, value)
+            }
+        })
+    }
+
+    /// Run `f` with XGBoost limited to `nthread` threads on the calling
+    /// thread
+    ///
+    /// Lets a latency-critical caller pin scoring to a few threads without
+    /// changing the booster, so it works through a shared `&Booster` (e.g.
+    /// an `Arc<Booster>` from [`WatchedBooster::current`](crate::WatchedBooster::current)).
+    /// The count is set in XGBoost's global configuration, which is kept per
+    /// thread, so other threads are unaffected; the previous value is
+    /// restored afterwards, even if `f` fails or panics. Requires
+    /// XGBoost ≥ 2.0.
+    ///
+    /// The booster's own `nthread` takes precedence, so this is an error on
+    /// a booster that sets it.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, PredictOptions};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let predictions = booster
+    ///     .with_nthread(1, |b| b.predict(&data, 2, 2, PredictOptions::empty(), false))
+    ///     .unwrap();
+    /// ```
+    #[cfg(xgboost_device_param)]
+    pub fn with_nthread<T>(
+        &self,
+        nthread: usize,
+        f: impl FnOnce(&Self) -> XGBoostResult<T>,
+    ) -> XGBoostResult<T> {
+        if let Some(own) = self
+            .generic_param("nthread")?
+            .and_then(|value| value.parse::<i64>().ok())
+            .filter(|&own| own > 0)
+        {
+            return Err(XGBoostError {
+                description: format!(
+                    "The booster sets nthread={}, which overrides with_nthread",
+                    own
+                ),
+            });
+        }
+        let previous = global_nthread()?;
+        set_global_nthread(nthread as i64)?;
+        // restores the count when dropped, including on panic
+        struct Restore(i64);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let _ = set_global_nthread(self.0);
+            }
+        }
+        let _restore = Restore(previous);
+        f(self)
+    }
+
+    /// Value of a `learner.generic_param` entry of the booster's JSON config
+    #[cfg(xgboost_device_param)]
+    fn generic_param(&self, name: &str) -> XGBoostResult<Option<String>> {
+        let mut out_len: u64 = 0;
+        let mut out: *const c_char = ptr::null();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSaveJsonConfig(self.handle, &mut out_len, &mut out)
+        })?;
+        if out.is_null() {
+            return Ok(None);
+        }
+        let config = crate::json::parse_json(unsafe { CStr::from_ptr(out) }.to_bytes())?;
+        Ok(config
+            .get("learner")
+            .and_then(|learner| learner.get("generic_param"))
+            .and_then(|params| params.get(name))
+            .and_then(|value| value.as_str())
+            .map(str::to_string))
+    }
+
+    /// Run one boosting iteration on the training data
+    ///
+    /// # Arguments
+    /// * `dtrain` - Training data (with labels)
+    /// * `iteration` - Index of the current iteration, starting at 0
+    pub fn update(&mut self, dtrain: &DMatrix, iteration: usize) -> XGBoostResult<()> {
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterUpdateOneIter(self.handle, iteration as i32, dtrain.handle())
+        })
+    }
+
+    /// Evaluate the model on a dataset with the configured `eval_metric`s
+    ///
+    /// # Arguments
+    /// * `dmatrix` - Data to evaluate on (with labels)
+    /// * `name` - Name of the dataset, as it appears in XGBoost's output
+    ///
+    /// # Returns
+    /// Metric values keyed by metric name (e.g. `"rmse"`)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, DMatrix};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let mut dtest = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+    /// dtest.set_label(&[0.0, 1.0]).unwrap();
+    /// let metrics = booster.eval(&dtest, "test").unwrap();
+    /// println!("{:?}", metrics.get("logloss"));
+    /// ```
+    pub fn eval(&self, dmatrix: &DMatrix, name: &str) -> XGBoostResult<BTreeMap<String, f64>> {
+        let mut results = self.eval_set(&[(dmatrix, name)])?;
+        Ok(results.remove(name).unwrap_or_default())
+    }
+
+    /// Evaluate the model on several datasets at once
+    ///
+    /// # Arguments
+    /// * `evals` - Pairs of dataset and name
+    ///
+    /// # Returns
+    /// Metric values keyed by dataset name, then metric name
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, DMatrix};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+    /// dtrain.set_label(&[0.0, 1.0]).unwrap();
+    /// let mut dtest = DMatrix::from_dense(&[5.0, 6.0], 1, 2).unwrap();
+    /// dtest.set_label(&[1.0]).unwrap();
+    /// let results = booster.eval_set(&[(&dtrain, "train"), (&dtest, "test")]).unwrap();
+    /// let test_rmse = results["test"]["rmse"];
+    /// ```
+    pub fn eval_set(
+        &self,
+        evals: &[(&DMatrix, &str)],
+    ) -> XGBoostResult<BTreeMap<String, BTreeMap<String, f64>>> {
+        let names: Vec<&str> = evals.iter().map(|(_, name)| *name).collect();
+        let raw = self.eval_one_iter(evals, 0)?;
+        parse_eval_result(&raw, &names)
+    }
+
+    /// Evaluate the model on several datasets, as one record per dataset
+    /// and metric in XGBoost's order
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, DMatrix};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let mut dtest = DMatrix::from_dense(&[5.0, 6.0], 1, 2).unwrap();
+    /// dtest.set_label(&[1.0]).unwrap();
+    /// for record in booster.eval_records(&[(&dtest, "test")]).unwrap() {
+    ///     println!("{} {} {}", record.dataset, record.metric, record.value);
+    /// }
+    /// ```
+    pub fn eval_records(&self, evals: &[(&DMatrix, &str)]) -> XGBoostResult<Vec<EvalRecord>> {
+        let names: Vec<&str> = evals.iter().map(|(_, name)| *name).collect();
+        let raw = self.eval_one_iter(evals, 0)?;
+        parse_eval_line(&raw, &names)
+    }
+
+    /// Raw evaluation string of `XGBoosterEvalOneIter`, e.g. `[3]\ttrain-rmse:0.5`
+    pub(crate) fn eval_one_iter(
+        &self,
+        evals: &[(&DMatrix, &str)],
+        iteration: usize,
+    ) -> XGBoostResult<String> {
+        if evals.is_empty() {
+            return Err(XGBoostError {
+                description: "No datasets given for evaluation".to_string(),
+            });
+        }
+        let names = evals
+            .iter()
+            .map(|(_, name)| {
+                CString::new(*name).map_err(|e| XGBoostError {
+                    description: format!("Dataset name contains NUL byte: {}", e),
+                })
+            })
+            .collect::<XGBoostResult<Vec<_>>>()?;
+        let mut name_ptrs: Vec<*const c_char> = names.iter().map(|n| n.as_ptr()).collect();
+        let mut handles: Vec<sys::DMatrixHandle> = evals.iter().map(|(d, _)| d.handle()).collect();
+        let mut out: *const c_char = ptr::null();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterEvalOneIter(
+                self.handle,
+                iteration as i32,
+                handles.as_mut_ptr(),
+                name_ptrs.as_mut_ptr(),
+                evals.len() as u64,
+                &mut out,
+            )
+        })?;
+        if out.is_null() {
+            return Err(XGBoostError {
+                description: "XGBoost returned null evaluation result".to_string(),
+            });
+        }
+        Ok(unsafe { CStr::from_ptr(out) }
+            .to_string_lossy()
+            .into_owned())
+    }
+
+    /// Save the model to a file
+    ///
+    /// Paths ending in `.gz` or `.zst` are compressed (XGBoost ≥ 1.6), with
+    /// the format chosen by the extension before it, e.g. `model.ubj.zst`.
+    ///
+    /// # Arguments
+    /// * `path` - Path where to save the model
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// booster.save("model_copy.json").unwrap();
+    /// ```
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> XGBoostResult<()> {
+        if let Some(compression) = Compression::from_path(path.as_ref()) {
+            #[cfg(xgboost_save_to_buffer)]
+            return self.save_compressed(path, compression);
+            #[cfg(not(xgboost_save_to_buffer))]
+            return Err(XGBoostError {
+                description: format!(
+                    "Saving {:?} compressed models requires XGBoost ≥ 1.6",
+                    compression
+                ),
+            });
+        }
+        let path_str = path.as_ref().to_str().ok_or_else(|| XGBoostError {
+            description: "Path contains invalid UTF-8 characters".to_string(),
+        })?;
+        let path_c_str = CString::new(path_str).map_err(|e| XGBoostError {
+            description: format!("Path contains NUL byte: {}", e),
+        })?;
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSaveModel(self.handle, path_c_str.as_ptr())
+        })
+    }
+
+    /// Save the model to a compressed file
+    ///
+    /// The serialization format is chosen by the extension of `path`
+    /// without a compression extension: UBJSON for `model.ubj` or
+    /// `model.ubj.gz`, JSON otherwise. Requires XGBoost ≥ 1.6 and the
+    /// feature of the compression.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, Compression};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// booster.save_compressed("model.ubj.zst", Compression::Zstd).unwrap();
+    /// ```
+    #[cfg(xgboost_save_to_buffer)]
+    pub fn save_compressed<P: AsRef<Path>>(
+        &self,
+        path: P,
+        compression: Compression,
+    ) -> XGBoostResult<()> {
+        let inner = compression::uncompressed_path(path.as_ref());
+        let format = if inner
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("ubj"))
+        {
+            ModelFormat::Ubjson
+        } else {
+            ModelFormat::Json
+        };
+        let bytes = compression::compress(&self.save_to_buffer(format)?, compression)?;
+        fs::write(path.as_ref(), bytes).map_err(|e| XGBoostError {
+            description: format!(
+                "Failed to write model file {}: {}",
+                path.as_ref().display(),
+                e
+            ),
+        })
+    }
+
+    /// Save the model encrypted with the key from `key_provider`
+    ///
+    /// The model is never written unencrypted. Its format and compression
+    /// follow the extensions before `.enc`, e.g. `model.ubj.zst.enc`. Requires XGBoost ≥ 1.6.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let key = [7u8; 32]; // from a secret store in practice
+    /// let booster = Booster::load("model.json").unwrap();
+    /// booster.save_encrypted("model.json.enc", &key).unwrap();
+    /// let booster = Booster::load_encrypted("model.json.enc", &key).unwrap();
+    /// ```
+    #[cfg(all(feature = "encryption", xgboost_save_to_buffer))]
+    pub fn save_encrypted<P: AsRef<Path>, K: KeyProvider + ?Sized>(
+        &self,
+        path: P,
+        key_provider: &K,
+    ) -> XGBoostResult<()> {
+        let inner = encrypted_path(path.as_ref());
+        let compression = Compression::from_path(&inner);
+        let inner = compression::uncompressed_path(&inner);
+        let format = if inner
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("ubj"))
+        {
+            ModelFormat::Ubjson
+        } else {
+            ModelFormat::Json
+        };
+        let mut model = self.save_to_buffer(format)?;
+        if let Some(compression) = compression {
+            model = compression::compress(&model, compression)?;
+        }
+        let key = key_provider.key(path.as_ref())?;
+        let encrypted = encryption::encrypt(&model, &key)?;
+        fs::write(path.as_ref(), encrypted).map_err(|e| XGBoostError {
+            description: format!(
+                "Failed to write model file {}: {}",
+                path.as_ref().display(),
+                e
+            ),
+        })
+    }
+
+    /// Save the model to a memory buffer
+    ///
+    /// Requires XGBoost ≥ 1.6.
+    ///
+    /// # Arguments
+    /// * `format` - Serialization format of the returned bytes
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, ModelFormat};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let bytes = booster.save_to_buffer(ModelFormat::Ubjson).unwrap();
+    /// ```
+    #[cfg(xgboost_save_to_buffer)]
+    pub fn save_to_buffer(&self, format: ModelFormat) -> XGBoostResult<Vec<u8>> {
+        let config = CString::new(format!("{{\"format\": \"{}\"}}", format.as_str())).unwrap();
+        let mut out_len: u64 = 0;
+        let mut out_dptr: *const c_char = ptr::null();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSaveModelToBuffer(
+                self.handle,
+                config.as_ptr(),
+                &mut out_len,
+                &mut out_dptr,
+            )
+        })?;
+
+        if out_dptr.is_null() {
+            return Err(XGBoostError {
+                description: "XGBoost returned null model buffer".to_string(),
+            });
+        }
+
+        // The buffer is owned by XGBoost and only valid until the next call
+        let bytes =
+            unsafe { std::slice::from_raw_parts(out_dptr as *const u8, out_len as usize).to_vec() };
+        Ok(bytes)
+    }
+
+    /// Parse the model structure out of its UBJSON serialization.
+    #[cfg(xgboost_save_to_buffer)]
+    pub(crate) fn learner(&self) -> XGBoostResult<Learner> {
+        let buffer = self.save_to_buffer(ModelFormat::Ubjson)?;
+        Learner::from_document(&json::parse_model_document(&buffer)?)
+    }
+
+    crate::backend::shared_methods!(
+        feature_names: xgboost_feature_score,
+        save_to_buffer: xgboost_save_to_buffer
+    );
+}
+
+impl Backend for Booster {
+    fn predict_dense(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.predict(data, num_rows, num_features, options, false)
+    }
+
+    fn num_features(&self) -> XGBoostResult<usize> {
+        Booster::num_features(self)
+    }
+
+    #[cfg(xgboost_feature_score)]
+    fn model_feature_names(&self) -> XGBoostResult<Vec<String>> {
+        self.feature_names()
+    }
+
+    #[cfg(not(xgboost_feature_score))]
+    fn model_feature_names(&self) -> XGBoostResult<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn attr(&self, key: &str) -> XGBoostResult<Option<String>> {
+        Booster::attr(self, key)
+    }
+}
+
+/// Copy an array of C strings owned by XGBoost.
+#[cfg(xgboost_feature_score)]
+fn c_strings(array: *mut *const c_char, len: usize) -> Vec<String> {
+    if array.is_null() {
+        return Vec::new();
+    }
+    unsafe { std::slice::from_raw_parts(array, len) }
+        .iter()
+        .map(|&s| unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Check that libxgboost was compiled with CUDA support.
+#[cfg(xgboost_build_info)]
+fn check_cuda_available() -> XGBoostResult<()> {
+    if !build_info::build_info()?.use_cuda {
+        return Err(XGBoostError {
+            description: "A CUDA device was requested but the linked XGBoost library was not \
+                          built with CUDA support (enable the `cuda` feature or point \
+                          XGBOOST_LIB_DIR at a GPU-enabled build)"
+                .to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// XGBoost < 1.6 has no build info; it rejects `gpu_predictor` itself when
+/// CUDA support is missing.
+#[cfg(not(xgboost_build_info))]
+fn check_cuda_available() -> XGBoostResult<()> {
+    Ok(())
+}
+
+/// Prediction config of the inplace prediction functions (`XGBoosterPredictFrom*`)
+#[cfg(any(feature = "cuda", xgboost_array_interface))]
+fn inplace_predict_config(options: PredictOptions, training: bool) -> XGBoostResult<PredictConfig> {
+    if !options.is_empty() && options != PredictOptions::OUTPUT_MARGIN {
+        return Err(XGBoostError {
+            description: format!(
+                "Unsupported options {:?} for inplace prediction: only OUTPUT_MARGIN is allowed",
+                options
+            ),
+        });
+    }
+    Ok(PredictConfig::new()
+        .predict_type(PredictType::from_options(options))
+        .training(training))
+}
+
+/// One metric value of an evaluation, from [`parse_eval_line`] or
+/// [`Booster::eval_records`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalRecord {
+    /// Name of the evaluated dataset, e.g. `"train"`
+    pub dataset: String,
+    /// Name of the metric, e.g. `"rmse"` or `"ndcg@10"`
+    pub metric: String,
+    /// Value of the metric
+    pub value: f64,
+}
+
+/// Parse an XGBoost evaluation line such as `[0]\ttrain-rmse:0.1\ttest-rmse:0.2`
+/// into one record per metric, in the order of the line
+///
+/// `datasets` are the names of the evaluated datasets: metric names may
+/// themselves contain `-` (`aft-nloglik`), so entries are matched against
+/// them rather than split on the dash. The `[iteration]` prefix is
+/// optional. Use it on the lines of [`Trainer::eval_log`](crate::Trainer::eval_log)
+/// or of logs written by other XGBoost bindings.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::parse_eval_line;
+///
+/// let records = parse_eval_line("[3]\ttrain-rmse:0.48\ttest-aft-nloglik:1.2", &["train", "test"])
+///     .unwrap();
+/// assert_eq!(records[1].dataset, "test");
+/// assert_eq!(records[1].metric, "aft-nloglik");
+/// assert_eq!(records[1].value, 1.2);
+/// ```
+pub fn parse_eval_line(line: &str, datasets: &[&str]) -> XGBoostResult<Vec<EvalRecord>> {
+    line.split_whitespace()
+        .filter(|e| !e.starts_with('['))
+        .map(|entry| {
+            let invalid = || XGBoostError {
+                description: format!("Unexpected evaluation output '{}'", entry),
+            };
+            let (key, value) = entry.rsplit_once(':').ok_or_else(invalid)?;
+            let value: f64 = value.parse().map_err(|_| invalid())?;
+            let (dataset, metric) = datasets
+                .iter()
+                .filter_map(|name| Some((*name, key.strip_prefix(name)?.strip_prefix('-')?)))
+                .max_by_key(|(name, _)| name.len())
+                .ok_or_else(invalid)?;
+            Ok(EvalRecord {
+                dataset: dataset.to_string(),
+                metric: metric.to_string(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Parse `[0]\ttrain-rmse:0.1\ttest-rmse:0.2` into dataset → metric → value.
+pub(crate) fn parse_eval_result(
+    raw: &str,
+    names: &[&str],
+) -> XGBoostResult<BTreeMap<String, BTreeMap<String, f64>>> {
+    let mut results: BTreeMap<String, BTreeMap<String, f64>> = names
+        .iter()
+        .map(|name| (name.to_string(), BTreeMap::new()))
+        .collect();
+    for record in parse_eval_line(raw, names)? {
+        results
+            .entry(record.dataset)
+            .or_default()
+            .insert(record.metric, record.value);
+    }
+    Ok(results)
+}
+
+/// `nthread` of XGBoost's global configuration for the calling thread
+#[cfg(xgboost_device_param)]
+fn global_nthread() -> XGBoostResult<i64> {
+    let mut out: *const c_char = ptr::null();
+    XGBoostError::check_return_value(unsafe { sys::XGBGetGlobalConfig(&mut out) })?;
+    if out.is_null() {
+        return Ok(0);
+    }
+    let config = crate::json::parse_json(unsafe { CStr::from_ptr(out) }.to_bytes())?;
+    Ok(config
+        .get("nthread")
+        .and_then(|value| value.as_f64())
+        .map_or(0, |n| n as i64))
+}
+
+#[cfg(xgboost_device_param)]
+fn set_global_nthread(nthread: i64) -> XGBoostResult<()> {
+    let config = CString::new(format!("{{\"nthread\": {}}}", nthread)).unwrap();
+    XGBoostError::check_return_value(unsafe { sys::XGBSetGlobalConfig(config.as_ptr()) })
 }
 
 impl Drop for Booster {
@@ -0,0 +1,137 @@
+use crate::date::UtcDateTime;
+use crate::error::{XGBoostError, XGBoostResult};
+#[cfg(feature = "native")]
+use crate::label_encoder::escape;
+use crate::label_encoder::split_escaped;
+use crate::Booster;
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+const TRAINED_AT: &str = "model_card_trained_at";
+const DATASET_HASH: &str = "model_card_dataset_hash";
+const GIT_COMMIT: &str = "model_card_git_commit";
+const METRICS: &str = "model_card_metrics";
+
+/// Provenance of a trained model, stored in its attributes so it travels
+/// with the model file.
+///
+/// Each field is a separate `model_card_*` attribute, readable from any
+/// XGBoost binding.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{Booster, ModelCard};
+///
+/// let booster = Booster::load("model.json").unwrap();
+/// if let Some(card) = ModelCard::from_booster(&booster).unwrap() {
+///     println!(
+///         "trained {:?} from commit {:?}, test auc {:?}",
+///         card.trained_at,
+///         card.git_commit,
+///         card.metrics.get("test-auc")
+///     );
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelCard {
+    /// When the model was trained, as an RFC 3339 UTC timestamp
+    pub trained_at: Option<String>,
+    /// Fingerprint of the training data, see [`ModelCard::hash_dataset`]
+    pub dataset_hash: Option<String>,
+    /// Commit of the training code
+    pub git_commit: Option<String>,
+    /// Evaluation results, e.g. `test-auc`
+    pub metrics: BTreeMap<String, f64>,
+}
+
+impl ModelCard {
+    /// A card stamped with the current time
+    pub fn new() -> Self {
+        ModelCard {
+            trained_at: Some(UtcDateTime::new(SystemTime::now()).to_rfc3339()),
+            ..Self::default()
+        }
+    }
+
+    /// A 64-bit FNV-1a fingerprint (16 hex digits) of training data and
+    /// labels, to tell whether two models were trained on the same data.
+    /// Not a cryptographic hash.
+    pub fn hash_dataset(data: &[f32], labels: &[f32]) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut update = |bytes: [u8; 8]| {
+            for byte in bytes {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        };
+        // the lengths separate the data from the labels
+        update((data.len() as u64).to_le_bytes());
+        for value in data.iter().chain(labels) {
+            update(u64::from(value.to_bits()).to_le_bytes());
+        }
+        update((labels.len() as u64).to_le_bytes());
+        format!("{:016x}", hash)
+    }
+
+    /// Add the results of `Booster::eval_set` as `<dataset>-<metric>`
+    /// metrics
+    pub fn add_eval_results(&mut self, results: &BTreeMap<String, BTreeMap<String, f64>>) {
+        for (dataset, metrics) in results {
+            for (metric, value) in metrics {
+                self.metrics
+                    .insert(format!("{}-{}", dataset, metric), *value);
+            }
+        }
+    }
+
+    /// Store the card as model attributes, saved with the model
+    #[cfg(feature = "native")]
+    pub fn save_to(&self, booster: &mut Booster) -> XGBoostResult<()> {
+        for (key, value) in [
+            (TRAINED_AT, &self.trained_at),
+            (DATASET_HASH, &self.dataset_hash),
+            (GIT_COMMIT, &self.git_commit),
+        ] {
+            if let Some(value) = value {
+                booster.set_attr(key, value)?;
+            }
+        }
+        if !self.metrics.is_empty() {
+            let fields: Vec<String> = self
+                .metrics
+                .iter()
+                .flat_map(|(name, value)| [escape(name), value.to_string()])
+                .collect();
+            booster.set_attr(METRICS, &fields.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// Restore the card stored with `save_to`, or `None` if the model has
+    /// none
+    pub fn from_booster(booster: &Booster) -> XGBoostResult<Option<Self>> {
+        let mut card = ModelCard {
+            trained_at: booster.attr(TRAINED_AT)?,
+            dataset_hash: booster.attr(DATASET_HASH)?,
+            git_commit: booster.attr(GIT_COMMIT)?,
+            metrics: BTreeMap::new(),
+        };
+        if let Some(metrics) = booster.attr(METRICS)? {
+            let invalid = || XGBoostError {
+                description: "Invalid model card metrics stored in the model".to_string(),
+            };
+            let fields = split_escaped(&metrics);
+            if fields.len() % 2 != 0 {
+                return Err(invalid());
+            }
+            for pair in fields.chunks(2) {
+                let value = pair[1].parse().map_err(|_| invalid())?;
+                card.metrics.insert(pair[0].clone(), value);
+            }
+        }
+        if card == ModelCard::default() {
+            return Ok(None);
+        }
+        Ok(Some(card))
+    }
+}
@@ -0,0 +1,348 @@
+//! Conversion of tree ensembles into ONNX `TreeEnsembleRegressor` /
+//! `TreeEnsembleClassifier` models (`ai.onnx.ml` domain).
+//!
+//! The protobuf messages are encoded by hand; only the handful of fields of
+//! `onnx.proto` needed to describe a single-operator graph are written.
+
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::gbm::{GradientBooster, Learner};
+
+const ONNX_IR_VERSION: u64 = 7;
+const ONNX_OPSET: u64 = 13;
+const ONNX_ML_OPSET: u64 = 1;
+
+// TensorProto.DataType
+const TENSOR_FLOAT: u64 = 1;
+const TENSOR_INT64: u64 = 7;
+
+// AttributeProto.AttributeType
+const ATTR_INT: u64 = 2;
+const ATTR_STRING: u64 = 3;
+const ATTR_FLOATS: u64 = 6;
+const ATTR_INTS: u64 = 7;
+const ATTR_STRINGS: u64 = 8;
+
+/// Minimal protobuf message writer.
+#[derive(Default)]
+struct Message(Vec<u8>);
+
+impl Message {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    fn key(&mut self, field: u32, wire_type: u8) {
+        self.varint(((field as u64) << 3) | wire_type as u64);
+    }
+
+    fn uint(&mut self, field: u32, value: u64) -> &mut Self {
+        self.key(field, 0);
+        self.varint(value);
+        self
+    }
+
+    fn int(&mut self, field: u32, value: i64) -> &mut Self {
+        self.uint(field, value as u64)
+    }
+
+    fn bytes(&mut self, field: u32, value: &[u8]) -> &mut Self {
+        self.key(field, 2);
+        self.varint(value.len() as u64);
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    fn string(&mut self, field: u32, value: &str) -> &mut Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    fn message(&mut self, field: u32, value: &Message) -> &mut Self {
+        self.bytes(field, &value.0)
+    }
+
+    fn packed_ints(&mut self, field: u32, values: &[i64]) -> &mut Self {
+        let mut packed = Message::default();
+        values.iter().for_each(|v| packed.varint(*v as u64));
+        self.bytes(field, &packed.0)
+    }
+
+    fn packed_floats(&mut self, field: u32, values: &[f32]) -> &mut Self {
+        let packed: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        self.bytes(field, &packed)
+    }
+}
+
+// AttributeProto builders
+fn attr_int(name: &str, value: i64) -> Message {
+    let mut m = Message::default();
+    m.string(1, name).int(3, value).uint(20, ATTR_INT);
+    m
+}
+
+fn attr_string(name: &str, value: &str) -> Message {
+    let mut m = Message::default();
+    m.string(1, name).string(4, value).uint(20, ATTR_STRING);
+    m
+}
+
+fn attr_ints(name: &str, values: &[i64]) -> Message {
+    let mut m = Message::default();
+    m.string(1, name).packed_ints(8, values).uint(20, ATTR_INTS);
+    m
+}
+
+fn attr_floats(name: &str, values: &[f32]) -> Message {
+    let mut m = Message::default();
+    m.string(1, name)
+        .packed_floats(7, values)
+        .uint(20, ATTR_FLOATS);
+    m
+}
+
+fn attr_strings(name: &str, values: &[&str]) -> Message {
+    let mut m = Message::default();
+    m.string(1, name);
+    for v in values {
+        m.string(9, v);
+    }
+    m.uint(20, ATTR_STRINGS);
+    m
+}
+
+/// ValueInfoProto for a tensor of shape `[N, width]` (or `[N]` without a width).
+fn value_info(name: &str, elem_type: u64, width: Option<usize>) -> Message {
+    let mut shape = Message::default();
+    let mut batch = Message::default();
+    batch.string(2, "N");
+    shape.message(1, &batch);
+    if let Some(width) = width {
+        let mut dim = Message::default();
+        dim.int(1, width as i64);
+        shape.message(1, &dim);
+    }
+    let mut tensor = Message::default();
+    tensor.uint(1, elem_type).message(2, &shape);
+    let mut type_proto = Message::default();
+    type_proto.message(1, &tensor);
+    let mut info = Message::default();
+    info.string(1, name).message(2, &type_proto);
+    info
+}
+
+fn node(
+    op_type: &str,
+    domain: &str,
+    inputs: &[&str],
+    outputs: &[&str],
+    attributes: &[Message],
+) -> Message {
+    let mut m = Message::default();
+    for input in inputs {
+        m.string(1, input);
+    }
+    for output in outputs {
+        m.string(2, output);
+    }
+    m.string(3, op_type).string(4, op_type);
+    for attribute in attributes {
+        m.message(5, attribute);
+    }
+    m.string(7, domain);
+    m
+}
+
+/// How the model output is produced from the raw ensemble margin.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputKind {
+    /// Regressor with the given `post_transform`.
+    Regression(&'static str),
+    /// Regressor followed by an `Exp` node.
+    ExpRegression,
+    /// Classifier with the given `post_transform`.
+    Classification(&'static str),
+}
+
+fn output_kind(objective: &str) -> XGBoostResult<OutputKind> {
+    Ok(match objective {
+        "binary:logistic" => OutputKind::Classification("LOGISTIC"),
+        "multi:softprob" | "multi:softmax" => OutputKind::Classification("SOFTMAX"),
+        "reg:logistic" => OutputKind::Regression("LOGISTIC"),
+        "count:poisson" | "reg:gamma" | "reg:tweedie" | "survival:cox" | "survival:aft" => {
+            OutputKind::ExpRegression
+        }
+        "binary:hinge" => {
+            return Err(XGBoostError {
+                description: "ONNX export does not support the binary:hinge objective".to_string(),
+            })
+        }
+        _ => OutputKind::Regression("NONE"),
+    })
+}
+
+/// Encode a tree model as a serialized ONNX `ModelProto`.
+pub(crate) fn to_onnx(learner: &Learner) -> XGBoostResult<Vec<u8>> {
+    let ensemble = match &learner.booster {
+        GradientBooster::Tree(ensemble) => ensemble,
+        GradientBooster::Linear(_) => {
+            return Err(XGBoostError {
+                description: "ONNX export only supports tree boosters (gbtree/dart)".to_string(),
+            })
+        }
+    };
+    let kind = output_kind(&learner.objective.name)?;
+
+    let mut tree_ids = Vec::new();
+    let mut node_ids = Vec::new();
+    let mut feature_ids = Vec::new();
+    let mut values = Vec::new();
+    let mut modes = Vec::new();
+    let mut true_ids = Vec::new();
+    let mut false_ids = Vec::new();
+    let mut missing_tracks_true = Vec::new();
+    let mut leaf_tree_ids = Vec::new();
+    let mut leaf_node_ids = Vec::new();
+    let mut leaf_targets = Vec::new();
+    let mut leaf_weights = Vec::new();
+
+    for (tid, ((tree, group), weight)) in ensemble
+        .trees
+        .iter()
+        .zip(&ensemble.tree_groups)
+        .zip(&ensemble.tree_weights)
+        .enumerate()
+    {
+        for (nid, n) in tree.nodes.iter().enumerate() {
+            if n.categories.is_some() {
+                return Err(XGBoostError {
+                    description: "ONNX export does not support categorical splits".to_string(),
+                });
+            }
+            tree_ids.push(tid as i64);
+            node_ids.push(nid as i64);
+            if n.is_leaf() {
+                feature_ids.push(0);
+                values.push(0.0);
+                modes.push("LEAF");
+                true_ids.push(0);
+                false_ids.push(0);
+                missing_tracks_true.push(0);
+
+                leaf_tree_ids.push(tid as i64);
+                leaf_node_ids.push(nid as i64);
+                leaf_targets.push(*group as i64);
+                leaf_weights.push(n.split_condition * weight);
+            } else {
+                feature_ids.push(n.split_index as i64);
+                values.push(n.split_condition);
+                modes.push("BRANCH_LT");
+                true_ids.push(n.left as i64);
+                false_ids.push(n.right as i64);
+                missing_tracks_true.push(n.default_left as i64);
+            }
+        }
+    }
+
+    let mut attributes = vec![
+        attr_ints("nodes_treeids", &tree_ids),
+        attr_ints("nodes_nodeids", &node_ids),
+        attr_ints("nodes_featureids", &feature_ids),
+        attr_floats("nodes_values", &values),
+        attr_strings("nodes_modes", &modes),
+        attr_ints("nodes_truenodeids", &true_ids),
+        attr_ints("nodes_falsenodeids", &false_ids),
+        attr_ints("nodes_missing_value_tracks_true", &missing_tracks_true),
+    ];
+
+    let num_feature = learner.num_feature;
+    let num_groups = learner.num_groups;
+    let mut graph = Message::default();
+    graph.string(2, "xgboost");
+
+    match kind {
+        OutputKind::Classification(post_transform) => {
+            // Binary models have a single margin; ONNX expands it to two probabilities.
+            let num_classes = num_groups.max(2);
+            let labels: Vec<i64> = (0..num_classes as i64).collect();
+            attributes.extend([
+                attr_ints("class_treeids", &leaf_tree_ids),
+                attr_ints("class_nodeids", &leaf_node_ids),
+                attr_ints("class_ids", &leaf_targets),
+                attr_floats("class_weights", &leaf_weights),
+                attr_ints("classlabels_int64s", &labels),
+                attr_floats("base_values", &learner.base_margin),
+                attr_string("post_transform", post_transform),
+            ]);
+            graph.message(
+                1,
+                &node(
+                    "TreeEnsembleClassifier",
+                    "ai.onnx.ml",
+                    &["input"],
+                    &["label", "probabilities"],
+                    &attributes,
+                ),
+            );
+            graph.message(11, &value_info("input", TENSOR_FLOAT, Some(num_feature)));
+            graph.message(12, &value_info("label", TENSOR_INT64, None));
+            graph.message(
+                12,
+                &value_info("probabilities", TENSOR_FLOAT, Some(num_classes)),
+            );
+        }
+        OutputKind::Regression(_) | OutputKind::ExpRegression => {
+            let post_transform = match kind {
+                OutputKind::Regression(t) => t,
+                _ => "NONE",
+            };
+            attributes.extend([
+                attr_int("n_targets", num_groups as i64),
+                attr_ints("target_treeids", &leaf_tree_ids),
+                attr_ints("target_nodeids", &leaf_node_ids),
+                attr_ints("target_ids", &leaf_targets),
+                attr_floats("target_weights", &leaf_weights),
+                attr_floats("base_values", &learner.base_margin),
+                attr_string("aggregate_function", "SUM"),
+                attr_string("post_transform", post_transform),
+            ]);
+            let ensemble_output = if kind == OutputKind::ExpRegression {
+                "margin"
+            } else {
+                "variable"
+            };
+            graph.message(
+                1,
+                &node(
+                    "TreeEnsembleRegressor",
+                    "ai.onnx.ml",
+                    &["input"],
+                    &[ensemble_output],
+                    &attributes,
+                ),
+            );
+            if kind == OutputKind::ExpRegression {
+                graph.message(1, &node("Exp", "", &["margin"], &["variable"], &[]));
+            }
+            graph.message(11, &value_info("input", TENSOR_FLOAT, Some(num_feature)));
+            graph.message(12, &value_info("variable", TENSOR_FLOAT, Some(num_groups)));
+        }
+    }
+
+    let mut onnx_opset = Message::default();
+    onnx_opset.string(1, "").uint(2, ONNX_OPSET);
+    let mut ml_opset = Message::default();
+    ml_opset.string(1, "ai.onnx.ml").uint(2, ONNX_ML_OPSET);
+
+    let mut model = Message::default();
+    model
+        .uint(1, ONNX_IR_VERSION)
+        .string(2, "xgboost-rust")
+        .string(3, env!("CARGO_PKG_VERSION"))
+        .message(7, &graph)
+        .message(8, &onnx_opset)
+        .message(8, &ml_opset);
+    Ok(model.0)
+}
@@ -0,0 +1,687 @@
+use crate::device::Device;
+use crate::error::{XGBoostError, XGBoostResult};
+use std::fmt;
+
+/// Learning objective (`objective` parameter).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Objective {
+    /// `reg:squarederror`
+    RegSquaredError,
+    /// `reg:squaredlogerror`
+    RegSquaredLogError,
+    /// `reg:logistic`
+    RegLogistic,
+    /// `reg:pseudohubererror`
+    RegPseudoHuberError,
+    /// `reg:absoluteerror`
+    RegAbsoluteError,
+    /// `reg:gamma`
+    RegGamma,
+    /// `reg:tweedie`
+    RegTweedie,
+    /// `reg:quantileerror` (XGBoost ≥ 2.0); set the levels with
+    /// [`BoosterParams::quantile_alpha`]
+    RegQuantileError,
+    /// `binary:logistic`
+    BinaryLogistic,
+    /// `binary:logitraw`
+    BinaryLogitRaw,
+    /// `binary:hinge`
+    BinaryHinge,
+    /// `count:poisson`
+    CountPoisson,
+    /// `survival:cox`
+    SurvivalCox,
+    /// `survival:aft`, accelerated failure time for censored data.
+    ///
+    /// Labels are given as intervals with [`DMatrix::set_label_lower_bound`]
+    /// and [`DMatrix::set_label_upper_bound`]; tune the noise model with
+    /// [`BoosterParams::aft_loss_distribution`].
+    ///
+    /// [`DMatrix::set_label_lower_bound`]: crate::DMatrix::set_label_lower_bound
+    /// [`DMatrix::set_label_upper_bound`]: crate::DMatrix::set_label_upper_bound
+    SurvivalAft,
+    /// `multi:softmax` with the given number of classes
+    MultiSoftmax(u32),
+    /// `multi:softprob` with the given number of classes
+    MultiSoftprob(u32),
+    /// `rank:pairwise`
+    RankPairwise,
+    /// `rank:ndcg`
+    RankNdcg,
+    /// `rank:map`
+    RankMap,
+    /// Any other objective, by name
+    Custom(String),
+}
+
+impl Objective {
+    /// The XGBoost name of the objective
+    pub fn as_str(&self) -> &str {
+        match self {
+            Objective::RegSquaredError => "reg:squarederror",
+            Objective::RegSquaredLogError => "reg:squaredlogerror",
+            Objective::RegLogistic => "reg:logistic",
+            Objective::RegPseudoHuberError => "reg:pseudohubererror",
+            Objective::RegAbsoluteError => "reg:absoluteerror",
+            Objective::RegGamma => "reg:gamma",
+            Objective::RegTweedie => "reg:tweedie",
+            Objective::RegQuantileError => "reg:quantileerror",
+            Objective::BinaryLogistic => "binary:logistic",
+            Objective::BinaryLogitRaw => "binary:logitraw",
+            Objective::BinaryHinge => "binary:hinge",
+            Objective::CountPoisson => "count:poisson",
+            Objective::SurvivalCox => "survival:cox",
+            Objective::SurvivalAft => "survival:aft",
+            Objective::MultiSoftmax(_) => "multi:softmax",
+            Objective::MultiSoftprob(_) => "multi:softprob",
+            Objective::RankPairwise => "rank:pairwise",
+            Objective::RankNdcg => "rank:ndcg",
+            Objective::RankMap => "rank:map",
+            Objective::Custom(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Booster type (`booster` parameter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoosterType {
+    /// Tree booster (`gbtree`)
+    GbTree,
+    /// Linear booster (`gblinear`)
+    GbLinear,
+    /// Tree booster with dropout (`dart`)
+    Dart,
+}
+
+impl BoosterType {
+    /// The XGBoost name of the booster
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BoosterType::GbTree => "gbtree",
+            BoosterType::GbLinear => "gblinear",
+            BoosterType::Dart => "dart",
+        }
+    }
+}
+
+/// Tree construction algorithm (`tree_method` parameter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeMethod {
+    /// Let XGBoost choose (`auto`)
+    Auto,
+    /// Exact greedy algorithm (`exact`)
+    Exact,
+    /// Approximate quantile sketch (`approx`)
+    Approx,
+    /// Histogram-based algorithm (`hist`)
+    Hist,
+}
+
+impl TreeMethod {
+    /// The XGBoost name of the tree method
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TreeMethod::Auto => "auto",
+            TreeMethod::Exact => "exact",
+            TreeMethod::Approx => "approx",
+            TreeMethod::Hist => "hist",
+        }
+    }
+}
+
+/// How multi-output models are built (`multi_strategy` parameter, XGBoost ≥ 2.0).
+#[cfg(xgboost_device_param)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultiStrategy {
+    /// Separate trees for each target or class (`one_output_per_tree`)
+    OneOutputPerTree,
+    /// Trees with vector leaves predicting all targets (`multi_output_tree`);
+    /// requires the `hist` tree method
+    MultiOutputTree,
+}
+
+#[cfg(xgboost_device_param)]
+impl MultiStrategy {
+    /// The XGBoost name of the strategy
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MultiStrategy::OneOutputPerTree => "one_output_per_tree",
+            MultiStrategy::MultiOutputTree => "multi_output_tree",
+        }
+    }
+}
+
+/// How DART selects the trees to drop (`sample_type` parameter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DartSampleType {
+    /// Every tree is equally likely to be dropped (`uniform`)
+    Uniform,
+    /// Trees are dropped in proportion to their weight (`weighted`)
+    Weighted,
+}
+
+impl DartSampleType {
+    /// The XGBoost name of the sampling algorithm
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DartSampleType::Uniform => "uniform",
+            DartSampleType::Weighted => "weighted",
+        }
+    }
+}
+
+/// How DART weights new and dropped trees (`normalize_type` parameter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DartNormalizeType {
+    /// New trees weigh `1 / (k + eta)` times the dropped ones (`tree`)
+    Tree,
+    /// New trees weigh `1 / (1 + eta)` times the dropped ones (`forest`)
+    Forest,
+}
+
+impl DartNormalizeType {
+    /// The XGBoost name of the normalization
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DartNormalizeType::Tree => "tree",
+            DartNormalizeType::Forest => "forest",
+        }
+    }
+}
+
+/// Noise distribution of the `survival:aft` objective (`aft_loss_distribution`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AftDistribution {
+    /// Normal distribution
+    Normal,
+    /// Logistic distribution
+    Logistic,
+    /// Extreme value (Gumbel) distribution
+    Extreme,
+}
+
+impl AftDistribution {
+    /// The XGBoost name of the distribution
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AftDistribution::Normal => "normal",
+            AftDistribution::Logistic => "logistic",
+            AftDistribution::Extreme => "extreme",
+        }
+    }
+}
+
+/// Typed training parameters.
+///
+/// Parameters are applied to the booster in the order they were set. Setting
+/// the same parameter twice keeps the last value, except for `eval_metric`,
+/// which XGBoost accumulates.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{AftDistribution, BoosterParams, Objective, TreeMethod};
+///
+/// let params = BoosterParams::new()
+///     .objective(Objective::SurvivalAft)
+///     .aft_loss_distribution(AftDistribution::Normal)
+///     .aft_loss_distribution_scale(1.2)
+///     .tree_method(TreeMethod::Hist)
+///     .eta(0.05)
+///     .max_depth(4)
+///     .eval_metric("aft-nloglik");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoosterParams {
+    params: Vec<(String, String)>,
+    unchecked: Vec<String>,
+}
+
+impl BoosterParams {
+    /// Empty parameter set (all XGBoost defaults)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set any parameter by name
+    ///
+    /// Names are checked when the parameters are applied to a booster, see
+    /// [`Booster::set_param`](crate::Booster::set_param); use
+    /// [`param_unchecked`](Self::param_unchecked) to skip the check.
+    pub fn param(mut self, name: &str, value: impl ToString) -> Self {
+        let value = value.to_string();
+        match self.params.iter_mut().find(|(n, _)| n == name) {
+            Some(entry) if name != "eval_metric" => entry.1 = value,
+            _ => self.params.push((name.to_string(), value)),
+        }
+        self
+    }
+
+    /// Set a parameter by name without checking the name when it is applied
+    ///
+    /// For parameters of plugins or of XGBoost versions newer than this
+    /// crate knows about, see
+    /// [`Booster::set_param_unchecked`](crate::Booster::set_param_unchecked).
+    pub fn param_unchecked(mut self, name: &str, value: impl ToString) -> Self {
+        if !self.unchecked.iter().any(|n| n == name) {
+            self.unchecked.push(name.to_string());
+        }
+        self.param(name, value)
+    }
+
+    /// Whether `name` was set with [`param_unchecked`](Self::param_unchecked)
+    pub(crate) fn is_unchecked(&self, name: &str) -> bool {
+        self.unchecked.iter().any(|n| n == name)
+    }
+
+    /// Learning objective; multi-class objectives also set `num_class`
+    pub fn objective(self, objective: Objective) -> Self {
+        let params = match objective {
+            Objective::MultiSoftmax(num_class) | Objective::MultiSoftprob(num_class) => {
+                self.param("num_class", num_class)
+            }
+            _ => self,
+        };
+        params.param("objective", objective.as_str())
+    }
+
+    /// Booster type
+    pub fn booster(self, booster: BoosterType) -> Self {
+        self.param("booster", booster.as_str())
+    }
+
+    /// Tree construction algorithm
+    pub fn tree_method(self, tree_method: TreeMethod) -> Self {
+        self.param("tree_method", tree_method.as_str())
+    }
+
+    /// Device to train on (XGBoost ≥ 2.0; use `Booster::set_device` for older versions)
+    pub fn device(self, device: Device) -> Self {
+        self.param("device", device)
+    }
+
+    /// Step size shrinkage (`eta`, alias `learning_rate`)
+    pub fn eta(self, eta: f64) -> Self {
+        self.param("eta", eta)
+    }
+
+    /// Maximum tree depth
+    pub fn max_depth(self, max_depth: u32) -> Self {
+        self.param("max_depth", max_depth)
+    }
+
+    /// Minimum sum of instance weight (hessian) needed in a child
+    pub fn min_child_weight(self, min_child_weight: f64) -> Self {
+        self.param("min_child_weight", min_child_weight)
+    }
+
+    /// Minimum loss reduction required to make a split (`gamma`)
+    pub fn gamma(self, gamma: f64) -> Self {
+        self.param("gamma", gamma)
+    }
+
+    /// Row subsample ratio per boosting round
+    pub fn subsample(self, subsample: f64) -> Self {
+        self.param("subsample", subsample)
+    }
+
+    /// Column subsample ratio per tree
+    pub fn colsample_bytree(self, colsample_bytree: f64) -> Self {
+        self.param("colsample_bytree", colsample_bytree)
+    }
+
+    /// Column subsample ratio per split
+    pub fn colsample_bynode(self, colsample_bynode: f64) -> Self {
+        self.param("colsample_bynode", colsample_bynode)
+    }
+
+    /// Number of trees grown in parallel per round (a forest per round)
+    pub fn num_parallel_tree(self, num_parallel_tree: u32) -> Self {
+        self.param("num_parallel_tree", num_parallel_tree)
+    }
+
+    /// Categorical features with fewer categories than this are split one
+    /// category against the rest; others are partitioned into two sets of
+    /// categories (XGBoost ≥ 1.6)
+    pub fn max_cat_to_onehot(self, max_cat_to_onehot: u32) -> Self {
+        self.param("max_cat_to_onehot", max_cat_to_onehot)
+    }
+
+    /// Maximum number of categories considered for each partition-based
+    /// categorical split (XGBoost ≥ 1.6)
+    pub fn max_cat_threshold(self, max_cat_threshold: u32) -> Self {
+        self.param("max_cat_threshold", max_cat_threshold)
+    }
+
+    /// Random forest settings, as in XGBoost's `XGBRFRegressor` and
+    /// `XGBRFClassifier`
+    ///
+    /// Grows `num_trees` trees in parallel on row and per-split column
+    /// subsamples of 0.8, without shrinkage (`eta` 1) and with almost no
+    /// L2 regularization. Train a single round: further rounds would boost
+    /// a new forest on the residuals of the previous ones. Set the
+    /// objective separately, and override any setting by calling its
+    /// setter afterwards.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{train, BoosterParams, DMatrix, Objective};
+    ///
+    /// # let dtrain = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+    /// let params = BoosterParams::new()
+    ///     .objective(Objective::BinaryLogistic)
+    ///     .random_forest(200)
+    ///     .max_depth(8);
+    /// let forest = train(&params, &dtrain, 1).unwrap();
+    /// ```
+    pub fn random_forest(self, num_trees: u32) -> Self {
+        self.num_parallel_tree(num_trees)
+            .eta(1.0)
+            .subsample(0.8)
+            .colsample_bynode(0.8)
+            .lambda(1e-5)
+    }
+
+    /// L2 regularization on weights (`lambda`)
+    pub fn lambda(self, lambda: f64) -> Self {
+        self.param("lambda", lambda)
+    }
+
+    /// L1 regularization on weights (`alpha`)
+    pub fn alpha(self, alpha: f64) -> Self {
+        self.param("alpha", alpha)
+    }
+
+    /// Initial prediction score of all instances
+    pub fn base_score(self, base_score: f64) -> Self {
+        self.param("base_score", base_score)
+    }
+
+    /// Weight of positive relative to negative rows in binary classification
+    ///
+    /// See [`DMatrix::scale_pos_weight`](crate::DMatrix::scale_pos_weight)
+    /// for the value that balances the training data.
+    pub fn scale_pos_weight(self, scale_pos_weight: f64) -> Self {
+        self.param("scale_pos_weight", scale_pos_weight)
+    }
+
+    /// Fraction of previous trees dropped in each DART round
+    ///
+    /// Dropout only happens during training. Prediction uses every tree at
+    /// its learned weight, unless `training` is `true` in the prediction
+    /// call, in which case a random set of trees is dropped as in a
+    /// training round and the results change from call to call.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{train, BoosterParams, BoosterType, DMatrix, PredictOptions};
+    ///
+    /// # let dtrain = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+    /// let params = BoosterParams::new()
+    ///     .booster(BoosterType::Dart)
+    ///     .rate_drop(0.1)
+    ///     .skip_drop(0.5);
+    /// let booster = train(&params, &dtrain, 50).unwrap();
+    ///
+    /// // inference: all trees, deterministic
+    /// let a = booster.predict_dmatrix(&dtrain, PredictOptions::empty(), false).unwrap();
+    /// let b = booster.predict_dmatrix(&dtrain, PredictOptions::empty(), false).unwrap();
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn rate_drop(self, rate_drop: f64) -> Self {
+        self.param("rate_drop", rate_drop)
+    }
+
+    /// Probability of skipping dropout in a DART round
+    pub fn skip_drop(self, skip_drop: f64) -> Self {
+        self.param("skip_drop", skip_drop)
+    }
+
+    /// Always drop at least one tree in a DART round
+    pub fn one_drop(self, one_drop: bool) -> Self {
+        self.param("one_drop", one_drop)
+    }
+
+    /// How DART selects the trees to drop
+    pub fn sample_type(self, sample_type: DartSampleType) -> Self {
+        self.param("sample_type", sample_type.as_str())
+    }
+
+    /// How DART weights new trees against the dropped ones
+    pub fn normalize_type(self, normalize_type: DartNormalizeType) -> Self {
+        self.param("normalize_type", normalize_type.as_str())
+    }
+
+    /// Evaluation metric; may be called several times to add metrics
+    pub fn eval_metric(self, metric: &str) -> Self {
+        self.param("eval_metric", metric)
+    }
+
+    /// Random seed
+    pub fn seed(self, seed: u64) -> Self {
+        self.param("seed", seed)
+    }
+
+    /// Derive each round's random seed from the round number
+    ///
+    /// Makes sampling reproducible when training is resumed or a model
+    /// is updated, as the seed no longer depends on the generator state.
+    pub fn seed_per_iteration(self, enabled: bool) -> Self {
+        self.param("seed_per_iteration", enabled)
+    }
+
+    /// Reproducible histogram sums in GPU `hist` training (XGBoost < 2.0;
+    /// from 2.0 on they are always deterministic)
+    #[cfg(not(xgboost_device_param))]
+    pub fn deterministic_histogram(self, enabled: bool) -> Self {
+        self.param("deterministic_histogram", enabled)
+    }
+
+    /// Settings for bit-identical models across runs on the same data
+    ///
+    /// Fixes the seed, derives per-round seeds from the round number and,
+    /// before XGBoost 2.0, enables deterministic GPU histograms. Results
+    /// still depend on the XGBoost version, the device, and the row and
+    /// column order of the data.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{train, BoosterParams, DMatrix, ModelFormat};
+    ///
+    /// # let dtrain = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+    /// let params = BoosterParams::new().subsample(0.8).deterministic(42);
+    /// let a = train(&params, &dtrain, 20).unwrap();
+    /// let b = train(&params, &dtrain, 20).unwrap();
+    /// assert_eq!(
+    ///     a.save_to_buffer(ModelFormat::Ubjson).unwrap(),
+    ///     b.save_to_buffer(ModelFormat::Ubjson).unwrap()
+    /// );
+    /// ```
+    pub fn deterministic(self, seed: u64) -> Self {
+        let params = self.seed(seed).seed_per_iteration(true);
+        #[cfg(not(xgboost_device_param))]
+        let params = params.deterministic_histogram(true);
+        params
+    }
+
+    /// Number of threads (0 uses all cores)
+    pub fn nthread(self, nthread: usize) -> Self {
+        self.param("nthread", nthread)
+    }
+
+    /// Verbosity of XGBoost's own logging (0 silent to 3 debug)
+    pub fn verbosity(self, verbosity: u32) -> Self {
+        self.param("verbosity", verbosity)
+    }
+
+    /// Noise distribution for `survival:aft`
+    pub fn aft_loss_distribution(self, distribution: AftDistribution) -> Self {
+        self.param("aft_loss_distribution", distribution.as_str())
+    }
+
+    /// Scaling factor of the `survival:aft` noise distribution
+    pub fn aft_loss_distribution_scale(self, scale: f64) -> Self {
+        self.param("aft_loss_distribution_scale", scale)
+    }
+
+    /// Tree layout for multi-target regression and multi-class models
+    /// (XGBoost ≥ 2.0)
+    #[cfg(xgboost_device_param)]
+    pub fn multi_strategy(self, strategy: MultiStrategy) -> Self {
+        self.param("multi_strategy", strategy.as_str())
+    }
+
+    /// Quantile levels for `reg:quantileerror`; one model output per level
+    pub fn quantile_alpha(self, alphas: &[f64]) -> Self {
+        let alphas: Vec<String> = alphas.iter().map(f64::to_string).collect();
+        self.param("quantile_alpha", format!("[{}]", alphas.join(",")))
+    }
+
+    /// Look up the value of a parameter
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Iterate over `(name, value)` pairs in the order they are applied
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+}
+
+/// Parameter names documented by XGBoost, including aliases and names
+/// only older versions understand
+const KNOWN_PARAMS: &[&str] = &[
+    // general
+    "booster",
+    "device",
+    "verbosity",
+    "silent",
+    "validate_parameters",
+    "nthread",
+    "n_jobs",
+    "disable_default_eval_metric",
+    "num_feature",
+    "gpu_id",
+    "predictor",
+    "multi_strategy",
+    "max_cached_hist_node",
+    "fail_on_invalid_gpu_id",
+    "n_gpus",
+    // tree booster
+    "eta",
+    "learning_rate",
+    "gamma",
+    "min_split_loss",
+    "max_depth",
+    "min_child_weight",
+    "max_delta_step",
+    "subsample",
+    "sampling_method",
+    "colsample_bytree",
+    "colsample_bylevel",
+    "colsample_bynode",
+    "lambda",
+    "reg_lambda",
+    "alpha",
+    "reg_alpha",
+    "tree_method",
+    "sketch_eps",
+    "scale_pos_weight",
+    "updater",
+    "refresh_leaf",
+    "process_type",
+    "grow_policy",
+    "max_leaves",
+    "max_bin",
+    "num_parallel_tree",
+    "monotone_constraints",
+    "interaction_constraints",
+    "max_cat_to_onehot",
+    "max_cat_threshold",
+    "single_precision_histogram",
+    "deterministic_histogram",
+    "sparse_threshold",
+    // DART
+    "sample_type",
+    "normalize_type",
+    "rate_drop",
+    "one_drop",
+    "skip_drop",
+    // linear booster
+    "feature_selector",
+    "top_k",
+    "lambda_bias",
+    // learning task
+    "objective",
+    "base_score",
+    "eval_metric",
+    "seed",
+    "random_state",
+    "seed_per_iteration",
+    "num_class",
+    "tweedie_variance_power",
+    "huber_slope",
+    "quantile_alpha",
+    "expectile_alpha",
+    "aft_loss_distribution",
+    "aft_loss_distribution_scale",
+    "lambdarank_pair_method",
+    "lambdarank_num_pair_per_sample",
+    "lambdarank_unbiased",
+    "lambdarank_bias_norm",
+    "lambdarank_normalization",
+    "lambdarank_score_normalization",
+    "ndcg_exp_gain",
+    "num_pairsample",
+    "fix_list_weight",
+];
+
+/// Check that `name` is a known parameter, suggesting the closest
+/// known name if it is not
+pub(crate) fn check_param_name(name: &str) -> XGBoostResult<()> {
+    if KNOWN_PARAMS.contains(&name) {
+        return Ok(());
+    }
+    let closest = KNOWN_PARAMS
+        .iter()
+        .map(|known| (edit_distance(name, known), known))
+        .min()
+        .filter(|&(distance, _)| distance <= 2.max(name.len() / 4));
+    let hint = match closest {
+        Some((_, known)) => format!("; did you mean '{}'?", known),
+        None => String::new(),
+    };
+    Err(XGBoostError {
+        description: format!(
+            "Unknown parameter '{}'{} (use set_param_unchecked or \
+             BoosterParams::param_unchecked for parameters of plugins or newer \
+             XGBoost versions)",
+            name, hint
+        ),
+    })
+}
+
+/// Levenshtein distance between two names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
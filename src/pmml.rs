@@ -0,0 +1,296 @@
+//! Conversion of tree ensembles into PMML 4.4 documents.
+//!
+//! The layout follows the common convention for boosted trees: a `modelChain`
+//! whose first segments are sum-of-trees `MiningModel`s (one per output group)
+//! and whose last segment is a `RegressionModel` applying the base score and
+//! the objective's link function.
+
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::gbm::{GradientBooster, Learner, Tree};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+const TARGET_FIELD: &str = "target";
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn margin_field(group: usize) -> String {
+    format!("xgbValue({})", group)
+}
+
+/// Link function applied by the final regression segment.
+fn normalization_method(objective: &str) -> XGBoostResult<(&'static str, bool)> {
+    // (normalizationMethod, is_classification)
+    Ok(match objective {
+        "binary:logistic" => ("logit", true),
+        "multi:softprob" | "multi:softmax" => ("softmax", true),
+        "reg:logistic" => ("logit", false),
+        "count:poisson" | "reg:gamma" | "reg:tweedie" | "survival:cox" | "survival:aft" => {
+            ("exp", false)
+        }
+        "binary:hinge" => {
+            return Err(XGBoostError {
+                description: "PMML export does not support the binary:hinge objective".to_string(),
+            })
+        }
+        _ => ("none", false),
+    })
+}
+
+fn write_mining_schema(out: &mut String, target: bool, fields: &[String], indent: &str) {
+    let _ = writeln!(out, "{}<MiningSchema>", indent);
+    if target {
+        let _ = writeln!(
+            out,
+            "{}  <MiningField name=\"{}\" usageType=\"target\"/>",
+            indent, TARGET_FIELD
+        );
+    }
+    for field in fields {
+        let _ = writeln!(out, "{}  <MiningField name=\"{}\"/>", indent, escape(field));
+    }
+    let _ = writeln!(out, "{}</MiningSchema>", indent);
+}
+
+fn write_node(
+    out: &mut String,
+    tree: &Tree,
+    nid: usize,
+    predicate: &str,
+    feature_names: &[String],
+    weight: f32,
+    depth: usize,
+) {
+    let indent = "  ".repeat(depth);
+    let node = &tree.nodes[nid];
+    if node.is_leaf() {
+        let _ = writeln!(
+            out,
+            "{}<Node id=\"{}\" score=\"{}\">{}</Node>",
+            indent,
+            nid,
+            node.split_condition * weight,
+            predicate
+        );
+        return;
+    }
+
+    let default_child = if node.default_left {
+        node.left
+    } else {
+        node.right
+    };
+    let field = escape(&feature_names[node.split_index as usize]);
+    let _ = writeln!(
+        out,
+        "{}<Node id=\"{}\" defaultChild=\"{}\">{}",
+        indent, nid, default_child, predicate
+    );
+    write_node(
+        out,
+        tree,
+        node.left as usize,
+        &format!(
+            "<SimplePredicate field=\"{}\" operator=\"lessThan\" value=\"{}\"/>",
+            field, node.split_condition
+        ),
+        feature_names,
+        weight,
+        depth + 1,
+    );
+    write_node(
+        out,
+        tree,
+        node.right as usize,
+        &format!(
+            "<SimplePredicate field=\"{}\" operator=\"greaterOrEqual\" value=\"{}\"/>",
+            field, node.split_condition
+        ),
+        feature_names,
+        weight,
+        depth + 1,
+    );
+    let _ = writeln!(out, "{}</Node>", indent);
+}
+
+/// Render a tree model as a PMML 4.4 document.
+pub(crate) fn to_pmml(learner: &Learner) -> XGBoostResult<String> {
+    let ensemble = match &learner.booster {
+        GradientBooster::Tree(ensemble) => ensemble,
+        GradientBooster::Linear(_) => {
+            return Err(XGBoostError {
+                description: "PMML export only supports tree boosters (gbtree/dart)".to_string(),
+            })
+        }
+    };
+    if ensemble
+        .trees
+        .iter()
+        .any(|tree| tree.nodes.iter().any(|n| n.categories.is_some()))
+    {
+        return Err(XGBoostError {
+            description: "PMML export does not support categorical splits".to_string(),
+        });
+    }
+    let (normalization, classification) = normalization_method(&learner.objective.name)?;
+
+    let feature_names: Vec<String> = if learner.feature_names.len() == learner.num_feature {
+        learner.feature_names.clone()
+    } else {
+        (0..learner.num_feature)
+            .map(|i| format!("f{}", i))
+            .collect()
+    };
+    let num_groups = learner.num_groups;
+    let categories: Vec<String> = (0..num_groups.max(2)).map(|c| c.to_string()).collect();
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<PMML xmlns=\"http://www.dmg.org/PMML-4_4\" version=\"4.4\">\n");
+    let _ = writeln!(
+        out,
+        "  <Header>\n    <Application name=\"xgboost-rust\" version=\"{}\"/>\n  </Header>",
+        env!("CARGO_PKG_VERSION")
+    );
+
+    // Data dictionary: features are float because XGBoost compares in single precision
+    out.push_str("  <DataDictionary>\n");
+    for name in &feature_names {
+        let _ = writeln!(
+            out,
+            "    <DataField name=\"{}\" optype=\"continuous\" dataType=\"float\"/>",
+            escape(name)
+        );
+    }
+    if classification {
+        let _ = writeln!(
+            out,
+            "    <DataField name=\"{}\" optype=\"categorical\" dataType=\"integer\">",
+            TARGET_FIELD
+        );
+        for category in &categories {
+            let _ = writeln!(out, "      <Value value=\"{}\"/>", category);
+        }
+        out.push_str("    </DataField>\n");
+    } else {
+        let _ = writeln!(
+            out,
+            "    <DataField name=\"{}\" optype=\"continuous\" dataType=\"float\"/>",
+            TARGET_FIELD
+        );
+    }
+    out.push_str("  </DataDictionary>\n");
+
+    let function_name = if classification {
+        "classification"
+    } else {
+        "regression"
+    };
+    let _ = writeln!(out, "  <MiningModel functionName=\"{}\">", function_name);
+    write_mining_schema(&mut out, true, &feature_names, "    ");
+    out.push_str("    <Segmentation multipleModelMethod=\"modelChain\">\n");
+
+    // One sum-of-trees segment per output group
+    for group in 0..num_groups {
+        let _ = writeln!(out, "      <Segment id=\"{}\">\n        <True/>", group + 1);
+        out.push_str("        <MiningModel functionName=\"regression\">\n");
+        write_mining_schema(&mut out, false, &feature_names, "          ");
+        let _ = writeln!(
+            out,
+            "          <Output>\n            <OutputField name=\"{}\" optype=\"continuous\" dataType=\"float\" feature=\"predictedValue\" isFinalResult=\"false\"/>\n          </Output>",
+            margin_field(group)
+        );
+        out.push_str("          <Segmentation multipleModelMethod=\"sum\">\n");
+        for (tid, (tree, weight)) in ensemble
+            .trees
+            .iter()
+            .zip(&ensemble.tree_weights)
+            .enumerate()
+            .filter(|(tid, _)| ensemble.tree_groups[*tid] == group)
+        {
+            let used: BTreeSet<u32> = tree
+                .nodes
+                .iter()
+                .filter(|n| !n.is_leaf())
+                .map(|n| n.split_index)
+                .collect();
+            let used: Vec<String> = used
+                .into_iter()
+                .map(|f| feature_names[f as usize].clone())
+                .collect();
+            let _ = writeln!(
+                out,
+                "            <Segment id=\"{}\">\n              <True/>",
+                tid + 1
+            );
+            out.push_str("              <TreeModel functionName=\"regression\" missingValueStrategy=\"defaultChild\" noTrueChildStrategy=\"returnLastPrediction\" splitCharacteristic=\"binarySplit\">\n");
+            write_mining_schema(&mut out, false, &used, "                ");
+            write_node(&mut out, tree, 0, "<True/>", &feature_names, *weight, 8);
+            out.push_str("              </TreeModel>\n            </Segment>\n");
+        }
+        out.push_str("          </Segmentation>\n        </MiningModel>\n      </Segment>\n");
+    }
+
+    // Final segment: base score and link function
+    let margins: Vec<String> = (0..num_groups).map(margin_field).collect();
+    let _ = writeln!(
+        out,
+        "      <Segment id=\"{}\">\n        <True/>",
+        num_groups + 1
+    );
+    let _ = writeln!(
+        out,
+        "        <RegressionModel functionName=\"{}\" normalizationMethod=\"{}\">",
+        function_name, normalization
+    );
+    write_mining_schema(&mut out, true, &margins, "          ");
+    let table = |out: &mut String, intercept: f32, group: Option<usize>, category: Option<&str>| {
+        let category = category
+            .map(|c| format!(" targetCategory=\"{}\"", c))
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "          <RegressionTable intercept=\"{}\"{}>",
+            intercept, category
+        );
+        if let Some(group) = group {
+            let _ = writeln!(
+                out,
+                "            <NumericPredictor name=\"{}\" coefficient=\"1\"/>",
+                margin_field(group)
+            );
+        }
+        out.push_str("          </RegressionTable>\n");
+    };
+    if classification && num_groups == 1 {
+        // Binary logit: the first table gives P(1), the second is 1 - P(1)
+        table(&mut out, learner.base_margin[0], Some(0), Some("1"));
+        table(&mut out, 0.0, None, Some("0"));
+    } else if classification {
+        for (group, category) in categories.iter().enumerate() {
+            table(
+                &mut out,
+                learner.base_margin[group],
+                Some(group),
+                Some(category),
+            );
+        }
+    } else {
+        if num_groups != 1 {
+            return Err(XGBoostError {
+                description: "PMML export does not support multi-target regression".to_string(),
+            });
+        }
+        table(&mut out, learner.base_margin[0], Some(0), None);
+    }
+    out.push_str("        </RegressionModel>\n      </Segment>\n");
+    out.push_str("    </Segmentation>\n  </MiningModel>\n</PMML>\n");
+    Ok(out)
+}
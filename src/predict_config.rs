@@ -0,0 +1,190 @@
+use crate::predict_options::PredictOptions;
+
+/// What a prediction outputs (the `type` of XGBoost's prediction config).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PredictType {
+    /// Predictions transformed by the objective (e.g. probabilities)
+    #[default]
+    Value,
+    /// Untransformed margins
+    Margin,
+    /// Feature contributions (SHAP values)
+    Contribs,
+    /// Approximate feature contributions
+    ApproxContribs,
+    /// Feature interaction contributions
+    Interactions,
+    /// Approximate feature interaction contributions
+    ApproxInteractions,
+    /// Leaf index of each tree
+    Leaf,
+}
+
+impl PredictType {
+    /// The output selected by a legacy option mask, with the precedence of
+    /// `XGBoosterPredict`
+    pub(crate) fn from_options(options: PredictOptions) -> Self {
+        let approx = options.contains(PredictOptions::PRED_APPROX_CONTRIBS);
+        if options.contains(PredictOptions::PRED_CONTRIBS) {
+            if approx {
+                PredictType::ApproxContribs
+            } else {
+                PredictType::Contribs
+            }
+        } else if options.contains(PredictOptions::PRED_INTERACTIONS) {
+            if approx {
+                PredictType::ApproxInteractions
+            } else {
+                PredictType::Interactions
+            }
+        } else if options.contains(PredictOptions::PRED_LEAF) {
+            PredictType::Leaf
+        } else if options.contains(PredictOptions::OUTPUT_MARGIN) {
+            PredictType::Margin
+        } else {
+            PredictType::Value
+        }
+    }
+
+    fn code(self) -> u32 {
+        match self {
+            PredictType::Value => 0,
+            PredictType::Margin => 1,
+            PredictType::Contribs => 2,
+            PredictType::ApproxContribs => 3,
+            PredictType::Interactions => 4,
+            PredictType::ApproxInteractions => 5,
+            PredictType::Leaf => 6,
+        }
+    }
+}
+
+/// Configuration of [`Booster::predict_with_config`].
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{PredictConfig, PredictType};
+///
+/// // SHAP values of the first 50 trees, shaped (rows, groups, features + 1)
+/// let config = PredictConfig::new()
+///     .predict_type(PredictType::Contribs)
+///     .iteration_range(0, 50)
+///     .strict_shape(true);
+/// ```
+///
+/// [`Booster::predict_with_config`]: crate::Booster::predict_with_config
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PredictConfig {
+    predict_type: PredictType,
+    iteration_range: Option<(usize, usize)>,
+    strict_shape: bool,
+    training: bool,
+}
+
+impl PredictConfig {
+    /// Normal prediction over all trees
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// What to predict
+    pub fn predict_type(mut self, predict_type: PredictType) -> Self {
+        self.predict_type = predict_type;
+        self
+    }
+
+    /// Only use the boosting rounds in `[begin, end)`; `end = 0` means all
+    ///
+    /// Without an explicit range, models with a `best_iteration` predict
+    /// with rounds `[0, best_iteration]`; `iteration_range(0, 0)` uses all
+    /// trees regardless.
+    pub fn iteration_range(mut self, begin: usize, end: usize) -> Self {
+        self.iteration_range = Some((begin, end));
+        self
+    }
+
+    pub(crate) fn has_iteration_range(&self) -> bool {
+        self.iteration_range.is_some()
+    }
+
+    /// Always output every dimension (rows, groups, ...), even if it is 1
+    pub fn strict_shape(mut self, strict_shape: bool) -> Self {
+        self.strict_shape = strict_shape;
+        self
+    }
+
+    /// Whether this prediction is part of training (e.g. enables dropout for DART)
+    pub fn training(mut self, training: bool) -> Self {
+        self.training = training;
+        self
+    }
+
+    /// JSON config of `XGBoosterPredictFromDMatrix`
+    pub(crate) fn to_json(&self) -> String {
+        format!(
+            "{{\"type\": {}, \"training\": {}, \"iteration_begin\": {}, \"iteration_end\": {}, \"strict_shape\": {}}}",
+            self.predict_type.code(),
+            self.training,
+            self.iteration_range.map_or(0, |(begin, _)| begin),
+            self.iteration_range.map_or(0, |(_, end)| end),
+            self.strict_shape
+        )
+    }
+
+    /// JSON config of the inplace prediction functions (`XGBoosterPredictFrom*`)
+    #[cfg(any(feature = "cuda", xgboost_array_interface))]
+    pub(crate) fn to_inplace_json(&self) -> String {
+        format!(
+            "{{\"type\": {}, \"training\": {}, \"iteration_begin\": {}, \"iteration_end\": {}, \"strict_shape\": {}, \"cache_id\": 0, \"missing\": NaN}}",
+            self.predict_type.code(),
+            self.training,
+            self.iteration_range.map_or(0, |(begin, _)| begin),
+            self.iteration_range.map_or(0, |(_, end)| end),
+            self.strict_shape
+        )
+    }
+}
+
+/// Prediction output together with its shape.
+///
+/// Values are row-major; the first dimension is always the number of rows.
+/// The remaining dimensions depend on the [`PredictType`] and on
+/// [`PredictConfig::strict_shape`], e.g. `(rows, groups)` for multi-class
+/// values or `(rows, features + 1)` for SHAP values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Predictions {
+    values: Vec<f32>,
+    shape: Vec<usize>,
+}
+
+impl Predictions {
+    pub(crate) fn new(values: Vec<f32>, shape: Vec<usize>) -> Self {
+        Predictions { values, shape }
+    }
+
+    /// Dimensions of the output
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Number of rows
+    pub fn num_rows(&self) -> usize {
+        self.shape.first().copied().unwrap_or(0)
+    }
+
+    /// All values of one row
+    pub fn row(&self, row: usize) -> &[f32] {
+        let width: usize = self.shape.iter().skip(1).product();
+        &self.values[row * width..(row + 1) * width]
+    }
+
+    /// Flat row-major values
+    pub fn as_slice(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// Take the flat row-major values
+    pub fn into_vec(self) -> Vec<f32> {
+        self.values
+    }
+}
@@ -0,0 +1,31 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// Prediction output options (the `option_mask` of `XGBoosterPredict`).
+    ///
+    /// The empty set is the normal prediction, transformed by the objective
+    /// (e.g. probabilities for `binary:logistic`). Use
+    /// [`PredictOptions::from_bits`] to pass a raw mask.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, PredictOptions};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let shap = booster.predict(&data, 2, 2, PredictOptions::PRED_CONTRIBS, false).unwrap();
+    /// ```
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct PredictOptions: u32 {
+        /// Output the untransformed margin value
+        const OUTPUT_MARGIN = 0x01;
+        /// Output the leaf index of trees
+        const PRED_LEAF = 0x02;
+        /// Output feature contributions (SHAP values)
+        const PRED_CONTRIBS = 0x04;
+        /// Approximate feature contributions (faster, less precise)
+        const PRED_APPROX_CONTRIBS = 0x08;
+        /// Output feature interaction contributions
+        const PRED_INTERACTIONS = 0x10;
+    }
+}
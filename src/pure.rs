@@ -0,0 +1,614 @@
+#[cfg(feature = "encryption")]
+use crate::backend::encrypted_path;
+use crate::backend::{check_dense_len, Backend};
+use crate::batches;
+use crate::compression::{self, Compression};
+use crate::convert;
+use crate::device::Device;
+#[cfg(feature = "encryption")]
+use crate::encryption::{self, KeyProvider};
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::features::FeatureType;
+use crate::gbm::Learner;
+#[cfg(feature = "integrity")]
+use crate::integrity;
+use crate::json::{self, ModelFormat};
+use crate::predict_options::PredictOptions;
+use std::fs;
+use std::path::Path;
+
+/// An XGBoost Booster evaluated entirely in Rust.
+///
+/// Enabled by the `pure` feature. It parses JSON or UBJSON model files and
+/// evaluates `gbtree`, `dart` and `gblinear` models without linking against
+/// libxgboost, and mirrors the API of the native booster. With the default
+/// `native` feature disabled it is also exported as `Booster`, so code can
+/// switch between the two with a feature flag.
+///
+/// # Limitations
+///
+/// - Only the JSON (`.json`) and UBJSON (`.ubj`) model formats are supported.
+/// - Supported prediction options are the default transformed output,
+///   `OUTPUT_MARGIN` and `PRED_LEAF`. SHAP contributions and interactions
+///   return an error.
+/// - Multi-output trees (`multi_strategy=multi_output_tree`) are not supported.
+///
+/// # WebAssembly
+///
+/// This booster has no native dependencies and compiles for `wasm32` targets.
+/// On `wasm32-unknown-unknown` there is no filesystem, so load models with
+/// [`PureBooster::load_from_buffer`] instead of [`PureBooster::load`].
+///
+/// # Thread Safety
+///
+/// The model is plain immutable data, so `PureBooster` is always `Send + Sync`
+/// and can be shared via `Arc<PureBooster>` regardless of XGBoost version.
+#[derive(Debug, Clone)]
+pub struct PureBooster {
+    learner: Learner,
+    raw: Vec<u8>,
+    format: ModelFormat,
+    use_best_iteration: bool,
+    max_chunk_rows: Option<usize>,
+}
+
+impl PureBooster {
+    /// Load a model from a file
+    ///
+    /// # Arguments
+    /// * `path` - Path to the model file (JSON or UBJSON, possibly gzip or
+    ///   zstd compressed; see [`Compression`])
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::PureBooster;
+    ///
+    /// let booster = PureBooster::load("model.json").unwrap();
+    /// ```
+    ///
+    /// With the `integrity` feature, a model with a `.sha256` checksum file
+    /// (see `PureBooster::save_with_checksum`) is verified against it first.
+    pub fn load<P: AsRef<Path>>(path: P) -> XGBoostResult<Self> {
+        #[cfg(feature = "integrity")]
+        if integrity::checksum_path(path.as_ref()).exists() {
+            return Ok(Self::load_verified(path)?);
+        }
+        let buffer = fs::read(path.as_ref()).map_err(|e| XGBoostError {
+            description: format!(
+                "Failed to read model file {}: {}",
+                path.as_ref().display(),
+                e
+            ),
+        })?;
+        Self::load_from_buffer(&buffer)
+    }
+
+    /// Load a model from a memory buffer
+    ///
+    /// # Arguments
+    /// * `buffer` - Model content as bytes (JSON or UBJSON, possibly gzip or
+    ///   zstd compressed)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::PureBooster;
+    /// use std::fs;
+    ///
+    /// let buffer = fs::read("model.json").unwrap();
+    /// let booster = PureBooster::load_from_buffer(&buffer).unwrap();
+    /// ```
+    pub fn load_from_buffer(buffer: &[u8]) -> XGBoostResult<Self> {
+        let buffer = &compression::decompress(buffer)?[..];
+        let document = json::parse_model_document(buffer)?;
+        let format = json::detect_format(buffer).unwrap_or(ModelFormat::Json);
+        Ok(PureBooster {
+            learner: Learner::from_document(&document)?,
+            raw: buffer.to_vec(),
+            format,
+            use_best_iteration: true,
+            max_chunk_rows: None,
+        })
+    }
+
+    /// Make predictions on data
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `options` - Prediction options
+    /// * `training` - Accepted for API compatibility; has no effect
+    ///
+    /// # Returns
+    /// A vector of prediction values
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{PureBooster, PredictOptions};
+    ///
+    /// let booster = PureBooster::load("model.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let predictions = booster.predict(&data, 2, 2, PredictOptions::empty(), false).unwrap();
+    /// ```
+    pub fn predict(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+        _training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+        batches::predict_chunked(num_rows, self.max_chunk_rows, |rows| {
+            let chunk = &data[rows.start * num_features..rows.end * num_features];
+            self.predict_inner(chunk, rows.len(), num_features, None, options)
+        })
+    }
+
+    /// Make predictions on dense `f64` data
+    ///
+    /// Split thresholds are stored as `f32`, so features are compared in
+    /// `f32` precision, exactly as libxgboost does.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `options` - Prediction options
+    /// * `training` - Accepted for API compatibility; has no effect
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{PureBooster, PredictOptions};
+    ///
+    /// let booster = PureBooster::load("model.json").unwrap();
+    /// let data = vec![1.0f64, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let predictions = booster.predict_f64(&data, 2, 2, PredictOptions::empty(), false).unwrap();
+    /// ```
+    pub fn predict_f64(
+        &self,
+        data: &[f64],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+        _training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+        batches::predict_chunked(num_rows, self.max_chunk_rows, |rows| {
+            let chunk =
+                convert::f64_to_f32(&data[rows.start * num_features..rows.end * num_features]);
+            self.predict_inner(&chunk, rows.len(), num_features, None, options)
+        })
+    }
+
+    /// Make predictions on dense half-precision data
+    ///
+    /// Values are IEEE 754 binary16 bit patterns, as stored by `half::f16`
+    /// (`bytemuck::cast_slice` turns a `&[half::f16]` into a `&[u16]`
+    /// without copying) or f16 columns of Arrow and Polars. They are widened
+    /// to `f32` in one pass, exactly, before predicting.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `options` - Prediction options
+    /// * `training` - Accepted for API compatibility; has no effect
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{PureBooster, PredictOptions};
+    ///
+    /// let booster = PureBooster::load("model.json").unwrap();
+    /// let data: Vec<u16> = vec![0x3c00, 0x4000, 0x4200, 0x4400]; // 1.0, 2.0, 3.0, 4.0
+    /// let predictions = booster.predict_f16(&data, 2, 2, PredictOptions::empty(), false).unwrap();
+    /// ```
+    pub fn predict_f16(
+        &self,
+        data: &[u16],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+        _training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+        batches::predict_chunked(num_rows, self.max_chunk_rows, |rows| {
+            let chunk =
+                convert::f16_to_f32(&data[rows.start * num_features..rows.end * num_features]);
+            self.predict_inner(&chunk, rows.len(), num_features, None, options)
+        })
+    }
+
+    /// Make predictions on data that encodes missing values with a sentinel
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `missing` - Value that marks a missing entry (e.g. `-999.0`)
+    /// * `options` - Prediction options
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{PureBooster, PredictOptions};
+    ///
+    /// let booster = PureBooster::load("model.json").unwrap();
+    /// let data = vec![1.0, -999.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let predictions = booster.predict_with_missing(&data, 2, 2, -999.0, PredictOptions::empty()).unwrap();
+    /// ```
+    pub fn predict_with_missing(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        missing: f32,
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        if missing.is_nan() {
+            return self.predict(data, num_rows, num_features, options, false);
+        }
+        check_dense_len(data.len(), num_rows, num_features)?;
+        batches::predict_chunked(num_rows, self.max_chunk_rows, |rows| {
+            let chunk: Vec<f32> = data[rows.start * num_features..rows.end * num_features]
+                .iter()
+                .map(|&x| if x == missing { f32::NAN } else { x })
+                .collect();
+            self.predict_inner(&chunk, rows.len(), num_features, None, options)
+        })
+    }
+
+    /// Make predictions on data, starting from per-row base margins
+    ///
+    /// The base margins replace the model's `base_score`, so the output of
+    /// a previous model (with `OUTPUT_MARGIN`) can be used as an offset.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `base_margin` - Initial margins (row-major, num_rows x num_groups)
+    /// * `options` - Prediction options
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{PureBooster, PredictOptions};
+    ///
+    /// let first = PureBooster::load("stage1.json").unwrap();
+    /// let second = PureBooster::load("stage2.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let offset = first.predict(&data, 2, 2, PredictOptions::OUTPUT_MARGIN, false).unwrap();
+    /// let predictions = second.predict_with_base_margin(&data, 2, 2, &offset, PredictOptions::empty()).unwrap();
+    /// ```
+    pub fn predict_with_base_margin(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        base_margin: &[f32],
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        let expected_len = num_rows.saturating_mul(self.learner.num_groups);
+        if base_margin.len() != expected_len {
+            return Err(XGBoostError {
+                description: format!(
+                    "Base margin length mismatch: expected {} values ({}×{}), got {}",
+                    expected_len,
+                    num_rows,
+                    self.learner.num_groups,
+                    base_margin.len()
+                ),
+            });
+        }
+        check_dense_len(data.len(), num_rows, num_features)?;
+        let num_groups = self.learner.num_groups;
+        batches::predict_chunked(num_rows, self.max_chunk_rows, |rows| {
+            let chunk = &data[rows.start * num_features..rows.end * num_features];
+            let margins = &base_margin[rows.start * num_groups..rows.end * num_groups];
+            self.predict_inner(chunk, rows.len(), num_features, Some(margins), options)
+        })
+    }
+
+    fn predict_inner(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        base_margin: Option<&[f32]>,
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        check_dense_len(data.len(), num_rows, num_features)?;
+
+        if num_features > self.learner.num_feature {
+            return Err(XGBoostError {
+                description: format!(
+                    "Feature count mismatch: model expects {} features, got {}",
+                    self.learner.num_feature, num_features
+                ),
+            });
+        }
+
+        if num_rows == 0 {
+            return Err(XGBoostError {
+                description: "Cannot predict on empty data".to_string(),
+            });
+        }
+
+        let unsupported = PredictOptions::PRED_CONTRIBS
+            | PredictOptions::PRED_APPROX_CONTRIBS
+            | PredictOptions::PRED_INTERACTIONS;
+        if options.intersects(unsupported) {
+            return Err(XGBoostError {
+                description: "Feature contributions are not supported by the pure-Rust predictor"
+                    .to_string(),
+            });
+        }
+
+        let iteration_end = self
+            .learner
+            .best_iteration()
+            .filter(|_| self.use_best_iteration)
+            .map(|best| best + 1);
+        if options.contains(PredictOptions::PRED_LEAF) {
+            self.learner
+                .predict_leaf(data, num_rows, num_features, iteration_end)
+        } else if options.contains(PredictOptions::OUTPUT_MARGIN) {
+            Ok(self.learner.predict_margin(
+                data,
+                num_rows,
+                num_features,
+                base_margin,
+                iteration_end,
+            ))
+        } else {
+            Ok(self
+                .learner
+                .predict(data, num_rows, num_features, base_margin, iteration_end))
+        }
+    }
+
+    /// Get a model attribute
+    ///
+    /// # Arguments
+    /// * `key` - Attribute name
+    pub fn attr(&self, key: &str) -> XGBoostResult<Option<String>> {
+        Ok(self
+            .learner
+            .attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone()))
+    }
+
+    /// The best boosting round found by early stopping (`best_iteration` attribute)
+    pub fn best_iteration(&self) -> XGBoostResult<Option<usize>> {
+        Ok(self.learner.best_iteration())
+    }
+
+    /// Whether predictions stop at [`PureBooster::best_iteration`] (the default)
+    ///
+    /// Models trained with early stopping keep the rounds after the best
+    /// one; by default they are ignored, as in the Python `XGBModel`
+    /// interface. Pass `false` to predict with all trees.
+    pub fn set_use_best_iteration(&mut self, use_best_iteration: bool) {
+        self.use_best_iteration = use_best_iteration;
+    }
+
+    /// Predict inputs of more than `max_chunk_rows` rows in chunks of at
+    /// most that many rows (default `None`, all at once)
+    ///
+    /// Outputs are concatenated in row order, so results are unchanged,
+    /// but the converted f64, f16, missing-value or struct data held at a
+    /// time is bounded by the chunk size rather than the input. Applies to
+    /// [`PureBooster::predict`], [`PureBooster::predict_f16`],
+    /// [`PureBooster::predict_f64`], [`PureBooster::predict_with_missing`],
+    /// [`PureBooster::predict_with_base_margin`],
+    /// [`PureBooster::predict_structs`] and the methods built on them.
+    pub fn set_max_chunk_rows(&mut self, max_chunk_rows: Option<usize>) {
+        self.max_chunk_rows = max_chunk_rows.map(|rows| rows.max(1));
+    }
+
+    /// Get the number of features the model expects
+    ///
+    /// # Returns
+    /// The number of features
+    pub fn num_features(&self) -> XGBoostResult<usize> {
+        Ok(self.learner.num_feature)
+    }
+
+    /// Feature names stored in the model, empty if it has none
+    pub fn feature_names(&self) -> XGBoostResult<Vec<String>> {
+        Ok(self.learner.feature_names.clone())
+    }
+
+    /// Feature types stored in the model, empty if it has none
+    pub fn feature_types(&self) -> XGBoostResult<Vec<FeatureType>> {
+        Ok(self
+            .learner
+            .feature_types
+            .iter()
+            .map(|name| FeatureType::from_name(name))
+            .collect())
+    }
+
+    /// Select the device used for prediction
+    ///
+    /// The pure-Rust predictor only runs on the CPU; requesting a CUDA device
+    /// returns an error.
+    ///
+    /// # Arguments
+    /// * `device` - A [`Device`] or a device string such as `"cpu"`
+    pub fn set_device<D>(&mut self, device: D) -> XGBoostResult<()>
+    where
+        D: TryInto<Device>,
+        D::Error: Into<XGBoostError>,
+    {
+        match device.try_into().map_err(Into::into)? {
+            Device::Cpu => Ok(()),
+            device => Err(XGBoostError {
+                description: format!(
+                    "Cannot predict on {}: the pure-Rust predictor only supports the CPU",
+                    device
+                ),
+            }),
+        }
+    }
+
+    /// Save the model to a file
+    ///
+    /// The model is written back exactly as it was loaded, so the file
+    /// extension must match the original format (`.ubj` for UBJSON models,
+    /// anything else for JSON).
+    ///
+    /// # Arguments
+    /// * `path` - Path where to save the model
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::PureBooster;
+    ///
+    /// let booster = PureBooster::load("model.json").unwrap();
+    /// booster.save("model_copy.json").unwrap();
+    /// ```
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> XGBoostResult<()> {
+        if let Some(compression) = Compression::from_path(path.as_ref()) {
+            return self.save_compressed(path, compression);
+        }
+        self.check_save_format(path.as_ref())?;
+        self.write(path.as_ref(), &self.raw)
+    }
+
+    /// Save the model to a compressed file
+    ///
+    /// The extension of `path` without a compression extension must match
+    /// the loaded format, e.g. `model.ubj.zst` for a UBJSON model. Requires
+    /// the feature of the compression.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{PureBooster, Compression};
+    ///
+    /// let booster = PureBooster::load("model.ubj").unwrap();
+    /// booster.save_compressed("model.ubj.zst", Compression::Zstd).unwrap();
+    /// ```
+    pub fn save_compressed<P: AsRef<Path>>(
+        &self,
+        path: P,
+        compression: Compression,
+    ) -> XGBoostResult<()> {
+        self.check_save_format(&compression::uncompressed_path(path.as_ref()))?;
+        self.write(
+            path.as_ref(),
+            &compression::compress(&self.raw, compression)?,
+        )
+    }
+
+    fn check_save_format(&self, path: &Path) -> XGBoostResult<()> {
+        let wants_ubjson = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("ubj"));
+        if wants_ubjson != (self.format == ModelFormat::Ubjson) {
+            return Err(XGBoostError {
+                description: format!(
+                    "Cannot save a {} model to {}: the pure-Rust booster cannot convert between formats",
+                    if self.format == ModelFormat::Ubjson { "UBJSON" } else { "JSON" },
+                    path.display()
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    fn write(&self, path: &Path, bytes: &[u8]) -> XGBoostResult<()> {
+        fs::write(path, bytes).map_err(|e| XGBoostError {
+            description: format!("Failed to write model file {}: {}", path.display(), e),
+        })
+    }
+
+    /// Save the model encrypted with the key from `key_provider`
+    ///
+    /// The model is never written unencrypted. Its format and compression
+    /// follow the extensions before `.enc`, e.g. `model.ubj.zst.enc`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::PureBooster;
+    ///
+    /// let key = [7u8; 32]; // from a secret store in practice
+    /// let booster = PureBooster::load("model.json").unwrap();
+    /// booster.save_encrypted("model.json.enc", &key).unwrap();
+    /// let booster = PureBooster::load_encrypted("model.json.enc", &key).unwrap();
+    /// ```
+    #[cfg(feature = "encryption")]
+    pub fn save_encrypted<P: AsRef<Path>, K: KeyProvider + ?Sized>(
+        &self,
+        path: P,
+        key_provider: &K,
+    ) -> XGBoostResult<()> {
+        let inner = encrypted_path(path.as_ref());
+        let compression = Compression::from_path(&inner);
+        self.check_save_format(&compression::uncompressed_path(&inner))?;
+        let model = match compression {
+            Some(compression) => compression::compress(&self.raw, compression)?,
+            None => self.raw.clone(),
+        };
+        let key = key_provider.key(path.as_ref())?;
+        let encrypted = encryption::encrypt(&model, &key)?;
+        fs::write(path.as_ref(), encrypted).map_err(|e| XGBoostError {
+            description: format!(
+                "Failed to write model file {}: {}",
+                path.as_ref().display(),
+                e
+            ),
+        })
+    }
+
+    /// Save the model to a memory buffer
+    ///
+    /// Returns the bytes the model was loaded from; requesting a different
+    /// format than the original is an error.
+    ///
+    /// # Arguments
+    /// * `format` - Serialization format of the returned bytes
+    pub fn save_to_buffer(&self, format: ModelFormat) -> XGBoostResult<Vec<u8>> {
+        if format != self.format {
+            return Err(XGBoostError {
+                description: format!(
+                    "Cannot convert a {:?} model to {:?}: the pure-Rust booster cannot convert between formats",
+                    self.format, format
+                ),
+            });
+        }
+        Ok(self.raw.clone())
+    }
+
+    /// The parsed model structure.
+    pub(crate) fn learner(&self) -> XGBoostResult<Learner> {
+        Ok(self.learner.clone())
+    }
+
+    crate::backend::shared_methods!(feature_names: all(), save_to_buffer: all());
+}
+
+impl Backend for PureBooster {
+    fn predict_dense(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.predict(data, num_rows, num_features, options, false)
+    }
+
+    fn num_features(&self) -> XGBoostResult<usize> {
+        PureBooster::num_features(self)
+    }
+
+    fn model_feature_names(&self) -> XGBoostResult<Vec<String>> {
+        Ok(self.learner.feature_names.clone())
+    }
+
+    fn attr(&self, key: &str) -> XGBoostResult<Option<String>> {
+        PureBooster::attr(self, key)
+    }
+}
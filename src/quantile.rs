@@ -0,0 +1,129 @@
+use crate::error::{XGBoostError, XGBoostResult};
+
+/// Predictions of a multi-quantile (`reg:quantileerror`) model.
+///
+/// Stored row-major as `num_rows × num_quantiles`, in the order of the
+/// `quantile_alpha` values the model was trained with.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::QuantilePredictions;
+///
+/// // 2 rows, quantiles 0.1 / 0.5 / 0.9
+/// let raw = vec![1.0, 2.0, 3.0, 1.5, 2.5, 3.5];
+/// let predictions = QuantilePredictions::new(raw, &[0.1, 0.5, 0.9]).unwrap();
+/// assert_eq!(predictions.row(1), &[1.5, 2.5, 3.5]);
+/// assert_eq!(predictions.for_alpha(0.5), Some(vec![2.0, 2.5]));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantilePredictions {
+    values: Vec<f32>,
+    alphas: Vec<f64>,
+}
+
+impl QuantilePredictions {
+    /// Wrap raw predictions of a model trained with the given `quantile_alpha` values
+    ///
+    /// # Arguments
+    /// * `values` - Raw prediction output (row-major, one value per row and quantile)
+    /// * `alphas` - Quantile levels, in training order
+    pub fn new(values: Vec<f32>, alphas: &[f64]) -> XGBoostResult<Self> {
+        if alphas.is_empty() || values.len() % alphas.len() != 0 {
+            return Err(XGBoostError {
+                description: format!(
+                    "Prediction length {} is not a multiple of the number of quantiles ({})",
+                    values.len(),
+                    alphas.len()
+                ),
+            });
+        }
+        Ok(QuantilePredictions {
+            values,
+            alphas: alphas.to_vec(),
+        })
+    }
+
+    /// Number of rows
+    pub fn num_rows(&self) -> usize {
+        self.values.len() / self.alphas.len()
+    }
+
+    /// Quantile levels, in column order
+    pub fn alphas(&self) -> &[f64] {
+        &self.alphas
+    }
+
+    /// All predicted quantiles of one row
+    pub fn row(&self, row: usize) -> &[f32] {
+        let width = self.alphas.len();
+        &self.values[row * width..(row + 1) * width]
+    }
+
+    /// Predictions of the `index`-th quantile for every row
+    pub fn quantile(&self, index: usize) -> Vec<f32> {
+        self.values
+            .iter()
+            .skip(index)
+            .step_by(self.alphas.len())
+            .copied()
+            .collect()
+    }
+
+    /// Predictions for the quantile level `alpha`, if the model has it
+    pub fn for_alpha(&self, alpha: f64) -> Option<Vec<f32>> {
+        self.alphas
+            .iter()
+            .position(|a| (a - alpha).abs() < 1e-9)
+            .map(|index| self.quantile(index))
+    }
+
+    /// Raw row-major values
+    pub fn as_slice(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// Mean pinball loss of each quantile against the true labels
+    pub fn pinball_loss(&self, labels: &[f32]) -> XGBoostResult<Vec<f64>> {
+        (0..self.alphas.len())
+            .map(|index| pinball_loss(labels, &self.quantile(index), self.alphas[index]))
+            .collect()
+    }
+}
+
+/// Mean pinball (quantile) loss of predictions for quantile level `alpha`
+///
+/// This is the `quantile` metric XGBoost reports for `reg:quantileerror`.
+///
+/// # Example
+/// ```no_run
+/// let loss = xgboost_rust::pinball_loss(&[1.0, 2.0], &[1.5, 1.5], 0.9).unwrap();
+/// ```
+pub fn pinball_loss(labels: &[f32], predictions: &[f32], alpha: f64) -> XGBoostResult<f64> {
+    if labels.len() != predictions.len() {
+        return Err(XGBoostError {
+            description: format!(
+                "Length mismatch: {} labels, {} predictions",
+                labels.len(),
+                predictions.len()
+            ),
+        });
+    }
+    if labels.is_empty() {
+        return Err(XGBoostError {
+            description: "Cannot compute pinball loss of empty data".to_string(),
+        });
+    }
+    let total: f64 = labels
+        .iter()
+        .zip(predictions)
+        .map(|(&y, &p)| {
+            let diff = (y - p) as f64;
+            if diff >= 0.0 {
+                alpha * diff
+            } else {
+                (alpha - 1.0) * diff
+            }
+        })
+        .sum();
+    Ok(total / labels.len() as f64)
+}
@@ -0,0 +1,193 @@
+//! Features from Apache Arrow record batches, and predictions written to
+//! Parquet.
+
+use crate::batches::sealed::{Layout, Sealed};
+use crate::error::{XGBoostError, XGBoostResult};
+use arrow::array::{Array, AsArray, RecordBatch};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float32Type};
+use std::borrow::Cow;
+#[cfg(feature = "parquet")]
+use {
+    crate::backend::Backend,
+    crate::predict_options::PredictOptions,
+    arrow::array::{ArrayRef, Float32Array},
+    arrow::datatypes::{Field, Schema},
+    parquet::arrow::ArrowWriter,
+    parquet::file::properties::WriterProperties,
+    std::fs::File,
+    std::path::Path,
+    std::sync::Arc,
+};
+
+/// Lay out the feature columns of `batch` as dense rows
+///
+/// Columns are cast to `f32`, so any numeric or boolean type works; nulls
+/// are missing values. Columns named like no feature (e.g. keys) are
+/// ignored.
+pub(crate) fn to_dense(batch: &RecordBatch, layout: &Layout) -> XGBoostResult<Vec<f32>> {
+    let columns: Vec<&dyn Array> = if layout.feature_names.is_empty() {
+        if batch.num_columns() != layout.num_features {
+            return Err(XGBoostError {
+                description: format!(
+                    "RecordBatch has {} columns but the model expects {} features",
+                    batch.num_columns(),
+                    layout.num_features
+                ),
+            });
+        }
+        batch
+            .columns()
+            .iter()
+            .map(|column| column.as_ref())
+            .collect()
+    } else {
+        layout
+            .feature_names
+            .iter()
+            .map(|name| {
+                batch
+                    .column_by_name(name)
+                    .map(|column| column.as_ref())
+                    .ok_or_else(|| XGBoostError {
+                        description: format!("RecordBatch has no column for feature '{}'", name),
+                    })
+            })
+            .collect::<XGBoostResult<_>>()?
+    };
+
+    let num_features = layout.num_features;
+    let mut data = vec![f32::NAN; batch.num_rows() * num_features];
+    for (feature, column) in columns.into_iter().enumerate() {
+        let values = cast(column, &DataType::Float32).map_err(|e| XGBoostError {
+            description: format!("Feature {} is not numeric: {}", feature, e),
+        })?;
+        for (row, value) in values.as_primitive::<Float32Type>().iter().enumerate() {
+            if let Some(value) = value {
+                data[row * num_features + feature] = value;
+            }
+        }
+    }
+    Ok(data)
+}
+
+impl Sealed for RecordBatch {
+    fn rows(&self, layout: &Layout) -> XGBoostResult<Cow<'_, [f32]>> {
+        to_dense(self, layout).map(Cow::Owned)
+    }
+}
+
+impl Sealed for &RecordBatch {
+    fn rows(&self, layout: &Layout) -> XGBoostResult<Cow<'_, [f32]>> {
+        to_dense(self, layout).map(Cow::Owned)
+    }
+}
+
+/// Predict `batches` and write the `key_columns` of every batch and its
+/// predictions to a Parquet file, one row group per batch
+#[cfg(feature = "parquet")]
+pub(crate) fn sink_parquet<B, I, S>(
+    booster: &B,
+    batches: I,
+    key_columns: &[S],
+    path: &Path,
+    options: PredictOptions,
+) -> XGBoostResult<usize>
+where
+    B: Backend,
+    I: IntoIterator<Item = RecordBatch>,
+    S: AsRef<str>,
+{
+    let layout = Layout {
+        num_features: booster.num_features()?,
+        feature_names: booster.model_feature_names()?,
+    };
+    let write_error = |e: parquet::errors::ParquetError| XGBoostError {
+        description: format!("Failed to write {}: {}", path.display(), e),
+    };
+    let mut file = Some(File::create(path).map_err(|e| XGBoostError {
+        description: format!("Failed to create {}: {}", path.display(), e),
+    })?);
+    // the schema is only known from the first batch
+    let mut writer = None;
+    let mut num_rows = 0;
+    for batch in batches {
+        if batch.num_rows() == 0 {
+            // nothing to write, and no predictions to tell the output width
+            continue;
+        }
+        let data = to_dense(&batch, &layout)?;
+        let predictions =
+            booster.predict_dense(&data, batch.num_rows(), layout.num_features, options)?;
+        let output = output_batch(&batch, key_columns, &predictions)?;
+        let writer = match &mut writer {
+            Some(writer) => writer,
+            None => {
+                // row groups are only closed by flush, so they follow the
+                // input batches however large those are
+                let properties = WriterProperties::builder()
+                    .set_max_row_group_size(usize::MAX)
+                    .build();
+                let file = file.take().expect("the file is only taken once");
+                writer.insert(
+                    ArrowWriter::try_new(file, output.schema(), Some(properties))
+                        .map_err(write_error)?,
+                )
+            }
+        };
+        writer.write(&output).map_err(write_error)?;
+        writer.flush().map_err(write_error)?;
+        num_rows += batch.num_rows();
+    }
+    let writer = match writer {
+        Some(writer) => writer,
+        None => {
+            let schema = Schema::new(vec![Field::new("prediction", DataType::Float32, false)]);
+            let file = file.take().expect("the file is only taken once");
+            ArrowWriter::try_new(file, Arc::new(schema), None).map_err(write_error)?
+        }
+    };
+    writer.close().map_err(write_error)?;
+    Ok(num_rows)
+}
+
+/// The key columns of `batch` followed by one `f32` column per output of
+/// the model: `prediction`, or `prediction_0`, `prediction_1`, … for
+/// multi-output models
+#[cfg(feature = "parquet")]
+fn output_batch<S: AsRef<str>>(
+    batch: &RecordBatch,
+    key_columns: &[S],
+    predictions: &[f32],
+) -> XGBoostResult<RecordBatch> {
+    let schema = batch.schema();
+    let mut fields = Vec::with_capacity(key_columns.len() + 1);
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(key_columns.len() + 1);
+    for name in key_columns {
+        let index = schema.index_of(name.as_ref()).map_err(|_| XGBoostError {
+            description: format!("RecordBatch has no key column '{}'", name.as_ref()),
+        })?;
+        fields.push(schema.field(index).clone());
+        columns.push(Arc::clone(batch.column(index)));
+    }
+
+    let width = predictions.len() / batch.num_rows();
+    for output in 0..width {
+        let name = if width == 1 {
+            "prediction".to_string()
+        } else {
+            format!("prediction_{}", output)
+        };
+        let values: Vec<f32> = predictions
+            .iter()
+            .skip(output)
+            .step_by(width)
+            .copied()
+            .collect();
+        fields.push(Field::new(name, DataType::Float32, false));
+        columns.push(Arc::new(Float32Array::from(values)));
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(|e| XGBoostError {
+        description: format!("Failed to build the output batch: {}", e),
+    })
+}
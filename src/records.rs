@@ -0,0 +1,111 @@
+//! Feature rows from JSON request payloads.
+
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::json::{self, Value};
+use std::collections::HashMap;
+
+/// Lay out JSON records (an array of objects, or one object) as dense rows
+/// with the columns of `feature_order`, one per model feature, returning
+/// the data and row count
+///
+/// Absent and `null` fields are missing values; booleans are 0 or 1 and
+/// numeric strings are parsed. Fields not in `feature_order` are ignored.
+pub(crate) fn to_dense<S: AsRef<str>>(
+    records: &[u8],
+    feature_order: &[S],
+    num_features: usize,
+) -> XGBoostResult<(Vec<f32>, usize)> {
+    let columns = column_index(feature_order, num_features)?;
+    let document = json::parse_json(records)?;
+    let records = match &document {
+        Value::Array(records) => records.as_slice(),
+        Value::Object(_) => std::slice::from_ref(&document),
+        _ => {
+            return Err(XGBoostError {
+                description: "JSON records must be an object or an array of objects".to_string(),
+            })
+        }
+    };
+
+    let mut data = vec![f32::NAN; records.len() * num_features];
+    for (row, record) in records.iter().enumerate() {
+        let fields = record.as_object().ok_or_else(|| XGBoostError {
+            description: format!("JSON record {} is not an object", row),
+        })?;
+        for (name, value) in fields {
+            let Some(&column) = columns.get(name.as_str()) else {
+                continue;
+            };
+            let value = match value {
+                Value::Null => continue,
+                value => value.as_f64().ok_or_else(|| XGBoostError {
+                    description: format!("JSON record {}: field '{}' is not a number", row, name),
+                })?,
+            };
+            data[row * num_features + column] = value as f32;
+        }
+    }
+    Ok((data, records.len()))
+}
+
+/// Lay out parsed `serde_json` records as dense rows, with the same rules
+/// as [`to_dense`]
+#[cfg(feature = "serde_json")]
+pub(crate) fn values_to_dense<S: AsRef<str>>(
+    records: &[serde_json::Value],
+    feature_order: &[S],
+    num_features: usize,
+) -> XGBoostResult<Vec<f32>> {
+    use serde_json::Value;
+
+    let columns = column_index(feature_order, num_features)?;
+    let mut data = vec![f32::NAN; records.len() * num_features];
+    for (row, record) in records.iter().enumerate() {
+        let fields = record.as_object().ok_or_else(|| XGBoostError {
+            description: format!("JSON record {} is not an object", row),
+        })?;
+        for (name, value) in fields {
+            let Some(&column) = columns.get(name.as_str()) else {
+                continue;
+            };
+            let value = match value {
+                Value::Null => continue,
+                Value::Number(n) => n.as_f64(),
+                Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+                Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| XGBoostError {
+                description: format!("JSON record {}: field '{}' is not a number", row, name),
+            })?;
+            data[row * num_features + column] = value as f32;
+        }
+    }
+    Ok(data)
+}
+
+/// Column of each feature name, rejecting names given twice or a name
+/// count other than the model's `num_features`
+fn column_index<S: AsRef<str>>(
+    feature_order: &[S],
+    num_features: usize,
+) -> XGBoostResult<HashMap<&str, usize>> {
+    if feature_order.len() != num_features {
+        return Err(XGBoostError {
+            description: format!(
+                "{} feature names given but the model expects {} features",
+                feature_order.len(),
+                num_features
+            ),
+        });
+    }
+    let mut columns = HashMap::with_capacity(feature_order.len());
+    for (column, name) in feature_order.iter().enumerate() {
+        if columns.insert(name.as_ref(), column).is_some() {
+            return Err(XGBoostError {
+                description: format!("Feature '{}' appears twice in feature_order", name.as_ref()),
+            });
+        }
+    }
+    Ok(columns)
+}
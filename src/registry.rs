@@ -0,0 +1,207 @@
+//! Versioned model sources.
+//!
+//! Serving code written against [`ModelSource`] can move between model
+//! registries without changes: [`FileSystemSource`] reads versions from a
+//! directory and, with the `object_store` feature, `UrlSource` from HTTP
+//! servers and object stores.
+//!
+//! # Example
+//! ```no_run
+//! use xgboost_rust::registry::{FileSystemSource, ModelSource};
+//!
+//! let source = FileSystemSource::new("/models/churn"); // v1.ubj, v2.ubj, ...
+//! let (version, booster) = source.load_latest().unwrap();
+//! println!("serving churn model {}", version);
+//! ```
+
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::Booster;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A store of model versions.
+pub trait ModelSource {
+    /// The newest available version
+    fn latest_version(&self) -> XGBoostResult<String>;
+
+    /// The serialized model (JSON or UBJSON) of a version
+    fn fetch(&self, version: &str) -> XGBoostResult<Vec<u8>>;
+
+    /// Load a version
+    fn load(&self, version: &str) -> XGBoostResult<Booster> {
+        Booster::load_from_buffer(&self.fetch(version)?)
+    }
+
+    /// Load the newest version, returning it with its version
+    fn load_latest(&self) -> XGBoostResult<(String, Booster)> {
+        let version = self.latest_version()?;
+        let booster = self.load(&version)?;
+        Ok((version, booster))
+    }
+}
+
+/// Model files in a directory, one per version, named `<version>.json`
+/// or `<version>.ubj`.
+///
+/// The latest version is the greatest in natural order, comparing runs of
+/// digits as numbers, so `v10` comes after `v9` and dates in `YYYY-MM-DD`
+/// form sort chronologically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystemSource {
+    directory: PathBuf,
+}
+
+impl FileSystemSource {
+    /// Versions stored in `directory`
+    pub fn new<P: AsRef<Path>>(directory: P) -> Self {
+        FileSystemSource {
+            directory: directory.as_ref().to_path_buf(),
+        }
+    }
+
+    /// All versions, oldest first
+    pub fn versions(&self) -> XGBoostResult<Vec<String>> {
+        let entries = fs::read_dir(&self.directory).map_err(|e| XGBoostError {
+            description: format!("Failed to list {}: {}", self.directory.display(), e),
+        })?;
+        let mut versions = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_model = matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("json") | Some("ubj")
+            );
+            if let (true, Some(stem)) = (is_model, path.file_stem().and_then(|s| s.to_str())) {
+                versions.push(stem.to_string());
+            }
+        }
+        versions.sort_by(|a, b| natural_cmp(a, b));
+        versions.dedup();
+        Ok(versions)
+    }
+
+    fn path(&self, version: &str) -> XGBoostResult<PathBuf> {
+        ["ubj", "json"]
+            .iter()
+            .map(|extension| self.directory.join(format!("{}.{}", version, extension)))
+            .find(|path| path.is_file())
+            .ok_or_else(|| XGBoostError {
+                description: format!(
+                    "No model version '{}' in {}",
+                    version,
+                    self.directory.display()
+                ),
+            })
+    }
+}
+
+impl ModelSource for FileSystemSource {
+    fn latest_version(&self) -> XGBoostResult<String> {
+        self.versions()?.pop().ok_or_else(|| XGBoostError {
+            description: format!("No models in {}", self.directory.display()),
+        })
+    }
+
+    fn fetch(&self, version: &str) -> XGBoostResult<Vec<u8>> {
+        let path = self.path(version)?;
+        fs::read(&path).map_err(|e| XGBoostError {
+            description: format!("Failed to read {}: {}", path.display(), e),
+        })
+    }
+}
+
+/// Models behind URLs, e.g. on an HTTP server or in S3.
+///
+/// Version `v` is fetched from the model URL with `{version}` replaced by
+/// `v`; the latest version is the trimmed text at the latest URL (a
+/// pointer file the publisher updates after uploading a model). Any URL
+/// `Booster::load_from_url` understands works.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::registry::{ModelSource, UrlSource};
+///
+/// let source = UrlSource::new(
+///     "https://models.example.com/churn/{version}.ubj",
+///     "https://models.example.com/churn/LATEST",
+/// );
+/// let (version, booster) = source.load_latest().unwrap();
+/// ```
+#[cfg(feature = "object_store")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlSource {
+    model_url: String,
+    latest_url: String,
+}
+
+#[cfg(feature = "object_store")]
+impl UrlSource {
+    /// Models at `model_url` (containing `{version}`), with the latest
+    /// version named at `latest_url`
+    pub fn new(model_url: &str, latest_url: &str) -> Self {
+        UrlSource {
+            model_url: model_url.to_string(),
+            latest_url: latest_url.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "object_store")]
+impl ModelSource for UrlSource {
+    fn latest_version(&self) -> XGBoostResult<String> {
+        let bytes = crate::object_store::fetch(&self.latest_url)?;
+        let version = String::from_utf8(bytes).map_err(|_| XGBoostError {
+            description: format!("{} does not hold a UTF-8 version", self.latest_url),
+        })?;
+        match version.trim() {
+            "" => Err(XGBoostError {
+                description: format!("{} is empty", self.latest_url),
+            }),
+            version => Ok(version.to_string()),
+        }
+    }
+
+    fn fetch(&self, version: &str) -> XGBoostResult<Vec<u8>> {
+        crate::object_store::fetch(&self.model_url.replace("{version}", version))
+    }
+}
+
+/// Compare strings with runs of digits compared by numeric value
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        match (a.chars().next(), b.chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (a_digits, a_rest) = split_digits(a);
+                let (b_digits, b_rest) = split_digits(b);
+                let (a_value, b_value) = (
+                    a_digits.trim_start_matches('0'),
+                    b_digits.trim_start_matches('0'),
+                );
+                let order = a_value
+                    .len()
+                    .cmp(&b_value.len())
+                    .then_with(|| a_value.cmp(b_value));
+                if order != Ordering::Equal {
+                    return order;
+                }
+                (a, b) = (a_rest, b_rest);
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+            }
+        }
+    }
+}
+
+fn split_digits(s: &str) -> (&str, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s.split_at(end)
+}
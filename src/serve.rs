@@ -0,0 +1,633 @@
+//! Building blocks for scoring services.
+//!
+//! [`BatchPredictor`] collects the requests of many concurrent callers
+//! (e.g. HTTP handlers) into batches and predicts each batch with a single
+//! call, which costs little more than predicting a single row. A bounded
+//! queue limits the number of waiting rows, so overload is reported with
+//! [`try_predict`](BatchPredictor::try_predict) instead of building up
+//! latency. Blocking calls fit thread-per-request servers; async servers
+//! call [`submit`](BatchPredictor::submit) and wait for the result on a
+//! blocking task (e.g. tokio's `spawn_blocking`).
+//!
+//! [`PredictorPool`] instead spreads requests over several worker threads,
+//! each predicting with its own copy of the model, for large requests that
+//! gain nothing from batching but would contend on one shared booster.
+//!
+//! # Example
+//! ```no_run
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use xgboost_rust::serve::BatchPredictor;
+//! use xgboost_rust::Booster;
+//!
+//! let booster = Arc::new(Booster::load("model.json").unwrap());
+//! let predictor = Arc::new(
+//!     BatchPredictor::builder(booster)
+//!         .max_batch_rows(256)
+//!         .max_delay(Duration::from_millis(2))
+//!         .max_queued_rows(10_000)
+//!         .build()
+//!         .unwrap(),
+//! );
+//!
+//! // from any number of request threads
+//! let scores = predictor.predict(&[1.0, 2.0], 1).unwrap();
+//! ```
+
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::predict_options::PredictOptions;
+use crate::Booster;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Rows of one caller, waiting to be predicted.
+struct Request {
+    data: Vec<f32>,
+    num_rows: usize,
+    reply: Reply,
+}
+
+/// Where the prediction of a [`Request`] goes
+enum Reply {
+    Channel(Sender<XGBoostResult<Vec<f32>>>),
+    #[cfg(feature = "tower")]
+    Future(service::Completion),
+}
+
+impl Reply {
+    fn send(self, result: XGBoostResult<Vec<f32>>) {
+        match self {
+            // the caller may have given up waiting
+            Reply::Channel(sender) => {
+                let _ = sender.send(result);
+            }
+            #[cfg(feature = "tower")]
+            Reply::Future(completion) => completion.send(result),
+        }
+    }
+}
+
+/// Options of a [`BatchPredictor`].
+#[derive(Clone)]
+pub struct BatchPredictorBuilder {
+    booster: Arc<Booster>,
+    options: PredictOptions,
+    max_batch_rows: usize,
+    max_delay: Duration,
+    max_queued_rows: usize,
+}
+
+impl BatchPredictorBuilder {
+    /// Prediction options applied to every batch (default: normal output)
+    pub fn options(mut self, options: PredictOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Rows at which a batch is predicted without waiting further
+    /// (default 128)
+    pub fn max_batch_rows(mut self, rows: usize) -> Self {
+        self.max_batch_rows = rows.max(1);
+        self
+    }
+
+    /// How long the first request of a batch waits for others (default 1 ms)
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Rows that may wait to be predicted before `try_predict` rejects new
+    /// requests (default 4096); `predict` and `submit` block instead once
+    /// this many requests are waiting
+    pub fn max_queued_rows(mut self, rows: usize) -> Self {
+        self.max_queued_rows = rows.max(1);
+        self
+    }
+
+    /// Start the batching thread
+    pub fn build(self) -> XGBoostResult<BatchPredictor> {
+        let num_features = self.booster.num_features()?;
+        let max_queued_rows = self.max_queued_rows;
+        let (sender, receiver) = mpsc::sync_channel(max_queued_rows);
+        let queued = Arc::new(AtomicUsize::new(0));
+        let worker = {
+            let queued = Arc::clone(&queued);
+            thread::Builder::new()
+                .name("xgboost-batch-predictor".to_string())
+                .spawn(move || run_batches(&self, num_features, &receiver, &queued))
+                .map_err(|e| XGBoostError {
+                    description: format!("Failed to start the batching thread: {}", e),
+                })?
+        };
+        Ok(BatchPredictor {
+            sender: Some(sender),
+            worker: Some(worker),
+            num_features,
+            queued,
+            max_queued_rows,
+        })
+    }
+}
+
+/// Micro-batching front end of a booster shared by many threads.
+///
+/// See the [module documentation](self) for an example.
+pub struct BatchPredictor {
+    sender: Option<SyncSender<Request>>,
+    worker: Option<JoinHandle<()>>,
+    num_features: usize,
+    queued: Arc<AtomicUsize>,
+    max_queued_rows: usize,
+}
+
+/// A prediction in progress, from [`BatchPredictor::submit`].
+pub struct PendingPrediction {
+    receiver: Receiver<XGBoostResult<Vec<f32>>>,
+}
+
+impl PendingPrediction {
+    /// Block until the prediction is done
+    pub fn wait(self) -> XGBoostResult<Vec<f32>> {
+        self.receiver.recv().unwrap_or_else(|_| Err(stopped()))
+    }
+
+    /// The prediction if it is done, without blocking
+    pub fn try_wait(&self) -> Option<XGBoostResult<Vec<f32>>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(stopped())),
+        }
+    }
+}
+
+impl BatchPredictor {
+    /// Batch predictions of `booster`
+    pub fn builder(booster: Arc<Booster>) -> BatchPredictorBuilder {
+        BatchPredictorBuilder {
+            booster,
+            options: PredictOptions::empty(),
+            max_batch_rows: 128,
+            max_delay: Duration::from_millis(1),
+            max_queued_rows: 4096,
+        }
+    }
+
+    /// Predict rows (row-major), waiting for a place in the queue if it is
+    /// full
+    pub fn predict(&self, data: &[f32], num_rows: usize) -> XGBoostResult<Vec<f32>> {
+        self.submit(data, num_rows)?.wait()
+    }
+
+    /// Predict rows, or fail immediately if the queue is full so the
+    /// caller can shed load (e.g. answer HTTP 503)
+    pub fn try_predict(&self, data: &[f32], num_rows: usize) -> XGBoostResult<Vec<f32>> {
+        let (request, pending) = channel_request(data, num_rows, self.num_features)?;
+        self.try_enqueue(request)?;
+        pending.wait()
+    }
+
+    /// Queue rows for prediction without waiting for the result
+    pub fn submit(&self, data: &[f32], num_rows: usize) -> XGBoostResult<PendingPrediction> {
+        let (request, pending) = channel_request(data, num_rows, self.num_features)?;
+        self.queued.fetch_add(num_rows, Ordering::SeqCst);
+        if self.sender().send(request).is_err() {
+            self.queued.fetch_sub(num_rows, Ordering::SeqCst);
+            return Err(stopped());
+        }
+        Ok(pending)
+    }
+
+    /// Number of features of each row
+    pub fn num_features(&self) -> usize {
+        self.num_features
+    }
+
+    /// Rows waiting to be predicted
+    pub fn queued_rows(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    /// Queue a request unless `max_queued_rows` rows are already waiting
+    fn try_enqueue(&self, request: Request) -> XGBoostResult<()> {
+        let num_rows = request.num_rows;
+        if self.queued.fetch_add(num_rows, Ordering::SeqCst) + num_rows > self.max_queued_rows {
+            self.queued.fetch_sub(num_rows, Ordering::SeqCst);
+            return Err(overloaded());
+        }
+        self.sender().try_send(request).map_err(|error| {
+            self.queued.fetch_sub(num_rows, Ordering::SeqCst);
+            match error {
+                TrySendError::Full(_) => overloaded(),
+                TrySendError::Disconnected(_) => stopped(),
+            }
+        })
+    }
+
+    fn sender(&self) -> &SyncSender<Request> {
+        self.sender.as_ref().expect("sender is only taken on drop")
+    }
+}
+
+impl Drop for BatchPredictor {
+    fn drop(&mut self) {
+        // disconnecting the queue stops the worker once it is drained
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Check the shape of rows and queue them with a reply channel
+fn channel_request(
+    data: &[f32],
+    num_rows: usize,
+    num_features: usize,
+) -> XGBoostResult<(Request, PendingPrediction)> {
+    check_shape(data, num_rows, num_features)?;
+    let (reply, receiver) = mpsc::channel();
+    let request = Request {
+        data: data.to_vec(),
+        num_rows,
+        reply: Reply::Channel(reply),
+    };
+    Ok((request, PendingPrediction { receiver }))
+}
+
+fn check_shape(data: &[f32], num_rows: usize, num_features: usize) -> XGBoostResult<()> {
+    if num_rows.checked_mul(num_features) != Some(data.len()) {
+        return Err(XGBoostError {
+            description: format!(
+                "Data length mismatch: expected {}×{} elements, got {}",
+                num_rows,
+                num_features,
+                data.len()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Worker loop: gather requests into batches until the queue disconnects
+fn run_batches(
+    config: &BatchPredictorBuilder,
+    num_features: usize,
+    receiver: &Receiver<Request>,
+    queued: &AtomicUsize,
+) {
+    while let Ok(first) = receiver.recv() {
+        let deadline = Instant::now() + config.max_delay;
+        let mut rows = first.num_rows;
+        let mut batch = vec![first];
+        while rows < config.max_batch_rows {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match receiver.recv_timeout(timeout) {
+                Ok(request) => {
+                    rows += request.num_rows;
+                    batch.push(request);
+                }
+                Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        queued.fetch_sub(rows, Ordering::SeqCst);
+
+        let mut data = Vec::with_capacity(rows * num_features);
+        for request in &batch {
+            data.extend_from_slice(&request.data);
+        }
+        match config
+            .booster
+            .predict(&data, rows, num_features, config.options, false)
+        {
+            Ok(predictions) => {
+                let width = predictions.len().checked_div(rows).unwrap_or(0);
+                let mut offset = 0;
+                for request in batch {
+                    let len = request.num_rows * width;
+                    request
+                        .reply
+                        .send(Ok(predictions[offset..offset + len].to_vec()));
+                    offset += len;
+                }
+            }
+            Err(error) => {
+                for request in batch {
+                    request.reply.send(Err(XGBoostError {
+                        description: error.description.clone(),
+                    }));
+                }
+            }
+        }
+    }
+}
+
+/// Options of a [`PredictorPool`].
+#[derive(Clone)]
+pub struct PredictorPoolBuilder {
+    model: Vec<u8>,
+    options: PredictOptions,
+    workers: usize,
+    max_queued_requests: usize,
+}
+
+impl PredictorPoolBuilder {
+    /// Prediction options applied to every request (default: normal output)
+    pub fn options(mut self, options: PredictOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Number of worker threads, each with its own booster (default: the
+    /// available parallelism)
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Requests that may wait in each worker's queue (default 64)
+    pub fn max_queued_requests(mut self, requests: usize) -> Self {
+        self.max_queued_requests = requests.max(1);
+        self
+    }
+
+    /// Load a booster for every worker and start the workers
+    pub fn build(self) -> XGBoostResult<PredictorPool> {
+        let mut senders = Vec::with_capacity(self.workers);
+        let mut workers = Vec::with_capacity(self.workers);
+        let mut num_features = 0;
+        for index in 0..self.workers {
+            let booster = Booster::load_from_buffer(&self.model)?;
+            num_features = booster.num_features()?;
+            let (sender, receiver) = mpsc::sync_channel::<Request>(self.max_queued_requests);
+            let options = self.options;
+            let handle = thread::Builder::new()
+                .name(format!("xgboost-predictor-{}", index))
+                .spawn(move || {
+                    while let Ok(request) = receiver.recv() {
+                        let result = booster.predict(
+                            &request.data,
+                            request.num_rows,
+                            num_features,
+                            options,
+                            false,
+                        );
+                        request.reply.send(result);
+                    }
+                })
+                .map_err(|e| XGBoostError {
+                    description: format!("Failed to start a prediction worker: {}", e),
+                })?;
+            senders.push(sender);
+            workers.push(handle);
+        }
+        Ok(PredictorPool {
+            senders,
+            workers,
+            next: AtomicUsize::new(0),
+            num_features,
+        })
+    }
+}
+
+/// Worker threads that each own a copy of a model.
+///
+/// Requests are handed to the workers in turn, each through its own
+/// bounded queue, so concurrent callers never share a booster. This uses
+/// one model's memory per worker; prefer one shared [`Booster`] or a
+/// [`BatchPredictor`] when requests are small.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::serve::PredictorPool;
+///
+/// let pool = PredictorPool::builder(std::fs::read("model.ubj").unwrap())
+///     .workers(8)
+///     .build()
+///     .unwrap();
+///
+/// // from any number of request threads
+/// let scores = pool.predict(&[1.0, 2.0], 1).unwrap();
+///
+/// // or without blocking the caller
+/// let pending = pool.submit(&[1.0, 2.0], 1).unwrap();
+/// let scores = pending.wait().unwrap();
+/// ```
+pub struct PredictorPool {
+    senders: Vec<SyncSender<Request>>,
+    workers: Vec<JoinHandle<()>>,
+    next: AtomicUsize,
+    num_features: usize,
+}
+
+impl PredictorPool {
+    /// A pool predicting with the serialized model `model` (JSON or
+    /// UBJSON)
+    pub fn builder(model: Vec<u8>) -> PredictorPoolBuilder {
+        PredictorPoolBuilder {
+            model,
+            options: PredictOptions::empty(),
+            workers: thread::available_parallelism().map_or(1, |n| n.get()),
+            max_queued_requests: 64,
+        }
+    }
+
+    /// A pool with default options predicting with the model file at `path`
+    pub fn load<P: AsRef<Path>>(path: P) -> XGBoostResult<Self> {
+        let path = path.as_ref();
+        let model = fs::read(path).map_err(|e| XGBoostError {
+            description: format!("Failed to read {}: {}", path.display(), e),
+        })?;
+        Self::builder(model).build()
+    }
+
+    /// Predict rows (row-major), waiting for a place in a queue if the
+    /// next worker's is full
+    pub fn predict(&self, data: &[f32], num_rows: usize) -> XGBoostResult<Vec<f32>> {
+        self.submit(data, num_rows)?.wait()
+    }
+
+    /// Predict rows, or fail immediately if every worker's queue is full
+    /// so the caller can shed load
+    pub fn try_predict(&self, data: &[f32], num_rows: usize) -> XGBoostResult<Vec<f32>> {
+        let (mut request, pending) = channel_request(data, num_rows, self.num_features)?;
+        let first = self.next.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..self.senders.len() {
+            match self.sender(first + offset).try_send(request) {
+                Ok(()) => return pending.wait(),
+                Err(TrySendError::Full(returned)) => request = returned,
+                Err(TrySendError::Disconnected(_)) => return Err(stopped()),
+            }
+        }
+        Err(overloaded())
+    }
+
+    /// Queue rows on the next worker without waiting for the result
+    pub fn submit(&self, data: &[f32], num_rows: usize) -> XGBoostResult<PendingPrediction> {
+        let (request, pending) = channel_request(data, num_rows, self.num_features)?;
+        let worker = self.next.fetch_add(1, Ordering::Relaxed);
+        self.sender(worker).send(request).map_err(|_| stopped())?;
+        Ok(pending)
+    }
+
+    /// Number of worker threads
+    pub fn workers(&self) -> usize {
+        self.senders.len()
+    }
+
+    fn sender(&self, worker: usize) -> &SyncSender<Request> {
+        &self.senders[worker % self.senders.len()]
+    }
+}
+
+impl Drop for PredictorPool {
+    fn drop(&mut self) {
+        // disconnect every queue before joining so the workers drain in
+        // parallel
+        self.senders.clear();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn overloaded() -> XGBoostError {
+    XGBoostError {
+        description: "Prediction queue is full".to_string(),
+    }
+}
+
+fn stopped() -> XGBoostError {
+    XGBoostError {
+        description: "The prediction thread has stopped".to_string(),
+    }
+}
+
+#[cfg(feature = "tower")]
+mod service {
+    use super::{check_shape, stopped, BatchPredictor, Reply, Request};
+    use crate::error::{XGBoostError, XGBoostResult};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Waker};
+
+    /// Result of a request and the task waiting for it
+    #[derive(Default)]
+    struct Slot {
+        state: Mutex<SlotState>,
+    }
+
+    #[derive(Default)]
+    struct SlotState {
+        result: Option<XGBoostResult<Vec<f32>>>,
+        waker: Option<Waker>,
+    }
+
+    /// Worker side of a [`PredictionFuture`]; dropping it unfulfilled
+    /// (the worker stopped) fails the future instead of leaving it pending
+    pub(super) struct Completion(Option<Arc<Slot>>);
+
+    impl Completion {
+        pub(super) fn send(mut self, result: XGBoostResult<Vec<f32>>) {
+            self.complete(result);
+        }
+
+        fn complete(&mut self, result: XGBoostResult<Vec<f32>>) {
+            let Some(slot) = self.0.take() else {
+                return;
+            };
+            let mut state = slot.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    impl Drop for Completion {
+        fn drop(&mut self) {
+            self.complete(Err(stopped()));
+        }
+    }
+
+    /// A prediction queued through the [`tower::Service`] implementation
+    /// of [`BatchPredictor`].
+    pub struct PredictionFuture(State);
+
+    enum State {
+        Failed(Option<XGBoostError>),
+        Queued(Arc<Slot>),
+    }
+
+    impl Future for PredictionFuture {
+        type Output = XGBoostResult<Vec<f32>>;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            match &mut self.0 {
+                State::Failed(error) => Poll::Ready(Err(error.take().unwrap_or_else(stopped))),
+                State::Queued(slot) => {
+                    let mut state = slot.state.lock().unwrap_or_else(|e| e.into_inner());
+                    match state.result.take() {
+                        Some(result) => Poll::Ready(result),
+                        None => {
+                            state.waker = Some(cx.waker().clone());
+                            Poll::Pending
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rows (row-major) to predict through the [`tower::Service`]
+    /// implementation of [`BatchPredictor`].
+    pub struct PredictRequest {
+        /// `num_rows` rows of `num_features` values
+        pub data: Vec<f32>,
+        /// Number of rows
+        pub num_rows: usize,
+    }
+
+    /// Predicts without blocking the executor.
+    ///
+    /// The service is always ready: like
+    /// [`try_predict`](BatchPredictor::try_predict), a call fails with an
+    /// overload error once `max_queued_rows` rows are waiting, so the
+    /// server can answer 503 instead of queueing.
+    impl tower::Service<PredictRequest> for Arc<BatchPredictor> {
+        type Response = Vec<f32>;
+        type Error = XGBoostError;
+        type Future = PredictionFuture;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: PredictRequest) -> Self::Future {
+            let PredictRequest { data, num_rows } = request;
+            if let Err(error) = check_shape(&data, num_rows, self.num_features) {
+                return PredictionFuture(State::Failed(Some(error)));
+            }
+            let slot = Arc::new(Slot::default());
+            let request = Request {
+                data,
+                num_rows,
+                reply: Reply::Future(Completion(Some(Arc::clone(&slot)))),
+            };
+            match self.try_enqueue(request) {
+                Ok(()) => PredictionFuture(State::Queued(slot)),
+                Err(error) => PredictionFuture(State::Failed(Some(error))),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tower")]
+pub use service::{PredictRequest, PredictionFuture};
@@ -0,0 +1,295 @@
+//! Shadow scoring of a candidate model next to the production model.
+//!
+//! [`ShadowPredictor`] answers every request with the primary model and
+//! also scores it with a candidate, handing both outputs to a
+//! [`ShadowRecorder`]. [`ShadowStats`] summarizes how far the candidate
+//! diverges and [`PairWriter`] logs the paired outputs for offline
+//! comparison before the candidate is promoted. Candidate failures never
+//! reach the caller.
+//!
+//! # Example
+//! ```no_run
+//! use std::fs::File;
+//! use std::sync::Arc;
+//! use xgboost_rust::shadow::{PairWriter, ShadowPredictor, ShadowStats};
+//! use xgboost_rust::{Booster, PredictOptions};
+//!
+//! let stats = Arc::new(ShadowStats::new(0.01));
+//! let pairs = PairWriter::new(File::create("pairs.csv").unwrap()).unwrap();
+//! let shadow = ShadowPredictor::new(
+//!     Booster::load("v7.json").unwrap(),
+//!     Booster::load("v8.json").unwrap(),
+//!     (Arc::clone(&stats), pairs),
+//! );
+//!
+//! let predictions = shadow.predict(&[1.0, 2.0], 1, 2, PredictOptions::empty()).unwrap();
+//! println!("mean |v8 - v7| = {}", stats.mean_abs_diff());
+//! ```
+
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::predict_options::PredictOptions;
+use crate::Booster;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Receives the primary and candidate outputs of every shadowed request.
+pub trait ShadowRecorder {
+    /// Both models scored request number `request` of `num_rows` rows;
+    /// `primary` and `candidate` have the same length
+    fn record(&self, request: u64, num_rows: usize, primary: &[f32], candidate: &[f32]);
+
+    /// The candidate failed on request number `request`
+    fn record_candidate_error(&self, _request: u64, _error: &XGBoostError) {}
+}
+
+impl<R: ShadowRecorder + ?Sized> ShadowRecorder for Arc<R> {
+    fn record(&self, request: u64, num_rows: usize, primary: &[f32], candidate: &[f32]) {
+        (**self).record(request, num_rows, primary, candidate)
+    }
+
+    fn record_candidate_error(&self, request: u64, error: &XGBoostError) {
+        (**self).record_candidate_error(request, error)
+    }
+}
+
+impl<A: ShadowRecorder, B: ShadowRecorder> ShadowRecorder for (A, B) {
+    fn record(&self, request: u64, num_rows: usize, primary: &[f32], candidate: &[f32]) {
+        self.0.record(request, num_rows, primary, candidate);
+        self.1.record(request, num_rows, primary, candidate);
+    }
+
+    fn record_candidate_error(&self, request: u64, error: &XGBoostError) {
+        self.0.record_candidate_error(request, error);
+        self.1.record_candidate_error(request, error);
+    }
+}
+
+/// A primary model serving requests and a candidate scored on the side.
+///
+/// The candidate runs after the primary on the calling thread, adding its
+/// latency to shadowed requests; use [`ShadowPredictor::sample_every`] to
+/// shadow only some of them.
+pub struct ShadowPredictor<R> {
+    primary: Booster,
+    candidate: Booster,
+    recorder: R,
+    sample_every: u64,
+    requests: AtomicU64,
+}
+
+impl<R: ShadowRecorder> ShadowPredictor<R> {
+    /// Serve `primary`, shadowed by `candidate`, reporting to `recorder`
+    pub fn new(primary: Booster, candidate: Booster, recorder: R) -> Self {
+        ShadowPredictor {
+            primary,
+            candidate,
+            recorder,
+            sample_every: 1,
+            requests: AtomicU64::new(0),
+        }
+    }
+
+    /// Score only every `n`th request with the candidate (default every
+    /// request)
+    pub fn sample_every(mut self, n: u64) -> Self {
+        self.sample_every = n.max(1);
+        self
+    }
+
+    /// The model whose predictions are returned
+    pub fn primary(&self) -> &Booster {
+        &self.primary
+    }
+
+    /// The shadowed model
+    pub fn candidate(&self) -> &Booster {
+        &self.candidate
+    }
+
+    /// The recorder
+    pub fn recorder(&self) -> &R {
+        &self.recorder
+    }
+
+    /// The models and the recorder: `(primary, candidate, recorder)`
+    pub fn into_inner(self) -> (Booster, Booster, R) {
+        (self.primary, self.candidate, self.recorder)
+    }
+
+    /// Predict with the primary model, recording the candidate's
+    /// predictions for the same rows
+    ///
+    /// Only a failure of the primary is returned.
+    pub fn predict(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        options: PredictOptions,
+    ) -> XGBoostResult<Vec<f32>> {
+        let primary = self
+            .primary
+            .predict(data, num_rows, num_features, options, false)?;
+        let request = self.requests.fetch_add(1, Ordering::Relaxed);
+        if request % self.sample_every != 0 {
+            return Ok(primary);
+        }
+        match self
+            .candidate
+            .predict(data, num_rows, num_features, options, false)
+        {
+            Ok(candidate) if candidate.len() == primary.len() => self
+                .recorder
+                .record(request, num_rows, &primary, &candidate),
+            Ok(candidate) => self.recorder.record_candidate_error(
+                request,
+                &XGBoostError {
+                    description: format!(
+                        "Candidate produced {} outputs but the primary produced {}",
+                        candidate.len(),
+                        primary.len()
+                    ),
+                },
+            ),
+            Err(e) => self.recorder.record_candidate_error(request, &e),
+        }
+        Ok(primary)
+    }
+}
+
+/// Running summary of the differences between candidate and primary
+/// outputs, safe to update from many threads.
+#[derive(Debug)]
+pub struct ShadowStats {
+    tolerance: f64,
+    inner: Mutex<Divergence>,
+}
+
+#[derive(Debug, Default)]
+struct Divergence {
+    requests: u64,
+    outputs: u64,
+    sum_abs: f64,
+    sum_squared: f64,
+    max_abs: f64,
+    over_tolerance: u64,
+    candidate_errors: u64,
+}
+
+impl ShadowStats {
+    /// Statistics counting outputs that differ by more than `tolerance`
+    pub fn new(tolerance: f64) -> Self {
+        ShadowStats {
+            tolerance,
+            inner: Mutex::new(Divergence::default()),
+        }
+    }
+
+    fn read<T>(&self, f: impl FnOnce(&Divergence) -> T) -> T {
+        f(&self.inner.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Requests scored by both models
+    pub fn requests(&self) -> u64 {
+        self.read(|d| d.requests)
+    }
+
+    /// Outputs compared
+    pub fn outputs(&self) -> u64 {
+        self.read(|d| d.outputs)
+    }
+
+    /// Requests the candidate failed on
+    pub fn candidate_errors(&self) -> u64 {
+        self.read(|d| d.candidate_errors)
+    }
+
+    /// Mean absolute difference of the outputs (0 before the first)
+    pub fn mean_abs_diff(&self) -> f64 {
+        self.read(|d| d.sum_abs / d.outputs.max(1) as f64)
+    }
+
+    /// Root mean squared difference of the outputs
+    pub fn rms_diff(&self) -> f64 {
+        self.read(|d| (d.sum_squared / d.outputs.max(1) as f64).sqrt())
+    }
+
+    /// Largest absolute difference of an output
+    pub fn max_abs_diff(&self) -> f64 {
+        self.read(|d| d.max_abs)
+    }
+
+    /// Fraction of outputs differing by more than the tolerance
+    pub fn over_tolerance_fraction(&self) -> f64 {
+        self.read(|d| d.over_tolerance as f64 / d.outputs.max(1) as f64)
+    }
+}
+
+impl ShadowRecorder for ShadowStats {
+    fn record(&self, _request: u64, _num_rows: usize, primary: &[f32], candidate: &[f32]) {
+        let mut d = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        d.requests += 1;
+        for (&p, &c) in primary.iter().zip(candidate) {
+            let diff = (f64::from(c) - f64::from(p)).abs();
+            d.outputs += 1;
+            d.sum_abs += diff;
+            d.sum_squared += diff * diff;
+            d.max_abs = d.max_abs.max(diff);
+            d.over_tolerance += u64::from(diff > self.tolerance);
+        }
+    }
+
+    fn record_candidate_error(&self, _request: u64, _error: &XGBoostError) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .candidate_errors += 1;
+    }
+}
+
+/// Writes paired outputs as CSV lines `request,row,output,primary,candidate`,
+/// one per output, and candidate failures as lines with only the request
+/// number.
+pub struct PairWriter<W> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write> PairWriter<W> {
+    /// Write the CSV header to `writer`
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writeln!(writer, "request,row,output,primary,candidate")?;
+        Ok(PairWriter {
+            writer: Mutex::new(writer),
+        })
+    }
+
+    /// The writer
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<W: Write> ShadowRecorder for PairWriter<W> {
+    fn record(&self, request: u64, num_rows: usize, primary: &[f32], candidate: &[f32]) {
+        let num_outputs = primary.len() / num_rows.max(1);
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        for (i, (p, c)) in primary.iter().zip(candidate).enumerate() {
+            // a failing log must not fail the request
+            let _ = writeln!(
+                writer,
+                "{},{},{},{},{}",
+                request,
+                i / num_outputs.max(1),
+                i % num_outputs.max(1),
+                p,
+                c
+            );
+        }
+    }
+
+    fn record_candidate_error(&self, request: u64, _error: &XGBoostError) {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = writeln!(writer, "{},,,,", request);
+    }
+}
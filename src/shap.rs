@@ -0,0 +1,396 @@
+//! Global explanations aggregated from SHAP values.
+//!
+//! [`Contributions`] wraps the output of a prediction with
+//! [`PredictOptions::PRED_CONTRIBS`]: for every row (and output group of a
+//! multi-class model) one value per feature plus the bias. From it come
+//! the mean |SHAP| importance of each feature, a ranking of the features,
+//! and per-feature summaries of how the SHAP value moves with the feature
+//! value — the numbers behind a SHAP summary or dependence plot.
+//! [`Interactions`] does the same for `PRED_INTERACTIONS` output and ranks
+//! the feature pairs that interact most, candidates for engineered
+//! features.
+//!
+//! [`PredictOptions::PRED_CONTRIBS`]: crate::PredictOptions::PRED_CONTRIBS
+//!
+//! # Example
+//! ```no_run
+//! use xgboost_rust::shap::Contributions;
+//! use xgboost_rust::{Booster, PredictOptions};
+//!
+//! let booster = Booster::load("model.json").unwrap();
+//! let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]; // 3 rows, 2 features
+//! let shap = booster.predict(&data, 3, 2, PredictOptions::PRED_CONTRIBS, false).unwrap();
+//!
+//! let contributions = Contributions::new(&shap, 3, 2).unwrap();
+//! for (feature, importance) in contributions.ranking() {
+//!     println!("f{}: {}", feature, importance);
+//! }
+//! let summary = contributions.summary(&data, 0, 0, 10).unwrap();
+//! println!("f0: correlation of value and SHAP {}", summary.correlation);
+//! ```
+
+use crate::error::{XGBoostError, XGBoostResult};
+use std::borrow::Cow;
+
+/// Per-row SHAP values of a prediction with `PRED_CONTRIBS`, borrowed or,
+/// from `Booster::predict_contributions`, owned.
+#[derive(Debug, Clone)]
+pub struct Contributions<'a> {
+    values: Cow<'a, [f32]>,
+    num_rows: usize,
+    num_features: usize,
+    num_groups: usize,
+}
+
+/// How the SHAP value of one feature depends on its value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureSummary {
+    /// Feature index
+    pub feature: usize,
+    /// Mean |SHAP| over all rows
+    pub mean_abs: f64,
+    /// Pearson correlation of the feature value and its SHAP value over
+    /// rows where it is present; positive when higher values push
+    /// predictions up (NaN if either is constant)
+    pub correlation: f64,
+    /// Present values in quantile bins, in increasing order of value
+    pub bins: Vec<ShapBin>,
+    /// Rows missing the feature
+    pub missing_rows: usize,
+    /// Mean SHAP value of the rows missing the feature (NaN if none)
+    pub missing_mean: f64,
+}
+
+/// Rows whose feature value falls in `[low, high]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapBin {
+    /// Smallest value in the bin
+    pub low: f32,
+    /// Largest value in the bin
+    pub high: f32,
+    /// Number of rows
+    pub rows: usize,
+    /// Mean feature value
+    pub mean_value: f64,
+    /// Mean SHAP value
+    pub mean_shap: f64,
+}
+
+impl<'a> Contributions<'a> {
+    /// Wrap `values` for `num_rows` rows of a model with `num_features`
+    /// features; the number of output groups follows from the length
+    pub fn new(values: &'a [f32], num_rows: usize, num_features: usize) -> XGBoostResult<Self> {
+        Self::from_cow(Cow::Borrowed(values), num_rows, num_features)
+    }
+
+    /// Like [`Contributions::new`], taking ownership of `values`
+    pub fn from_vec(
+        values: Vec<f32>,
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Contributions<'static>> {
+        Contributions::from_cow(Cow::Owned(values), num_rows, num_features)
+    }
+
+    fn from_cow(
+        values: Cow<'a, [f32]>,
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Self> {
+        let row_len = num_rows * (num_features + 1);
+        if row_len == 0 || values.len() % row_len != 0 {
+            return Err(XGBoostError {
+                description: format!(
+                    "{} contributions do not fit {} rows of {} features plus bias",
+                    values.len(),
+                    num_rows,
+                    num_features
+                ),
+            });
+        }
+        Ok(Contributions {
+            num_groups: values.len() / row_len,
+            values,
+            num_rows,
+            num_features,
+        })
+    }
+
+    /// Number of rows
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Number of features
+    pub fn num_features(&self) -> usize {
+        self.num_features
+    }
+
+    /// Number of output groups (classes of a multi-class model, else 1)
+    pub fn num_groups(&self) -> usize {
+        self.num_groups
+    }
+
+    /// The raw values: `num_features + 1` per row and output group, the
+    /// bias last
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// SHAP value of `feature` for `row` and output `group`
+    pub fn get(&self, row: usize, group: usize, feature: usize) -> f32 {
+        self.values[(row * self.num_groups + group) * (self.num_features + 1) + feature]
+    }
+
+    /// Bias (expected value) term for `row` and output `group`
+    pub fn bias(&self, row: usize, group: usize) -> f32 {
+        self.get(row, group, self.num_features)
+    }
+
+    /// Mean |SHAP| of every feature, summed over output groups
+    pub fn mean_abs(&self) -> Vec<f64> {
+        let mut totals = vec![0.0; self.num_features];
+        for row in self.values.chunks(self.num_features + 1) {
+            for (total, &value) in totals.iter_mut().zip(row) {
+                *total += f64::from(value.abs());
+            }
+        }
+        for total in &mut totals {
+            *total /= self.num_rows as f64;
+        }
+        totals
+    }
+
+    /// `(feature, mean |SHAP|)` for every feature, most important first
+    pub fn ranking(&self) -> Vec<(usize, f64)> {
+        let mut ranking: Vec<(usize, f64)> = self.mean_abs().into_iter().enumerate().collect();
+        ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranking
+    }
+
+    /// Summarize the SHAP values of `feature` for output `group` against
+    /// its values in `data`, the row-major rows that were explained, in up
+    /// to `bins` quantile bins
+    ///
+    /// Rows with equal values always share a bin, so there may be fewer.
+    pub fn summary(
+        &self,
+        data: &[f32],
+        feature: usize,
+        group: usize,
+        bins: usize,
+    ) -> XGBoostResult<FeatureSummary> {
+        if data.len() != self.num_rows * self.num_features {
+            return Err(XGBoostError {
+                description: format!(
+                    "Data has {} values but the contributions are for {}×{}",
+                    data.len(),
+                    self.num_rows,
+                    self.num_features
+                ),
+            });
+        }
+        if feature >= self.num_features || group >= self.num_groups {
+            return Err(XGBoostError {
+                description: format!(
+                    "Feature {} of group {} is out of range ({} features, {} groups)",
+                    feature, group, self.num_features, self.num_groups
+                ),
+            });
+        }
+
+        let mut present: Vec<(f32, f32)> = Vec::with_capacity(self.num_rows);
+        let mut missing_total = 0.0;
+        let mut abs_total = 0.0;
+        for row in 0..self.num_rows {
+            let shap = self.get(row, group, feature);
+            abs_total += f64::from(shap.abs());
+            let value = data[row * self.num_features + feature];
+            if value.is_nan() {
+                missing_total += f64::from(shap);
+            } else {
+                present.push((value, shap));
+            }
+        }
+        let missing_rows = self.num_rows - present.len();
+        present.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Ok(FeatureSummary {
+            feature,
+            mean_abs: abs_total / self.num_rows as f64,
+            correlation: correlation(&present),
+            bins: quantile_bins(&present, bins.max(1)),
+            missing_rows,
+            missing_mean: if missing_rows == 0 {
+                f64::NAN
+            } else {
+                missing_total / missing_rows as f64
+            },
+        })
+    }
+}
+
+/// Split `(value, shap)` pairs sorted by value into bins of about equal
+/// size, never separating equal values
+fn quantile_bins(sorted: &[(f32, f32)], bins: usize) -> Vec<ShapBin> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    for bin in 1..=bins {
+        let mut end = (sorted.len() * bin / bins).max(start);
+        while end < sorted.len() && end > 0 && sorted[end].0 == sorted[end - 1].0 {
+            end += 1;
+        }
+        if end == start {
+            continue;
+        }
+        let pairs = &sorted[start..end];
+        let n = pairs.len() as f64;
+        out.push(ShapBin {
+            low: pairs[0].0,
+            high: pairs[pairs.len() - 1].0,
+            rows: pairs.len(),
+            mean_value: pairs.iter().map(|p| f64::from(p.0)).sum::<f64>() / n,
+            mean_shap: pairs.iter().map(|p| f64::from(p.1)).sum::<f64>() / n,
+        });
+        start = end;
+    }
+    out
+}
+
+fn correlation(pairs: &[(f32, f32)]) -> f64 {
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|p| f64::from(p.0)).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|p| f64::from(p.1)).sum::<f64>() / n;
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for &(x, y) in pairs {
+        let (dx, dy) = (f64::from(x) - mean_x, f64::from(y) - mean_y);
+        sxy += dx * dy;
+        sxx += dx * dx;
+        syy += dy * dy;
+    }
+    sxy / (sxx * syy).sqrt()
+}
+
+/// Per-row SHAP interaction values of a prediction with
+/// `PRED_INTERACTIONS`.
+///
+/// For every row (and output group) a `(num_features + 1)²` matrix whose
+/// off-diagonal entries split each pairwise interaction in half between
+/// `(i, j)` and `(j, i)`; the last row and column hold the bias.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::shap::Interactions;
+/// use xgboost_rust::{Booster, PredictOptions};
+///
+/// let booster = Booster::load("model.json").unwrap();
+/// let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]; // 2 rows, 3 features
+/// let values = booster.predict(&data, 2, 3, PredictOptions::PRED_INTERACTIONS, false).unwrap();
+///
+/// let interactions = Interactions::new(&values, 2, 3).unwrap();
+/// for pair in interactions.top_pairs(5) {
+///     println!("f{} × f{}: {}", pair.first, pair.second, pair.strength);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Interactions<'a> {
+    values: &'a [f32],
+    num_rows: usize,
+    num_features: usize,
+    num_groups: usize,
+}
+
+/// Interaction strength of two features.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeaturePair {
+    /// Index of the first feature
+    pub first: usize,
+    /// Index of the second feature, greater than `first`
+    pub second: usize,
+    /// Mean over rows of the absolute total interaction `|φᵢⱼ + φⱼᵢ|`,
+    /// summed over output groups
+    pub strength: f64,
+}
+
+impl<'a> Interactions<'a> {
+    /// Wrap `values` for `num_rows` rows of a model with `num_features`
+    /// features; the number of output groups follows from the length
+    pub fn new(values: &'a [f32], num_rows: usize, num_features: usize) -> XGBoostResult<Self> {
+        let side = num_features + 1;
+        let row_len = num_rows * side * side;
+        if row_len == 0 || values.len() % row_len != 0 {
+            return Err(XGBoostError {
+                description: format!(
+                    "{} interaction values do not fit {} rows of {}×{} matrices",
+                    values.len(),
+                    num_rows,
+                    side,
+                    side
+                ),
+            });
+        }
+        Ok(Interactions {
+            values,
+            num_rows,
+            num_features,
+            num_groups: values.len() / row_len,
+        })
+    }
+
+    /// Number of rows
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Number of output groups (classes of a multi-class model, else 1)
+    pub fn num_groups(&self) -> usize {
+        self.num_groups
+    }
+
+    /// Interaction value `(first, second)` for `row` and output `group`;
+    /// `(i, i)` is the main effect of feature `i`
+    pub fn get(&self, row: usize, group: usize, first: usize, second: usize) -> f32 {
+        let side = self.num_features + 1;
+        self.values[((row * self.num_groups + group) * side + first) * side + second]
+    }
+
+    /// Mean absolute total interaction of every pair, as a row-major
+    /// `num_features × num_features` matrix (symmetric; the diagonal holds
+    /// the mean |main effect|)
+    pub fn mean_abs(&self) -> Vec<f64> {
+        let (f, side) = (self.num_features, self.num_features + 1);
+        let mut totals = vec![0.0; f * f];
+        for matrix in self.values.chunks(side * side) {
+            for i in 0..f {
+                totals[i * f + i] += f64::from(matrix[i * side + i].abs());
+                for j in i + 1..f {
+                    let total = f64::from((matrix[i * side + j] + matrix[j * side + i]).abs());
+                    totals[i * f + j] += total;
+                    totals[j * f + i] += total;
+                }
+            }
+        }
+        for total in &mut totals {
+            *total /= self.num_rows as f64;
+        }
+        totals
+    }
+
+    /// The `k` feature pairs with the strongest interactions, strongest
+    /// first
+    pub fn top_pairs(&self, k: usize) -> Vec<FeaturePair> {
+        let f = self.num_features;
+        let mean_abs = self.mean_abs();
+        let mut pairs: Vec<FeaturePair> = (0..f)
+            .flat_map(|first| (first + 1..f).map(move |second| (first, second)))
+            .map(|(first, second)| FeaturePair {
+                first,
+                second,
+                strength: mean_abs[first * f + second],
+            })
+            .collect();
+        pairs.sort_by(|a, b| b.strength.total_cmp(&a.strength));
+        pairs.truncate(k);
+        pairs
+    }
+}
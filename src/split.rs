@@ -0,0 +1,429 @@
+//! Splitting data into training and validation sets.
+//!
+//! Splits are sets of row indices, so the same split can be applied to the
+//! features, labels, weights or any other per-row data. Use
+//! [`stratified_split`] for imbalanced classification data and
+//! [`TimeSeriesSplit`] for forecasting, where validation data must come after
+//! the training data.
+//!
+//! # Example
+//! ```no_run
+//! use xgboost_rust::split::train_test_split;
+//! use xgboost_rust::DMatrix;
+//!
+//! let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]; // 4 rows, 2 features
+//! let labels = vec![0.0, 1.0, 0.0, 1.0];
+//!
+//! let split = train_test_split(4, 0.25, 42).unwrap();
+//! let (train_data, test_data) = split.take(&data, 2).unwrap();
+//! let (train_labels, test_labels) = split.take(&labels, 1).unwrap();
+//!
+//! // or split an existing DMatrix, labels and weights included
+//! let dmatrix = DMatrix::from_dense(&data, 4, 2).unwrap();
+//! let (dtrain, dtest) = split.dmatrices(&dmatrix).unwrap();
+//! ```
+
+use crate::dmatrix::DMatrix;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::rng::SplitMix64;
+
+/// Row indices of a training and a test set, each in increasing order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Split {
+    /// Rows of the training set
+    pub train: Vec<usize>,
+    /// Rows of the test (validation) set
+    pub test: Vec<usize>,
+}
+
+impl Split {
+    /// Split per-row data, with `row_len` values per row (the number of
+    /// features for a row-major matrix, 1 for labels or weights)
+    pub fn take<T: Clone>(&self, data: &[T], row_len: usize) -> XGBoostResult<(Vec<T>, Vec<T>)> {
+        let num_rows = self.train.len() + self.test.len();
+        if num_rows.checked_mul(row_len) != Some(data.len()) {
+            return Err(XGBoostError {
+                description: format!(
+                    "Data length mismatch: expected {}×{} elements, got {}",
+                    num_rows,
+                    row_len,
+                    data.len()
+                ),
+            });
+        }
+        Ok((
+            take_rows(data, row_len, &self.train),
+            take_rows(data, row_len, &self.test),
+        ))
+    }
+
+    /// Split a DMatrix, including its labels and weights
+    ///
+    /// Matrices with query groups cannot be split this way.
+    pub fn dmatrices(&self, dmatrix: &DMatrix) -> XGBoostResult<(DMatrix, DMatrix)> {
+        Ok((dmatrix.slice(&self.train)?, dmatrix.slice(&self.test)?))
+    }
+}
+
+fn take_rows<T: Clone>(data: &[T], row_len: usize, rows: &[usize]) -> Vec<T> {
+    let mut out = Vec::with_capacity(rows.len() * row_len);
+    for &row in rows {
+        out.extend_from_slice(&data[row * row_len..(row + 1) * row_len]);
+    }
+    out
+}
+
+/// Randomly hold out `test_fraction` of `num_rows` rows
+///
+/// The test set has `round(test_fraction × num_rows)` rows, but at least
+/// one, and the training set keeps at least one row.
+pub fn train_test_split(num_rows: usize, test_fraction: f64, seed: u64) -> XGBoostResult<Split> {
+    let num_test = test_size(num_rows, test_fraction)?;
+    let mut rows: Vec<usize> = (0..num_rows).collect();
+    SplitMix64::new(seed).shuffle(&mut rows);
+    let mut test = rows.split_off(num_rows - num_test);
+    rows.sort_unstable();
+    test.sort_unstable();
+    Ok(Split { train: rows, test })
+}
+
+/// Hold out `test_fraction` of the rows while preserving class proportions
+///
+/// Each distinct label is split separately, so rare classes (e.g. fraud
+/// cases) are represented in both sets in the same ratio as in the data.
+/// The class shares of the test set are rounded by largest remainder to
+/// give the same test size as [`train_test_split`].
+///
+/// # Example
+/// ```
+/// use xgboost_rust::split::stratified_split;
+///
+/// let labels: Vec<f32> = (0..1000).map(|i| if i % 100 == 0 { 1.0 } else { 0.0 }).collect();
+/// let split = stratified_split(&labels, 0.2, 42).unwrap();
+/// let positives = split.test.iter().filter(|&&row| labels[row] == 1.0).count();
+/// assert_eq!(split.test.len(), 200);
+/// assert_eq!(positives, 2);
+/// ```
+pub fn stratified_split(labels: &[f32], test_fraction: f64, seed: u64) -> XGBoostResult<Split> {
+    let num_rows = labels.len();
+    let num_test = test_size(num_rows, test_fraction)?;
+    let mut rng = SplitMix64::new(seed);
+    let mut classes = class_rows(labels);
+    for rows in &mut classes {
+        rng.shuffle(rows);
+    }
+
+    // floor of each class's share, then the remaining rows by largest remainder
+    let shares: Vec<f64> = classes
+        .iter()
+        .map(|rows| rows.len() as f64 * num_test as f64 / num_rows as f64)
+        .collect();
+    let mut counts: Vec<usize> = shares.iter().map(|share| share.floor() as usize).collect();
+    let mut by_remainder: Vec<usize> = (0..classes.len()).collect();
+    by_remainder.sort_by(|&a, &b| {
+        (shares[b] - shares[b].floor()).total_cmp(&(shares[a] - shares[a].floor()))
+    });
+    let remaining = num_test.saturating_sub(counts.iter().sum::<usize>());
+    for &class in by_remainder.iter().take(remaining) {
+        counts[class] += 1;
+    }
+
+    let (mut train, mut test) = (Vec::new(), Vec::new());
+    for (rows, count) in classes.iter().zip(counts) {
+        test.extend_from_slice(&rows[..count]);
+        train.extend_from_slice(&rows[count..]);
+    }
+    train.sort_unstable();
+    test.sort_unstable();
+    Ok(Split { train, test })
+}
+
+/// Rows of each distinct label, ordered by label value
+pub(crate) fn class_rows(labels: &[f32]) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..labels.len()).collect();
+    order.sort_by(|&a, &b| labels[a].total_cmp(&labels[b]));
+    let mut classes: Vec<Vec<usize>> = Vec::new();
+    for (i, &row) in order.iter().enumerate() {
+        match classes.last_mut() {
+            Some(class) if labels[order[i - 1]].total_cmp(&labels[row]).is_eq() => class.push(row),
+            _ => classes.push(vec![row]),
+        }
+    }
+    classes
+}
+
+/// Splits for time-ordered data, where every test set lies after its
+/// training set (like scikit-learn's `TimeSeriesSplit`).
+///
+/// The last `n_splits × test_size` rows are divided into consecutive test
+/// sets. Each training set is every earlier row (an expanding window) or
+/// only the most recent ones ([`rolling_window`](Self::rolling_window)),
+/// optionally leaving a [`gap`](Self::gap) before the test set.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::split::TimeSeriesSplit;
+/// use xgboost_rust::{BoosterParams, CrossValidation, DMatrix};
+///
+/// # let dmatrix = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+/// let timestamps: Vec<i64> = vec![1_700_000_000, 1_700_000_060 /* ... */];
+/// let folds = TimeSeriesSplit::new(5).gap(24).split_by_time(&timestamps).unwrap();
+///
+/// let params = BoosterParams::new();
+/// let result = CrossValidation::new(&params, &dmatrix)
+///     .folds(folds)
+///     .run()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSeriesSplit {
+    n_splits: usize,
+    test_size: Option<usize>,
+    max_train_size: Option<usize>,
+    gap: usize,
+}
+
+impl TimeSeriesSplit {
+    /// `n_splits` folds with expanding training windows
+    pub fn new(n_splits: usize) -> Self {
+        TimeSeriesSplit {
+            n_splits,
+            test_size: None,
+            max_train_size: None,
+            gap: 0,
+        }
+    }
+
+    /// Rows per test set (default `num_rows / (n_splits + 1)`)
+    pub fn test_size(mut self, test_size: usize) -> Self {
+        self.test_size = Some(test_size);
+        self
+    }
+
+    /// Train on at most the `max_train_size` rows before each test set
+    pub fn rolling_window(mut self, max_train_size: usize) -> Self {
+        self.max_train_size = Some(max_train_size);
+        self
+    }
+
+    /// Rows left out between each training and test set, e.g. to cover
+    /// the forecast horizon
+    pub fn gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Splits of `num_rows` rows that are already in time order
+    pub fn split(&self, num_rows: usize) -> XGBoostResult<Vec<Split>> {
+        let order: Vec<usize> = (0..num_rows).collect();
+        self.split_ordered(&order, |_| false)
+    }
+
+    /// Splits of rows in the order of their timestamps
+    ///
+    /// Rows with equal timestamps always end up on the same side: a test
+    /// set starts after the last row sharing a timestamp with its first row.
+    pub fn split_by_time<T: PartialOrd>(&self, timestamps: &[T]) -> XGBoostResult<Vec<Split>> {
+        let mut order: Vec<usize> = (0..timestamps.len()).collect();
+        order.sort_by(|&a, &b| {
+            timestamps[a]
+                .partial_cmp(&timestamps[b])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let tied = |position: usize| timestamps[order[position - 1]] == timestamps[order[position]];
+        self.split_ordered(&order, tied)
+    }
+
+    /// Positional splits over `order`; `tied(p)` tells whether positions
+    /// `p - 1` and `p` share a timestamp
+    fn split_ordered(
+        &self,
+        order: &[usize],
+        tied: impl Fn(usize) -> bool,
+    ) -> XGBoostResult<Vec<Split>> {
+        let num_rows = order.len();
+        if self.n_splits == 0 {
+            return Err(XGBoostError {
+                description: "n_splits must be at least 1".to_string(),
+            });
+        }
+        let test_size = self.test_size.unwrap_or(num_rows / (self.n_splits + 1));
+        let needed = (self.n_splits * test_size)
+            .checked_add(self.gap)
+            .filter(|&n| n < num_rows && test_size > 0);
+        if needed.is_none() {
+            return Err(XGBoostError {
+                description: format!(
+                    "Cannot make {} splits with test size {} and gap {} from {} rows",
+                    self.n_splits, test_size, self.gap, num_rows
+                ),
+            });
+        }
+
+        let mut splits = Vec::with_capacity(self.n_splits);
+        for fold in 0..self.n_splits {
+            let mut test_start = num_rows - (self.n_splits - fold) * test_size;
+            let test_end = test_start + test_size;
+            while test_start < test_end && test_start > 0 && tied(test_start) {
+                test_start += 1;
+            }
+            let train_end = test_start.saturating_sub(self.gap);
+            let train_start = self
+                .max_train_size
+                .map_or(0, |size| train_end.saturating_sub(size));
+            let mut train: Vec<usize> = order[train_start..train_end].to_vec();
+            let mut test: Vec<usize> = order[test_start..test_end].to_vec();
+            if train.is_empty() || test.is_empty() {
+                return Err(XGBoostError {
+                    description: format!(
+                        "Split {} has an empty training or test set; use fewer splits",
+                        fold
+                    ),
+                });
+            }
+            train.sort_unstable();
+            test.sort_unstable();
+            splits.push(Split { train, test });
+        }
+        Ok(splits)
+    }
+}
+
+fn test_size(num_rows: usize, test_fraction: f64) -> XGBoostResult<usize> {
+    if !(test_fraction > 0.0 && test_fraction < 1.0) {
+        return Err(XGBoostError {
+            description: format!("test_fraction must be in (0, 1), got {}", test_fraction),
+        });
+    }
+    if num_rows < 2 {
+        return Err(XGBoostError {
+            description: format!("Cannot split {} rows into two sets", num_rows),
+        });
+    }
+    let size = (test_fraction * num_rows as f64).round() as usize;
+    Ok(size.clamp(1, num_rows - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_partition(split: &Split, num_rows: usize) {
+        let mut rows: Vec<usize> = split.train.iter().chain(&split.test).copied().collect();
+        assert!(split.train.windows(2).all(|w| w[0] < w[1]));
+        assert!(split.test.windows(2).all(|w| w[0] < w[1]));
+        rows.sort_unstable();
+        assert_eq!(rows, (0..num_rows).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn random_split_sizes() {
+        let split = train_test_split(10, 0.3, 7).unwrap();
+        assert_eq!((split.train.len(), split.test.len()), (7, 3));
+        assert_partition(&split, 10);
+        assert_eq!(split, train_test_split(10, 0.3, 7).unwrap());
+
+        // at least one row on each side
+        assert_eq!(train_test_split(10, 0.01, 7).unwrap().test.len(), 1);
+        assert_eq!(train_test_split(10, 0.99, 7).unwrap().train.len(), 1);
+        assert!(train_test_split(10, 0.0, 7).is_err());
+        assert!(train_test_split(10, 1.0, 7).is_err());
+        assert!(train_test_split(10, f64::NAN, 7).is_err());
+        assert!(train_test_split(1, 0.5, 7).is_err());
+    }
+
+    #[test]
+    fn stratified_split_keeps_class_shares() {
+        let labels: Vec<f32> = (0..50).map(|i| (i % 5 == 0) as u8 as f32).collect();
+        let split = stratified_split(&labels, 0.2, 3).unwrap();
+        assert_partition(&split, 50);
+        assert_eq!(split.test.len(), 10);
+        let positives = split.test.iter().filter(|&&row| labels[row] == 1.0).count();
+        assert_eq!(positives, 2);
+
+        // both classes have a share of 1.5 rows; one of them gets the third
+        let labels = [0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        assert_eq!(stratified_split(&labels, 0.5, 3).unwrap().test.len(), 3);
+    }
+
+    #[test]
+    fn classes_are_ordered_by_label() {
+        assert_eq!(
+            class_rows(&[1.0, 0.0, 1.0, 2.0, 0.0]),
+            [vec![1, 4], vec![0, 2], vec![3]]
+        );
+        assert!(class_rows(&[]).is_empty());
+    }
+
+    #[test]
+    fn take_splits_rows() {
+        let split = Split {
+            train: vec![0, 2],
+            test: vec![1],
+        };
+        let data = [1, 2, 3, 4, 5, 6];
+        assert_eq!(
+            split.take(&data, 2).unwrap(),
+            (vec![1, 2, 5, 6], vec![3, 4])
+        );
+        assert!(split.take(&data, 3).is_err());
+    }
+
+    #[test]
+    fn time_series_expanding_windows() {
+        let splits = TimeSeriesSplit::new(3).split(8).unwrap();
+        let folds: Vec<(Vec<usize>, Vec<usize>)> = splits
+            .into_iter()
+            .map(|split| (split.train, split.test))
+            .collect();
+        assert_eq!(
+            folds,
+            [
+                (vec![0, 1], vec![2, 3]),
+                (vec![0, 1, 2, 3], vec![4, 5]),
+                (vec![0, 1, 2, 3, 4, 5], vec![6, 7]),
+            ]
+        );
+    }
+
+    #[test]
+    fn time_series_rolling_window_with_gap() {
+        let splits = TimeSeriesSplit::new(3)
+            .gap(1)
+            .rolling_window(2)
+            .split(8)
+            .unwrap();
+        let train: Vec<Vec<usize>> = splits.into_iter().map(|split| split.train).collect();
+        assert_eq!(train, [vec![0], vec![1, 2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn time_series_ties_stay_together() {
+        let timestamps = [5, 1, 2, 2, 3, 4];
+        let splits = TimeSeriesSplit::new(2)
+            .test_size(2)
+            .split_by_time(&timestamps)
+            .unwrap();
+        // the first test set would start between the two rows at time 2
+        assert_eq!(splits[0].train, [1, 2, 3]);
+        assert_eq!(splits[0].test, [4]);
+        assert_eq!(splits[1].train, [1, 2, 3, 4]);
+        assert_eq!(splits[1].test, [0, 5]);
+    }
+
+    #[test]
+    fn time_series_needs_enough_rows() {
+        assert!(TimeSeriesSplit::new(0).split(10).is_err());
+        assert!(TimeSeriesSplit::new(5).split(5).is_err());
+        assert!(TimeSeriesSplit::new(2)
+            .test_size(3)
+            .gap(4)
+            .split(10)
+            .is_err());
+        assert!(TimeSeriesSplit::new(2)
+            .test_size(3)
+            .gap(3)
+            .split(10)
+            .is_ok());
+    }
+}
@@ -0,0 +1,223 @@
+//! Conversion of tree ensembles into SQL scoring expressions.
+//!
+//! Every tree becomes a nested `CASE` expression over the feature columns;
+//! the expressions are summed with the base score and wrapped in the
+//! objective's link function, so a warehouse can score rows without a UDF.
+
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::gbm::{GradientBooster, Learner, Tree};
+use std::fmt::Write;
+
+/// SQL dialect of [`Booster::to_sql`](crate::Booster::to_sql), which decides
+/// how column and table names are quoted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SqlDialect {
+    /// Standard SQL double quotes: PostgreSQL, Snowflake, DuckDB, Redshift,
+    /// Oracle, Trino
+    #[default]
+    Ansi,
+    /// Backticks: MySQL, MariaDB, BigQuery, Spark SQL, Databricks, Hive
+    Backtick,
+    /// Square brackets: SQL Server, Azure Synapse
+    SqlServer,
+}
+
+impl SqlDialect {
+    fn quote(&self, identifier: &str) -> String {
+        match self {
+            SqlDialect::Ansi => format!("\"{}\"", identifier.replace('"', "\"\"")),
+            SqlDialect::Backtick => format!("`{}`", identifier.replace('`', "``")),
+            SqlDialect::SqlServer => format!("[{}]", identifier.replace(']', "]]")),
+        }
+    }
+}
+
+fn write_node(
+    out: &mut String,
+    tree: &Tree,
+    nid: usize,
+    columns: &[String],
+    weight: f32,
+    depth: usize,
+) {
+    let node = &tree.nodes[nid];
+    if node.is_leaf() {
+        let _ = write!(out, "{}", node.split_condition * weight);
+        return;
+    }
+
+    let column = &columns[node.split_index as usize];
+    // A NULL comparison is not true, so rows with a missing value take the
+    // ELSE branch unless the default direction is left
+    let missing = if node.default_left {
+        format!("{} IS NULL OR ", column)
+    } else {
+        String::new()
+    };
+    let condition = match &node.categories {
+        // Same rule as XGBoost: categories in the split set go right
+        Some(categories) if categories.is_empty() => {
+            if node.default_left {
+                write_node(out, tree, node.left as usize, columns, weight, depth);
+                return;
+            }
+            format!("{} IS NOT NULL", column)
+        }
+        Some(categories) => {
+            // XGBoost truncates values to category codes, so 1.5 is category
+            // 1; negative values floor to codes outside every set
+            let set: Vec<String> = categories.iter().map(|c| c.to_string()).collect();
+            format!("{}FLOOR({}) NOT IN ({})", missing, column, set.join(", "))
+        }
+        None => format!("{}{} < {}", missing, column, node.split_condition),
+    };
+    let indent = "  ".repeat(depth + 1);
+    let _ = write!(out, "CASE\n{}WHEN {} THEN ", indent, condition);
+    write_node(out, tree, node.left as usize, columns, weight, depth + 1);
+    let _ = write!(out, "\n{}ELSE ", indent);
+    write_node(out, tree, node.right as usize, columns, weight, depth + 1);
+    let _ = write!(out, "\n{}END", "  ".repeat(depth));
+}
+
+/// Render a single-output tree model as a SQL expression.
+pub(crate) fn to_sql(
+    learner: &Learner,
+    dialect: SqlDialect,
+    table_alias: Option<&str>,
+) -> XGBoostResult<String> {
+    let ensemble = match &learner.booster {
+        GradientBooster::Tree(ensemble) => ensemble,
+        GradientBooster::Linear(_) => {
+            return Err(XGBoostError {
+                description: "SQL export only supports tree boosters (gbtree/dart)".to_string(),
+            })
+        }
+    };
+    if learner.num_groups != 1 {
+        return Err(XGBoostError {
+            description: format!(
+                "SQL export only supports single-output models, this one has {} outputs",
+                learner.num_groups
+            ),
+        });
+    }
+
+    let prefix = table_alias
+        .map(|alias| format!("{}.", dialect.quote(alias)))
+        .unwrap_or_default();
+    let feature_names: Vec<String> = if learner.feature_names.len() == learner.num_feature {
+        learner.feature_names.clone()
+    } else {
+        (0..learner.num_feature)
+            .map(|i| format!("f{}", i))
+            .collect()
+    };
+    let columns: Vec<String> = feature_names
+        .iter()
+        .map(|name| format!("{}{}", prefix, dialect.quote(name)))
+        .collect();
+
+    let mut margin = format!("{}", learner.base_margin[0]);
+    for (tree, weight) in ensemble.trees.iter().zip(&ensemble.tree_weights) {
+        margin.push_str("\n  + ");
+        write_node(&mut margin, tree, 0, &columns, *weight, 1);
+    }
+
+    Ok(match learner.objective.name.as_str() {
+        "binary:logistic" | "reg:logistic" => {
+            format!("1.0 / (1.0 + EXP(-(\n  {}\n)))", margin)
+        }
+        "count:poisson" | "reg:gamma" | "reg:tweedie" | "survival:cox" | "survival:aft" => {
+            format!("EXP(\n  {}\n)", margin)
+        }
+        "binary:hinge" => format!("CASE WHEN (\n  {}\n) > 0 THEN 1 ELSE 0 END", margin),
+        _ => format!("(\n  {}\n)", margin),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::{parse_json, parse_model_document};
+
+    fn learner(document: &str) -> Learner {
+        Learner::from_document(&parse_json(document.as_bytes()).unwrap()).unwrap()
+    }
+
+    /// One stump, `f0 < 0.5` with missing going right, under `objective`
+    fn stump(objective: &str, gradient_booster: &str) -> Learner {
+        learner(&format!(
+            r#"{{"learner": {{
+                "learner_model_param": {{"num_feature": "1", "num_class": "0", "base_score": "0"}},
+                "objective": {{"name": "{}"}},
+                "gradient_booster": {}
+            }}}}"#,
+            objective, gradient_booster
+        ))
+    }
+
+    const STUMP: &str = r#"{"left_children": [1, -1, -1], "right_children": [2, -1, -1],
+        "split_indices": [0, 0, 0], "split_conditions": [0.5, -1.0, 1.0],
+        "default_left": [0, 0, 0]}"#;
+
+    #[test]
+    fn categorical_model() {
+        let model = include_bytes!("../tests/data/categorical.json");
+        let learner = Learner::from_document(&parse_model_document(model).unwrap()).unwrap();
+        assert_eq!(
+            to_sql(&learner, SqlDialect::Ansi, Some("t")).unwrap(),
+            r#"(
+  0.5
+  + CASE
+    WHEN "t"."color" IS NULL OR FLOOR("t"."color") NOT IN (1, 3) THEN -0.25
+    ELSE CASE
+      WHEN "t"."price" < 2.5 THEN 0.125
+      ELSE 0.375
+    END
+  END
+  + CASE
+    WHEN "t"."price" IS NULL OR "t"."price" < 1 THEN CASE
+      WHEN FLOOR("t"."size") NOT IN (0, 4) THEN -0.125
+      ELSE 0.25
+    END
+    ELSE 0.0625
+  END
+)"#
+        );
+    }
+
+    #[test]
+    fn dart_weights_scale_the_leaves() {
+        let dart = format!(
+            r#"{{"name": "dart", "weight_drop": [0.5],
+                "gbtree": {{"name": "gbtree", "model": {{"trees": [{}], "tree_info": [0]}}}}}}"#,
+            STUMP
+        );
+        let sql = to_sql(&stump("reg:squarederror", &dart), SqlDialect::Ansi, None).unwrap();
+        assert!(sql.contains(r#"WHEN "f0" < 0.5 THEN -0.5"#), "{}", sql);
+        assert!(sql.contains("ELSE 0.5\n"), "{}", sql);
+    }
+
+    #[test]
+    fn links_and_quoting() {
+        let gbtree = format!(
+            r#"{{"name": "gbtree", "model": {{"trees": [{}], "tree_info": [0]}}}}"#,
+            STUMP
+        );
+        let sql = |objective, dialect| to_sql(&stump(objective, &gbtree), dialect, None).unwrap();
+        assert!(sql("binary:logistic", SqlDialect::Ansi).starts_with("1.0 / (1.0 + EXP(-("));
+        assert!(sql("count:poisson", SqlDialect::Ansi).starts_with("EXP(\n"));
+        assert!(sql("binary:hinge", SqlDialect::Ansi).ends_with(") > 0 THEN 1 ELSE 0 END"));
+        assert!(sql("reg:squarederror", SqlDialect::Backtick).contains("WHEN `f0` < 0.5"));
+        assert!(sql("reg:squarederror", SqlDialect::SqlServer).contains("WHEN [f0] < 0.5"));
+    }
+
+    #[test]
+    fn linear_models_are_rejected() {
+        let linear = r#"{"name": "gblinear", "model": {"weights": [1.0, 0.0]}}"#;
+        let error = to_sql(&stump("reg:squarederror", linear), SqlDialect::Ansi, None)
+            .unwrap_err()
+            .description;
+        assert!(error.contains("only supports tree boosters"), "{}", error);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::cv::{CrossValidation, CvResult};
+use crate::dmatrix::DMatrix;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::model::Booster;
+use crate::params::BoosterParams;
+use crate::predict_options::PredictOptions;
+use crate::train::train;
+
+/// Stacked generalization: several base models trained with different
+/// parameters, blended by a meta-model trained on their out-of-fold
+/// predictions.
+///
+/// Each base model is cross-validated to predict every training row with a
+/// model that did not see it; the meta-model learns from these honest
+/// predictions, with the labels and weights of the training data. The base
+/// models are then retrained on all rows for prediction.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{BoosterParams, DMatrix, Objective, Stacker};
+///
+/// let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]; // 4 rows, 2 features
+/// let mut dtrain = DMatrix::from_dense(&data, 4, 2).unwrap();
+/// dtrain.set_label(&[0.0, 1.0, 0.0, 1.0]).unwrap();
+///
+/// let base = BoosterParams::new().objective(Objective::BinaryLogistic);
+/// let model = Stacker::new(base.clone().max_depth(2).eta(0.1), 50)
+///     .base_model(base.clone().max_depth(6).eta(0.05), 200)
+///     .base_model(base.clone().max_depth(3).subsample(0.7), 100)
+///     .base_model(base.random_forest(100), 1)
+///     .nfold(2)
+///     .fit(&dtrain)
+///     .unwrap();
+/// let predictions = model.predict_dmatrix(&dtrain).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct Stacker {
+    meta_params: BoosterParams,
+    meta_rounds: usize,
+    base: Vec<(BoosterParams, usize)>,
+    nfold: usize,
+    stratified: bool,
+    seed: u64,
+    options: PredictOptions,
+}
+
+impl Stacker {
+    /// A stack whose meta-model is trained with `meta_params` for
+    /// `meta_rounds` rounds
+    pub fn new(meta_params: BoosterParams, meta_rounds: usize) -> Self {
+        Stacker {
+            meta_params,
+            meta_rounds,
+            base: Vec::new(),
+            nfold: 5,
+            stratified: false,
+            seed: 0,
+            options: PredictOptions::empty(),
+        }
+    }
+
+    /// Add a base model trained with `params` for `num_boost_round` rounds
+    pub fn base_model(mut self, params: BoosterParams, num_boost_round: usize) -> Self {
+        self.base.push((params, num_boost_round));
+        self
+    }
+
+    /// Number of folds of the out-of-fold predictions (default 5)
+    pub fn nfold(mut self, nfold: usize) -> Self {
+        self.nfold = nfold;
+        self
+    }
+
+    /// Keep the class proportions of the labels in every fold
+    pub fn stratified(mut self, stratified: bool) -> Self {
+        self.stratified = stratified;
+        self
+    }
+
+    /// Seed of the fold assignment
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Feed the base models' margins instead of their transformed
+    /// predictions to the meta-model
+    pub fn margins(mut self, margins: bool) -> Self {
+        self.options.set(PredictOptions::OUTPUT_MARGIN, margins);
+        self
+    }
+
+    /// Train the base models and the meta-model
+    pub fn fit(&self, dtrain: &DMatrix) -> XGBoostResult<StackedModel> {
+        if self.base.is_empty() {
+            return Err(XGBoostError {
+                description: "A stack needs at least one base model".to_string(),
+            });
+        }
+        let num_rows = dtrain.num_rows()?;
+
+        let mut columns = Vec::with_capacity(self.base.len());
+        let mut cv_results = Vec::with_capacity(self.base.len());
+        let mut base = Vec::with_capacity(self.base.len());
+        for (params, num_boost_round) in &self.base {
+            let oof = CrossValidation::new(params, dtrain)
+                .nfold(self.nfold)
+                .num_boost_round(*num_boost_round)
+                .stratified(self.stratified)
+                .seed(self.seed)
+                .run_predict(self.options)?;
+            columns.push((oof.predictions, oof.num_outputs));
+            cv_results.push(oof.result);
+            base.push(train(params, dtrain, *num_boost_round)?);
+        }
+
+        let num_meta_features = columns.iter().map(|(_, n)| n).sum();
+        let mut dmeta =
+            DMatrix::from_dense(&interleave(&columns, num_rows), num_rows, num_meta_features)?;
+        dmeta.set_label(&dtrain.label()?)?;
+        let weights = dtrain.weight()?;
+        if !weights.is_empty() {
+            dmeta.set_weight(&weights)?;
+        }
+        let meta = train(&self.meta_params, &dmeta, self.meta_rounds)?;
+
+        Ok(StackedModel {
+            base,
+            meta,
+            options: self.options,
+            cv_results,
+        })
+    }
+}
+
+/// Lay out per-model row-major predictions side by side, one row per
+/// training row
+fn interleave(columns: &[(Vec<f32>, usize)], num_rows: usize) -> Vec<f32> {
+    let width: usize = columns.iter().map(|(_, n)| n).sum();
+    let mut out = Vec::with_capacity(num_rows * width);
+    for row in 0..num_rows {
+        for (predictions, n) in columns {
+            out.extend_from_slice(&predictions[row * n..(row + 1) * n]);
+        }
+    }
+    out
+}
+
+/// Base models and meta-model fitted by [`Stacker::fit`].
+pub struct StackedModel {
+    base: Vec<Booster>,
+    meta: Booster,
+    options: PredictOptions,
+    cv_results: Vec<CvResult>,
+}
+
+impl StackedModel {
+    /// Predict with the base models, then the meta-model
+    pub fn predict(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.predict_dmatrix(&DMatrix::from_dense(data, num_rows, num_features)?)
+    }
+
+    /// Predict a `DMatrix` with the base models, then the meta-model
+    pub fn predict_dmatrix(&self, dmatrix: &DMatrix) -> XGBoostResult<Vec<f32>> {
+        let features = self.meta_features(dmatrix)?;
+        self.meta.predict(
+            &features,
+            dmatrix.num_rows()?,
+            self.meta.num_features()?,
+            PredictOptions::empty(),
+            false,
+        )
+    }
+
+    /// The meta-model's input: the base models' predictions for each row,
+    /// side by side in the order the base models were added
+    pub fn meta_features(&self, dmatrix: &DMatrix) -> XGBoostResult<Vec<f32>> {
+        let num_rows = dmatrix.num_rows()?;
+        let columns = self
+            .base
+            .iter()
+            .map(|booster| {
+                let predictions = booster.predict_dmatrix(dmatrix, self.options, false)?;
+                let n = predictions.len() / num_rows.max(1);
+                Ok((predictions, n))
+            })
+            .collect::<XGBoostResult<Vec<_>>>()?;
+        Ok(interleave(&columns, num_rows))
+    }
+
+    /// The base models, retrained on all rows
+    pub fn base_models(&self) -> &[Booster] {
+        &self.base
+    }
+
+    /// The meta-model
+    pub fn meta_model(&self) -> &Booster {
+        &self.meta
+    }
+
+    /// Cross-validation scores of each base model
+    pub fn cv_results(&self) -> &[CvResult] {
+        &self.cv_results
+    }
+}
@@ -0,0 +1,259 @@
+//! Target encoding of categorical columns.
+//!
+//! [`TargetEncoder`] replaces each category by the smoothed mean label of
+//! its rows, fitting the training encodings out of fold so that they do not
+//! leak the label, and stores the fitted mapping as a model attribute so
+//! that serving encodes categories exactly as training did.
+
+use crate::error::{XGBoostError, XGBoostResult};
+#[cfg(feature = "native")]
+use crate::label_encoder::escape;
+use crate::label_encoder::split_escaped;
+use crate::rng::SplitMix64;
+use crate::Booster;
+use std::collections::HashMap;
+
+/// Prefix of the model attribute holding the encoding of a column
+const ATTRIBUTE_PREFIX: &str = "target_encoder_";
+
+/// Replaces the categories of a column by the smoothed mean label of their
+/// rows.
+///
+/// The encoding of a category with `n` rows and label sum `s` is
+/// `(s + smoothing × prior) / (n + smoothing)`, where `prior` is the mean
+/// label of all rows; categories not seen when fitting are encoded as the
+/// prior.
+///
+/// Encoding the training rows with a mapping fitted on their own labels
+/// leaks the label into the feature, so
+/// [`fit_transform`](Self::fit_transform) encodes each training row with a
+/// mapping fitted on the other folds only. The returned encoder is fitted
+/// on all rows and is what `transform` uses at prediction time; store it in
+/// the model with `save_to` and restore it with
+/// [`from_booster`](Self::from_booster).
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::TargetEncoder;
+///
+/// let cities = ["Berlin", "Paris", "Berlin", "Rome", "Paris", "Berlin"];
+/// let labels = [1.0, 0.0, 1.0, 0.0, 1.0, 0.0];
+///
+/// // out-of-fold encodings, to train on
+/// let (encoder, encoded) = TargetEncoder::new().folds(3).fit_transform(&cities, &labels).unwrap();
+/// assert_eq!(encoded.len(), cities.len());
+///
+/// // full-data encodings, at prediction time
+/// let serving = encoder.transform(&["Paris", "Madrid"]);
+/// assert_eq!(serving[1], encoder.prior() as f32);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetEncoder {
+    smoothing: f64,
+    folds: usize,
+    seed: u64,
+    prior: f64,
+    encodings: HashMap<String, f64>,
+}
+
+impl Default for TargetEncoder {
+    fn default() -> Self {
+        TargetEncoder {
+            smoothing: 10.0,
+            folds: 5,
+            seed: 0,
+            prior: 0.0,
+            encodings: HashMap::new(),
+        }
+    }
+}
+
+impl TargetEncoder {
+    /// An unfitted encoder with smoothing 10 and 5 folds
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Weight of the prior, in rows; larger values pull rare categories
+    /// towards the overall mean
+    pub fn smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Number of folds used by `fit_transform` (default 5)
+    pub fn folds(mut self, folds: usize) -> Self {
+        self.folds = folds;
+        self
+    }
+
+    /// Seed for assigning rows to folds (default 0)
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Fit the encoding on all rows
+    pub fn fit<S: AsRef<str>>(mut self, categories: &[S], labels: &[f32]) -> XGBoostResult<Self> {
+        self.check(categories, labels)?;
+        let rows: Vec<usize> = (0..categories.len()).collect();
+        let (prior, encodings) = self.encode_rows(categories, labels, &rows);
+        self.prior = prior;
+        self.encodings = encodings;
+        Ok(self)
+    }
+
+    /// Fit the encoding on all rows and return it with the out-of-fold
+    /// encoding of every row
+    pub fn fit_transform<S: AsRef<str>>(
+        self,
+        categories: &[S],
+        labels: &[f32],
+    ) -> XGBoostResult<(Self, Vec<f32>)> {
+        self.check(categories, labels)?;
+        if self.folds < 2 || self.folds > categories.len() {
+            return Err(XGBoostError {
+                description: format!(
+                    "Target encoding needs between 2 and {} folds, got {}",
+                    categories.len(),
+                    self.folds
+                ),
+            });
+        }
+
+        let mut order: Vec<usize> = (0..categories.len()).collect();
+        SplitMix64::new(self.seed).shuffle(&mut order);
+        let mut fold_of = vec![0; categories.len()];
+        for (position, &row) in order.iter().enumerate() {
+            fold_of[row] = position % self.folds;
+        }
+        let mut encoded = vec![0.0; categories.len()];
+        for fold in 0..self.folds {
+            let rest: Vec<usize> = (0..categories.len())
+                .filter(|&row| fold_of[row] != fold)
+                .collect();
+            let (prior, encodings) = self.encode_rows(categories, labels, &rest);
+            for row in (0..categories.len()).filter(|&row| fold_of[row] == fold) {
+                let encoding = encodings.get(categories[row].as_ref()).unwrap_or(&prior);
+                encoded[row] = *encoding as f32;
+            }
+        }
+
+        let encoder = self.fit(categories, labels)?;
+        Ok((encoder, encoded))
+    }
+
+    /// Encode categories with the fitted mapping
+    pub fn transform<S: AsRef<str>>(&self, categories: &[S]) -> Vec<f32> {
+        categories
+            .iter()
+            .map(|category| self.encoding(category.as_ref()) as f32)
+            .collect()
+    }
+
+    /// Encoding of one category (the prior if it was not seen)
+    pub fn encoding(&self, category: &str) -> f64 {
+        self.encodings.get(category).copied().unwrap_or(self.prior)
+    }
+
+    /// Mean label of the fitted rows
+    pub fn prior(&self) -> f64 {
+        self.prior
+    }
+
+    /// Number of categories seen when fitting
+    pub fn num_categories(&self) -> usize {
+        self.encodings.len()
+    }
+
+    /// Store the encoding of `column` as a model attribute, saved with the
+    /// model
+    #[cfg(feature = "native")]
+    pub fn save_to(&self, booster: &mut Booster, column: &str) -> XGBoostResult<()> {
+        let mut categories: Vec<(&String, &f64)> = self.encodings.iter().collect();
+        categories.sort_by(|a, b| a.0.cmp(b.0));
+        let mut fields = vec![self.prior.to_string()];
+        for (category, encoding) in categories {
+            fields.push(escape(category));
+            fields.push(encoding.to_string());
+        }
+        booster.set_attr(
+            &format!("{}{}", ATTRIBUTE_PREFIX, column),
+            &fields.join(","),
+        )
+    }
+
+    /// Restore the encoding of `column` stored with `save_to`, or `None` if
+    /// the model has none
+    pub fn from_booster(booster: &Booster, column: &str) -> XGBoostResult<Option<Self>> {
+        let Some(value) = booster.attr(&format!("{}{}", ATTRIBUTE_PREFIX, column))? else {
+            return Ok(None);
+        };
+        let invalid = || XGBoostError {
+            description: format!("Invalid target encoding stored for column '{}'", column),
+        };
+        let fields = split_escaped(&value);
+        if fields.len() % 2 == 0 {
+            return Err(invalid());
+        }
+        let prior = fields[0].parse().map_err(|_| invalid())?;
+        let encodings = fields[1..]
+            .chunks(2)
+            .map(|pair| Ok((pair[0].clone(), pair[1].parse().map_err(|_| invalid())?)))
+            .collect::<XGBoostResult<_>>()?;
+        Ok(Some(TargetEncoder {
+            prior,
+            encodings,
+            ..Self::default()
+        }))
+    }
+
+    fn check<S>(&self, categories: &[S], labels: &[f32]) -> XGBoostResult<()> {
+        if categories.len() != labels.len() {
+            return Err(XGBoostError {
+                description: format!(
+                    "Got {} categories for {} labels",
+                    categories.len(),
+                    labels.len()
+                ),
+            });
+        }
+        if categories.is_empty() {
+            return Err(XGBoostError {
+                description: "Target encoding needs at least one row".to_string(),
+            });
+        }
+        if self.smoothing.is_nan() || self.smoothing < 0.0 {
+            return Err(XGBoostError {
+                description: format!("Smoothing must be non-negative, got {}", self.smoothing),
+            });
+        }
+        Ok(())
+    }
+
+    /// Prior and smoothed encodings of `rows`
+    fn encode_rows<S: AsRef<str>>(
+        &self,
+        categories: &[S],
+        labels: &[f32],
+        rows: &[usize],
+    ) -> (f64, HashMap<String, f64>) {
+        let mut stats: HashMap<&str, (f64, f64)> = HashMap::new();
+        let mut total = 0.0;
+        for &row in rows {
+            let entry = stats.entry(categories[row].as_ref()).or_default();
+            entry.0 += labels[row] as f64;
+            entry.1 += 1.0;
+            total += labels[row] as f64;
+        }
+        let prior = total / rows.len().max(1) as f64;
+        let encodings = stats
+            .into_iter()
+            .map(|(category, (sum, count))| {
+                let encoding = (sum + self.smoothing * prior) / (count + self.smoothing);
+                (category.to_string(), encoding)
+            })
+            .collect();
+        (prior, encodings)
+    }
+}
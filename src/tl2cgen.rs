@@ -0,0 +1,190 @@
+//! Treelite / TL2cgen export hook.
+//!
+//! Treelite's checkpoint format is an internal, versioned binary layout, so
+//! instead of re-implementing it this module drives the official Python
+//! tooling: the model is written to a temporary JSON file, loaded with
+//! `treelite.frontend.load_xgboost_model`, and then either serialized as a
+//! Treelite checkpoint or compiled into a shared library with
+//! `tl2cgen.export_lib`.
+//!
+//! Requires a Python interpreter with `treelite >= 4` (and `tl2cgen` for
+//! [`compile`]) installed.
+
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::Booster;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const EXPORT_CHECKPOINT_SCRIPT: &str = r#"
+import sys, treelite
+model = treelite.frontend.load_xgboost_model(sys.argv[1])
+model.serialize(sys.argv[2])
+"#;
+
+const COMPILE_SCRIPT: &str = r#"
+import sys, json, treelite, tl2cgen
+model = treelite.frontend.load_xgboost_model(sys.argv[1])
+options = json.loads(sys.argv[3])
+tl2cgen.export_lib(
+    model,
+    toolchain=options["toolchain"],
+    libpath=sys.argv[2],
+    params=options["params"],
+    nthread=options["nthread"],
+)
+"#;
+
+/// Options for invoking the Treelite/TL2cgen Python tooling.
+#[derive(Debug, Clone)]
+pub struct Tl2cgenOptions {
+    /// Python interpreter to run (default: `python3`)
+    pub python: String,
+    /// C compiler toolchain passed to TL2cgen (`gcc`, `clang` or `msvc`; default: `gcc`)
+    pub toolchain: String,
+    /// Split the generated code into this many files to speed up compilation
+    pub parallel_comp: Option<usize>,
+    /// Quantize thresholds into integer bins in the generated code
+    pub quantize: bool,
+    /// Number of compiler jobs (default: all cores)
+    pub nthread: Option<usize>,
+}
+
+impl Default for Tl2cgenOptions {
+    fn default() -> Self {
+        Tl2cgenOptions {
+            python: "python3".to_string(),
+            toolchain: "gcc".to_string(),
+            parallel_comp: None,
+            quantize: false,
+            nthread: None,
+        }
+    }
+}
+
+/// Temporary JSON copy of a model, removed on drop.
+struct TempModel(PathBuf);
+
+impl TempModel {
+    fn new(booster: &Booster) -> XGBoostResult<Self> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = env::temp_dir().join(format!("xgboost-rust-{}-{}.json", process::id(), nanos));
+        booster.save(&path)?;
+        Ok(TempModel(path))
+    }
+}
+
+impl Drop for TempModel {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn path_str(path: &Path) -> XGBoostResult<&str> {
+    path.to_str().ok_or_else(|| XGBoostError {
+        description: "Path contains invalid UTF-8 characters".to_string(),
+    })
+}
+
+fn run_python(python: &str, script: &str, args: &[&str]) -> XGBoostResult<()> {
+    let output = Command::new(python)
+        .arg("-c")
+        .arg(script)
+        .args(args)
+        .output()
+        .map_err(|e| XGBoostError {
+            description: format!("Failed to run {}: {}", python, e),
+        })?;
+    if !output.status.success() {
+        return Err(XGBoostError {
+            description: format!(
+                "Treelite export failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Export a model as a Treelite checkpoint
+///
+/// # Arguments
+/// * `booster` - Model to export
+/// * `path` - Path where to write the checkpoint
+/// * `options` - Python interpreter to use (other options are ignored)
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{tl2cgen, Booster};
+///
+/// let booster = Booster::load("model.json").unwrap();
+/// tl2cgen::export_checkpoint(&booster, "model.tl", &Default::default()).unwrap();
+/// ```
+pub fn export_checkpoint<P: AsRef<Path>>(
+    booster: &Booster,
+    path: P,
+    options: &Tl2cgenOptions,
+) -> XGBoostResult<()> {
+    let model = TempModel::new(booster)?;
+    run_python(
+        &options.python,
+        EXPORT_CHECKPOINT_SCRIPT,
+        &[path_str(&model.0)?, path_str(path.as_ref())?],
+    )
+}
+
+/// Compile a model into a shared library with TL2cgen
+///
+/// The resulting library can be loaded with the TL2cgen runtime for
+/// low-latency inference without libxgboost.
+///
+/// # Arguments
+/// * `booster` - Model to compile
+/// * `lib_path` - Path of the shared library to produce (e.g. `model.so`)
+/// * `options` - Toolchain and code generation options
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{tl2cgen, Booster};
+///
+/// let booster = Booster::load("model.json").unwrap();
+/// let options = tl2cgen::Tl2cgenOptions {
+///     parallel_comp: Some(8),
+///     ..Default::default()
+/// };
+/// tl2cgen::compile(&booster, "model.so", &options).unwrap();
+/// ```
+pub fn compile<P: AsRef<Path>>(
+    booster: &Booster,
+    lib_path: P,
+    options: &Tl2cgenOptions,
+) -> XGBoostResult<()> {
+    let mut params = Vec::new();
+    if let Some(parallel_comp) = options.parallel_comp {
+        params.push(format!("\"parallel_comp\": {}", parallel_comp));
+    }
+    if options.quantize {
+        params.push("\"quantize\": 1".to_string());
+    }
+    let config = format!(
+        "{{\"toolchain\": \"{}\", \"params\": {{{}}}, \"nthread\": {}}}",
+        options.toolchain.replace('\\', "\\\\").replace('"', "\\\""),
+        params.join(", "),
+        options
+            .nthread
+            .map_or_else(|| "null".to_string(), |n| n.to_string())
+    );
+
+    let model = TempModel::new(booster)?;
+    run_python(
+        &options.python,
+        COMPILE_SCRIPT,
+        &[path_str(&model.0)?, path_str(lib_path.as_ref())?, &config],
+    )
+}
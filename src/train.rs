@@ -0,0 +1,664 @@
+use crate::cancel::CancellationToken;
+use crate::dmatrix::DMatrix;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::model::{Booster, EvalRecord};
+use crate::params::BoosterParams;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Train a booster
+///
+/// # Arguments
+/// * `params` - Training parameters
+/// * `dtrain` - Training data (with labels and, optionally, weights)
+/// * `num_boost_round` - Number of boosting iterations
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{train, BoosterParams, DMatrix, Objective};
+///
+/// let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]; // 3 rows, 2 features
+/// let mut dtrain = DMatrix::from_dense(&data, 3, 2).unwrap();
+/// dtrain.set_label(&[0.0, 1.0, 1.0]).unwrap();
+///
+/// let params = BoosterParams::new()
+///     .objective(Objective::BinaryLogistic)
+///     .max_depth(3);
+/// let booster = train(&params, &dtrain, 10).unwrap();
+/// booster.save("model.json").unwrap();
+/// ```
+pub fn train(
+    params: &BoosterParams,
+    dtrain: &DMatrix,
+    num_boost_round: usize,
+) -> XGBoostResult<Booster> {
+    Trainer::new(params, dtrain)
+        .num_boost_round(num_boost_round)
+        .train()
+        .map(|output| output.booster)
+}
+
+/// Training with evaluation sets and early stopping.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{BoosterParams, DMatrix, Objective, Trainer};
+///
+/// let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]; // 3 rows, 2 features
+/// let mut dtrain = DMatrix::from_dense(&data, 3, 2).unwrap();
+/// dtrain.set_label(&[0.0, 1.0, 1.0]).unwrap();
+/// let mut dvalid = DMatrix::from_dense(&[2.0, 3.0], 1, 2).unwrap();
+/// dvalid.set_label(&[1.0]).unwrap();
+///
+/// let params = BoosterParams::new()
+///     .objective(Objective::BinaryLogistic)
+///     .eval_metric("logloss");
+/// let output = Trainer::new(&params, &dtrain)
+///     .num_boost_round(500)
+///     .evals(&[(&dtrain, "train"), (&dvalid, "valid")])
+///     .early_stopping_rounds(20)
+///     .train()
+///     .unwrap();
+/// println!("best round {:?}", output.best_iteration);
+/// println!("{:?}", output.history.get("valid", "logloss"));
+/// ```
+#[derive(Clone)]
+pub struct Trainer<'a> {
+    params: &'a BoosterParams,
+    dtrain: &'a DMatrix,
+    num_boost_round: usize,
+    evals: Vec<(&'a DMatrix, &'a str)>,
+    early_stopping_rounds: Option<usize>,
+    maximize: Option<bool>,
+    cancellation: Option<&'a CancellationToken>,
+    time_budget: Option<Duration>,
+    observers: Vec<&'a dyn TrainingObserver>,
+    verbose_eval: usize,
+    eval_log: Option<EvalLog<'a>>,
+}
+
+/// Destination of the eval lines of [`Trainer::verbose_eval`]
+type EvalLog<'a> = Rc<RefCell<dyn FnMut(&str) -> io::Result<()> + 'a>>;
+
+impl<'a> Trainer<'a> {
+    /// Train on `dtrain` with `params` for 10 rounds
+    pub fn new(params: &'a BoosterParams, dtrain: &'a DMatrix) -> Self {
+        Trainer {
+            params,
+            dtrain,
+            num_boost_round: 10,
+            evals: Vec::new(),
+            early_stopping_rounds: None,
+            maximize: None,
+            cancellation: None,
+            time_budget: None,
+            observers: Vec::new(),
+            verbose_eval: 0,
+            eval_log: None,
+        }
+    }
+
+    /// Maximum number of boosting rounds
+    pub fn num_boost_round(mut self, num_boost_round: usize) -> Self {
+        self.num_boost_round = num_boost_round;
+        self
+    }
+
+    /// Datasets evaluated after every round, with their names
+    pub fn evals(mut self, evals: &[(&'a DMatrix, &'a str)]) -> Self {
+        self.evals = evals.to_vec();
+        self
+    }
+
+    /// Stop when the score on the last eval set has not improved for `rounds`
+    ///
+    /// The monitored metric is the last `eval_metric` in the parameters (or
+    /// the objective's default metric). The best round is stored in the
+    /// model's `best_iteration` and `best_score` attributes, so predictions
+    /// ignore the rounds after it.
+    pub fn early_stopping_rounds(mut self, rounds: usize) -> Self {
+        self.early_stopping_rounds = Some(rounds);
+        self
+    }
+
+    /// Whether a higher score is better for early stopping
+    ///
+    /// Defaults to maximizing ranking/AUC style metrics (`auc`, `aucpr`,
+    /// `map`, `ndcg`, `pre`) and minimizing everything else.
+    pub fn maximize(mut self, maximize: bool) -> Self {
+        self.maximize = Some(maximize);
+        self
+    }
+
+    /// Stop between rounds once `token` is cancelled
+    ///
+    /// The booster trained so far is returned as usual, with
+    /// [`StopReason::Cancelled`]; with early stopping its best round is
+    /// recorded as well.
+    pub fn cancellation(mut self, token: &'a CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Stop when training would exceed a wall-clock budget
+    ///
+    /// A round is not started if, at the average round duration so far, it
+    /// would end after `budget`; the result then has
+    /// [`StopReason::TimeBudget`]. The budget covers the rounds and their
+    /// evaluation, not the construction of the DMatrices.
+    pub fn time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Report training progress to `observer`, e.g. an experiment tracker
+    ///
+    /// May be called several times; observers are notified in order.
+    pub fn observer(mut self, observer: &'a dyn TrainingObserver) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// Log the evaluation results every `period` rounds (0 disables)
+    ///
+    /// Lines have XGBoost's format, e.g. `[10]\ttrain-logloss:0.31\tvalid-logloss:0.35`.
+    /// As in the Python package, the first and the last round are always
+    /// logged. Lines go to stdout unless [`eval_log`](Self::eval_log) or
+    /// [`eval_log_writer`](Self::eval_log_writer) redirects them. Nothing
+    /// is logged without eval sets.
+    pub fn verbose_eval(mut self, period: usize) -> Self {
+        self.verbose_eval = period;
+        self
+    }
+
+    /// Send eval lines to `log`, e.g. a structured logger, instead of stdout
+    ///
+    /// Logs every round unless [`verbose_eval`](Self::verbose_eval) sets a
+    /// period. To consume the values rather than the text, use a
+    /// [`TrainingObserver`] and [`RoundEvent::records`] instead of parsing
+    /// the lines.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{BoosterParams, DMatrix, Trainer};
+    ///
+    /// # let dtrain = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+    /// let params = BoosterParams::new();
+    /// let output = Trainer::new(&params, &dtrain)
+    ///     .num_boost_round(200)
+    ///     .evals(&[(&dtrain, "train")])
+    ///     .verbose_eval(10)
+    ///     .eval_log(|line| eprintln!("xgboost: {}", line))
+    ///     .train()
+    ///     .unwrap();
+    /// ```
+    pub fn eval_log(mut self, mut log: impl FnMut(&str) + 'a) -> Self {
+        self.eval_log = Some(Rc::new(RefCell::new(move |line: &str| {
+            log(line);
+            Ok(())
+        })));
+        self
+    }
+
+    /// Write eval lines, one per line, to `writer` (e.g. a log file)
+    /// instead of stdout; write errors abort training
+    pub fn eval_log_writer(mut self, mut writer: impl Write + 'a) -> Self {
+        self.eval_log = Some(Rc::new(RefCell::new(move |line: &str| {
+            writeln!(writer, "{}", line)
+        })));
+        self
+    }
+
+    /// Log an eval line to the configured destination
+    fn log_eval(&self, line: &str) -> XGBoostResult<()> {
+        let result = match &self.eval_log {
+            Some(log) => (log.borrow_mut())(line),
+            None => writeln!(io::stdout(), "{}", line),
+        };
+        result.map_err(|e| XGBoostError {
+            description: format!("Failed to write eval log: {}", e),
+        })
+    }
+
+    /// Run training
+    pub fn train(&self) -> XGBoostResult<TrainOutput> {
+        if self.early_stopping_rounds.is_some() && self.evals.is_empty() {
+            return Err(XGBoostError {
+                description: "Early stopping requires at least one eval set".to_string(),
+            });
+        }
+
+        let cache: Vec<&DMatrix> = std::iter::once(self.dtrain)
+            .chain(self.evals.iter().map(|(d, _)| *d))
+            .collect();
+        let mut booster = Booster::new(&cache)?;
+        booster.set_params(self.params)?;
+        for observer in &self.observers {
+            observer.on_train_begin(self.params);
+        }
+
+        let mut history = EvalHistory::default();
+        let mut stopper: Option<EarlyStopping> = None;
+        let mut stop_reason = StopReason::Completed;
+        let mut num_rounds = 0;
+        let log_period = match (self.verbose_eval, &self.eval_log) {
+            (0, Some(_)) => 1,
+            (period, _) => period,
+        };
+        let start = Instant::now();
+        for iteration in 0..self.num_boost_round {
+            if self
+                .cancellation
+                .is_some_and(CancellationToken::is_cancelled)
+            {
+                stop_reason = StopReason::Cancelled;
+                break;
+            }
+            if let Some(budget) = self.time_budget {
+                let elapsed = start.elapsed();
+                let round = elapsed.checked_div(iteration as u32).unwrap_or_default();
+                if elapsed + round > budget {
+                    stop_reason = StopReason::TimeBudget;
+                    break;
+                }
+            }
+            booster.update(self.dtrain, iteration)?;
+            num_rounds += 1;
+            let raw = if self.evals.is_empty() {
+                None
+            } else {
+                Some(booster.eval_one_iter(&self.evals, iteration)?)
+            };
+            let results = match &raw {
+                Some(raw) => {
+                    let names: Vec<&str> = self.evals.iter().map(|(_, name)| *name).collect();
+                    crate::model::parse_eval_result(raw, &names)?
+                }
+                None => BTreeMap::new(),
+            };
+            history.push(&results);
+            for observer in &self.observers {
+                observer.on_round(&RoundEvent {
+                    iteration,
+                    params: self.params,
+                    metrics: &results,
+                });
+            }
+
+            if let Some(rounds) = self.early_stopping_rounds {
+                let (_, dataset) = self.evals[self.evals.len() - 1];
+                let stopper = match &mut stopper {
+                    Some(stopper) => stopper,
+                    None => stopper.insert(EarlyStopping::new(
+                        self.params,
+                        dataset,
+                        &results[dataset],
+                        self.maximize,
+                    )?),
+                };
+                if stopper.update(iteration, dataset, &results[dataset], rounds)? {
+                    stop_reason = StopReason::EarlyStopping;
+                }
+            }
+
+            let last =
+                iteration + 1 == self.num_boost_round || stop_reason != StopReason::Completed;
+            if let Some(raw) = raw.filter(|_| log_period > 0) {
+                if iteration % log_period == 0 || last {
+                    self.log_eval(raw.trim())?;
+                }
+            }
+            if stop_reason != StopReason::Completed {
+                break;
+            }
+        }
+
+        if let Some(stopper) = &stopper {
+            booster.set_attr("best_iteration", &stopper.best_iteration.to_string())?;
+            booster.set_attr("best_score", &stopper.best_score.to_string())?;
+        }
+
+        let output = TrainOutput {
+            booster,
+            history,
+            metric: stopper.as_ref().map(|s| s.metric.clone()),
+            best_iteration: stopper.as_ref().map(|s| s.best_iteration),
+            best_score: stopper.as_ref().map(|s| s.best_score),
+            num_rounds,
+            stop_reason,
+        };
+        for observer in &self.observers {
+            observer.on_train_end(&output);
+        }
+        Ok(output)
+    }
+}
+
+/// Progress of one boosting round, passed to [`TrainingObserver::on_round`].
+#[derive(Debug, Clone, Copy)]
+pub struct RoundEvent<'e> {
+    /// Index of the round just trained
+    pub iteration: usize,
+    /// The training parameters
+    pub params: &'e BoosterParams,
+    /// Evaluation results of the round, dataset → metric → value (empty
+    /// without eval sets)
+    pub metrics: &'e BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+impl RoundEvent<'_> {
+    /// Evaluation results of the round as one record per dataset and
+    /// metric, in dataset then metric name order
+    pub fn records(&self) -> Vec<EvalRecord> {
+        self.metrics
+            .iter()
+            .flat_map(|(dataset, metrics)| {
+                metrics.iter().map(move |(metric, value)| EvalRecord {
+                    dataset: dataset.clone(),
+                    metric: metric.clone(),
+                    value: *value,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Receives training events, for integrations such as experiment trackers.
+///
+/// All methods have empty defaults, so implementations only override the
+/// events they need. They take `&self`; observers that keep state use
+/// interior mutability (`Cell`, `RefCell`, `Mutex`).
+///
+/// # Example
+/// ```no_run
+/// use std::cell::RefCell;
+/// use xgboost_rust::{BoosterParams, DMatrix, RoundEvent, Trainer, TrainingObserver};
+///
+/// #[derive(Default)]
+/// struct Tracker {
+///     logged: RefCell<Vec<(usize, String, f64)>>,
+/// }
+///
+/// impl TrainingObserver for Tracker {
+///     fn on_train_begin(&self, params: &BoosterParams) {
+///         for (name, value) in params.iter() {
+///             println!("param {} = {}", name, value);
+///         }
+///     }
+///
+///     fn on_round(&self, event: &RoundEvent<'_>) {
+///         for record in event.records() {
+///             let key = format!("{}-{}", record.dataset, record.metric);
+///             self.logged.borrow_mut().push((event.iteration, key, record.value));
+///         }
+///     }
+/// }
+///
+/// # let dtrain = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+/// let tracker = Tracker::default();
+/// let params = BoosterParams::new();
+/// let output = Trainer::new(&params, &dtrain)
+///     .evals(&[(&dtrain, "train")])
+///     .observer(&tracker)
+///     .train()
+///     .unwrap();
+/// ```
+pub trait TrainingObserver {
+    /// Called once before the first round
+    fn on_train_begin(&self, _params: &BoosterParams) {}
+
+    /// Called after every round, once it has been evaluated
+    fn on_round(&self, _event: &RoundEvent<'_>) {}
+
+    /// Called once training has finished, before the output is returned
+    fn on_train_end(&self, _output: &TrainOutput) {}
+}
+
+/// Why training ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// All `num_boost_round` rounds were trained
+    Completed,
+    /// The monitored score stopped improving
+    EarlyStopping,
+    /// The [`CancellationToken`] was cancelled
+    Cancelled,
+    /// The next round would have exceeded [`Trainer::time_budget`]
+    TimeBudget,
+}
+
+/// Result of [`Trainer::train`].
+pub struct TrainOutput {
+    /// The trained booster (including rounds after the best one)
+    pub booster: Booster,
+    /// Evaluation results of every round
+    pub history: EvalHistory,
+    /// Metric monitored by early stopping
+    pub metric: Option<String>,
+    /// Best round found by early stopping
+    pub best_iteration: Option<usize>,
+    /// Score of the monitored metric at `best_iteration`
+    pub best_score: Option<f64>,
+    /// Number of rounds trained; the last completed round is `num_rounds - 1`
+    pub num_rounds: usize,
+    /// Why training ended
+    pub stop_reason: StopReason,
+}
+
+/// Evaluation results recorded during training, one value per round.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvalHistory {
+    results: BTreeMap<String, BTreeMap<String, Vec<f64>>>,
+}
+
+impl EvalHistory {
+    /// Values of `metric` on `dataset`, indexed by round
+    pub fn get(&self, dataset: &str, metric: &str) -> Option<&[f64]> {
+        self.results
+            .get(dataset)
+            .and_then(|metrics| metrics.get(metric))
+            .map(Vec::as_slice)
+    }
+
+    /// Names of the evaluated datasets
+    pub fn datasets(&self) -> impl Iterator<Item = &str> {
+        self.results.keys().map(String::as_str)
+    }
+
+    /// Names of the metrics recorded for `dataset`
+    pub fn metrics(&self, dataset: &str) -> impl Iterator<Item = &str> {
+        self.results
+            .get(dataset)
+            .into_iter()
+            .flat_map(|metrics| metrics.keys().map(String::as_str))
+    }
+
+    /// Number of recorded rounds
+    pub fn num_rounds(&self) -> usize {
+        self.results
+            .values()
+            .flat_map(|metrics| metrics.values())
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Write the history as CSV, one row per round
+    ///
+    /// The columns are `round` and `<dataset>-<metric>` for every recorded
+    /// metric (e.g. `valid-logloss`), as in XGBoost's log output.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use xgboost_rust::EvalHistory;
+    /// # let history = EvalHistory::default();
+    /// let file = std::fs::File::create("history.csv").unwrap();
+    /// history.to_csv(std::io::BufWriter::new(file)).unwrap();
+    /// ```
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> XGBoostResult<()> {
+        let columns = self.columns();
+        let mut csv = String::from("round");
+        for (name, _) in &columns {
+            csv.push(',');
+            csv.push_str(&csv_field(name));
+        }
+        csv.push('\n');
+        for round in 0..self.num_rounds() {
+            csv.push_str(&round.to_string());
+            for (_, values) in &columns {
+                csv.push(',');
+                if let Some(value) = values.get(round) {
+                    csv.push_str(&value.to_string());
+                }
+            }
+            csv.push('\n');
+        }
+
+        writer
+            .write_all(csv.as_bytes())
+            .and_then(|_| writer.flush())
+            .map_err(|e| XGBoostError {
+                description: format!("Failed to write eval history: {}", e),
+            })
+    }
+
+    /// The history as a Polars `DataFrame`, one row per round
+    ///
+    /// The columns are the same as those of [`to_csv`](Self::to_csv): a
+    /// `u32` `round` column and an `f64` column per metric, null for rounds
+    /// a metric was not recorded in.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use xgboost_rust::EvalHistory;
+    /// # let history = EvalHistory::default();
+    /// let curves = history.to_dataframe().unwrap();
+    /// println!("{}", curves.height());
+    /// ```
+    #[cfg(feature = "polars")]
+    pub fn to_dataframe(&self) -> XGBoostResult<polars::prelude::DataFrame> {
+        use polars::prelude::{Column, DataFrame, NamedFrom};
+
+        let num_rounds = self.num_rounds();
+        let rounds: Vec<u32> = (0..num_rounds as u32).collect();
+        let mut columns = vec![Column::new("round".into(), rounds)];
+        for (name, values) in self.columns() {
+            let values: Vec<Option<f64>> =
+                (0..num_rounds).map(|r| values.get(r).copied()).collect();
+            columns.push(Column::new(name.into(), values));
+        }
+        DataFrame::new(columns).map_err(|e| XGBoostError {
+            description: format!("Failed to build the eval history DataFrame: {}", e),
+        })
+    }
+
+    /// `<dataset>-<metric>` name and values of every recorded metric
+    fn columns(&self) -> Vec<(String, &[f64])> {
+        self.results
+            .iter()
+            .flat_map(|(dataset, metrics)| {
+                metrics
+                    .iter()
+                    .map(move |(metric, values)| (format!("{}-{}", dataset, metric), &values[..]))
+            })
+            .collect()
+    }
+
+    pub(crate) fn push(&mut self, round: &BTreeMap<String, BTreeMap<String, f64>>) {
+        for (dataset, metrics) in round {
+            let entry = self.results.entry(dataset.clone()).or_default();
+            for (metric, value) in metrics {
+                entry.entry(metric.clone()).or_default().push(*value);
+            }
+        }
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Early stopping state for one monitored metric.
+pub(crate) struct EarlyStopping {
+    pub(crate) metric: String,
+    maximize: bool,
+    pub(crate) best_iteration: usize,
+    pub(crate) best_score: f64,
+}
+
+impl EarlyStopping {
+    pub(crate) fn new(
+        params: &BoosterParams,
+        dataset: &str,
+        first_round: &BTreeMap<String, f64>,
+        maximize: Option<bool>,
+    ) -> XGBoostResult<Self> {
+        let metric = match params.get("eval_metric") {
+            Some(metric) => metric.to_string(),
+            None if first_round.len() == 1 => first_round.keys().next().unwrap().clone(),
+            None => {
+                return Err(XGBoostError {
+                    description: format!(
+                    "Cannot choose a metric for early stopping among {:?} on '{}'; set eval_metric",
+                    first_round.keys().collect::<Vec<_>>(),
+                    dataset
+                ),
+                })
+            }
+        };
+        let maximize = maximize.unwrap_or_else(|| is_maximize_metric(&metric));
+        Ok(EarlyStopping {
+            metric,
+            maximize,
+            best_iteration: 0,
+            best_score: if maximize {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            },
+        })
+    }
+
+    /// Record the results of a round on `dataset`; returns whether training should stop
+    pub(crate) fn update(
+        &mut self,
+        iteration: usize,
+        dataset: &str,
+        results: &BTreeMap<String, f64>,
+        rounds: usize,
+    ) -> XGBoostResult<bool> {
+        let score = results
+            .get(&self.metric)
+            .copied()
+            .ok_or_else(|| XGBoostError {
+                description: format!(
+                    "Metric '{}' missing from evaluation of '{}'",
+                    self.metric, dataset
+                ),
+            })?;
+        let improved = if self.maximize {
+            score > self.best_score
+        } else {
+            score < self.best_score
+        };
+        if improved {
+            self.best_score = score;
+            self.best_iteration = iteration;
+        }
+        Ok(iteration - self.best_iteration >= rounds)
+    }
+}
+
+/// Whether XGBoost's Python package maximizes this metric during early stopping
+pub(crate) fn is_maximize_metric(metric: &str) -> bool {
+    const MAXIMIZE: [&str; 5] = ["auc", "aucpr", "map", "ndcg", "pre"];
+    metric != "mape" && MAXIMIZE.iter().any(|prefix| metric.starts_with(prefix))
+}
@@ -0,0 +1,684 @@
+//! Hyperparameter search.
+//!
+//! A [`ParamSpace`] describes parameter distributions to sample from, a
+//! [`ParamGrid`] lists the values to try exhaustively, both on top of a set
+//! of base parameters. Every trial trains with early stopping and is scored
+//! by its best validation round: on a validation set for [`random_search`]
+//! and [`successive_halving`], by cross-validation for [`grid_search_cv`].
+//!
+//! # Example
+//! ```no_run
+//! use xgboost_rust::tuning::{random_search, ParamSpace};
+//! use xgboost_rust::{BoosterParams, DMatrix, Objective};
+//!
+//! let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+//! dtrain.set_label(&[0.0, 1.0]).unwrap();
+//! let mut dvalid = DMatrix::from_dense(&[2.0, 3.0], 1, 2).unwrap();
+//! dvalid.set_label(&[1.0]).unwrap();
+//!
+//! let base = BoosterParams::new()
+//!     .objective(Objective::BinaryLogistic)
+//!     .eval_metric("logloss");
+//! let space = ParamSpace::new(base)
+//!     .log_uniform("eta", 0.01, 0.3)
+//!     .int_range("max_depth", 2, 8)
+//!     .uniform("subsample", 0.5, 1.0)
+//!     .choice("tree_method", &["hist", "approx"])
+//!     .num_boost_round(200)
+//!     .early_stopping_rounds(10);
+//!
+//! let trials = random_search(&space, &dtrain, &dvalid, 20).unwrap();
+//! let best = &trials[0];
+//! println!("{:?} scored {} at round {}", best.values, best.best_score, best.best_iteration);
+//! ```
+
+use std::fmt;
+
+use crate::cv::CrossValidation;
+use crate::dmatrix::DMatrix;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::model::{parse_eval_result, Booster};
+use crate::params::BoosterParams;
+use crate::rng::SplitMix64;
+use crate::train::{is_maximize_metric, EarlyStopping, EvalHistory, Trainer};
+
+/// How the values of one parameter are sampled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Distribution {
+    /// Uniform over `[low, high]`
+    Uniform { low: f64, high: f64 },
+    /// Uniform in log space over `[low, high]`; both must be positive
+    LogUniform { low: f64, high: f64 },
+    /// Integers in `[low, high]` (inclusive)
+    IntRange { low: i64, high: i64 },
+    /// One of the listed values
+    Choice(Vec<String>),
+}
+
+impl Distribution {
+    fn validate(&self, name: &str) -> XGBoostResult<()> {
+        let valid = match self {
+            Distribution::Uniform { low, high } => low <= high,
+            Distribution::LogUniform { low, high } => *low > 0.0 && low <= high,
+            Distribution::IntRange { low, high } => low <= high,
+            Distribution::Choice(values) => !values.is_empty(),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(XGBoostError {
+                description: format!("Invalid search distribution for '{}': {:?}", name, self),
+            })
+        }
+    }
+
+    fn sample(&self, rng: &mut SplitMix64) -> ParamValue {
+        match self {
+            Distribution::Uniform { low, high } => {
+                ParamValue::Float(low + rng.next_f64() * (high - low))
+            }
+            Distribution::LogUniform { low, high } => {
+                let (low, high) = (low.ln(), high.ln());
+                ParamValue::Float((low + rng.next_f64() * (high - low)).exp())
+            }
+            Distribution::IntRange { low, high } => {
+                let span = (high - low) as u64 + 1;
+                ParamValue::Int(low + (rng.next_u64() % span) as i64)
+            }
+            Distribution::Choice(values) => {
+                ParamValue::Choice(values[rng.below(values.len())].clone())
+            }
+        }
+    }
+}
+
+/// A sampled parameter value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamValue {
+    Float(f64),
+    Int(i64),
+    Choice(String),
+}
+
+impl fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamValue::Float(value) => write!(f, "{}", value),
+            ParamValue::Int(value) => write!(f, "{}", value),
+            ParamValue::Choice(value) => f.write_str(value),
+        }
+    }
+}
+
+/// Parameters to search over, and how each trial is trained.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamSpace {
+    base: BoosterParams,
+    dimensions: Vec<(String, Distribution)>,
+    num_boost_round: usize,
+    early_stopping_rounds: usize,
+    seed: u64,
+}
+
+impl ParamSpace {
+    /// Search on top of `base`; sampled values override base values
+    ///
+    /// Trials train for at most 100 rounds and stop after 10 rounds without
+    /// improvement.
+    pub fn new(base: BoosterParams) -> Self {
+        ParamSpace {
+            base,
+            dimensions: Vec::new(),
+            num_boost_round: 100,
+            early_stopping_rounds: 10,
+            seed: 0,
+        }
+    }
+
+    /// Sample `name` from any distribution
+    pub fn param(mut self, name: &str, distribution: Distribution) -> Self {
+        self.dimensions.push((name.to_string(), distribution));
+        self
+    }
+
+    /// Sample `name` uniformly from `[low, high]`
+    pub fn uniform(self, name: &str, low: f64, high: f64) -> Self {
+        self.param(name, Distribution::Uniform { low, high })
+    }
+
+    /// Sample `name` log-uniformly from `[low, high]` (e.g. learning rates)
+    pub fn log_uniform(self, name: &str, low: f64, high: f64) -> Self {
+        self.param(name, Distribution::LogUniform { low, high })
+    }
+
+    /// Sample `name` from the integers in `[low, high]`
+    pub fn int_range(self, name: &str, low: i64, high: i64) -> Self {
+        self.param(name, Distribution::IntRange { low, high })
+    }
+
+    /// Sample `name` from a list of values
+    pub fn choice(self, name: &str, values: &[impl ToString]) -> Self {
+        let values = values.iter().map(ToString::to_string).collect();
+        self.param(name, Distribution::Choice(values))
+    }
+
+    /// Maximum number of boosting rounds per trial
+    pub fn num_boost_round(mut self, num_boost_round: usize) -> Self {
+        self.num_boost_round = num_boost_round;
+        self
+    }
+
+    /// Early stopping patience of each trial
+    pub fn early_stopping_rounds(mut self, rounds: usize) -> Self {
+        self.early_stopping_rounds = rounds;
+        self
+    }
+
+    /// Seed of the sampler, so searches are reproducible
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    fn validate(&self) -> XGBoostResult<()> {
+        self.dimensions
+            .iter()
+            .try_for_each(|(name, distribution)| distribution.validate(name))
+    }
+
+    fn sample(&self, rng: &mut SplitMix64) -> Vec<(String, ParamValue)> {
+        self.dimensions
+            .iter()
+            .map(|(name, distribution)| (name.clone(), distribution.sample(rng)))
+            .collect()
+    }
+
+    fn params_for(&self, values: &[(String, ParamValue)]) -> BoosterParams {
+        values
+            .iter()
+            .fold(self.base.clone(), |params, (name, value)| {
+                params.param(name, value)
+            })
+    }
+}
+
+/// Outcome of one tuning trial.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrialResult {
+    /// Sampled values, in the order of the search space
+    pub values: Vec<(String, ParamValue)>,
+    /// Full parameters the trial was trained with
+    pub params: BoosterParams,
+    /// Metric the trial was scored on
+    pub metric: String,
+    /// Best validation score
+    pub best_score: f64,
+    /// Round of the best validation score
+    pub best_iteration: usize,
+    /// Evaluation results on `train` and `valid` for every round
+    pub history: EvalHistory,
+}
+
+/// Random search over `space`
+///
+/// Trains `budget` trials on `dtrain` with early stopping on `dvalid` and
+/// returns them ranked best first. The metric is the last `eval_metric` of
+/// the parameters (or the objective's default); higher is better for
+/// ranking/AUC style metrics, lower for everything else.
+pub fn random_search(
+    space: &ParamSpace,
+    dtrain: &DMatrix,
+    dvalid: &DMatrix,
+    budget: usize,
+) -> XGBoostResult<Vec<TrialResult>> {
+    space.validate()?;
+    let mut rng = SplitMix64::new(space.seed);
+    let mut trials = Vec::with_capacity(budget);
+    for _ in 0..budget {
+        let values = space.sample(&mut rng);
+        let params = space.params_for(&values);
+        let output = Trainer::new(&params, dtrain)
+            .num_boost_round(space.num_boost_round)
+            .evals(&[(dtrain, "train"), (dvalid, "valid")])
+            .early_stopping_rounds(space.early_stopping_rounds)
+            .train()?;
+        let (Some(metric), Some(best_score), Some(best_iteration)) =
+            (output.metric, output.best_score, output.best_iteration)
+        else {
+            return Err(XGBoostError {
+                description: "Trial finished without any evaluation round".to_string(),
+            });
+        };
+        trials.push(TrialResult {
+            values,
+            params,
+            metric,
+            best_score,
+            best_iteration,
+            history: output.history,
+        });
+    }
+    rank(&mut trials);
+    Ok(trials)
+}
+
+/// Sort trials best first
+fn rank(trials: &mut [TrialResult]) {
+    trials.sort_by(|a, b| {
+        if is_maximize_metric(&a.metric) {
+            b.best_score.total_cmp(&a.best_score)
+        } else {
+            a.best_score.total_cmp(&b.best_score)
+        }
+    });
+}
+
+/// Parameter values to try exhaustively, and how each combination is trained.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamGrid {
+    base: BoosterParams,
+    dimensions: Vec<(String, Vec<ParamValue>)>,
+    num_boost_round: usize,
+    early_stopping_rounds: usize,
+    seed: u64,
+    #[cfg(xgboost_thread_safe)]
+    parallel_folds: bool,
+}
+
+impl ParamGrid {
+    /// Grid on top of `base`; grid values override base values
+    ///
+    /// Each combination trains for at most 100 rounds and stops after 10
+    /// rounds without improvement of the mean test score.
+    pub fn new(base: BoosterParams) -> Self {
+        ParamGrid {
+            base,
+            dimensions: Vec::new(),
+            num_boost_round: 100,
+            early_stopping_rounds: 10,
+            seed: 0,
+            #[cfg(xgboost_thread_safe)]
+            parallel_folds: false,
+        }
+    }
+
+    /// Try each of `values` for `name`
+    pub fn values(mut self, name: &str, values: Vec<ParamValue>) -> Self {
+        self.dimensions.push((name.to_string(), values));
+        self
+    }
+
+    /// Try each float in `values` for `name`
+    pub fn floats(self, name: &str, values: &[f64]) -> Self {
+        self.values(
+            name,
+            values.iter().copied().map(ParamValue::Float).collect(),
+        )
+    }
+
+    /// Try each integer in `values` for `name`
+    pub fn ints(self, name: &str, values: &[i64]) -> Self {
+        self.values(name, values.iter().copied().map(ParamValue::Int).collect())
+    }
+
+    /// Try each of `values` for `name`
+    pub fn choices(self, name: &str, values: &[impl ToString]) -> Self {
+        let values = values
+            .iter()
+            .map(|value| ParamValue::Choice(value.to_string()))
+            .collect();
+        self.values(name, values)
+    }
+
+    /// Maximum number of boosting rounds per combination
+    pub fn num_boost_round(mut self, num_boost_round: usize) -> Self {
+        self.num_boost_round = num_boost_round;
+        self
+    }
+
+    /// Early stopping patience of each combination
+    pub fn early_stopping_rounds(mut self, rounds: usize) -> Self {
+        self.early_stopping_rounds = rounds;
+        self
+    }
+
+    /// Seed of the fold split, shared by all combinations
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Train the folds of each combination on separate threads (XGBoost ≥ 1.4)
+    #[cfg(xgboost_thread_safe)]
+    pub fn parallel_folds(mut self, parallel_folds: bool) -> Self {
+        self.parallel_folds = parallel_folds;
+        self
+    }
+
+    /// Every combination of grid values, the last dimension varying fastest
+    fn combinations(&self) -> XGBoostResult<Vec<Vec<(String, ParamValue)>>> {
+        if let Some((name, _)) = self.dimensions.iter().find(|(_, v)| v.is_empty()) {
+            return Err(XGBoostError {
+                description: format!("No grid values given for '{}'", name),
+            });
+        }
+        let mut combinations = vec![Vec::new()];
+        for (name, values) in &self.dimensions {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|prefix: Vec<(String, ParamValue)>| {
+                    values.iter().map(move |value| {
+                        let mut combination = prefix.clone();
+                        combination.push((name.clone(), value.clone()));
+                        combination
+                    })
+                })
+                .collect();
+        }
+        Ok(combinations)
+    }
+
+    fn params_for(&self, values: &[(String, ParamValue)]) -> BoosterParams {
+        values
+            .iter()
+            .fold(self.base.clone(), |params, (name, value)| {
+                params.param(name, value)
+            })
+    }
+}
+
+/// Cross-validated score of one grid combination.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridResult {
+    /// Grid values, in the order of the grid
+    pub values: Vec<(String, ParamValue)>,
+    /// Full parameters the combination was trained with
+    pub params: BoosterParams,
+    /// Mean test score over folds at the best round
+    pub mean: f64,
+    /// Standard deviation of the test score over folds at the best round
+    pub std: f64,
+    /// Round with the best mean test score
+    pub best_iteration: usize,
+}
+
+/// Exhaustive grid search scored by k-fold cross-validation
+///
+/// Every combination of `grid` is cross-validated on `dmatrix` with `nfold`
+/// folds and early stopping on `metric`, which is added as the last
+/// `eval_metric`. All combinations use the same folds. Results are ranked
+/// best first; higher is better for ranking/AUC style metrics, lower for
+/// everything else.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::tuning::{grid_search_cv, ParamGrid};
+/// use xgboost_rust::{BoosterParams, DMatrix, Objective};
+///
+/// # let dmatrix = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+/// let grid = ParamGrid::new(BoosterParams::new().objective(Objective::BinaryLogistic))
+///     .ints("max_depth", &[3, 5, 7])
+///     .floats("eta", &[0.05, 0.1, 0.3]);
+///
+/// for result in grid_search_cv(&grid, &dmatrix, 5, "auc").unwrap() {
+///     println!("{:?}: {:.4} ± {:.4}", result.values, result.mean, result.std);
+/// }
+/// ```
+pub fn grid_search_cv(
+    grid: &ParamGrid,
+    dmatrix: &DMatrix,
+    nfold: usize,
+    metric: &str,
+) -> XGBoostResult<Vec<GridResult>> {
+    let mut results = Vec::new();
+    for values in grid.combinations()? {
+        let params = grid.params_for(&values).eval_metric(metric);
+        let cv = CrossValidation::new(&params, dmatrix)
+            .nfold(nfold)
+            .num_boost_round(grid.num_boost_round)
+            .early_stopping_rounds(grid.early_stopping_rounds)
+            .seed(grid.seed);
+        #[cfg(xgboost_thread_safe)]
+        let cv = cv.parallel(grid.parallel_folds);
+        let cv = cv.run()?;
+
+        let (Some(mean), Some(best_iteration)) = (cv.best_score, cv.best_iteration) else {
+            return Err(XGBoostError {
+                description: "Cross-validation finished without any evaluation round".to_string(),
+            });
+        };
+        let std = cv
+            .std
+            .get("test", metric)
+            .and_then(|std| std.get(best_iteration))
+            .copied()
+            .unwrap_or(f64::NAN);
+        results.push(GridResult {
+            values,
+            params,
+            mean,
+            std,
+            best_iteration,
+        });
+    }
+
+    let maximize = is_maximize_metric(metric);
+    results.sort_by(|a, b| {
+        if maximize {
+            b.mean.total_cmp(&a.mean)
+        } else {
+            a.mean.total_cmp(&b.mean)
+        }
+    });
+    Ok(results)
+}
+
+/// Budget schedule of [`successive_halving`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HalvingSchedule {
+    num_configs: usize,
+    min_rounds: usize,
+    reduction_factor: usize,
+}
+
+impl HalvingSchedule {
+    /// Start with `num_configs` sampled configurations
+    ///
+    /// The first rung trains every configuration for 10 rounds; each
+    /// following rung keeps the best third and triples their rounds.
+    pub fn new(num_configs: usize) -> Self {
+        HalvingSchedule {
+            num_configs,
+            min_rounds: 10,
+            reduction_factor: 3,
+        }
+    }
+
+    /// Rounds of the first rung
+    pub fn min_rounds(mut self, min_rounds: usize) -> Self {
+        self.min_rounds = min_rounds;
+        self
+    }
+
+    /// Keep `1 / factor` of the configurations per rung, training them `factor` times longer
+    pub fn reduction_factor(mut self, reduction_factor: usize) -> Self {
+        self.reduction_factor = reduction_factor;
+        self
+    }
+}
+
+/// A configuration being trained by successive halving.
+struct Candidate {
+    values: Vec<(String, ParamValue)>,
+    params: BoosterParams,
+    booster: Booster,
+    history: EvalHistory,
+    stopper: Option<EarlyStopping>,
+    rounds: usize,
+    stopped: bool,
+}
+
+impl Candidate {
+    fn new(
+        space: &ParamSpace,
+        values: Vec<(String, ParamValue)>,
+        dtrain: &DMatrix,
+        dvalid: &DMatrix,
+    ) -> XGBoostResult<Self> {
+        let params = space.params_for(&values);
+        let mut booster = Booster::new(&[dtrain, dvalid])?;
+        booster.set_params(&params)?;
+        Ok(Candidate {
+            values,
+            params,
+            booster,
+            history: EvalHistory::default(),
+            stopper: None,
+            rounds: 0,
+            stopped: false,
+        })
+    }
+
+    /// Resume boosting until `rounds` rounds in total, or early stopping
+    fn train_to(
+        &mut self,
+        rounds: usize,
+        early_stopping_rounds: usize,
+        dtrain: &DMatrix,
+        dvalid: &DMatrix,
+    ) -> XGBoostResult<()> {
+        let evals = [(dtrain, "train"), (dvalid, "valid")];
+        while self.rounds < rounds && !self.stopped {
+            let iteration = self.rounds;
+            self.booster.update(dtrain, iteration)?;
+            let raw = self.booster.eval_one_iter(&evals, iteration)?;
+            let results = parse_eval_result(&raw, &["train", "valid"])?;
+            self.history.push(&results);
+            let stopper = match &mut self.stopper {
+                Some(stopper) => stopper,
+                None => self.stopper.insert(EarlyStopping::new(
+                    &self.params,
+                    "valid",
+                    &results["valid"],
+                    None,
+                )?),
+            };
+            self.stopped =
+                stopper.update(iteration, "valid", &results["valid"], early_stopping_rounds)?;
+            self.rounds += 1;
+        }
+        Ok(())
+    }
+
+    fn into_result(self) -> XGBoostResult<TrialResult> {
+        let stopper = self.stopper.ok_or_else(|| XGBoostError {
+            description: "Trial finished without any evaluation round".to_string(),
+        })?;
+        Ok(TrialResult {
+            values: self.values,
+            params: self.params,
+            metric: stopper.metric,
+            best_score: stopper.best_score,
+            best_iteration: stopper.best_iteration,
+            history: self.history,
+        })
+    }
+}
+
+/// Successive halving over `space`
+///
+/// Samples `schedule`'s configurations, trains them all for a few rounds
+/// and repeatedly keeps the best fraction, resuming their boosting for
+/// more rounds, until one configuration is left or the survivors reach the
+/// space's `num_boost_round`. Most of the compute goes to promising
+/// configurations instead of being spread evenly as in a grid search.
+///
+/// Every configuration also stops early after the space's
+/// `early_stopping_rounds` without improvement on `dvalid`. All trials are
+/// returned best first: survivors of the last rung ranked by score, followed
+/// by the configurations eliminated at each earlier rung.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::tuning::{successive_halving, HalvingSchedule, ParamSpace};
+/// use xgboost_rust::{BoosterParams, DMatrix, Objective};
+///
+/// # let dtrain = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+/// # let dvalid = DMatrix::from_dense(&[1.0, 2.0], 1, 2).unwrap();
+/// let space = ParamSpace::new(BoosterParams::new().objective(Objective::BinaryLogistic))
+///     .log_uniform("eta", 0.01, 0.3)
+///     .int_range("max_depth", 2, 10)
+///     .num_boost_round(810);
+///
+/// // 81 configs × 10 rounds, 27 × 30, 9 × 90, 3 × 270, 1 × 810
+/// let schedule = HalvingSchedule::new(81).min_rounds(10).reduction_factor(3);
+/// let trials = successive_halving(&space, &dtrain, &dvalid, &schedule).unwrap();
+/// println!("best: {:?}", trials[0].values);
+/// ```
+pub fn successive_halving(
+    space: &ParamSpace,
+    dtrain: &DMatrix,
+    dvalid: &DMatrix,
+    schedule: &HalvingSchedule,
+) -> XGBoostResult<Vec<TrialResult>> {
+    space.validate()?;
+    if schedule.min_rounds == 0 || schedule.reduction_factor < 2 {
+        return Err(XGBoostError {
+            description: format!(
+                "Successive halving needs min_rounds >= 1 and reduction_factor >= 2, got {} and {}",
+                schedule.min_rounds, schedule.reduction_factor
+            ),
+        });
+    }
+
+    let mut rng = SplitMix64::new(space.seed);
+    let mut survivors = (0..schedule.num_configs)
+        .map(|_| Candidate::new(space, space.sample(&mut rng), dtrain, dvalid))
+        .collect::<XGBoostResult<Vec<_>>>()?;
+    let mut eliminated: Vec<Vec<TrialResult>> = Vec::new();
+    let mut rounds = schedule.min_rounds.min(space.num_boost_round);
+    loop {
+        for candidate in &mut survivors {
+            candidate.train_to(rounds, space.early_stopping_rounds, dtrain, dvalid)?;
+        }
+        rank_candidates(&mut survivors);
+        if survivors.len() <= 1 || rounds >= space.num_boost_round {
+            break;
+        }
+
+        let keep = (survivors.len() + schedule.reduction_factor - 1) / schedule.reduction_factor;
+        let losers = survivors.split_off(keep);
+        eliminated.push(
+            losers
+                .into_iter()
+                .map(Candidate::into_result)
+                .collect::<XGBoostResult<_>>()?,
+        );
+        rounds = rounds
+            .saturating_mul(schedule.reduction_factor)
+            .min(space.num_boost_round);
+    }
+
+    let mut results = survivors
+        .into_iter()
+        .map(Candidate::into_result)
+        .collect::<XGBoostResult<Vec<_>>>()?;
+    results.extend(eliminated.into_iter().rev().flatten());
+    Ok(results)
+}
+
+/// Sort candidates best first by their best validation score so far
+fn rank_candidates(candidates: &mut [Candidate]) {
+    let maximize = candidates
+        .first()
+        .and_then(|c| c.stopper.as_ref())
+        .is_some_and(|s| is_maximize_metric(&s.metric));
+    let score = |c: &Candidate| c.stopper.as_ref().map_or(f64::NAN, |s| s.best_score);
+    candidates.sort_by(|a, b| {
+        if maximize {
+            score(b).total_cmp(&score(a))
+        } else {
+            score(a).total_cmp(&score(b))
+        }
+    });
+}
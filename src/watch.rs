@@ -0,0 +1,306 @@
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::Booster;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::Hasher;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+type ReloadCallback = Box<dyn FnMut(&XGBoostResult<()>) + Send>;
+
+/// Modification time resolution of the coarsest common file systems
+/// (FAT); a rewrite this soon after the last one may keep its mtime
+const MTIME_GRANULARITY: Duration = Duration::from_secs(2);
+
+/// What is known about a file, to notice when it changes
+#[derive(Debug, Clone, PartialEq)]
+struct FileVersion {
+    modified: Option<SystemTime>,
+    len: u64,
+    /// Device and inode on Unix, so a file renamed over the path is seen
+    /// even with the same mtime and size
+    file_id: Option<(u64, u64)>,
+    /// Hash of the contents, taken while the mtime is too recent to tell
+    /// a same-size rewrite apart
+    hash: Option<u64>,
+}
+
+impl FileVersion {
+    fn same_as(&self, other: &FileVersion) -> bool {
+        self.modified == other.modified
+            && self.len == other.len
+            && self.file_id == other.file_id
+            && match (self.hash, other.hash) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+    }
+}
+
+/// Options of a [`WatchedBooster`].
+pub struct WatchedBoosterBuilder {
+    path: PathBuf,
+    poll_interval: Duration,
+    on_reload: Option<ReloadCallback>,
+    warmup: Option<Warmup>,
+}
+
+/// Sample rows predicted on each new model before it is swapped in
+struct Warmup {
+    sample: Vec<f32>,
+    num_rows: usize,
+    iterations: usize,
+}
+
+impl WatchedBoosterBuilder {
+    /// How often the file is checked for changes (default 1 s)
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Called after every reload attempt, with the error if the new file
+    /// could not be loaded (the previous model stays in use)
+    pub fn on_reload<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&XGBoostResult<()>) + Send + 'static,
+    {
+        self.on_reload = Some(Box::new(callback));
+        self
+    }
+
+    /// Warm up every loaded model with `iterations` predictions of `sample`
+    /// before it serves requests (see [`Booster::warmup`])
+    pub fn warmup(mut self, sample: &[f32], num_rows: usize, iterations: usize) -> Self {
+        self.warmup = Some(Warmup {
+            sample: sample.to_vec(),
+            num_rows,
+            iterations,
+        });
+        self
+    }
+
+    /// Load the model and start watching its file
+    pub fn build(self) -> XGBoostResult<WatchedBooster> {
+        let version = file_version(&self.path)?;
+        let booster = load(&self.path, self.warmup.as_ref())?;
+        let shared = Arc::new(Shared {
+            booster: RwLock::new(Arc::new(booster)),
+            path: self.path,
+            version: Mutex::new(version),
+            on_reload: Mutex::new(self.on_reload),
+            warmup: self.warmup,
+        });
+        let (stop, stopped) = mpsc::channel::<()>();
+        let watcher = {
+            let shared = Arc::clone(&shared);
+            let interval = self.poll_interval;
+            thread::Builder::new()
+                .name("xgboost-model-watcher".to_string())
+                .spawn(move || {
+                    while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                        shared.reload_if_changed();
+                    }
+                })
+                .map_err(|e| XGBoostError {
+                    description: format!("Failed to start the model watcher: {}", e),
+                })?
+        };
+        Ok(WatchedBooster {
+            shared,
+            stop: Some(stop),
+            watcher: Some(watcher),
+        })
+    }
+}
+
+struct Shared {
+    booster: RwLock<Arc<Booster>>,
+    path: PathBuf,
+    version: Mutex<FileVersion>,
+    on_reload: Mutex<Option<ReloadCallback>>,
+    warmup: Option<Warmup>,
+}
+
+impl Shared {
+    fn reload_if_changed(&self) {
+        let mut known = self.version.lock().unwrap();
+        let Ok(version) = file_version(&self.path) else {
+            // the file may be between a delete and a rename; check again later
+            return;
+        };
+        if known.same_as(&version) {
+            // keep the newest hash, or drop it once the mtime can be trusted
+            *known = version;
+            return;
+        }
+        // remember failed versions too, so a broken file is only retried
+        // once it changes again
+        *known = version;
+        drop(known);
+        let _ = self.reload();
+    }
+
+    fn reload(&self) -> XGBoostResult<()> {
+        let result = load(&self.path, self.warmup.as_ref()).map(|booster| {
+            *self.booster.write().unwrap() = Arc::new(booster);
+        });
+        if let Some(callback) = self.on_reload.lock().unwrap().as_mut() {
+            callback(&result);
+        }
+        result
+    }
+}
+
+fn load(path: &Path, warmup: Option<&Warmup>) -> XGBoostResult<Booster> {
+    let booster = Booster::load(path)?;
+    if let Some(warmup) = warmup {
+        booster.warmup(&warmup.sample, warmup.num_rows, warmup.iterations)?;
+    }
+    Ok(booster)
+}
+
+/// The current version of the file at `path`
+///
+/// The contents are hashed only while the mtime is within
+/// [`MTIME_GRANULARITY`] of now, so a rewrite in that window is compared
+/// by content; once the mtime is older, any later write changes it, so
+/// older files are never read just to be compared.
+fn file_version(path: &Path) -> XGBoostResult<FileVersion> {
+    let error = |e: std::io::Error| XGBoostError {
+        description: format!("Failed to read {}: {}", path.display(), e),
+    };
+    let metadata = fs::metadata(path).map_err(error)?;
+    let modified = metadata.modified().ok();
+    let recent = modified
+        .and_then(|modified| modified.checked_add(MTIME_GRANULARITY))
+        .map_or(true, |trusted| SystemTime::now() < trusted);
+    let hash = if recent {
+        Some(hash_file(path).map_err(error)?)
+    } else {
+        None
+    };
+    Ok(FileVersion {
+        modified,
+        len: metadata.len(),
+        file_id: file_id(&metadata),
+        hash,
+    })
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+fn hash_file(path: &Path) -> std::io::Result<u64> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buffer[..read]);
+    }
+}
+
+/// A booster that reloads itself when its model file changes.
+///
+/// A background thread polls the file's modification time, size and (on
+/// Unix) inode and, when they change, loads the new model and swaps it in.
+/// While the modification time is too recent to be trusted, the contents
+/// are compared too, so a same-size rewrite within the file system's
+/// timestamp resolution is not missed. Predictions in
+/// flight keep the model they started with; a file that fails to load
+/// (e.g. while it is still being written) leaves the previous model in
+/// place. Write new models to a temporary file and rename them over the
+/// watched path so the watcher never sees a partial file.
+///
+/// The file is polled rather than watched with file system notifications
+/// (inotify, FSEvents or the `notify` crate): polling needs no extra
+/// dependency or platform code, and notifications are often not delivered
+/// on network file systems or for the symlink swaps of Kubernetes
+/// ConfigMap and Secret volumes, where models are commonly mounted.
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use xgboost_rust::{PredictOptions, WatchedBooster};
+///
+/// let model = WatchedBooster::builder("/models/churn.ubj")
+///     .poll_interval(Duration::from_secs(5))
+///     .on_reload(|result| match result {
+///         Ok(()) => eprintln!("loaded new churn model"),
+///         Err(e) => eprintln!("keeping the previous churn model: {}", e),
+///     })
+///     .build()
+///     .unwrap();
+///
+/// // per request
+/// let booster = model.current();
+/// let predictions = booster.predict(&[1.0, 2.0], 1, 2, PredictOptions::empty(), false).unwrap();
+/// ```
+pub struct WatchedBooster {
+    shared: Arc<Shared>,
+    stop: Option<Sender<()>>,
+    watcher: Option<JoinHandle<()>>,
+}
+
+impl WatchedBooster {
+    /// Watch `path`, checking for changes every second
+    pub fn new<P: AsRef<Path>>(path: P) -> XGBoostResult<Self> {
+        Self::builder(path).build()
+    }
+
+    /// Configure watching `path`
+    pub fn builder<P: AsRef<Path>>(path: P) -> WatchedBoosterBuilder {
+        WatchedBoosterBuilder {
+            path: path.as_ref().to_path_buf(),
+            poll_interval: Duration::from_secs(1),
+            on_reload: None,
+            warmup: None,
+        }
+    }
+
+    /// The model currently in use
+    ///
+    /// Hold on to the returned booster for the duration of a request so
+    /// all of its predictions come from the same model.
+    pub fn current(&self) -> Arc<Booster> {
+        Arc::clone(&self.shared.booster.read().unwrap())
+    }
+
+    /// Reload the file now, whether or not it changed
+    pub fn reload(&self) -> XGBoostResult<()> {
+        if let Ok(version) = file_version(&self.shared.path) {
+            *self.shared.version.lock().unwrap() = version;
+        }
+        self.shared.reload()
+    }
+
+    /// The watched file
+    pub fn path(&self) -> &Path {
+        &self.shared.path
+    }
+}
+
+impl Drop for WatchedBooster {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(watcher) = self.watcher.take() {
+            let _ = watcher.join();
+        }
+    }
+}
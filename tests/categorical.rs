@@ -0,0 +1,86 @@
+//! Categorical splits against a reference model.
+//!
+//! `data/categorical.json` is a model with partition splits on two
+//! categorical features and `data/categorical_predictions.csv` holds the
+//! predictions XGBoost makes for rows that cover the edge cases;
+//! `data/categorical.py` regenerates both with the Python package.
+#![cfg(any(not(feature = "native"), xgboost_device_param))]
+
+use xgboost_rust::{Booster, FeatureType, PredictOptions};
+
+const MODEL: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/categorical.json");
+const PREDICTIONS: &str = include_str!("data/categorical_predictions.csv");
+
+/// Rows of 3 features and the expected prediction of each
+fn reference() -> (Vec<f32>, Vec<f32>) {
+    let mut data = Vec::new();
+    let mut expected = Vec::new();
+    for line in PREDICTIONS.lines().skip(1) {
+        let values: Vec<f32> = line.split(',').map(|v| v.parse().unwrap()).collect();
+        data.extend_from_slice(&values[..3]);
+        expected.push(values[3]);
+    }
+    (data, expected)
+}
+
+#[test]
+fn predictions_match_the_reference() {
+    let booster = Booster::load(MODEL).unwrap();
+    let (data, expected) = reference();
+    let predictions = booster
+        .predict(&data, expected.len(), 3, PredictOptions::empty(), false)
+        .unwrap();
+
+    assert_eq!(predictions.len(), expected.len());
+    for (row, (got, want)) in predictions.iter().zip(&expected).enumerate() {
+        assert!(
+            (got - want).abs() < 1e-6,
+            "row {}: predicted {}, expected {}",
+            row,
+            got,
+            want
+        );
+    }
+}
+
+#[test]
+fn feature_types_are_read_from_the_model() {
+    let booster = Booster::load(MODEL).unwrap();
+    assert_eq!(
+        booster.feature_types().unwrap(),
+        [
+            FeatureType::Numerical,
+            FeatureType::Categorical,
+            FeatureType::Categorical
+        ]
+    );
+}
+
+/// A depth-1 tree can only separate colors {1, 3} from {0, 2} with a
+/// partition split, so this fails if training ignores the feature type
+#[cfg(feature = "native")]
+#[test]
+fn training_learns_partition_splits() {
+    use xgboost_rust::{train, BoosterParams, DMatrix, TreeMethod};
+
+    let colors: Vec<f32> = (0..40).map(|i| (i % 4) as f32).collect();
+    let labels: Vec<f32> = colors
+        .iter()
+        .map(|&c| if c == 1.0 || c == 3.0 { 1.0 } else { 0.0 })
+        .collect();
+    let mut dtrain = DMatrix::from_dense(&colors, colors.len(), 1).unwrap();
+    dtrain.set_label(&labels).unwrap();
+    dtrain.set_categorical_features(&[0]).unwrap();
+
+    let params = BoosterParams::new()
+        .tree_method(TreeMethod::Hist)
+        .max_depth(1)
+        .max_cat_to_onehot(1)
+        .eta(1.0);
+    let booster = train(&params, &dtrain, 1).unwrap();
+    let predictions = booster
+        .predict(&[0.0, 1.0, 2.0, 3.0], 4, 1, PredictOptions::empty(), false)
+        .unwrap();
+    assert!(predictions[0] < 0.1 && predictions[2] < 0.1);
+    assert!(predictions[1] > 0.9 && predictions[3] > 0.9);
+}
@@ -0,0 +1,76 @@
+"""Regenerate categorical.json and categorical_predictions.csv.
+
+Trains a small model with one numerical and two categorical features
+in the Python package and records its predictions on rows that cover
+the categorical edge cases: categories in and out of a split set,
+missing values, negative codes and codes above every category seen in
+training. tests/categorical.rs checks both backends against the file.
+
+    pip install "xgboost>=2.0,<3" numpy pandas
+    python tests/data/categorical.py
+"""
+
+from pathlib import Path
+
+import numpy as np
+import pandas as pd
+import xgboost as xgb
+
+HERE = Path(__file__).parent
+rng = np.random.default_rng(0)
+
+n = 256
+price = rng.uniform(0, 4, n)
+color = rng.integers(0, 4, n)
+size = rng.integers(0, 5, n)
+label = (
+    np.isin(color, [1, 3]) * 0.5
+    + (price > 2.5) * 0.25
+    + np.isin(size, [0, 4]) * 0.375
+    + rng.normal(0, 0.01, n)
+)
+train = pd.DataFrame(
+    {
+        "price": price,
+        "color": pd.Categorical(color, categories=range(4)),
+        "size": pd.Categorical(size, categories=range(5)),
+    }
+)
+
+booster = xgb.train(
+    {
+        "tree_method": "hist",
+        "max_depth": 2,
+        "eta": 1.0,
+        # partition splits rather than one-hot splits
+        "max_cat_to_onehot": 1,
+        "base_score": 0.5,
+    },
+    xgb.DMatrix(train, label=label, enable_categorical=True),
+    num_boost_round=2,
+)
+booster.save_model(HERE / "categorical.json")
+
+# Category codes as the Rust crate sees them: plain floats
+rows = np.array(
+    [
+        [0.5, 1, 0],
+        [3.0, 3, 2],
+        [0.0, 2, 4],
+        [np.nan, np.nan, np.nan],
+        [2.0, -1, 1],
+        [np.nan, 3, 7],
+        [0.25, 1000, 4],
+        [1.5, 0, np.nan],
+    ],
+    dtype=np.float32,
+)
+predictions = booster.inplace_predict(rows)
+np.savetxt(
+    HERE / "categorical_predictions.csv",
+    np.column_stack([rows, predictions]),
+    delimiter=",",
+    header="price,color,size,prediction",
+    comments="",
+    fmt="%.9g",
+)
@@ -0,0 +1,132 @@
+//! Training behavior that needs libxgboost.
+#![cfg(feature = "native")]
+
+use xgboost_rust::{train, BoosterParams, DMatrix};
+
+/// `num_rows` rows of 4 features with a noisy linear label
+fn regression_data(num_rows: usize) -> (Vec<f32>, DMatrix) {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut uniform = move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 40) as f32 / (1u64 << 24) as f32
+    };
+    let mut data = Vec::with_capacity(num_rows * 4);
+    let mut labels = Vec::with_capacity(num_rows);
+    for _ in 0..num_rows {
+        let row = [uniform(), uniform(), uniform(), uniform()];
+        labels.push(row[0] + 2.0 * row[1] - row[2] * row[3] + 0.1 * uniform());
+        data.extend_from_slice(&row);
+    }
+    let mut dmatrix = DMatrix::from_dense(&data, num_rows, 4).unwrap();
+    dmatrix.set_label(&labels).unwrap();
+    (data, dmatrix)
+}
+
+#[cfg(xgboost_save_to_buffer)]
+#[test]
+fn deterministic_training_is_bit_identical() {
+    use xgboost_rust::ModelFormat;
+
+    let (_, dtrain) = regression_data(500);
+    let params = |seed| {
+        BoosterParams::new()
+            .max_depth(4)
+            .subsample(0.7)
+            .colsample_bytree(0.8)
+            .deterministic(seed)
+    };
+    let model = |seed| {
+        train(&params(seed), &dtrain, 20)
+            .unwrap()
+            .save_to_buffer(ModelFormat::Ubjson)
+            .unwrap()
+    };
+
+    let first = model(42);
+    assert_eq!(first, model(42));
+    // the sampling does depend on the seed, so the comparison is not vacuous
+    assert_ne!(first, model(7));
+}
+
+#[test]
+fn dart_drops_trees_only_when_predicting_for_training() {
+    use xgboost_rust::{BoosterType, PredictOptions};
+
+    let (data, dtrain) = regression_data(300);
+    let params = BoosterParams::new()
+        .booster(BoosterType::Dart)
+        .max_depth(3)
+        .rate_drop(0.5)
+        .skip_drop(0.0)
+        .one_drop(true)
+        .seed(1);
+    let booster = train(&params, &dtrain, 30).unwrap();
+    let predict = |training| {
+        booster
+            .predict(&data, 300, 4, PredictOptions::empty(), training)
+            .unwrap()
+    };
+
+    let inference = predict(false);
+    assert_eq!(inference, predict(false));
+    assert_ne!(inference, predict(true));
+}
+
+#[test]
+fn unknown_params_fail_unless_unchecked() {
+    let (_, dtrain) = regression_data(50);
+
+    let typo = BoosterParams::new().param("learning_rte", 0.1);
+    let error = train(&typo, &dtrain, 1).err().unwrap();
+    assert!(error.description.contains("did you mean 'learning_rate'"));
+
+    // Parameters XGBoost knows but the names list misses still go through
+    let params = BoosterParams::new()
+        .param("lambda_bias", 0.0)
+        .param_unchecked("max_cached_hist_node", 1024);
+    train(&params, &dtrain, 1).unwrap();
+}
+
+#[cfg(xgboost_save_to_buffer)]
+#[test]
+fn best_iteration_follows_the_attribute() {
+    use xgboost_rust::{Booster, ModelFormat};
+
+    let (_, dtrain) = regression_data(50);
+    let mut booster = train(&BoosterParams::new(), &dtrain, 5).unwrap();
+    assert_eq!(booster.best_iteration().unwrap(), None);
+
+    booster.set_attr("best_iteration", "3").unwrap();
+    assert_eq!(booster.best_iteration().unwrap(), Some(3));
+    let buffer = booster.save_to_buffer(ModelFormat::Ubjson).unwrap();
+    let loaded = Booster::load_from_buffer(&buffer).unwrap();
+    assert_eq!(loaded.best_iteration().unwrap(), Some(3));
+
+    booster.remove_attr("best_iteration").unwrap();
+    assert_eq!(booster.best_iteration().unwrap(), None);
+}
+
+#[cfg(feature = "polars")]
+#[test]
+fn eval_history_converts_to_a_dataframe() {
+    use xgboost_rust::Trainer;
+
+    let (_, dtrain) = regression_data(100);
+    let params = BoosterParams::new().max_depth(2).eval_metric("rmse");
+    let output = Trainer::new(&params, &dtrain)
+        .num_boost_round(5)
+        .evals(&[(&dtrain, "train")])
+        .train()
+        .unwrap();
+
+    let curves = output.history.to_dataframe().unwrap();
+    assert_eq!(curves.height(), 5);
+    let names: Vec<&str> = curves
+        .get_column_names()
+        .into_iter()
+        .map(|name| name.as_str())
+        .collect();
+    assert_eq!(names, ["round", "train-rmse"]);
+}
@@ -0,0 +1,83 @@
+//! `#[derive(XgbFeatures)]` for [xgboost-rust](https://docs.rs/xgboost-rust).
+//!
+//! Use it through the `derive` feature of `xgboost-rust`, which re-exports
+//! the macro next to the `XgbFeatures` trait.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+/// Implement `xgboost_rust::XgbFeatures` for a struct with named fields.
+///
+/// Every field is a feature, in declaration order, named after the field.
+/// Field types must implement `xgboost_rust::AsFeature` (numbers, `bool`
+/// and `Option` of those, where `None` is a missing value).
+///
+/// * `#[xgb(rename = "name")]` uses another feature name
+/// * `#[xgb(skip)]` leaves a field out of the features
+#[proc_macro_derive(XgbFeatures, attributes(xgb))]
+pub fn derive_xgb_features(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "XgbFeatures needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "XgbFeatures can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut names = Vec::new();
+    let mut idents = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+        let mut name = ident.to_string();
+        let mut skip = false;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("xgb")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `skip` or `rename = \"...\"`"))
+                }
+            })?;
+        }
+        if !skip {
+            // raw identifiers name the feature without the `r#`
+            names.push(name.trim_start_matches("r#").to_string());
+            idents.push(ident);
+        }
+    }
+
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::xgboost_rust::XgbFeatures for #ty #ty_generics #where_clause {
+            const FEATURE_NAMES: &'static [&'static str] = &[#(#names),*];
+
+            fn write_features(&self, out: &mut ::std::vec::Vec<f32>) {
+                #(out.push(::xgboost_rust::AsFeature::as_feature(&self.#idents));)*
+            }
+        }
+    })
+}